@@ -37,6 +37,7 @@ fn bench_params_badge(c: &mut Criterion) {
                 extra_link: Some("https://example.org"),
                 logo: Some("rust"),
                 logo_color: Some("#FFF"),
+                ..Default::default()
             };
             let _svg = render_badge_svg(&params);
         });
@@ -1,19 +1,166 @@
 //! Build script for shields crate.
+use std::env;
 use std::fs;
 use std::io;
 use std::path::Path;
 
-const TEMPLATE_FILES: [&str; 5] = [
+const TEMPLATE_FILES: [&str; 9] = [
     "templates/flat_badge_template.svg",
     "templates/flat_square_badge_template.svg",
     "templates/plastic_badge_template.svg",
     "templates/social_badge_template.svg",
     "templates/for_the_badge_template.svg",
+    "templates/for_the_badge_two_line_template.svg",
+    "templates/pill_badge_template.svg",
+    "templates/outline_badge_template.svg",
+    "templates/monospace_badge_template.svg",
 ];
 
+const STYLES: [&str; 9] = [
+    "flat",
+    "flat-square",
+    "plastic",
+    "social",
+    "for-the-badge",
+    "for-the-badge-two-line",
+    "pill",
+    "outline",
+    "monospace",
+];
+
+/// Cfgs set when any of their styles is enabled, naming the layout code those styles share so
+/// it is gated on one cfg rather than a list of features. `any_style` and `all_styles` are set
+/// when any and all styles are enabled.
+const STYLE_CFGS: [(&str, &[&str]); 6] = [
+    (
+        "classic_layout",
+        &[
+            "flat",
+            "flat-square",
+            "plastic",
+            "pill",
+            "outline",
+            "monospace",
+        ],
+    ),
+    (
+        "flat_layout",
+        &["flat", "flat-square", "pill", "outline", "monospace"],
+    ),
+    (
+        "for_the_badge_layout",
+        &["for-the-badge", "for-the-badge-two-line"],
+    ),
+    (
+        "colored_style",
+        &[
+            "flat",
+            "flat-square",
+            "plastic",
+            "for-the-badge",
+            "for-the-badge-two-line",
+            "pill",
+            "outline",
+            "monospace",
+        ],
+    ),
+    (
+        "label_color_style",
+        &[
+            "flat",
+            "flat-square",
+            "plastic",
+            "for-the-badge",
+            "pill",
+            "outline",
+            "monospace",
+        ],
+    ),
+    (
+        "proportional_style",
+        &[
+            "flat",
+            "flat-square",
+            "plastic",
+            "social",
+            "for-the-badge",
+            "for-the-badge-two-line",
+            "pill",
+            "outline",
+        ],
+    ),
+];
+
+/// Width tables with the styles measuring text in them; a table no enabled style needs is not
+/// embedded. The Verdana 11px table is always embedded, as logo glyphs and fonts without a
+/// table are measured with it.
+const WIDTH_TABLES: [(&str, &[&str]); 9] = [
+    ("assets/fonts/verdana-11px-normal.json", &[]),
+    (
+        "assets/fonts/helvetica-11px-bold.json",
+        &[
+            "flat",
+            "flat-square",
+            "plastic",
+            "social",
+            "for-the-badge",
+            "for-the-badge-two-line",
+            "pill",
+            "outline",
+        ],
+    ),
+    (
+        "assets/fonts/verdana-10px-normal.json",
+        &["for-the-badge", "for-the-badge-two-line"],
+    ),
+    (
+        "assets/fonts/verdana-10px-bold.json",
+        &["social", "for-the-badge", "for-the-badge-two-line"],
+    ),
+    ("assets/fonts/monospace-11px-normal.json", &["monospace"]),
+    (
+        "assets/fonts/dejavu-sans-11px-normal.json",
+        &["flat", "flat-square", "plastic", "pill", "outline"],
+    ),
+    ("assets/fonts/dejavu-sans-11px-bold.json", &["social"]),
+    (
+        "assets/fonts/dejavu-sans-10px-normal.json",
+        &["for-the-badge", "for-the-badge-two-line"],
+    ),
+    (
+        "assets/fonts/dejavu-sans-10px-bold.json",
+        &["for-the-badge", "for-the-badge-two-line"],
+    ),
+];
+
+/// Copy of simple-icons' `_data/simple-icons.json`, from the release whose artwork the pinned
+/// `simpleicons` crate bundles.
+const SIMPLE_ICONS_DATA: &str = "assets/simple-icons.json";
+
+/// Magic bytes opening a binary width table (see `CharWidthMeasurer::from_binary`).
+const WIDTH_TABLE_MAGIC: &[u8; 4] = b"SHWT";
+
 fn main() -> io::Result<()> {
     println!("cargo:rerun-if-changed=build.rs");
 
+    let enabled: Vec<&str> = STYLES
+        .into_iter()
+        .filter(|style| style_enabled(style))
+        .collect();
+    let any_enabled = |styles: &[&str]| styles.iter().any(|style| enabled.contains(style));
+    let cfgs = [
+        ("any_style", !enabled.is_empty()),
+        ("all_styles", enabled.len() == STYLES.len()),
+    ]
+    .into_iter()
+    .chain(STYLE_CFGS.map(|(cfg, styles)| (cfg, any_enabled(styles))));
+    for (cfg, set) in cfgs {
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+        if set {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+    }
+
     for file in &TEMPLATE_FILES {
         println!("cargo:rerun-if-changed={}", file);
 
@@ -24,9 +171,125 @@ fn main() -> io::Result<()> {
         let min_content = minify_svg(&content);
         fs::write(dest, min_content)?;
     }
+
+    // Width tables are embedded in binary form, so loading them needs no parsing
+    let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let names = WIDTH_TABLES.map(|(file, _)| format!("{:?}", table_name(file)));
+    println!(
+        "cargo:rustc-check-cfg=cfg(width_table, values({}))",
+        names.join(", ")
+    );
+    for (file, styles) in &WIDTH_TABLES {
+        if !styles.is_empty() && !any_enabled(styles) {
+            continue;
+        }
+        println!("cargo:rustc-cfg=width_table={:?}", table_name(file));
+        println!("cargo:rerun-if-changed={}", file);
+
+        let dest = Path::new(&out_dir).join(format!("{}.bin", table_name(file)));
+        let table = width_table_bytes(&fs::read_to_string(file)?)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file, e)))?;
+        fs::write(dest, table)?;
+    }
+
+    println!("cargo:rerun-if-changed={}", SIMPLE_ICONS_DATA);
+    let colors = brand_colors(&fs::read_to_string(SIMPLE_ICONS_DATA)?)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", SIMPLE_ICONS_DATA, e)))?;
+    fs::write(Path::new(&out_dir).join("brand_colors.rs"), colors)?;
     Ok(())
 }
 
+/// Whether the `style-<style>` feature is enabled.
+fn style_enabled(style: &str) -> bool {
+    let feature = format!(
+        "CARGO_FEATURE_STYLE_{}",
+        style.to_uppercase().replace('-', "_")
+    );
+    env::var_os(feature).is_some()
+}
+
+/// Name of a width table, e.g. `verdana-11px-normal` for its JSON source, as used for the
+/// embedded file and the `width_table` cfg.
+fn table_name(file: &str) -> &str {
+    Path::new(file).file_stem().unwrap().to_str().unwrap()
+}
+
+/// Converts simple-icons data to a Rust slice expression of `(title, slug, hex)` entries. The
+/// slug is only given where the data overrides the one derived from the title.
+fn brand_colors(json: &str) -> io::Result<String> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let data: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| invalid(&e.to_string()))?;
+    // Releases before 14.0 wrap the list in an `icons` object
+    let icons = data
+        .as_array()
+        .or_else(|| data["icons"].as_array())
+        .ok_or_else(|| invalid("JSON is not a list of icons"))?;
+    let mut entries = String::from("&[\n");
+    for icon in icons {
+        let title = icon["title"]
+            .as_str()
+            .ok_or_else(|| invalid("icon has no title"))?;
+        let hex = icon["hex"]
+            .as_str()
+            .filter(|hex| hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .ok_or_else(|| invalid(&format!("{} has no valid hex color", title)))?;
+        let slug = icon["slug"].as_str();
+        entries.push_str(&format!("    ({:?}, {:?}, {:?}),\n", title, slug, hex));
+    }
+    entries.push(']');
+    Ok(entries)
+}
+
+/// Converts a JSON width table of `[lower, upper, width]` ranges to the binary format: the
+/// magic bytes and a little-endian `u32` range count, then each range's lower and upper code
+/// points as `u32` and width as `f64`, all little-endian.
+fn width_table_bytes(json: &str) -> io::Result<Vec<u8>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let body = json
+        .trim()
+        .strip_prefix('[')
+        .and_then(|body| body.strip_suffix(']'))
+        .ok_or_else(|| invalid("JSON is not an array"))?;
+    let mut ranges: Vec<(u32, u32, f64)> = Vec::new();
+    for range in body.split(']') {
+        let range = range.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        if range.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = range
+            .strip_prefix('[')
+            .ok_or_else(|| invalid("subitem is not an array"))?
+            .split(',')
+            .map(str::trim)
+            .collect();
+        let [lower, upper, width] = fields[..] else {
+            return Err(invalid("subitem is not a [lower, upper, width] triple"));
+        };
+        let lower = lower
+            .parse()
+            .map_err(|_| invalid("lower is not an integer"))?;
+        let upper = upper
+            .parse()
+            .map_err(|_| invalid("upper is not an integer"))?;
+        let width = width.parse().map_err(|_| invalid("width is not a float"))?;
+        if lower > upper || ranges.last().is_some_and(|&(_, last, _)| last >= lower) {
+            return Err(invalid("ranges are not sorted and disjoint"));
+        }
+        ranges.push((lower, upper, width));
+    }
+
+    let mut table = Vec::with_capacity(8 + 16 * ranges.len());
+    table.extend_from_slice(WIDTH_TABLE_MAGIC);
+    table.extend_from_slice(&(ranges.len() as u32).to_le_bytes());
+    for (lower, upper, width) in ranges {
+        table.extend_from_slice(&lower.to_le_bytes());
+        table.extend_from_slice(&upper.to_le_bytes());
+        table.extend_from_slice(&f64::to_le_bytes(width));
+    }
+    Ok(table)
+}
+
 // Minify SVG content by trimming lines, joining whitespace, and removing unnecessary spaces
 fn minify_svg(content: &str) -> String {
     let min_content = content.lines().map(str::trim).collect::<String>();
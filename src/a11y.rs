@@ -0,0 +1,342 @@
+//! Accessibility audit for badge parameters.
+//!
+//! [`audit`] renders a badge and reports the accessibility problems it finds, so documentation
+//! pipelines can enforce a policy on generated badges (for example, fail the build when any
+//! [`A11yIssue::LowContrast`] is reported).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use shields::a11y::{A11yIssue, audit};
+//! use shields::BadgeParams;
+//!
+//! let issues = audit(&BadgeParams {
+//!     label: Some("build"),
+//!     message: Some(""),
+//!     message_color: Some("red"),
+//!     ..Default::default()
+//! });
+//! assert!(issues.iter().any(|i| matches!(i, A11yIssue::ColorOnlyStatus { .. })));
+//! ```
+
+use crate::color_util::to_svg_color;
+use crate::{
+    BadgeParams, BadgeStyle, RenderContext, RenderOptions, contrast_ratio, default_label_color,
+    default_message_color, render_badge_svg,
+};
+use csscolorparser::Color;
+use std::borrow::Cow;
+use std::str::FromStr;
+use xmltree::{Element, XMLNode};
+
+/// Minimum text contrast ratio (WCAG 2 level AA for normal-size text).
+pub const MIN_CONTRAST_RATIO: f64 = crate::MIN_CONTRAST_RATIO;
+
+/// Minimum width in pixels of a link's clickable area (WCAG 2.2 target size, level AA).
+///
+/// Badge heights are fixed by their style, so only widths are checked.
+pub const MIN_TARGET_SIZE: u32 = 24;
+
+/// Part of a badge an issue refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgePart {
+    /// The left-hand label segment.
+    Label,
+    /// The right-hand message segment.
+    Message,
+}
+
+/// An accessibility problem found by [`audit`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum A11yIssue {
+    /// Text contrast against its background is below [`MIN_CONTRAST_RATIO`].
+    LowContrast { part: BadgePart, ratio: f64 },
+    /// The badge has no label or message text, so assistive technology has nothing to announce.
+    MissingAccessibleText,
+    /// A link's clickable area is narrower than [`MIN_TARGET_SIZE`]; `width` is 0 when the
+    /// link has no clickable area at all.
+    SmallLinkTarget { href: String, width: u32 },
+    /// The message is colored but has no text, so its meaning is conveyed by color alone.
+    ColorOnlyStatus { color: String },
+}
+
+/// Checks a badge for accessibility problems.
+///
+/// # Arguments
+/// * `params` - Badge parameters (see [`BadgeParams`]).
+///
+/// # Returns
+/// The issues found, empty if the badge passes every check.
+pub fn audit(params: &BadgeParams) -> Vec<A11yIssue> {
+    let mut issues = Vec::new();
+    let label = params.label.unwrap_or("").trim();
+    let message = params.message.unwrap_or("").trim();
+
+    if label.is_empty() && message.is_empty() {
+        issues.push(A11yIssue::MissingAccessibleText);
+    }
+
+    // Social badges use fixed dark-on-light colors regardless of the color parameters.
+    if params.style != BadgeStyle::Social {
+        let label_color = match params.label_color {
+            Some(c) if !c.trim().is_empty() => c,
+            _ => default_label_color(),
+        };
+        let message_color = match params.message_color {
+            Some(c) if !c.trim().is_empty() => c,
+            _ => default_message_color(),
+        };
+        for (part, text, color, fallback) in [
+            (BadgePart::Label, label, label_color, default_label_color()),
+            (
+                BadgePart::Message,
+                message,
+                message_color,
+                default_message_color(),
+            ),
+        ] {
+            if text.is_empty() {
+                continue;
+            }
+            let ratio = text_contrast(color, fallback, &RenderContext::default());
+            if ratio < MIN_CONTRAST_RATIO {
+                issues.push(A11yIssue::LowContrast { part, ratio });
+            }
+        }
+
+        if let Some(color) = params.message_color.map(str::trim)
+            && !color.is_empty()
+            && !message.chars().any(char::is_alphanumeric)
+        {
+            issues.push(A11yIssue::ColorOnlyStatus {
+                color: color.to_string(),
+            });
+        }
+    }
+
+    let link = params.link.unwrap_or("");
+    let extra_link = params.extra_link.unwrap_or("");
+    let expected: &[&str] = match (link.is_empty(), extra_link.is_empty()) {
+        (true, _) => &[],
+        (false, true) => &[link],
+        (false, false) => &[link, extra_link],
+    };
+    if !expected.is_empty() {
+        let mut targets = Vec::new();
+        if let Ok(root) = Element::parse(render_badge_svg(params).as_bytes()) {
+            collect_link_targets(&root, &mut targets);
+        }
+        for href in expected {
+            let width = match targets.iter().position(|(h, _)| h == href) {
+                Some(i) => targets.remove(i).1,
+                None => 0,
+            };
+            if width < MIN_TARGET_SIZE {
+                issues.push(A11yIssue::SmallLinkTarget {
+                    href: href.to_string(),
+                    width,
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// Text contrast ratios of a badge's label and message (see [`contrast_report`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContrastReport {
+    /// Contrast ratio of the label text against the label background, from 1 to 21.
+    pub label: f64,
+    /// Contrast ratio of the message text against the message background, from 1 to 21.
+    pub message: f64,
+}
+
+impl ContrastReport {
+    /// Whether both ratios reach [`MIN_CONTRAST_RATIO`].
+    pub fn passes(&self) -> bool {
+        self.label >= MIN_CONTRAST_RATIO && self.message >= MIN_CONTRAST_RATIO
+    }
+}
+
+/// Computes the text contrast ratios of a badge rendered with `options`, including any
+/// [`RenderOptions::contrast`] correction.
+///
+/// Social badges draw dark text on fixed light backgrounds and are reported with those.
+///
+/// ## Example
+/// ```rust
+/// use shields::a11y::contrast_report;
+/// use shields::{BadgeParams, ContrastFix, RenderOptions};
+///
+/// let params = BadgeParams {
+///     message: Some("71%"),
+///     message_color: Some("yellowgreen"),
+///     ..Default::default()
+/// };
+/// assert!(!contrast_report(&params, &RenderOptions::new()).passes());
+/// let fixed = RenderOptions::new().contrast(ContrastFix::Background);
+/// assert!(contrast_report(&params, &fixed).passes());
+/// ```
+pub fn contrast_report(params: &BadgeParams, options: &RenderOptions) -> ContrastReport {
+    let ctx = RenderContext {
+        options: Cow::Borrowed(options),
+        ..Default::default()
+    };
+    if params.style == BadgeStyle::Social {
+        let ratio = text_contrast("#fcfcfc", "#fcfcfc", &ctx);
+        return ContrastReport {
+            label: ratio,
+            message: ratio,
+        };
+    }
+    let color = |color: Option<&str>, fallback: &str| {
+        let color = color
+            .filter(|c| !c.trim().is_empty())
+            .and_then(to_svg_color)
+            .unwrap_or(fallback.to_string());
+        ctx.contrast_background(&color).unwrap_or(color)
+    };
+    let label = color(params.label_color, default_label_color());
+    let message = color(params.message_color, default_message_color());
+    ContrastReport {
+        label: text_contrast(&label, default_label_color(), &ctx),
+        message: text_contrast(&message, default_message_color(), &ctx),
+    }
+}
+
+/// Contrast ratio between the badge text color and `background` (WCAG 2 formula).
+fn text_contrast(background: &str, fallback: &str, ctx: &RenderContext) -> f64 {
+    let background = to_svg_color(background)
+        .and_then(|c| Color::from_str(&c).ok())
+        .unwrap_or_else(|| Color::from_str(fallback).unwrap());
+    let (text, _) = ctx.text_colors(&background.to_css_hex());
+    contrast_ratio(&background, &Color::from_str(text).unwrap())
+}
+
+/// Collects `(href, width)` for every `<a>`, where width is that of its widest `<rect>`.
+fn collect_link_targets(element: &Element, targets: &mut Vec<(String, u32)>) {
+    for child in &element.children {
+        if let XMLNode::Element(child) = child {
+            if child.name == "a" {
+                let href = child.attributes.get("href").cloned().unwrap_or_default();
+                targets.push((href, widest_rect(child)));
+            } else {
+                collect_link_targets(child, targets);
+            }
+        }
+    }
+}
+
+fn widest_rect(element: &Element) -> u32 {
+    element
+        .children
+        .iter()
+        .filter_map(XMLNode::as_element)
+        .map(|child| {
+            let own = if child.name == "rect" {
+                child
+                    .attributes
+                    .get("width")
+                    .and_then(|w| w.parse::<f32>().ok())
+                    .map_or(0, |w| w as u32)
+            } else {
+                0
+            };
+            own.max(widest_rect(child))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContrastFix;
+
+    fn params() -> BadgeParams<'static> {
+        BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            label_color: Some("#555"),
+            message_color: Some("#005a9c"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_accessible_badge_passes() {
+        assert_eq!(audit(&params()), vec![]);
+    }
+
+    #[test]
+    fn test_low_contrast_and_missing_text() {
+        let issues = audit(&BadgeParams {
+            message_color: Some("yellowgreen"),
+            ..params()
+        });
+        assert!(matches!(
+            issues.as_slice(),
+            [A11yIssue::LowContrast {
+                part: BadgePart::Message,
+                ..
+            }]
+        ));
+        let issues = audit(&BadgeParams {
+            label: None,
+            message: None,
+            ..params()
+        });
+        assert!(issues.contains(&A11yIssue::MissingAccessibleText));
+    }
+
+    #[test]
+    fn test_contrast_report() {
+        let report = contrast_report(&params(), &RenderOptions::new());
+        assert!(report.passes());
+        assert!((report.label - 7.46).abs() < 0.01, "{:?}", report);
+
+        let params = BadgeParams {
+            message_color: Some("yellowgreen"),
+            ..params()
+        };
+        let report = contrast_report(&params, &RenderOptions::new());
+        assert!(report.message < MIN_CONTRAST_RATIO);
+        for fix in [ContrastFix::Text, ContrastFix::Background] {
+            let report = contrast_report(&params, &RenderOptions::new().contrast(fix));
+            assert!(report.passes(), "{:?} {:?}", fix, report);
+        }
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_small_link_targets() {
+        let issues = audit(&BadgeParams {
+            label: Some("a"),
+            link: Some("https://example.com/a"),
+            extra_link: Some("https://example.com/b"),
+            ..params()
+        });
+        assert!(matches!(
+            issues.as_slice(),
+            [A11yIssue::SmallLinkTarget { href, width }] if href == "https://example.com/a" && *width < 24
+        ));
+        let issues = audit(&BadgeParams {
+            label: Some(""),
+            link: Some("https://example.com/a"),
+            extra_link: Some("https://example.com/b"),
+            ..params()
+        });
+        assert!(issues.contains(&A11yIssue::SmallLinkTarget {
+            href: "https://example.com/a".to_string(),
+            width: 0
+        }));
+        // A single link covers the whole badge
+        let issues = audit(&BadgeParams {
+            label: Some("a"),
+            link: Some("https://example.com/a"),
+            ..params()
+        });
+        assert_eq!(issues, vec![]);
+    }
+}
@@ -0,0 +1,163 @@
+//! [actix-web](https://actix.rs) integration: badges returned straight from handlers.
+//!
+//! [`BadgeResponse`] implements [`Responder`], serving its SVG as `image/svg+xml` with a
+//! `Cache-Control` header. Builders convert into one with
+//! [`BadgeBuilder::into_response`](crate::builder::BadgeBuilder::into_response), which also
+//! tags it with an `ETag` so revalidating clients get `304 Not Modified`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use actix_web::{App, Responder, web};
+//! use shields::BadgeStyle;
+//! use shields::builder::Badge;
+//!
+//! async fn build_badge() -> impl Responder {
+//!     Badge::style(BadgeStyle::Flat)
+//!         .label("build")
+//!         .message("passing")
+//!         .into_response()
+//! }
+//!
+//! let app = App::new().route("/build.svg", web::get().to(build_badge));
+//! # let _ = app;
+//! ```
+
+use crate::http_cache::{self, DEFAULT_MAX_AGE};
+use actix_web::body::BoxBody;
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, Responder};
+
+/// A rendered badge served as `image/svg+xml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadgeResponse {
+    svg: String,
+    max_age: u32,
+    etag: Option<String>,
+}
+
+impl BadgeResponse {
+    /// Wraps a rendered badge, cacheable for [`DEFAULT_MAX_AGE`] seconds.
+    pub fn new(svg: String) -> Self {
+        Self {
+            svg,
+            max_age: DEFAULT_MAX_AGE,
+            etag: None,
+        }
+    }
+
+    /// Sets how long browsers and shared caches may keep the badge, in seconds. Zero makes
+    /// the response `no-cache`, for badges that must always be current.
+    pub fn max_age(mut self, seconds: u32) -> Self {
+        self.max_age = seconds;
+        self
+    }
+
+    /// Sets the `ETag` sent with the badge (see [`http_cache::etag_for`]). Requests whose
+    /// `If-None-Match` header lists it are answered with `304 Not Modified`.
+    pub fn etag(mut self, etag: String) -> Self {
+        self.etag = Some(etag);
+        self
+    }
+
+    /// The badge SVG.
+    pub fn svg(&self) -> &str {
+        &self.svg
+    }
+}
+
+impl From<String> for BadgeResponse {
+    fn from(svg: String) -> Self {
+        Self::new(svg)
+    }
+}
+
+impl Responder for BadgeResponse {
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let if_none_match = req
+            .headers()
+            .get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok());
+        let modified = self
+            .etag
+            .as_deref()
+            .is_none_or(|etag| http_cache::is_modified(if_none_match, etag));
+        let mut response = if modified {
+            HttpResponse::Ok()
+        } else {
+            HttpResponse::NotModified()
+        };
+        response.insert_header((
+            header::CACHE_CONTROL,
+            http_cache::cache_control(self.max_age),
+        ));
+        if let Some(etag) = self.etag {
+            response.insert_header((header::ETAG, etag));
+        }
+        if modified {
+            response
+                .content_type("image/svg+xml;charset=utf-8")
+                .body(self.svg)
+        } else {
+            response.finish()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BadgeStyle;
+    use crate::builder::Badge;
+    use actix_web::body::MessageBody;
+    use actix_web::http::StatusCode;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_respond_to() {
+        let badge = Badge::style(BadgeStyle::Flat)
+            .label("build")
+            .message("passing")
+            .into_response();
+        let svg = badge.svg().to_string();
+        let request = TestRequest::default().to_http_request();
+        let response = badge.respond_to(&request);
+        let headers = response.headers();
+        assert_eq!(
+            headers.get(header::CONTENT_TYPE).unwrap(),
+            "image/svg+xml;charset=utf-8"
+        );
+        assert_eq!(
+            headers.get(header::CACHE_CONTROL).unwrap(),
+            "max-age=300, s-maxage=300"
+        );
+        let etag = headers
+            .get(header::ETAG)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = response.into_body().try_into_bytes().unwrap();
+        assert_eq!(body, svg.as_bytes());
+
+        let revalidation = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, etag.as_str()))
+            .to_http_request();
+        let response = BadgeResponse::from(svg.clone())
+            .etag(etag.clone())
+            .respond_to(&revalidation);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), etag.as_str());
+
+        let response = BadgeResponse::from(svg)
+            .max_age(0)
+            .respond_to(&revalidation);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            "no-cache"
+        );
+    }
+}
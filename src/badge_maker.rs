@@ -0,0 +1,128 @@
+//! Compatibility with the npm [`badge-maker`](https://www.npmjs.com/package/badge-maker)
+//! package, for services moving from Node.
+//!
+//! [`Format`] mirrors the option object passed to `makeBadge(format)` and deserializes from
+//! the same JSON; [`make_badge`] renders it.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(all(feature = "unstable", feature = "style-flat-square"))] {
+//! use shields::badge_maker::{Format, make_badge};
+//!
+//! let format = Format::from_json(
+//!     r##"{"label": "build", "message": "passed", "color": "green", "style": "flat-square",
+//!         "links": ["https://ci.example.com", "https://ci.example.com/latest"]}"##,
+//! )
+//! .unwrap();
+//! let svg = make_badge(&format);
+//! assert!(svg.contains("passed"));
+//! # }
+//! ```
+
+use crate::{BadgeParams, BadgeStyle, IdSuffix, RenderOptions, render_badge_svg_with_options};
+use serde::{Deserialize, Serialize};
+
+/// The `format` object accepted by badge-maker's `makeBadge`.
+///
+/// Like badge-maker, `message` is required and an unknown `style` is rejected when parsing.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Format {
+    /// Left-hand text (default empty, which renders a message-only badge).
+    #[serde(default)]
+    pub label: String,
+    /// Right-hand text.
+    pub message: String,
+    /// Label color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_color: Option<String>,
+    /// Message color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Badge style (default `flat`).
+    #[serde(default)]
+    pub style: BadgeStyle,
+    /// Logo as a `data:image/...;base64,` URI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_base64: Option<String>,
+    /// Up to two links, for the left and right halves of the badge.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+    /// Suffix appended to the badge's element ids, keeping them unique when several badges are
+    /// inlined in one page. Without one the ids are badge-maker's plain ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_suffix: Option<String>,
+}
+
+impl Format {
+    /// Parses a badge-maker `format` object.
+    ///
+    /// # Errors
+    /// Returns an error if `message` is missing, the style is unknown or the JSON does not
+    /// match the shape of a `format` object.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Borrows the format as badge parameters.
+    pub fn as_params(&self) -> BadgeParams<'_> {
+        let link = |i: usize| self.links.get(i).map(String::as_str);
+        BadgeParams {
+            style: self.style,
+            label: Some(self.label.as_str()),
+            message: Some(self.message.as_str()),
+            label_color: self.label_color.as_deref(),
+            message_color: self.color.as_deref(),
+            link: link(0),
+            extra_link: link(1),
+            logo: self.logo_base64.as_deref(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Renders a badge-maker `format` as SVG, like badge-maker's `makeBadge`.
+pub fn make_badge(format: &Format) -> String {
+    let id_suffix = match &format.id_suffix {
+        Some(id_suffix) => IdSuffix::Fixed(id_suffix.clone()),
+        None => IdSuffix::Plain,
+    };
+    render_badge_svg_with_options(
+        &format.as_params(),
+        &RenderOptions::new().id_suffix(id_suffix),
+    )
+}
+
+#[cfg(all(test, feature = "style-flat"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_badge() {
+        let format = Format::from_json(
+            r##"{"label": "build", "message": "passed", "labelColor": "#333",
+                "color": "green", "links": ["https://a.example", "https://b.example"]}"##,
+        )
+        .unwrap();
+        assert_eq!(format.style, BadgeStyle::Flat);
+        let params = format.as_params();
+        assert_eq!(params.label_color, Some("#333"));
+        assert_eq!(params.extra_link, Some("https://b.example"));
+        let plain = RenderOptions::new().id_suffix(IdSuffix::Plain);
+        assert_eq!(
+            make_badge(&format),
+            render_badge_svg_with_options(&params, &plain)
+        );
+        assert!(make_badge(&format).contains(r#"id="r""#));
+
+        let format = Format {
+            id_suffix: Some("one".to_string()),
+            ..format
+        };
+        assert!(make_badge(&format).contains(r#"id="r-one""#));
+
+        assert!(Format::from_json(r#"{"label": "build"}"#).is_err());
+        assert!(Format::from_json(r#"{"message": "x", "style": "bogus"}"#).is_err());
+    }
+}
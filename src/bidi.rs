@@ -0,0 +1,90 @@
+//! Right-to-left text support: direction detection and the attributes that lay out Arabic,
+//! Hebrew and other right-to-left labels correctly.
+
+/// Attributes making a centered `<text>` lay out right to left.
+const RTL: &str = r#" direction="rtl" unicode-bidi="embed""#;
+/// Attributes making a start-anchored `<text>` lay out right to left while staying anchored
+/// on its left edge.
+const RTL_LEFT_ANCHORED: &str = r#" direction="rtl" unicode-bidi="embed" text-anchor="end""#;
+
+/// Returns whether `text` is right to left, judged by its first strongly directional
+/// character as the Unicode bidi algorithm does for a paragraph. Digits, punctuation and
+/// spaces have no direction of their own.
+pub(crate) fn is_rtl(text: &str) -> bool {
+    text.chars()
+        .find_map(|c| {
+            if is_strong_rtl(c) {
+                Some(true)
+            } else if c.is_alphabetic() {
+                Some(false)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(false)
+}
+
+/// Direction attributes for a centered `<text>` holding `text`, empty for left-to-right text.
+pub(crate) fn text_attributes(text: &str) -> &'static str {
+    if is_rtl(text) { RTL } else { "" }
+}
+
+/// Direction attributes for a `<text>` anchored at its start, empty for left-to-right text.
+pub(crate) fn left_anchored_text_attributes(text: &str) -> &'static str {
+    if is_rtl(text) { RTL_LEFT_ANCHORED } else { "" }
+}
+
+/// Letters of the right-to-left scripts.
+fn is_strong_rtl(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0590..=0x08FF // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic
+            | 0xFB1D..=0xFDFF // Hebrew and Arabic presentation forms A
+            | 0xFE70..=0xFEFF // Arabic presentation forms B
+            | 0x10800..=0x10FFF // historic right-to-left scripts
+            | 0x1E800..=0x1EFFF // Mende Kikakui, Adlam, Arabic mathematical symbols
+    ) && c.is_alphabetic()
+}
+
+/// Characters drawn without advancing the text: bidi formatting controls, zero-width
+/// joiners and the combining vowel marks of Hebrew and Arabic.
+pub(crate) fn is_zero_width(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0591..=0x05BD
+            | 0x05BF
+            | 0x05C1..=0x05C2
+            | 0x05C4..=0x05C5
+            | 0x05C7
+            | 0x0610..=0x061A
+            | 0x061C
+            | 0x064B..=0x065F
+            | 0x0670
+            | 0x06D6..=0x06DC
+            | 0x06DF..=0x06E4
+            | 0x06E7..=0x06E8
+            | 0x06EA..=0x06ED
+            | 0x200B..=0x200F
+            | 0x202A..=0x202E
+            | 0x2066..=0x2069
+            | 0xFEFF
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_rtl() {
+        assert!(is_rtl("שלום"));
+        assert!(is_rtl("مرحبا"));
+        assert!(is_rtl("2.0 גרסה"));
+        assert!(!is_rtl("build"));
+        assert!(!is_rtl("v2 גרסה"));
+        assert!(!is_rtl("1.0"));
+        assert!(is_zero_width('\u{200F}'));
+        assert!(is_zero_width('\u{064E}'));
+        assert!(!is_zero_width('ש'));
+    }
+}
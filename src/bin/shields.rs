@@ -0,0 +1,332 @@
+//! Command-line badge renderer.
+//!
+//! ```sh
+//! shields --label build --message passing --color brightgreen -o build.svg
+//! cat coverage.txt | shields --label coverage --message-template "{value}%" \
+//!     --color-scale "0:red,60:yellow,80:brightgreen" -o coverage.svg
+//! ```
+
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+use shields::color_scale::{ColorScale, ParseColorScaleError};
+use shields::{
+    BadgeError, BadgeParams, BadgeStyle, FontFamily, ParseBadgeStyleError, ParseFontFamilyError,
+    render_badge_svg, svg_size, try_render_badge_svg,
+};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Render shields.io-compatible SVG badges.
+#[derive(Parser, Debug)]
+#[command(name = "shields", version, about)]
+struct Cli {
+    /// Badge style: flat, flat-square, plastic, social, for-the-badge, for-the-badge-two-line,
+    /// pill, outline or monospace.
+    #[arg(long, default_value = "flat", value_parser = parse_style)]
+    style: BadgeStyle,
+    /// Left-hand label text.
+    #[arg(long)]
+    label: Option<String>,
+    /// Right-hand message text.
+    #[arg(long, conflicts_with = "message_template")]
+    message: Option<String>,
+    /// Message built from a template; every `{value}` is replaced with the input value.
+    #[arg(long)]
+    message_template: Option<String>,
+    /// File the value is read from, `-` for stdin (the default when a template or color
+    /// scale needs a value).
+    #[arg(long)]
+    value_from: Option<String>,
+    /// Message (right-hand) color.
+    #[arg(long, conflicts_with = "color_scale")]
+    color: Option<String>,
+    /// Picks the message color from a numeric value: comma-separated `threshold:color`
+    /// steps, e.g. `0:red,60:yellow,80:brightgreen`. The color of the highest threshold not
+    /// above the value wins.
+    #[arg(long, value_parser = parse_color_scale)]
+    color_scale: Option<ColorScale>,
+    /// Label (left-hand) color.
+    #[arg(long)]
+    label_color: Option<String>,
+    /// simple-icons slug, comma-separated slugs or single symbol shown before the label.
+    #[arg(long)]
+    logo: Option<String>,
+    /// Logo color, or `auto` for the logo's brand color.
+    #[arg(long)]
+    logo_color: Option<String>,
+    /// Link for the whole badge, or the label when `--extra-link` is set.
+    #[arg(long)]
+    link: Option<String>,
+    /// Link for the message.
+    #[arg(long)]
+    extra_link: Option<String>,
+    /// Tooltip shown when hovering the badge, in place of the `label: message` text.
+    #[arg(long)]
+    title: Option<String>,
+    /// Font family of the text: verdana, helvetica or dejavu-sans.
+    #[arg(long, value_parser = parse_font)]
+    font: Option<FontFamily>,
+    /// Output file; the SVG is written to stdout when omitted.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// How results are reported: `text` prints warnings to stderr, `json` prints one JSON
+    /// object per rendered badge to stdout (requires `--output`).
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// One line of `--format json` output.
+#[derive(Serialize, Debug)]
+struct Report {
+    file: String,
+    width: u32,
+    height: u32,
+    bytes: usize,
+    warnings: Vec<String>,
+}
+
+fn parse_style(s: &str) -> Result<BadgeStyle, String> {
+    s.parse().map_err(|e: ParseBadgeStyleError| e.to_string())
+}
+
+fn parse_font(s: &str) -> Result<FontFamily, String> {
+    s.parse().map_err(|e: ParseFontFamilyError| e.to_string())
+}
+
+fn parse_color_scale(s: &str) -> Result<ColorScale, String> {
+    s.parse().map_err(|e: ParseColorScaleError| e.to_string())
+}
+
+fn read_value(source: &str) -> Result<String, String> {
+    let value = if source == "-" {
+        let mut value = String::new();
+        io::stdin()
+            .read_to_string(&mut value)
+            .map_err(|e| format!("failed to read value from stdin: {}", e))?;
+        value
+    } else {
+        fs::read_to_string(source).map_err(|e| format!("failed to read {}: {}", source, e))?
+    };
+    Ok(value.trim().to_string())
+}
+
+/// Renders the badge, reporting the problems [`try_render_badge_svg`] finds as warnings.
+///
+/// Each rejected parameter is dropped and the check repeated, so every problem is reported;
+/// the badge itself is rendered from the original parameters with [`render_badge_svg`]'s
+/// fallbacks.
+fn render(params: &BadgeParams) -> (String, Vec<String>) {
+    let mut warnings = Vec::new();
+    let mut checked = params.clone();
+    loop {
+        let error = match try_render_badge_svg(&checked) {
+            Ok(svg) if warnings.is_empty() => return (svg, warnings),
+            Ok(_) => break,
+            Err(error) => error,
+        };
+        match error {
+            BadgeError::InvalidColor { param, value } => {
+                warnings.push(format!(
+                    "{} `{}` is not a recognized color; using the default",
+                    flag(param),
+                    value
+                ));
+                match param {
+                    "label_color" => checked.label_color = None,
+                    "message_color" => checked.message_color = None,
+                    _ => checked.logo_color = None,
+                }
+            }
+            BadgeError::InvalidLink { param, value } => {
+                warnings.push(format!("{} `{}` is not a valid link", flag(param), value));
+                match param {
+                    "link" => checked.link = None,
+                    _ => checked.extra_link = None,
+                }
+            }
+            BadgeError::InvalidLogo(slug) => {
+                warnings.push(format!(
+                    "--logo `{}` is not a known logo; rendering without it",
+                    slug
+                ));
+                checked.logo = None;
+            }
+            error => {
+                warnings.push(error.to_string());
+                break;
+            }
+        }
+    }
+    (render_badge_svg(params), warnings)
+}
+
+/// Command-line flag setting the [`BadgeParams`] field `param`.
+fn flag(param: &str) -> String {
+    match param {
+        "message_color" => "--color".to_string(),
+        param => format!("--{}", param.replace('_', "-")),
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    if cli.format == Format::Json && cli.output.is_none() {
+        return Err("--format json requires --output".to_string());
+    }
+    let needs_value = cli.message_template.is_some() || cli.color_scale.is_some();
+    let value = match (&cli.value_from, needs_value) {
+        (Some(source), _) => Some(read_value(source)?),
+        (None, true) => Some(read_value("-")?),
+        (None, false) => None,
+    };
+
+    let message = match (&cli.message_template, &value) {
+        (Some(template), Some(value)) => Some(template.replace("{value}", value)),
+        _ => cli.message.clone().or_else(|| value.clone()),
+    };
+    let color = match (&cli.color_scale, &value) {
+        (Some(scale), Some(value)) => {
+            let number = value
+                .trim_end_matches('%')
+                .parse::<f64>()
+                .map_err(|_| format!("--color-scale needs a numeric value, got `{}`", value))?;
+            scale.color_for(number).map(str::to_string)
+        }
+        _ => cli.color.clone(),
+    };
+
+    let params = BadgeParams {
+        style: cli.style,
+        label: cli.label.as_deref(),
+        message: message.as_deref(),
+        label_color: cli.label_color.as_deref(),
+        message_color: color.as_deref(),
+        link: cli.link.as_deref(),
+        extra_link: cli.extra_link.as_deref(),
+        logo: cli.logo.as_deref(),
+        logo_color: cli.logo_color.as_deref(),
+        title: cli.title.as_deref(),
+        font: cli.font,
+        ..Default::default()
+    };
+    let (svg, warnings) = render(&params);
+
+    match &cli.output {
+        Some(path) => {
+            fs::write(path, &svg)
+                .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+        }
+        None => writeln!(io::stdout(), "{}", svg)
+            .map_err(|e| format!("failed to write to stdout: {}", e))?,
+    }
+    match cli.format {
+        Format::Text => {
+            for warning in &warnings {
+                eprintln!("warning: {}", warning);
+            }
+        }
+        Format::Json => {
+            let (width, height) = svg_size(&svg).unwrap_or_default();
+            let report = Report {
+                file: cli
+                    .output
+                    .as_ref()
+                    .map_or_else(String::new, |p| p.display().to_string()),
+                width: width as u32,
+                height: height as u32,
+                bytes: svg.len(),
+                warnings,
+            };
+            let line = serde_json::to_string(&report).expect("report serialization cannot fail");
+            writeln!(io::stdout(), "{}", line)
+                .map_err(|e| format!("failed to write to stdout: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_scale() {
+        let scale = parse_color_scale("80:brightgreen, 0:red ,60:yellow").unwrap();
+        assert_eq!(scale.color_for(92.5), Some("brightgreen"));
+        assert_eq!(scale.color_for(60.0), Some("yellow"));
+        assert_eq!(scale.color_for(12.0), Some("red"));
+        // Values below the lowest threshold take its color
+        assert_eq!(scale.color_for(-1.0), Some("red"));
+        assert!(parse_color_scale("80-green").is_err());
+        assert!(parse_color_scale("high:green").is_err());
+    }
+
+    #[test]
+    fn test_message_template() {
+        let dir = std::env::temp_dir().join(format!("shields-cli-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let value = dir.join("coverage.txt");
+        let output = dir.join("coverage.svg");
+        fs::write(&value, "87.5\n").unwrap();
+        let cli = Cli::parse_from([
+            "shields",
+            "--label",
+            "coverage",
+            "--message-template",
+            "{value}%",
+            "--value-from",
+            value.to_str().unwrap(),
+            "--color-scale",
+            "0:red,80:brightgreen",
+            "-o",
+            output.to_str().unwrap(),
+        ]);
+        run(cli).unwrap();
+        let svg = fs::read_to_string(&output).unwrap();
+        assert!(svg.contains("coverage: 87.5%"), "{}", svg);
+        assert!(svg.contains(r##"fill="#4c1""##), "{}", svg);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_warnings_and_dimensions() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            message_color: Some("greenish"),
+            logo: Some("no-such-logo"),
+            ..Default::default()
+        };
+        let (svg, warnings) = render(&params);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(warnings[0].starts_with("--color `greenish`"));
+        assert!(warnings[1].starts_with("--logo `no-such-logo`"));
+        assert_eq!(svg, render_badge_svg(&params));
+        assert_eq!(svg_size(&svg), Some((88.0, 20.0)));
+        let glyph = BadgeParams {
+            logo: Some("✔"),
+            message_color: Some("brightgreen"),
+            ..params
+        };
+        assert_eq!(render(&glyph).1, Vec::<String>::new());
+
+        assert!(run(Cli::parse_from(["shields", "--format", "json"])).is_err());
+    }
+}
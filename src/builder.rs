@@ -6,6 +6,7 @@
 //! ## Example
 //!
 //! ```rust
+//! # #[cfg(feature = "style-flat")] {
 //! use shields::{BadgeStyle};
 //! use shields::builder::Badge;
 //!
@@ -17,12 +18,17 @@
 //!     .logo("github")
 //!     .build();
 //! assert!(svg.contains("passing"));
+//! # }
 //! ```
 //!
 //! See [`BadgeBuilder`] and [`Badge`] for details.
 use crate::{
-    BadgeParams, BadgeStyle, default_label_color, default_message_color, render_badge_svg,
+    BadgeColor, BadgeError, BadgeParams, BadgeParamsOwned, BadgeStyle, ContrastFix, DarkColors,
+    EmbeddedFont, FontFamily, GradientStop, IdSuffix, LinkPolicy, LogoSize, Mirror, RenderContext,
+    RenderLimits, RenderOptions, SocialIcon, Status, TextColors, default_label_color,
+    default_message_color, format, render_badge_svg_with_options, try_render_badge_svg_with,
 };
+use std::borrow::Cow;
 
 /// Builder for constructing SVG badges with a fluent API.
 ///
@@ -32,6 +38,7 @@ use crate::{
 /// ## Example
 ///
 /// ```rust
+/// # #[cfg(feature = "style-flat")] {
 /// use shields::{BadgeStyle};
 /// use shields::builder::Badge;
 ///
@@ -43,17 +50,26 @@ use crate::{
 ///     .logo("github")
 ///     .build();
 /// assert!(svg.contains("passing"));
+/// # }
 /// ```
+#[derive(Clone)]
 pub struct BadgeBuilder<'a> {
     style: BadgeStyle,
-    label: Option<&'a str>,
-    message: Option<&'a str>,
-    label_color: Option<&'a str>,
-    message_color: Option<&'a str>,
-    logo: Option<&'a str>,
-    logo_color: Option<&'a str>,
-    link: Option<&'a str>,
-    extra_link: Option<&'a str>,
+    label: Option<Cow<'a, str>>,
+    message: Option<Cow<'a, str>>,
+    label_color: BadgeColor,
+    message_color: BadgeColor,
+    logo: Option<Cow<'a, str>>,
+    logo_color: BadgeColor,
+    link: Option<Cow<'a, str>>,
+    extra_link: Option<Cow<'a, str>>,
+    bubble_icon: Option<SocialIcon>,
+    logo_width: Option<u32>,
+    logo_size: Option<LogoSize>,
+    sparkline: Option<&'a [f64]>,
+    title: Option<Cow<'a, str>>,
+    font: Option<FontFamily>,
+    options: RenderOptions,
 }
 
 impl<'a> BadgeBuilder<'a> {
@@ -68,17 +84,91 @@ impl<'a> BadgeBuilder<'a> {
             style,
             label: None,
             message: None,
-            label_color: None,
-            message_color: None,
+            label_color: BadgeColor::Default,
+            message_color: BadgeColor::Default,
             logo: None,
-            logo_color: None,
+            logo_color: BadgeColor::Default,
             link: None,
             extra_link: None,
+            bubble_icon: None,
+            logo_width: None,
+            logo_size: None,
+            sparkline: None,
+            title: None,
+            font: None,
+            options: RenderOptions::new(),
         }
     }
 
+    /// Creates a builder starting from existing parameters, e.g. a template badge loaded from
+    /// configuration, so single fields can be overridden before rendering.
+    ///
+    /// Render options start at their defaults.
+    ///
+    /// # Arguments
+    /// * `params` - The parameters to start from.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(all(feature = "style-flat", feature = "style-flat-square"))] {
+    /// use shields::builder::BadgeBuilder;
+    /// use shields::{BadgeParams, BadgeStyle};
+    ///
+    /// let template = BadgeParams {
+    ///     style: BadgeStyle::FlatSquare,
+    ///     label: Some("build"),
+    ///     message_color: Some("brightgreen"),
+    ///     ..Default::default()
+    /// };
+    /// let svg = BadgeBuilder::from_params(&template).message("passing").build();
+    /// assert!(svg.contains(">passing</text>"));
+    /// # }
+    /// ```
+    pub fn from_params(params: &BadgeParams<'a>) -> Self {
+        let color = |color: Option<&str>| color.map_or(BadgeColor::Default, BadgeColor::from);
+        Self {
+            style: params.style,
+            label: params.label.map(Cow::Borrowed),
+            message: params.message.map(Cow::Borrowed),
+            label_color: color(params.label_color),
+            message_color: color(params.message_color),
+            logo: params.logo.map(Cow::Borrowed),
+            logo_color: color(params.logo_color),
+            link: params.link.map(Cow::Borrowed),
+            extra_link: params.extra_link.map(Cow::Borrowed),
+            bubble_icon: params.bubble_icon,
+            logo_width: params.logo_width,
+            logo_size: params.logo_size,
+            sparkline: params.sparkline,
+            title: params.title.map(Cow::Borrowed),
+            font: params.font,
+            options: RenderOptions::new(),
+        }
+    }
+
+    /// Returns the parameters [`build`](BadgeBuilder::build) renders, with default colors
+    /// filled in for unset ones.
+    ///
+    /// ## Example
+    /// ```
+    /// use shields::{BadgeStyle, render_badge_svg};
+    /// use shields::builder::Badge;
+    ///
+    /// let mut builder = Badge::style(BadgeStyle::Flat);
+    /// builder.label("build").message("passing");
+    /// let params = builder.to_params();
+    /// assert_eq!(params.label_color.as_deref(), Some("#555"));
+    /// assert_eq!(render_badge_svg(&params.as_params()), builder.build());
+    /// ```
+    pub fn to_params(&self) -> BadgeParamsOwned {
+        self.with_params(|params| BadgeParamsOwned::from(params))
+    }
+
     /// Sets the label text (left side).
     ///
+    /// Text and links accept borrowed or owned strings, so values built with `format!` can be
+    /// passed directly.
+    ///
     /// # Arguments
     /// * `label` - The label text.
     ///
@@ -87,14 +177,48 @@ impl<'a> BadgeBuilder<'a> {
     ///
     /// ## Example
     /// ```
+    /// # #[cfg(feature = "style-flat")] {
     /// use shields::{BadgeStyle};
     /// use shields::builder::Badge;
     ///
     /// let mut builder = Badge::style(BadgeStyle::Flat);
     /// builder.label("build");
+    /// let version = 3;
+    /// builder.message(format!("v{version}"));
+    /// assert!(builder.build().contains(">v3</text>"));
+    /// # }
+    /// ```
+    pub fn label(&mut self, label: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the label text and removes the message, for a single-section badge drawn in the
+    /// label color.
+    ///
+    /// # Arguments
+    /// * `label` - The label text.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::BadgeStyle;
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .label_only("docs")
+    ///     .label_color("purple")
+    ///     .build();
+    /// assert!(svg.contains(">docs</text>"));
+    /// assert!(svg.contains("aria-label=\"docs\""));
+    /// # }
     /// ```
-    pub fn label(&mut self, label: &'a str) -> &mut Self {
-        self.label = Some(label);
+    pub fn label_only(&mut self, label: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.label = Some(label.into());
+        self.message = None;
         self
     }
 
@@ -105,32 +229,87 @@ impl<'a> BadgeBuilder<'a> {
     ///
     /// # Returns
     /// Mutable reference to self for chaining.
-    pub fn message(&mut self, message: &'a str) -> &mut Self {
-        self.message = Some(message);
+    pub fn message(&mut self, message: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Sets the message to a count abbreviated like shields.io social badges, e.g. `1.2k`
+    /// stars for 1234.
+    ///
+    /// # Arguments
+    /// * `count` - The count to show.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(all(feature = "style-flat", feature = "style-social"))] {
+    /// use shields::{BadgeStyle};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Social)
+    ///     .label("stars")
+    ///     .message_count(1234)
+    ///     .build();
+    /// assert!(svg.contains(">1.2k</text>"));
+    /// # }
+    /// ```
+    pub fn message_count(&mut self, count: u64) -> &mut Self {
+        self.message = Some(Cow::Owned(format::social_count(count)));
+        self
+    }
+
+    /// Sets the message and message color for a build status, e.g. a green `passing`.
+    ///
+    /// # Arguments
+    /// * `status` - The status to show.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::{BadgeStyle, Status};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .label("build")
+    ///     .status(Status::Passing)
+    ///     .build();
+    /// assert!(svg.contains(">passing</text>"));
+    /// # }
+    /// ```
+    pub fn status(&mut self, status: Status) -> &mut Self {
+        let (message, color) = status.into();
+        self.message = Some(Cow::Borrowed(message));
+        self.message_color = color.into();
         self
     }
 
     /// Sets the label background color.
     ///
     /// # Arguments
-    /// * `color` - Color string (hex, name, or alias).
+    /// * `color` - Color string (hex, name, or alias) or [`BadgeColor`].
     ///
     /// # Returns
     /// Mutable reference to self for chaining.
-    pub fn label_color(&mut self, color: &'a str) -> &mut Self {
-        self.label_color = Some(color);
+    pub fn label_color(&mut self, color: impl Into<BadgeColor>) -> &mut Self {
+        self.label_color = color.into();
         self
     }
 
     /// Sets the message background color.
     ///
     /// # Arguments
-    /// * `color` - Color string (hex, name, or alias).
+    /// * `color` - Color string (hex, name, or alias) or [`BadgeColor`].
     ///
     /// # Returns
     /// Mutable reference to self for chaining.
-    pub fn message_color(&mut self, color: &'a str) -> &mut Self {
-        self.message_color = Some(color);
+    pub fn message_color(&mut self, color: impl Into<BadgeColor>) -> &mut Self {
+        self.message_color = color.into();
         self
     }
 
@@ -141,20 +320,51 @@ impl<'a> BadgeBuilder<'a> {
     ///
     /// # Returns
     /// Mutable reference to self for chaining.
-    pub fn logo(&mut self, logo: &'a str) -> &mut Self {
-        self.logo = Some(logo);
+    pub fn logo(&mut self, logo: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.logo = Some(logo.into());
+        self
+    }
+
+    /// Sets the logo and removes the label and message, for an icon centered on a square
+    /// block as tall as the badge, in the message color (or the label color if only that is
+    /// set).
+    ///
+    /// # Arguments
+    /// * `logo` - Logo name or SVG data.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::BadgeStyle;
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .logo_only("github")
+    ///     .message_color("#181717")
+    ///     .build();
+    /// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" height=\"20\""));
+    /// assert!(svg.contains("aria-label=\"github\""));
+    /// # }
+    /// ```
+    pub fn logo_only(&mut self, logo: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.logo = Some(logo.into());
+        self.label = None;
+        self.message = None;
         self
     }
 
     /// Sets the logo color.
     ///
     /// # Arguments
-    /// * `color` - Logo color string.
+    /// * `color` - Logo color string or [`BadgeColor`].
     ///
     /// # Returns
     /// Mutable reference to self for chaining.
-    pub fn logo_color(&mut self, color: &'a str) -> &mut Self {
-        self.logo_color = Some(color);
+    pub fn logo_color(&mut self, color: impl Into<BadgeColor>) -> &mut Self {
+        self.logo_color = color.into();
         self
     }
 
@@ -165,20 +375,409 @@ impl<'a> BadgeBuilder<'a> {
     ///
     /// # Returns
     /// Mutable reference to self for chaining.
-    pub fn link(&mut self, link: &'a str) -> &mut Self {
-        self.link = Some(link);
+    pub fn link(&mut self, link: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.link = Some(link.into());
         self
     }
 
-    /// Sets the extra (secondary) link URL.
+    /// Sets the extra (secondary) link URL, covering the message while the main link covers
+    /// the label.
     ///
     /// # Arguments
     /// * `link` - Extra link URL.
     ///
     /// # Returns
     /// Mutable reference to self for chaining.
-    pub fn extra_link(&mut self, link: &'a str) -> &mut Self {
-        self.extra_link = Some(link);
+    pub fn extra_link(&mut self, link: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.extra_link = Some(link.into());
+        self
+    }
+
+    /// Sets the icon drawn next to the count inside the social message bubble.
+    ///
+    /// Only used by [`BadgeStyle::Social`].
+    ///
+    /// # Arguments
+    /// * `icon` - The bubble icon.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(all(feature = "style-flat", feature = "style-social"))] {
+    /// use shields::{BadgeStyle, SocialIcon};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Social)
+    ///     .label("forks")
+    ///     .message("42")
+    ///     .bubble_icon(SocialIcon::Fork)
+    ///     .build();
+    /// assert!(svg.contains("42"));
+    /// # }
+    /// ```
+    pub fn bubble_icon(&mut self, icon: SocialIcon) -> &mut Self {
+        self.bubble_icon = Some(icon);
+        self
+    }
+
+    /// Sets the logo width in pixels, for wide logos such as wordmarks (default 14).
+    ///
+    /// # Arguments
+    /// * `width` - Logo width at the standard 14px logo height.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::{BadgeStyle};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .label("built with")
+    ///     .message("rust")
+    ///     .logo("rust")
+    ///     .logo_width(20)
+    ///     .build();
+    /// assert!(svg.contains(r#"width="20" height="14""#));
+    /// # }
+    /// ```
+    pub fn logo_width(&mut self, width: u32) -> &mut Self {
+        self.logo_width = Some(width);
+        self
+    }
+
+    /// Sets how the logo is sized (see [`LogoSize`]).
+    ///
+    /// # Arguments
+    /// * `size` - Logo sizing mode.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::{BadgeStyle, LogoSize};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .label("docs")
+    ///     .logo("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 2 1\"/>")
+    ///     .logo_size(LogoSize::Auto)
+    ///     .build();
+    /// assert!(svg.contains(r#"width="28" height="14""#));
+    /// # }
+    /// ```
+    pub fn logo_size(&mut self, size: LogoSize) -> &mut Self {
+        self.logo_size = Some(size);
+        self
+    }
+
+    /// Sets a series drawn as a sparkline after the message.
+    ///
+    /// # Arguments
+    /// * `values` - The values to plot, oldest first.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::{BadgeStyle};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .label("downloads")
+    ///     .message("30d")
+    ///     .sparkline(&[3.0, 5.0, 4.0, 9.0])
+    ///     .build();
+    /// assert!(svg.contains("<polyline"));
+    /// # }
+    /// ```
+    pub fn sparkline(&mut self, values: &'a [f64]) -> &mut Self {
+        self.sparkline = Some(values);
+        self
+    }
+
+    /// Sets the tooltip shown when hovering the badge.
+    ///
+    /// # Arguments
+    /// * `title` - The tooltip text, which also becomes the badge's accessible name.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::{BadgeStyle};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .label("coverage")
+    ///     .message("94%")
+    ///     .title("Line coverage of the main branch")
+    ///     .build();
+    /// assert!(svg.contains(">Line coverage of the main branch</title>"));
+    /// # }
+    /// ```
+    pub fn title(&mut self, title: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the font family of the text, sizing the badge with that family's widths.
+    ///
+    /// # Arguments
+    /// * `font` - The font family (see [`FontFamily`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::{BadgeStyle, FontFamily};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .label("build")
+    ///     .message("passing")
+    ///     .font(FontFamily::DejaVuSans)
+    ///     .build();
+    /// assert!(svg.contains(r#"font-family="DejaVu Sans,"#));
+    /// # }
+    /// ```
+    pub fn font(&mut self, font: FontFamily) -> &mut Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the output height in pixels, scaling the badge uniformly
+    /// (see [`RenderOptions::height`]).
+    ///
+    /// # Arguments
+    /// * `height` - Output height in pixels.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::{BadgeStyle};
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .label("build")
+    ///     .message("passing")
+    ///     .height(28)
+    ///     .build();
+    /// assert!(svg.contains(r#"height="28" viewBox="0 0 "#));
+    /// # }
+    /// ```
+    pub fn height(&mut self, height: u32) -> &mut Self {
+        self.options.height = Some(height);
+        self
+    }
+
+    /// Sets the output scale factor (see [`RenderOptions::scale`]).
+    ///
+    /// # Arguments
+    /// * `scale` - Factor applied to the badge's width and height.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn scale(&mut self, scale: f32) -> &mut Self {
+        self.options.scale = scale;
+        self
+    }
+
+    /// Sets the horizontal padding (see [`RenderOptions::horizontal_padding`]).
+    ///
+    /// # Arguments
+    /// * `padding` - Padding in pixels.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn horizontal_padding(&mut self, padding: u32) -> &mut Self {
+        self.options.horizontal_padding = padding;
+        self
+    }
+
+    /// Sets the colors used when the viewer prefers a dark color scheme
+    /// (see [`RenderOptions::dark`]).
+    ///
+    /// # Arguments
+    /// * `dark` - Dark mode background colors.
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn dark(&mut self, dark: DarkColors) -> &mut Self {
+        self.options.dark = Some(dark);
+        self
+    }
+
+    /// Sets whether `shields-*` classes are added for styling with external CSS
+    /// (see [`RenderOptions::class_hooks`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn class_hooks(&mut self, class_hooks: bool) -> &mut Self {
+        self.options.class_hooks = class_hooks;
+        self
+    }
+
+    /// Sets whether fills are left out for external CSS to provide
+    /// (see [`RenderOptions::unstyled`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn unstyled(&mut self, unstyled: bool) -> &mut Self {
+        self.options.unstyled = unstyled;
+        self
+    }
+
+    /// Sets the suffix appended to element ids, for badges inlined together in one HTML
+    /// document (see [`RenderOptions::id_suffix`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn id_suffix(&mut self, id_suffix: IdSuffix) -> &mut Self {
+        self.options.id_suffix = id_suffix;
+        self
+    }
+
+    /// Sets whether monospace badges show a `$ ` prompt before the message
+    /// (see [`RenderOptions::prompt`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn prompt(&mut self, prompt: bool) -> &mut Self {
+        self.options.prompt = prompt;
+        self
+    }
+
+    /// Sets how text with a contrast ratio below WCAG AA is made readable
+    /// (see [`RenderOptions::contrast`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn contrast(&mut self, contrast: ContrastFix) -> &mut Self {
+        self.options.contrast = Some(contrast);
+        self
+    }
+
+    /// Sets the text and shadow colors and the brightness threshold between them
+    /// (see [`RenderOptions::text_colors`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn text_colors(&mut self, text_colors: TextColors) -> &mut Self {
+        self.options.text_colors = text_colors;
+        self
+    }
+
+    /// Sets the gloss gradient of plastic badges (see [`RenderOptions::plastic_gradient`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn plastic_gradient(&mut self, plastic_gradient: Vec<GradientStop>) -> &mut Self {
+        self.options.plastic_gradient = plastic_gradient;
+        self
+    }
+
+    /// Sets which links are emitted (see [`RenderOptions::link_policy`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn link_policy(&mut self, link_policy: LinkPolicy) -> &mut Self {
+        self.options.link_policy = link_policy;
+        self
+    }
+
+    /// Sets the exact badge width in pixels (see [`RenderOptions::fixed_width`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn fixed_width(&mut self, width: u32) -> &mut Self {
+        self.options.fixed_width = Some(width);
+        self
+    }
+
+    /// Sets when the label and message swap sides (see [`RenderOptions::mirror`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn mirror(&mut self, mirror: Mirror) -> &mut Self {
+        self.options.mirror = mirror;
+        self
+    }
+
+    /// Sets whether the text keeps its case in every style (see
+    /// [`RenderOptions::preserve_case`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn preserve_case(&mut self, preserve_case: bool) -> &mut Self {
+        self.options.preserve_case = preserve_case;
+        self
+    }
+
+    /// Sets the font embedded into the badge (see [`RenderOptions::embed_font`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn embed_font(&mut self, font: EmbeddedFont) -> &mut Self {
+        self.options.embed_font = Some(font);
+        self
+    }
+
+    /// Sets the color of a border around the whole badge (see
+    /// [`RenderOptions::border_color`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::BadgeStyle;
+    /// use shields::builder::Badge;
+    ///
+    /// let svg = Badge::style(BadgeStyle::Flat)
+    ///     .message("passing")
+    ///     .message_color("white")
+    ///     .border_color("#d0d7de")
+    ///     .build();
+    /// assert!(svg.contains(r##"stroke="#d0d7de" stroke-width="1""##));
+    /// # }
+    /// ```
+    pub fn border_color(&mut self, color: impl Into<String>) -> &mut Self {
+        self.options.border_color = Some(color.into());
+        self
+    }
+
+    /// Sets the border width in pixels (see [`RenderOptions::border_width`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn border_width(&mut self, width: f32) -> &mut Self {
+        self.options.border_width = width;
+        self
+    }
+
+    /// Sets whether the SVG is indented for reading (see [`RenderOptions::pretty`]).
+    ///
+    /// # Returns
+    /// Mutable reference to self for chaining.
+    pub fn pretty(&mut self, pretty: bool) -> &mut Self {
+        self.options.pretty = pretty;
         self
     }
 
@@ -189,6 +788,7 @@ impl<'a> BadgeBuilder<'a> {
     ///
     /// ## Example
     /// ```
+    /// # #[cfg(feature = "style-flat")] {
     /// use shields::{BadgeStyle};
     /// use shields::builder::Badge;
     ///
@@ -197,27 +797,163 @@ impl<'a> BadgeBuilder<'a> {
     ///     .message("passing")
     ///     .build();
     /// assert!(svg.contains("passing"));
+    /// # }
     /// ```
     pub fn build(&self) -> String {
+        self.with_params(|params| render_badge_svg_with_options(params, &self.options))
+    }
+
+    /// Builds the badge as an actix-web response, served as `image/svg+xml` with
+    /// `Cache-Control` and `ETag` headers (see [`BadgeResponse`](crate::actix::BadgeResponse)).
+    #[cfg(feature = "actix")]
+    pub fn into_response(&self) -> crate::actix::BadgeResponse {
+        let etag = self.with_params(crate::http_cache::etag_for);
+        crate::actix::BadgeResponse::new(self.build()).etag(etag)
+    }
+
+    /// Builds the SVG badge string, reporting invalid colors, links and logos instead of
+    /// falling back silently like [`build`](BadgeBuilder::build) does.
+    ///
+    /// # Returns
+    /// SVG string representing the badge, or the [`BadgeError`] naming the first invalid
+    /// field.
+    ///
+    /// ## Example
+    /// ```
+    /// use shields::{BadgeError, BadgeStyle};
+    /// use shields::builder::Badge;
+    ///
+    /// let result = Badge::style(BadgeStyle::Flat)
+    ///     .label("build")
+    ///     .message("passing")
+    ///     .message_color("greeen")
+    ///     .try_build();
+    /// assert!(matches!(
+    ///     result,
+    ///     Err(BadgeError::InvalidColor { param: "message_color", .. })
+    /// ));
+    /// ```
+    pub fn try_build(&self) -> Result<String, BadgeError> {
+        let ctx = RenderContext {
+            options: Cow::Borrowed(&self.options),
+            ..Default::default()
+        };
+        self.with_params(|params| try_render_badge_svg_with(params, &ctx, &RenderLimits::default()))
+    }
+
+    /// Captures this builder's style, colors, logo, links and render options as a
+    /// [`BadgePreset`] for rendering many badges that differ only in their text.
+    ///
+    /// ## Example
+    /// ```
+    /// # #[cfg(all(feature = "style-flat", feature = "style-flat-square"))] {
+    /// use shields::{BadgeStyle};
+    /// use shields::builder::Badge;
+    ///
+    /// let preset = Badge::style(BadgeStyle::FlatSquare)
+    ///     .label_color("#0f62fe")
+    ///     .logo("rust")
+    ///     .preset();
+    /// let svg = preset.render("tests", "412 passed");
+    /// assert!(svg.contains(">412 passed</text>"));
+    /// # }
+    /// ```
+    pub fn preset(&self) -> BadgePreset<'a> {
+        BadgePreset { base: self.clone() }
+    }
+
+    /// Calls `f` with the parameters this builder renders.
+    fn with_params<R>(&self, f: impl FnOnce(&BadgeParams) -> R) -> R {
+        let label_color = self.label_color.as_str();
+        let message_color = self.message_color.as_str();
+        let logo_color = self.logo_color.as_str();
         let (label_color, message_color) = if self.style == BadgeStyle::Social {
             (None, Some(""))
         } else {
             (
-                Some(self.label_color.unwrap_or(default_label_color())),
-                Some(self.message_color.unwrap_or(default_message_color())),
+                Some(label_color.as_deref().unwrap_or(default_label_color())),
+                Some(message_color.as_deref().unwrap_or(default_message_color())),
             )
         };
 
-        render_badge_svg(&BadgeParams {
+        let params = BadgeParams {
             style: self.style,
-            label: self.label,
-            message: self.message,
+            label: self.label.as_deref(),
+            message: self.message.as_deref(),
             label_color,
             message_color,
-            logo: self.logo,
-            logo_color: self.logo_color,
-            link: self.link,
-            extra_link: self.extra_link,
+            logo: self.logo.as_deref(),
+            logo_color: logo_color.as_deref(),
+            link: self.link.as_deref(),
+            extra_link: self.extra_link.as_deref(),
+            bubble_icon: self.bubble_icon,
+            logo_width: self.logo_width,
+            logo_size: self.logo_size,
+            sparkline: self.sparkline,
+            title: self.title.as_deref(),
+            font: self.font,
+        };
+        f(&params)
+    }
+}
+
+/// Shared style, colors, logo, links and render options applied to every badge it renders.
+///
+/// Created with [`BadgeBuilder::preset`]; the label and message given to
+/// [`render`](BadgePreset::render) replace the builder's own.
+///
+/// ## Example
+///
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeStyle, NamedColor};
+/// use shields::builder::Badge;
+///
+/// let preset = Badge::style(BadgeStyle::Flat)
+///     .message_color(NamedColor::BrightGreen)
+///     .link("https://ci.example.com")
+///     .preset();
+/// for (job, status) in [("lint", "passing"), ("test", "passing")] {
+///     let svg = preset.render(job, status);
+///     assert!(svg.contains(r#"href="https://ci.example.com""#));
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct BadgePreset<'a> {
+    base: BadgeBuilder<'a>,
+}
+
+impl BadgePreset<'_> {
+    /// Renders a badge with this preset's settings and the given label and message.
+    ///
+    /// # Returns
+    /// SVG string representing the badge.
+    pub fn render(&self, label: &str, message: &str) -> String {
+        self.base.with_params(|params| {
+            let params = BadgeParams {
+                label: Some(label),
+                message: Some(message),
+                ..params.clone()
+            };
+            render_badge_svg_with_options(&params, &self.base.options)
+        })
+    }
+
+    /// Like [`render`](BadgePreset::render), reporting invalid settings as
+    /// [`BadgeBuilder::try_build`] does.
+    pub fn try_render(&self, label: &str, message: &str) -> Result<String, BadgeError> {
+        let ctx = RenderContext {
+            options: Cow::Borrowed(&self.base.options),
+            ..Default::default()
+        };
+        self.base.with_params(|params| {
+            let params = BadgeParams {
+                label: Some(label),
+                message: Some(message),
+                ..params.clone()
+            };
+            try_render_badge_svg_with(&params, &ctx, &RenderLimits::default())
         })
     }
 }
@@ -229,6 +965,7 @@ impl<'a> BadgeBuilder<'a> {
 ///
 /// ## Example
 /// ```rust
+/// # #[cfg(feature = "style-flat")] {
 /// use shields::{BadgeStyle};
 /// use shields::builder::Badge;
 ///
@@ -237,6 +974,7 @@ impl<'a> BadgeBuilder<'a> {
 ///     .message("passing")
 ///     .build();
 /// assert!(svg.contains("passing"));
+/// # }
 /// ```
 pub struct Badge;
 
@@ -264,7 +1002,9 @@ impl Badge {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::NamedColor;
 
+    #[cfg(feature = "style-flat")]
     #[test]
     fn test_optimized_flat_badge() {
         let mut builder = Badge::style(BadgeStyle::Flat);
@@ -279,6 +1019,7 @@ mod tests {
         assert!(badge.contains("test2"));
     }
 
+    #[cfg(feature = "style-plastic")]
     #[test]
     fn test_optimized_chaining() {
         let badge = Badge::style(BadgeStyle::Plastic)
@@ -291,6 +1032,7 @@ mod tests {
         assert!(badge.contains("1.0.0"));
     }
 
+    #[cfg(feature = "style-flat")]
     #[test]
     fn test_alternative_chaining() {
         let badge = {
@@ -301,6 +1043,7 @@ mod tests {
         assert!(badge.contains("hello"));
         assert!(badge.contains("world"));
     }
+    #[cfg(feature = "style-flat")]
     #[test]
     fn test_configuring_step_by_step() {
         let mut badge = Badge::style(BadgeStyle::Flat);
@@ -312,4 +1055,137 @@ mod tests {
         assert!(resp.contains("no chaining"));
         assert!(resp.contains("test"));
     }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_status() {
+        let badge = Badge::style(BadgeStyle::Flat)
+            .label("build")
+            .status(Status::Failing)
+            .build();
+        assert!(badge.contains(">failing</text>"));
+        assert!(badge.contains(r##"fill="#e05d44""##), "{}", badge);
+
+        // A later message color overrides the status color
+        let badge = Badge::style(BadgeStyle::Flat)
+            .status(Status::Skipped)
+            .message_color("blue")
+            .build();
+        assert!(badge.contains(r##"fill="#007ec6""##), "{}", badge);
+        assert_eq!(
+            <(&str, &str)>::from(Status::Passing),
+            ("passing", "brightgreen")
+        );
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_try_build() {
+        let mut builder = Badge::style(BadgeStyle::Flat);
+        builder.label("docs").message("latest").logo("rust");
+        assert_eq!(builder.try_build().unwrap(), builder.build());
+
+        builder.link("https://example.com/a b");
+        assert_eq!(
+            builder.try_build(),
+            Err(BadgeError::InvalidLink {
+                param: "link",
+                value: "https://example.com/a b".to_string()
+            })
+        );
+        builder.link("https://example.com").logo("no-such-logo");
+        assert_eq!(
+            builder.try_build(),
+            Err(BadgeError::InvalidLogo("no-such-logo".to_string()))
+        );
+        builder.logo("rust").logo_color(BadgeColor::Hex(0xffffff));
+        assert!(builder.try_build().is_ok());
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_owned_values() {
+        let config = (String::from("docs"), String::from("https://docs.rs"));
+        let badge = {
+            let mut b = Badge::style(BadgeStyle::Flat);
+            b.label(config.0.clone())
+                .message(format!("v{}", 2))
+                .link(&config.1)
+                .extra_link(Cow::Borrowed("https://example.com"));
+            b.build()
+        };
+        assert!(badge.contains(">docs</text>"));
+        assert!(badge.contains(">v2</text>"));
+        assert!(badge.contains(r#"href="https://docs.rs""#));
+    }
+
+    #[test]
+    fn test_params_round_trip() {
+        let mut builder = Badge::style(BadgeStyle::Plastic);
+        builder
+            .label("coverage")
+            .message("93%")
+            .message_color(NamedColor::Green)
+            .logo("rust")
+            .link("https://example.com");
+        let params = builder.to_params();
+        assert_eq!(params.message_color.as_deref(), Some("#97ca00"));
+        let rebuilt = BadgeBuilder::from_params(&params.as_params()).build();
+        assert_eq!(rebuilt, builder.build());
+        assert_eq!(
+            BadgeBuilder::from_params(&params.as_params()).to_params(),
+            params
+        );
+
+        let overridden = BadgeBuilder::from_params(&params.as_params())
+            .message("71%")
+            .to_params();
+        assert_eq!(overridden.message.as_deref(), Some("71%"));
+        assert_eq!(overridden.logo.as_deref(), Some("rust"));
+    }
+
+    #[cfg(all(feature = "style-flat", feature = "style-plastic"))]
+    #[test]
+    fn test_preset() {
+        let mut builder = Badge::style(BadgeStyle::Plastic);
+        builder
+            .label("ignored")
+            .message_color("orange")
+            .logo("rust")
+            .height(40);
+        let preset = builder.preset();
+        let expected = builder.label("lint").message("3 warnings").build();
+        assert_eq!(preset.render("lint", "3 warnings"), expected);
+        assert_eq!(preset.try_render("lint", "3 warnings").unwrap(), expected);
+
+        let invalid = Badge::style(BadgeStyle::Flat).logo("no-such-logo").preset();
+        assert!(invalid.try_render("a", "b").is_err());
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_typed_colors() {
+        let typed = Badge::style(BadgeStyle::Flat)
+            .label("build")
+            .message("passing")
+            .label_color(BadgeColor::Hex(0x333333))
+            .message_color(NamedColor::Green)
+            .logo_color(BadgeColor::Default)
+            .build();
+        let strings = Badge::style(BadgeStyle::Flat)
+            .label("build")
+            .message("passing")
+            .label_color("#333333")
+            .message_color("#97ca00")
+            .build();
+        assert_eq!(typed, strings);
+
+        let owned = Badge::style(BadgeStyle::Flat)
+            .message_color(format!("#{}", "97ca00"))
+            .label_color(BadgeColor::Default)
+            .build();
+        assert!(owned.contains(r##"fill="#97ca00""##));
+        assert_eq!(BadgeColor::Hex(0x4c1).as_str().as_deref(), Some("#0004c1"));
+        assert_eq!(BadgeColor::Default.as_str(), None);
+    }
 }
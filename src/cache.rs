@@ -0,0 +1,133 @@
+//! Sharded LRU caches shared by every rendering thread.
+//!
+//! Each cache splits its entries across [`SHARDS`] independently locked LRU maps chosen by
+//! key hash, so threads rendering different badges rarely wait on one another.
+
+use lru::LruCache;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Number of independently locked shards per cache.
+const SHARDS: usize = 16;
+
+/// A bounded LRU cache split into shards by key hash.
+///
+/// Eviction is per shard, so the cache may hold slightly fewer entries than its capacity
+/// when keys hash unevenly. A capacity of zero disables caching.
+pub(crate) struct ShardedLru<K, V> {
+    shards: Box<[Mutex<LruCache<K, V>>]>,
+    hasher: RandomState,
+    enabled: AtomicBool,
+}
+
+impl<K: Hash + Eq, V> ShardedLru<K, V> {
+    /// Creates a cache holding about `capacity` entries.
+    pub(crate) fn new(capacity: usize) -> Self {
+        let per_shard = shard_capacity(capacity);
+        ShardedLru {
+            shards: (0..SHARDS)
+                .map(|_| Mutex::new(LruCache::new(per_shard)))
+                .collect(),
+            hasher: RandomState::new(),
+            enabled: AtomicBool::new(capacity > 0),
+        }
+    }
+
+    fn shard<Q: Hash + ?Sized>(&self, key: &Q) -> &Mutex<LruCache<K, V>> {
+        &self.shards[self.hasher.hash_one(key) as usize % SHARDS]
+    }
+
+    /// Calls `f` with the cached value for `key`, marking it recently used.
+    pub(crate) fn with<Q, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        self.shard(key).lock().unwrap().get(key).map(f)
+    }
+
+    /// Returns a copy of the cached value for `key`.
+    pub(crate) fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.with(key, V::clone)
+    }
+
+    /// Caches `value` under `key`, evicting the shard's least recently used entry when full.
+    pub(crate) fn put(&self, key: K, value: V) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        self.shard(&key).lock().unwrap().put(key, value);
+    }
+
+    /// Removes every entry.
+    pub(crate) fn clear(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().unwrap().clear();
+        }
+    }
+
+    /// Changes the capacity, evicting least recently used entries to fit.
+    pub(crate) fn set_capacity(&self, capacity: usize) {
+        let per_shard = shard_capacity(capacity);
+        self.enabled.store(capacity > 0, Ordering::Relaxed);
+        for shard in self.shards.iter() {
+            let mut shard = shard.lock().unwrap();
+            shard.resize(per_shard);
+            if capacity == 0 {
+                shard.clear();
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.lock().unwrap().len()).sum()
+    }
+}
+
+fn shard_capacity(capacity: usize) -> NonZeroUsize {
+    NonZeroUsize::new(capacity.div_ceil(SHARDS).max(1)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sharded_lru() {
+        let cache = ShardedLru::new(64);
+        for i in 0..1000 {
+            cache.put(i.to_string(), i);
+        }
+        assert!(cache.len() <= 64);
+        assert_eq!(cache.get("999"), Some(999));
+        assert_eq!(cache.with("999", |v| v * 2), Some(1998));
+
+        cache.set_capacity(16);
+        assert!(cache.len() <= 16);
+        assert_eq!(cache.get("999"), Some(999));
+
+        cache.set_capacity(0);
+        cache.put("1".to_string(), 1);
+        assert_eq!(cache.get("1"), None);
+        assert_eq!(cache.len(), 0);
+
+        cache.set_capacity(64);
+        cache.put("1".to_string(), 1);
+        assert_eq!(cache.get("1"), Some(1));
+        cache.clear();
+        assert_eq!(cache.get("1"), None);
+    }
+}
@@ -0,0 +1,253 @@
+//! Badges derived from a crate's `Cargo.toml`: version, license, minimum supported Rust
+//! version and edition.
+//!
+//! [`cargo_badges`] reads the manifest so `cargo xtask`-style scripts can regenerate README
+//! badges from the same source of truth as the published crate. Fields inherited from the
+//! workspace (`version.workspace = true`) are resolved from the workspace root's
+//! `[workspace.package]` table.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use shields::cargo::cargo_badges;
+//! use shields::output::write_badges;
+//!
+//! let badges = cargo_badges(env!("CARGO_MANIFEST_DIR")).unwrap();
+//! assert_eq!(badges[0].name, "version");
+//! let dir = std::env::temp_dir().join("shields-doc-cargo");
+//! write_badges(&dir, &badges).unwrap();
+//! assert!(dir.join("version.svg").exists());
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use crate::output::NamedBadge;
+use crate::{BadgeParamsOwned, presets};
+use std::fs;
+use std::io;
+use std::path::Path;
+use toml::{Table, Value};
+
+/// Edition Cargo assumes when a manifest does not set one.
+const DEFAULT_EDITION: &str = "2015";
+
+/// Reads the package manifest at `path` (a `Cargo.toml` or the directory containing it).
+///
+/// # Returns
+/// Badges named `version` (e.g. `version | v1.4.2`, colored like [`presets::version`]),
+/// `license` (`license | MIT OR Apache-2.0`), `msrv` (`msrv | 1.70`, from `rust-version`)
+/// and `edition` (`edition | 2021`), in that order. Badges for fields the manifest does not
+/// set are left out, except the edition, which defaults to 2015 as in Cargo.
+///
+/// # Errors
+/// Returns [`io::ErrorKind::InvalidData`] when the manifest is not valid TOML, has no
+/// `[package]` table (as in a virtual workspace manifest) or inherits a field the workspace
+/// does not define, and any error raised while reading files.
+pub fn cargo_badges(path: impl AsRef<Path>) -> io::Result<Vec<NamedBadge>> {
+    let mut path = path.as_ref().to_path_buf();
+    if path.is_dir() {
+        path.push("Cargo.toml");
+    }
+    let manifest = read_manifest(&path)?;
+    let package = manifest
+        .get("package")
+        .and_then(Value::as_table)
+        .ok_or_else(|| invalid_data(format!("{} has no [package] table", path.display())))?;
+    let mut workspace = None;
+    let mut field = |key: &str| -> io::Result<Option<String>> {
+        let Some(value) = package.get(key) else {
+            return Ok(None);
+        };
+        if let Some(value) = value.as_str() {
+            return Ok(Some(value.to_string()));
+        }
+        let inherited = value
+            .get("workspace")
+            .and_then(Value::as_bool)
+            .unwrap_or_default();
+        if !inherited {
+            return Err(invalid_data(format!("`package.{}` is not a string", key)));
+        }
+        if workspace.is_none() {
+            workspace = Some(workspace_package(&path)?);
+        }
+        let workspace = workspace.as_ref().expect("workspace package was just read");
+        workspace
+            .get(key)
+            .and_then(Value::as_str)
+            .map(|value| Some(value.to_string()))
+            .ok_or_else(|| invalid_data(format!("workspace does not define `package.{}`", key)))
+    };
+
+    let mut badges = Vec::new();
+    if let Some(version) = field("version")? {
+        badges.push(NamedBadge::new(
+            "version",
+            presets::version("version", &version),
+        ));
+    }
+    if let Some(license) = field("license")? {
+        badges.push(NamedBadge::new("license", badge("license", &license)));
+    }
+    if let Some(rust_version) = field("rust-version")? {
+        badges.push(NamedBadge::new("msrv", badge("msrv", &rust_version)));
+    }
+    let edition = field("edition")?.unwrap_or_else(|| DEFAULT_EDITION.to_string());
+    badges.push(NamedBadge::new("edition", badge("edition", &edition)));
+    Ok(badges)
+}
+
+fn badge(label: &str, message: &str) -> BadgeParamsOwned {
+    BadgeParamsOwned {
+        label: Some(label.to_string()),
+        message: Some(message.to_string()),
+        message_color: Some("blue".to_string()),
+        ..Default::default()
+    }
+}
+
+fn read_manifest(path: &Path) -> io::Result<Table> {
+    let contents = fs::read_to_string(path)?;
+    contents
+        .parse::<Table>()
+        .map_err(|error| invalid_data(format!("{}: {}", path.display(), error)))
+}
+
+/// The `[workspace.package]` table of the nearest manifest above `member` declaring a
+/// `[workspace]`, the member's own manifest included.
+fn workspace_package(member: &Path) -> io::Result<Table> {
+    for dir in member.ancestors().skip(1) {
+        let path = dir.join("Cargo.toml");
+        if path.is_file() {
+            let manifest = read_manifest(&path)?;
+            if let Some(workspace) = manifest.get("workspace") {
+                return Ok(workspace
+                    .get("package")
+                    .and_then(Value::as_table)
+                    .cloned()
+                    .unwrap_or_default());
+            }
+        }
+    }
+    Err(invalid_data(format!(
+        "no workspace found above {}",
+        member.display()
+    )))
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("shields-cargo-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn messages(badges: &[NamedBadge]) -> Vec<(&str, &str)> {
+        badges
+            .iter()
+            .map(|badge| {
+                (
+                    badge.name.as_str(),
+                    badge.params.message.as_deref().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cargo_badges() {
+        let dir = temp_dir("package");
+        fs::write(
+            dir.join("Cargo.toml"),
+            r#"
+            [package]
+            name = "demo"
+            version = "0.3.1"
+            license = "MIT OR Apache-2.0"
+            rust-version = "1.70"
+            edition = "2021"
+            "#,
+        )
+        .unwrap();
+        let badges = cargo_badges(&dir).unwrap();
+        assert_eq!(
+            messages(&badges),
+            [
+                ("version", "v0.3.1"),
+                ("license", "MIT OR Apache-2.0"),
+                ("msrv", "1.70"),
+                ("edition", "2021"),
+            ]
+        );
+        assert_eq!(badges[0].params.message_color.as_deref(), Some("orange"));
+
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"demo\"\n").unwrap();
+        let badges = cargo_badges(dir.join("Cargo.toml")).unwrap();
+        assert_eq!(messages(&badges), [("edition", "2015")]);
+
+        fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = []\n").unwrap();
+        let err = cargo_badges(&dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::write(dir.join("Cargo.toml"), "[package\n").unwrap();
+        let err = cargo_badges(&dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_workspace_inheritance() {
+        let dir = temp_dir("workspace");
+        fs::write(
+            dir.join("Cargo.toml"),
+            r#"
+            [workspace]
+            members = ["crates/demo"]
+
+            [workspace.package]
+            version = "2.0.0"
+            edition = "2024"
+            "#,
+        )
+        .unwrap();
+        let member = dir.join("crates/demo");
+        fs::create_dir_all(&member).unwrap();
+        fs::write(
+            member.join("Cargo.toml"),
+            r#"
+            [package]
+            name = "demo"
+            version.workspace = true
+            edition = { workspace = true }
+            license = "MIT"
+            "#,
+        )
+        .unwrap();
+        let badges = cargo_badges(&member).unwrap();
+        assert_eq!(
+            messages(&badges),
+            [
+                ("version", "v2.0.0"),
+                ("license", "MIT"),
+                ("edition", "2024")
+            ]
+        );
+
+        fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nrust-version.workspace = true\n",
+        )
+        .unwrap();
+        let err = cargo_badges(&member).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! Deriving shades from badge colors.
+//!
+//! Each helper accepts any color a badge accepts (a named color such as `brightgreen`, an
+//! alias, hex or a CSS color) and resolves it exactly like badge rendering does, so hover or
+//! secondary shades derived here stay consistent with the badges. Results are hex strings
+//! (`#rrggbb`, or `#rrggbbaa` when translucent) usable anywhere a color is accepted; `None`
+//! is returned for colors the crate does not recognize.
+//!
+//! Applications can add their own color names with [`register`], e.g. a `brand` color shared
+//! by all their badges; registered names are accepted everywhere a color is.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "unstable")] {
+//! use shields::color;
+//!
+//! assert_eq!(color::darken("brightgreen", 0.1).as_deref(), Some("#349d0d"));
+//! assert_eq!(color::with_alpha("blue", 0.5).as_deref(), Some("#007ec680"));
+//! # }
+//! ```
+
+use crate::color_util::{self, to_svg_color};
+use csscolorparser::Color;
+use std::str::FromStr;
+
+/// Resolves a badge color to its rendered value.
+fn parse(color: &str) -> Option<Color> {
+    Color::from_str(&to_svg_color(color)?).ok()
+}
+
+/// Shifts the HSL lightness of a color by `amount`, clamped to `0.0..=1.0`.
+fn adjust_lightness(color: &str, amount: f32) -> Option<String> {
+    let [h, s, l, a] = parse(color)?.to_hsla();
+    let h = if h.is_nan() { 0.0 } else { h };
+    Some(Color::from_hsla(h, s, (l + amount).clamp(0.0, 1.0), a).to_css_hex())
+}
+
+/// Makes a color lighter by raising its HSL lightness by `amount` (`0.0` to `1.0`).
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::color;
+/// assert_eq!(color::lighten("#555", 0.2).as_deref(), Some("#888888"));
+/// # }
+/// ```
+pub fn lighten(color: &str, amount: f32) -> Option<String> {
+    adjust_lightness(color, amount.clamp(0.0, 1.0))
+}
+
+/// Makes a color darker by lowering its HSL lightness by `amount` (`0.0` to `1.0`).
+pub fn darken(color: &str, amount: f32) -> Option<String> {
+    adjust_lightness(color, -amount.clamp(0.0, 1.0))
+}
+
+/// Blends two colors in RGB; `ratio` is the share of `other`, from `0.0` (only `color`) to
+/// `1.0` (only `other`).
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::color;
+/// assert_eq!(color::mix("black", "white", 0.5).as_deref(), Some("#808080"));
+/// # }
+/// ```
+pub fn mix(color: &str, other: &str, ratio: f32) -> Option<String> {
+    let color = parse(color)?;
+    let other = parse(other)?;
+    Some(
+        color
+            .interpolate_rgb(&other, ratio.clamp(0.0, 1.0))
+            .to_css_hex(),
+    )
+}
+
+/// Returns a color with its opacity set to `alpha`, from `0.0` (transparent) to `1.0`
+/// (opaque).
+pub fn with_alpha(color: &str, alpha: f32) -> Option<String> {
+    let mut color = parse(color)?;
+    color.a = alpha.clamp(0.0, 1.0);
+    Some(color.to_css_hex())
+}
+
+/// Registers `name` as a color for every badge and helper, resolving to `color` (a named
+/// color, alias, hex or CSS color). Names are case-insensitive and registering a name again
+/// replaces its color.
+///
+/// Returns `false` and registers nothing when `color` is not recognized or `name` is empty or
+/// one of the built-in names and aliases, which cannot be redefined.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(all(feature = "unstable", feature = "style-flat"))] {
+/// use shields::{BadgeParams, color, normalize_color, render_badge_svg};
+///
+/// assert!(color::register("brand", "#0f62fe"));
+/// assert_eq!(normalize_color("Brand").as_deref(), Some("brand"));
+/// let svg = render_badge_svg(&BadgeParams {
+///     message: Some("docs"),
+///     message_color: Some("brand"),
+///     ..Default::default()
+/// });
+/// assert!(svg.contains("#0f62fe"));
+/// assert!(!color::register("red", "#f00"));
+/// color::unregister("brand");
+/// # }
+/// ```
+pub fn register(name: &str, color: &str) -> bool {
+    color_util::register_color(name, color)
+}
+
+/// Removes a name added with [`register`], returning whether it was registered.
+pub fn unregister(name: &str) -> bool {
+    color_util::unregister_color(name)
+}
+
+/// Removes every name added with [`register`].
+pub fn clear_registered() {
+    color_util::clear_colors()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lighten_and_darken() {
+        assert_eq!(lighten("black", 0.5).as_deref(), Some("#808080"));
+        assert_eq!(lighten("white", 0.5).as_deref(), Some("#ffffff"));
+        assert_eq!(darken("#fff", 1.0).as_deref(), Some("#000000"));
+        // Named colors and aliases resolve to their badge hex values
+        assert_eq!(darken("success", 0.1), darken("#4c1", 0.1));
+        assert_eq!(lighten("red", 0.0).as_deref(), Some("#e05d44"));
+        assert_eq!(lighten("not-a-color", 0.1), None);
+    }
+
+    #[test]
+    fn test_mix_and_alpha() {
+        assert_eq!(mix("red", "blue", 0.0).as_deref(), Some("#e05d44"));
+        assert_eq!(mix("red", "blue", 1.0).as_deref(), Some("#007ec6"));
+        assert_eq!(mix("#000", "#fff", 2.0).as_deref(), Some("#ffffff"));
+        assert_eq!(mix("#000", "nope", 0.5), None);
+        assert_eq!(with_alpha("grey", 0.0).as_deref(), Some("#55555500"));
+        assert_eq!(with_alpha("rgb(1, 2, 3)", 1.0).as_deref(), Some("#010203"));
+    }
+
+    #[test]
+    fn test_register() {
+        assert!(register("test-accent", "critical"));
+        assert_eq!(to_svg_color("Test-Accent").as_deref(), Some("#e05d44"));
+        assert_eq!(darken("test-accent", 0.0).as_deref(), Some("#e05d44"));
+        assert!(register("test-accent", "rgb(0, 0, 255)"));
+        assert_eq!(
+            to_svg_color("test-accent").as_deref(),
+            Some("rgb(0, 0, 255)")
+        );
+        assert!(!register("test-accent", "not-a-color"));
+        assert!(!register("success", "#000"));
+        assert!(!register(" ", "#000"));
+
+        assert!(unregister("test-accent"));
+        assert!(!unregister("test-accent"));
+        assert_eq!(to_svg_color("test-accent"), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Picking badge colors from numeric values.
+//!
+//! [`coverage`] colors a coverage percentage the way shields.io coverage badges do, and
+//! [`ColorScale`] maps values to colors with user-defined breakpoints, so CI tooling colors
+//! badges consistently.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "unstable")] {
+//! use shields::color_scale::{self, ColorScale};
+//!
+//! assert_eq!(color_scale::coverage(93.0), "green");
+//! let scale: ColorScale = "0:red,60:yellow,80:brightgreen".parse().unwrap();
+//! assert_eq!(scale.color_for(72.0), Some("yellow"));
+//! # }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Color of a coverage badge for `percent`, as on shields.io: `red` below 70, `orange` below
+/// 80, `yellow` below 90, `green` below 100 and `brightgreen` for 100.
+pub fn coverage(percent: f32) -> &'static str {
+    match percent {
+        p if p < 70.0 || p.is_nan() => "red",
+        p if p < 80.0 => "orange",
+        p if p < 90.0 => "yellow",
+        p if p < 100.0 => "green",
+        _ => "brightgreen",
+    }
+}
+
+/// Color of a download count badge, as on shields.io: `red` for none, then `yellow` below 10,
+/// `yellowgreen` below 100, `green` below 1000 and `brightgreen` from there.
+pub fn downloads(count: u64) -> &'static str {
+    match count {
+        0 => "red",
+        1..10 => "yellow",
+        10..100 => "yellowgreen",
+        100..1000 => "green",
+        _ => "brightgreen",
+    }
+}
+
+/// Colors for ranges of values, each starting at a threshold.
+///
+/// A value takes the color of the highest threshold not above it; values below the lowest
+/// threshold take the lowest threshold's color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorScale {
+    /// Steps sorted by ascending threshold.
+    steps: Vec<(f64, String)>,
+}
+
+impl ColorScale {
+    /// Creates a scale from `(threshold, color)` steps in any order.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # #[cfg(feature = "unstable")] {
+    /// use shields::color_scale::ColorScale;
+    /// let scale = ColorScale::new([(0.0, "red"), (1.0, "brightgreen")]);
+    /// assert_eq!(scale.color_for(0.5), Some("red"));
+    /// # }
+    /// ```
+    pub fn new<S: Into<String>>(steps: impl IntoIterator<Item = (f64, S)>) -> Self {
+        let mut steps: Vec<(f64, String)> = steps
+            .into_iter()
+            .map(|(threshold, color)| (threshold, color.into()))
+            .collect();
+        steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ColorScale { steps }
+    }
+
+    /// Returns the color for `value`, or `None` if the scale has no steps.
+    pub fn color_for(&self, value: f64) -> Option<&str> {
+        self.steps
+            .iter()
+            .rev()
+            .find(|(threshold, _)| value >= *threshold)
+            .or(self.steps.first())
+            .map(|(_, color)| color.as_str())
+    }
+}
+
+/// Error returned when parsing a [`ColorScale`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorScaleError(String);
+
+impl fmt::Display for ParseColorScaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseColorScaleError {}
+
+impl FromStr for ColorScale {
+    type Err = ParseColorScaleError;
+
+    /// Parses comma-separated `threshold:color` steps, e.g. `0:red,60:yellow,80:brightgreen`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let steps = s
+            .split(',')
+            .map(|step| {
+                let (threshold, color) = step.split_once(':').ok_or_else(|| {
+                    ParseColorScaleError(format!("expected `threshold:color`, got `{}`", step))
+                })?;
+                let threshold = threshold.trim().parse::<f64>().map_err(|_| {
+                    ParseColorScaleError(format!("invalid threshold `{}`", threshold))
+                })?;
+                Ok((threshold, color.trim().to_string()))
+            })
+            .collect::<Result<Vec<_>, ParseColorScaleError>>()?;
+        Ok(ColorScale::new(steps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage() {
+        assert_eq!(coverage(0.0), "red");
+        assert_eq!(coverage(f32::NAN), "red");
+        assert_eq!(coverage(69.9), "red");
+        assert_eq!(coverage(70.0), "orange");
+        assert_eq!(coverage(79.9), "orange");
+        assert_eq!(coverage(80.0), "yellow");
+        assert_eq!(coverage(89.9), "yellow");
+        assert_eq!(coverage(90.0), "green");
+        assert_eq!(coverage(99.9), "green");
+        assert_eq!(coverage(100.0), "brightgreen");
+        assert_eq!(downloads(0), "red");
+        assert_eq!(downloads(9), "yellow");
+        assert_eq!(downloads(10), "yellowgreen");
+        assert_eq!(downloads(999), "green");
+        assert_eq!(downloads(1000), "brightgreen");
+    }
+
+    #[test]
+    fn test_color_scale() {
+        let scale: ColorScale = "80:brightgreen, 0:red ,60:yellow".parse().unwrap();
+        assert_eq!(scale.color_for(92.5), Some("brightgreen"));
+        assert_eq!(scale.color_for(60.0), Some("yellow"));
+        assert_eq!(scale.color_for(-1.0), Some("red"));
+        assert_eq!(ColorScale::new::<String>([]).color_for(1.0), None);
+        assert_eq!(
+            "80-green".parse::<ColorScale>().unwrap_err().to_string(),
+            "expected `threshold:color`, got `80-green`"
+        );
+        assert!("high:green".parse::<ColorScale>().is_err());
+    }
+}
@@ -0,0 +1,174 @@
+//! Parsing of shields.io badge URLs into badge parameters.
+
+use crate::{BadgeParamsOwned, BadgeStyle, LogoSize};
+
+impl BadgeParamsOwned {
+    /// Parses a shields.io static badge path such as `build-passing-brightgreen`.
+    ///
+    /// The path is `<label>-<message>-<color>` or `<message>-<color>`, optionally followed by
+    /// `.svg`, with shields.io's escaping: `--` is a dash, `__` an underscore and `_` a space.
+    /// It is expected to be percent-decoded already, as web frameworks do for path segments.
+    ///
+    /// # Returns
+    /// The parameters, or `None` if the path does not have two or three parts.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use shields::BadgeParamsOwned;
+    /// let params = BadgeParamsOwned::from_badge_path("code_coverage-v1--rc-brightgreen").unwrap();
+    /// assert_eq!(params.label.as_deref(), Some("code coverage"));
+    /// assert_eq!(params.message.as_deref(), Some("v1-rc"));
+    /// assert_eq!(params.message_color.as_deref(), Some("brightgreen"));
+    /// ```
+    pub fn from_badge_path(path: &str) -> Option<Self> {
+        let path = path.strip_suffix(".svg").unwrap_or(path);
+        let mut parts = split_parts(path);
+        let (label, message, color) = match parts.len() {
+            2 => {
+                let color = parts.pop()?;
+                (None, parts.pop()?, color)
+            }
+            3 => {
+                let color = parts.pop()?;
+                let message = parts.pop()?;
+                (parts.pop(), message, color)
+            }
+            _ => return None,
+        };
+        Some(BadgeParamsOwned {
+            label,
+            message: Some(message),
+            message_color: Some(color),
+            ..Default::default()
+        })
+    }
+
+    /// Parses a shields.io query string such as
+    /// `label=build&message=passing&style=for-the-badge&logo=rust&logoColor=white`.
+    ///
+    /// Recognized keys are `style`, `label`, `message`, `color`, `labelColor`, `logo`,
+    /// `logoColor`, `logoWidth`, `logoSize` (`auto`), `title`, `font` (e.g. `dejavu-sans`) and up
+    /// to two `link`s (the second becomes [`extra_link`](BadgeParamsOwned::extra_link)), plus the legacy `colorA` and `colorB`
+    /// aliases of `labelColor` and `color`. Like shields.io, unknown keys are ignored and an
+    /// unknown style falls back to flat. A leading `?` is allowed.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use shields::{BadgeParamsOwned, BadgeStyle};
+    /// let params = BadgeParamsOwned::from_query_str(
+    ///     "label=build&message=passing&style=for-the-badge&logo=rust&logoColor=white",
+    /// );
+    /// assert_eq!(params.style, BadgeStyle::ForTheBadge);
+    /// assert_eq!(params.logo_color.as_deref(), Some("white"));
+    /// ```
+    pub fn from_query_str(query: &str) -> Self {
+        let mut params = BadgeParamsOwned::default();
+        let query = query.strip_prefix('?').unwrap_or(query);
+        params.apply_query(form_urlencoded::parse(query.as_bytes()));
+        params
+    }
+
+    /// Overrides fields with decoded shields.io query parameters, see [`Self::from_query_str`].
+    pub(crate) fn apply_query<K, V>(&mut self, pairs: impl IntoIterator<Item = (K, V)>)
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let mut links = 0;
+        for (key, value) in pairs {
+            let value = value.into();
+            match key.as_ref() {
+                "style" => self.style = parse_style(&value).unwrap_or_default(),
+                "label" => self.label = Some(value),
+                "message" => self.message = Some(value),
+                "color" | "colorB" => self.message_color = Some(value),
+                "labelColor" | "colorA" => self.label_color = Some(value),
+                "logo" => self.logo = Some(value),
+                "logoColor" => self.logo_color = Some(value),
+                "logoWidth" => self.logo_width = value.trim().parse().ok(),
+                "logoSize" => self.logo_size = (value == "auto").then_some(LogoSize::Auto),
+                "title" => self.title = Some(value),
+                "font" => self.font = value.parse().ok(),
+                "link" => {
+                    match links {
+                        0 => self.link = Some(value),
+                        1 => self.extra_link = Some(value),
+                        _ => {}
+                    }
+                    links += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn parse_style(s: &str) -> Option<BadgeStyle> {
+    s.parse().ok()
+}
+
+/// Splits a badge path on single dashes, undoing the shields.io escapes.
+fn split_parts(path: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        let part = parts.last_mut().expect("parts is never empty");
+        match c {
+            '-' | '_' if chars.peek() == Some(&c) => {
+                chars.next();
+                part.push(c);
+            }
+            '-' => parts.push(String::new()),
+            '_' => part.push(' '),
+            c => part.push(c),
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_badge_path() {
+        let params = BadgeParamsOwned::from_badge_path("build-passing-brightgreen").unwrap();
+        assert_eq!(params.label.as_deref(), Some("build"));
+        assert_eq!(params.message.as_deref(), Some("passing"));
+        assert_eq!(params.message_color.as_deref(), Some("brightgreen"));
+
+        let params = BadgeParamsOwned::from_badge_path("v1--rc_build__7-blue.svg").unwrap();
+        assert_eq!(params.label, None);
+        assert_eq!(params.message.as_deref(), Some("v1-rc build_7"));
+        assert_eq!(params.message_color.as_deref(), Some("blue"));
+
+        assert!(BadgeParamsOwned::from_badge_path("nodash").is_none());
+        assert!(BadgeParamsOwned::from_badge_path("a-b-c-d").is_none());
+    }
+
+    #[test]
+    fn test_from_query_str() {
+        let params = BadgeParamsOwned::from_query_str(
+            "?label=build%20status&message=passing+now&style=for-the-badge&colorA=black\
+             &color=%23e05d44&logo=rust&logoColor=white&logoWidth=20&logoSize=auto\
+             &link=https://a.example\
+             &link=https://b.example&link=https://c.example&unknown=1",
+        );
+        assert_eq!(params.style, BadgeStyle::ForTheBadge);
+        assert_eq!(params.label.as_deref(), Some("build status"));
+        assert_eq!(params.message.as_deref(), Some("passing now"));
+        assert_eq!(params.label_color.as_deref(), Some("black"));
+        assert_eq!(params.message_color.as_deref(), Some("#e05d44"));
+        assert_eq!(params.logo.as_deref(), Some("rust"));
+        assert_eq!(params.logo_color.as_deref(), Some("white"));
+        assert_eq!(params.logo_width, Some(20));
+        assert_eq!(params.logo_size, Some(LogoSize::Auto));
+        assert_eq!(params.link.as_deref(), Some("https://a.example"));
+        assert_eq!(params.extra_link.as_deref(), Some("https://b.example"));
+
+        assert_eq!(
+            BadgeParamsOwned::from_query_str("style=bogus"),
+            BadgeParamsOwned::default()
+        );
+    }
+}
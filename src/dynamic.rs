@@ -0,0 +1,958 @@
+//! shields.io dynamic badges: a value read from a document the badge service fetches.
+//!
+//! See <https://shields.io/badges/dynamic-json-badge>. A dynamic badge URL carries the
+//! document's `url`, a `query` selecting values in it, an optional `prefix` and `suffix`,
+//! and the usual badge parameters. [`DynamicBadge::from_query_str`] parses such a query
+//! string and [`DynamicBadge::render`] builds the badge from the fetched document; failures
+//! render as the error badges shields.io shows, through [`error_badge`].
+//!
+//! JSON documents are queried with a subset of JSONPath: `$` followed by `.name`, `['name']`,
+//! `[index]` (negative indexes count from the end), `[start:end]`, wildcards (`.*`, `[*]`),
+//! unions (`['a','b']`, `[0,2]`) and recursive descent (`..name`). Filter expressions are not
+//! supported. YAML and TOML documents (with the `dynamic-formats` feature) take the same
+//! queries, or a plain key path such as `package.version`. XML documents are queried with a
+//! subset of XPath, see [`xpath`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "unstable")] {
+//! use shields::dynamic::{DynamicBadge, DynamicFormat};
+//!
+//! let badge = DynamicBadge::from_query_str(
+//!     "url=https://example.com/package.json&query=$.version&label=version&prefix=v",
+//! )
+//! .unwrap();
+//! assert_eq!(badge.url, "https://example.com/package.json");
+//! let params = badge
+//!     .render(DynamicFormat::Json, r#"{"name": "app", "version": "1.2.0"}"#)
+//!     .unwrap();
+//! assert_eq!(params.label.as_deref(), Some("version"));
+//! assert_eq!(params.message.as_deref(), Some("v1.2.0"));
+//! # }
+//! ```
+
+use crate::BadgeParamsOwned;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use xmltree::{Element, XMLNode};
+
+/// Label of dynamic badges without a `label` parameter.
+pub const DEFAULT_LABEL: &str = "custom badge";
+
+/// A dynamic badge request, parsed from the badge URL's query string.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DynamicBadge {
+    /// URL of the document to read.
+    pub url: String,
+    /// Query selecting the values shown in the message.
+    pub query: String,
+    /// Text put before the values.
+    pub prefix: String,
+    /// Text put after the values.
+    pub suffix: String,
+    /// Badge parameters from the rest of the query string (label, colors, style, logo...).
+    pub params: BadgeParamsOwned,
+}
+
+impl DynamicBadge {
+    /// Parses a dynamic badge query string such as
+    /// `url=https://example.com/package.json&query=$.version&label=version`. A leading `?` is
+    /// allowed.
+    ///
+    /// # Errors
+    /// Returns [`DynamicError::MissingParameter`] if `url` or `query` is missing or empty.
+    pub fn from_query_str(query: &str) -> Result<Self, DynamicError> {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        let mut badge = DynamicBadge {
+            params: BadgeParamsOwned {
+                label: Some(DEFAULT_LABEL.to_string()),
+                message_color: Some("blue".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut rest = Vec::new();
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            match key.as_ref() {
+                "url" => badge.url = value.into_owned(),
+                "query" => badge.query = value.into_owned(),
+                "prefix" => badge.prefix = value.into_owned(),
+                "suffix" => badge.suffix = value.into_owned(),
+                _ => rest.push((key, value)),
+            }
+        }
+        if badge.url.is_empty() {
+            return Err(DynamicError::MissingParameter("url"));
+        }
+        if badge.query.is_empty() {
+            return Err(DynamicError::MissingParameter("query"));
+        }
+        badge.params.apply_query(rest);
+        Ok(badge)
+    }
+
+    /// Builds the badge from a fetched document, showing the values [`query`](Self::query)
+    /// selects joined by `, ` between the prefix and suffix.
+    ///
+    /// # Errors
+    /// Returns [`DynamicError::Unparseable`] if the document is not in the given format or
+    /// the query is malformed, [`DynamicError::NoResult`] if the query selects nothing and
+    /// [`DynamicError::InvalidValue`] if it selects an object or array.
+    pub fn render(
+        &self,
+        format: DynamicFormat,
+        document: &str,
+    ) -> Result<BadgeParamsOwned, DynamicError> {
+        let unparseable = DynamicError::Unparseable(format.response());
+        let document: Value = match format {
+            DynamicFormat::Json => serde_json::from_str(document).map_err(|_| unparseable)?,
+            DynamicFormat::Xml => {
+                let root = Element::parse(document.as_bytes()).map_err(|_| unparseable)?;
+                return self.render_values(&xpath(&root, &self.query)?);
+            }
+            #[cfg(feature = "dynamic-formats")]
+            DynamicFormat::Yaml => serde_yaml::from_str(document).map_err(|_| unparseable)?,
+            #[cfg(feature = "dynamic-formats")]
+            DynamicFormat::Toml => toml::from_str(document).map_err(|_| unparseable)?,
+        };
+        let query = match format {
+            DynamicFormat::Json => Cow::Borrowed(self.query.as_str()),
+            _ => key_path(&self.query),
+        };
+        let values = json_path(&document, &query)?
+            .into_iter()
+            .map(|value| match value {
+                Value::String(s) => Ok(s.clone()),
+                Value::Number(_) | Value::Bool(_) | Value::Null => Ok(value.to_string()),
+                Value::Array(_) | Value::Object(_) => Err(DynamicError::InvalidValue),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.render_values(&values)
+    }
+
+    /// Builds the badge from a fetched JSON document (see [`render`](Self::render)).
+    ///
+    /// # Errors
+    /// See [`render`](Self::render).
+    pub fn render_json(&self, document: &str) -> Result<BadgeParamsOwned, DynamicError> {
+        self.render(DynamicFormat::Json, document)
+    }
+
+    /// Builds the badge from values selected in the fetched document.
+    fn render_values(&self, values: &[String]) -> Result<BadgeParamsOwned, DynamicError> {
+        if values.is_empty() {
+            return Err(DynamicError::NoResult);
+        }
+        Ok(BadgeParamsOwned {
+            message: Some(format!(
+                "{}{}{}",
+                self.prefix,
+                values.join(", "),
+                self.suffix
+            )),
+            ..self.params.clone()
+        })
+    }
+}
+
+/// Format of the document behind a dynamic badge, named as in shields.io's
+/// `/badge/dynamic/<format>` routes.
+///
+/// The variants depend on the `dynamic-formats` feature and more may be added, so matches
+/// need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DynamicFormat {
+    /// JSON, queried with JSONPath.
+    Json,
+    /// XML, queried with XPath.
+    Xml,
+    /// YAML, queried with JSONPath or a key path.
+    #[cfg(feature = "dynamic-formats")]
+    Yaml,
+    /// TOML, queried with JSONPath or a key path.
+    #[cfg(feature = "dynamic-formats")]
+    Toml,
+}
+
+impl DynamicFormat {
+    /// Every supported format.
+    pub const ALL: &[DynamicFormat] = &[
+        DynamicFormat::Json,
+        DynamicFormat::Xml,
+        #[cfg(feature = "dynamic-formats")]
+        DynamicFormat::Yaml,
+        #[cfg(feature = "dynamic-formats")]
+        DynamicFormat::Toml,
+    ];
+
+    /// Returns the format's name, e.g. `json`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DynamicFormat::Json => "json",
+            DynamicFormat::Xml => "xml",
+            #[cfg(feature = "dynamic-formats")]
+            DynamicFormat::Yaml => "yaml",
+            #[cfg(feature = "dynamic-formats")]
+            DynamicFormat::Toml => "toml",
+        }
+    }
+
+    /// What [`DynamicError::Unparseable`] names when a document is not in this format.
+    fn response(&self) -> &'static str {
+        match self {
+            DynamicFormat::Json => "json response",
+            DynamicFormat::Xml => "xml response",
+            #[cfg(feature = "dynamic-formats")]
+            DynamicFormat::Yaml => "yaml response",
+            #[cfg(feature = "dynamic-formats")]
+            DynamicFormat::Toml => "toml response",
+        }
+    }
+}
+
+impl fmt::Display for DynamicFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DynamicFormat {
+    type Err = DynamicError;
+
+    /// Parses a format name; fails with [`DynamicError::UnknownFormat`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DynamicFormat::ALL
+            .iter()
+            .copied()
+            .find(|format| format.as_str() == s)
+            .ok_or(DynamicError::UnknownFormat)
+    }
+}
+
+/// Why a dynamic badge could not show its value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicError {
+    /// A required query parameter is missing.
+    MissingParameter(&'static str),
+    /// The document could not be fetched.
+    Inaccessible,
+    /// The document does not exist.
+    NotFound,
+    /// The document or the query could not be parsed; names what, e.g. `"json response"`.
+    Unparseable(&'static str),
+    /// The query selected nothing.
+    NoResult,
+    /// The query selected a value that cannot be shown, such as an object.
+    InvalidValue,
+    /// The document format is not supported by this build.
+    UnknownFormat,
+}
+
+impl DynamicError {
+    /// Message color of the error badge: red for mistakes in the badge URL, light grey for
+    /// problems with the document.
+    fn color(&self) -> &'static str {
+        match self {
+            DynamicError::MissingParameter(_)
+            | DynamicError::NotFound
+            | DynamicError::UnknownFormat => "red",
+            _ => "lightgrey",
+        }
+    }
+}
+
+impl fmt::Display for DynamicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicError::MissingParameter(name) => write!(f, "invalid query parameter: {}", name),
+            DynamicError::Inaccessible => write!(f, "inaccessible"),
+            DynamicError::NotFound => write!(f, "resource not found"),
+            DynamicError::Unparseable(what) => write!(f, "unparseable {}", what),
+            DynamicError::NoResult => write!(f, "no result"),
+            DynamicError::InvalidValue => write!(f, "invalid response data"),
+            DynamicError::UnknownFormat => write!(f, "unknown format"),
+        }
+    }
+}
+
+impl std::error::Error for DynamicError {}
+
+/// Builds the error badge shields.io shows for a failed dynamic badge.
+///
+/// The label, style and logo of the badge URL's `query` string still apply, but its colors
+/// are ignored so errors always look like errors.
+pub fn error_badge(query: &str, error: &DynamicError) -> BadgeParamsOwned {
+    let query = query.strip_prefix('?').unwrap_or(query);
+    let mut params = BadgeParamsOwned {
+        label: Some(DEFAULT_LABEL.to_string()),
+        ..Default::default()
+    };
+    params.apply_query(form_urlencoded::parse(query.as_bytes()).filter(|(key, _)| {
+        !matches!(
+            key.as_ref(),
+            "color" | "colorB" | "labelColor" | "colorA" | "message"
+        )
+    }));
+    params.message = Some(error.to_string());
+    params.message_color = Some(error.color().to_string());
+    params
+}
+
+/// Turns a key path such as `package.version` into the equivalent JSONPath query; JSONPath
+/// queries are kept.
+fn key_path(query: &str) -> Cow<'_, str> {
+    if query.starts_with('$') {
+        Cow::Borrowed(query)
+    } else {
+        Cow::Owned(format!("$.{}", query))
+    }
+}
+
+/// One step of a JSONPath query.
+#[derive(Debug, PartialEq)]
+struct Segment {
+    /// Whether the step applies to every descendant (`..`) rather than only to children.
+    descendants: bool,
+    selectors: Vec<Selector>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+}
+
+/// Evaluates a JSONPath query, returning the selected values. Array elements come in order and
+/// object members sorted by key.
+///
+/// # Errors
+/// Returns [`DynamicError::Unparseable`] if the query is malformed or uses unsupported syntax.
+pub fn json_path<'a>(document: &'a Value, query: &str) -> Result<Vec<&'a Value>, DynamicError> {
+    let mut nodes = vec![document];
+    for segment in parse_json_path(query).ok_or(DynamicError::Unparseable("jsonpath query"))? {
+        let mut next = Vec::new();
+        for node in nodes {
+            let mut targets = vec![node];
+            if segment.descendants {
+                collect_descendants(node, &mut targets);
+            }
+            for target in targets {
+                for selector in &segment.selectors {
+                    select(target, selector, &mut next);
+                }
+            }
+        }
+        nodes = next;
+    }
+    Ok(nodes)
+}
+
+fn collect_descendants<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    let children: Box<dyn Iterator<Item = &Value>> = match value {
+        Value::Array(items) => Box::new(items.iter()),
+        Value::Object(map) => Box::new(map.values()),
+        _ => return,
+    };
+    for child in children {
+        out.push(child);
+        collect_descendants(child, out);
+    }
+}
+
+fn select<'a>(value: &'a Value, selector: &Selector, out: &mut Vec<&'a Value>) {
+    match (selector, value) {
+        (Selector::Name(name), Value::Object(map)) => out.extend(map.get(name)),
+        (Selector::Wildcard, Value::Object(map)) => out.extend(map.values()),
+        (Selector::Wildcard, Value::Array(items)) => out.extend(items),
+        (Selector::Index(index), Value::Array(items)) => {
+            let len = items.len() as i64;
+            let index = if *index < 0 { len + index } else { *index };
+            if (0..len).contains(&index) {
+                out.push(&items[index as usize]);
+            }
+        }
+        (Selector::Slice(start, end), Value::Array(items)) => {
+            let len = items.len() as i64;
+            let bound = |i: i64| (if i < 0 { len + i } else { i }).clamp(0, len) as usize;
+            let start = start.map_or(0, bound);
+            let end = end.map_or(items.len(), bound);
+            if start < end {
+                out.extend(&items[start..end]);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Splits a JSONPath query into segments, or `None` if it is malformed.
+fn parse_json_path(query: &str) -> Option<Vec<Segment>> {
+    let mut rest = query.trim().strip_prefix('$')?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        let descendants = rest.starts_with("..");
+        if descendants {
+            rest = &rest[2..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            rest = after;
+        } else if !rest.starts_with('[') {
+            return None;
+        }
+        let selectors = if let Some(after) = rest.strip_prefix('[') {
+            let (inner, after) = split_bracket(after)?;
+            rest = after;
+            split_union(inner)?
+                .into_iter()
+                .map(parse_selector)
+                .collect::<Option<Vec<_>>>()?
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let name = &rest[..end];
+            rest = &rest[end..];
+            match name {
+                "" => return None,
+                "*" => vec![Selector::Wildcard],
+                name => vec![Selector::Name(name.to_string())],
+            }
+        };
+        segments.push(Segment {
+            descendants,
+            selectors,
+        });
+    }
+    Some(segments)
+}
+
+/// Splits `inner]rest` at the `]` closing a bracket, skipping brackets inside quotes.
+fn split_bracket(s: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, ']') => return Some((&s[..i], &s[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits a bracket's content on the commas outside quotes.
+fn split_union(s: &str) -> Option<Vec<&str>> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, ',') => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if quote.is_some() {
+        return None;
+    }
+    parts.push(&s[start..]);
+    Some(parts)
+}
+
+fn parse_selector(s: &str) -> Option<Selector> {
+    let s = s.trim();
+    if s == "*" {
+        return Some(Selector::Wildcard);
+    }
+    for quote in ['\'', '"'] {
+        if let Some(name) = s.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)) {
+            return Some(Selector::Name(name.to_string()));
+        }
+    }
+    if let Some((start, end)) = s.split_once(':') {
+        let bound = |b: &str| match b.trim() {
+            "" => Some(None),
+            b => b.parse().ok().map(Some),
+        };
+        return Some(Selector::Slice(bound(start)?, bound(end)?));
+    }
+    s.parse().ok().map(Selector::Index)
+}
+
+/// One step of an XPath query.
+#[derive(Debug, PartialEq)]
+struct XPathStep {
+    /// Whether the step applies to every descendant (`//`) rather than only to children.
+    descendants: bool,
+    test: NodeTest,
+    predicates: Vec<XPathPredicate>,
+}
+
+#[derive(Debug, PartialEq)]
+enum NodeTest {
+    /// Elements with this local name, or any element for `*`.
+    Element(Option<String>),
+    /// The named attribute, or every attribute for `@*`.
+    Attribute(Option<String>),
+    /// `text()`.
+    Text,
+}
+
+#[derive(Debug, PartialEq)]
+enum XPathPredicate {
+    /// `[n]`, counted from 1.
+    Position(usize),
+    /// `[last()]`.
+    Last,
+    /// `[@name]`, or `[@name='value']` when a value is given.
+    Attribute(String, Option<String>),
+    /// `[name='value']`: a child element with this text.
+    Child(String, String),
+}
+
+/// An XPath node: an element, or the text of an attribute or text node.
+enum XPathNode<'a> {
+    /// The document, whose only child is the root element.
+    Document(&'a Element),
+    Element(&'a Element),
+    Text(Cow<'a, str>),
+}
+
+/// Evaluates an XPath query, returning the string values of the selected nodes, trimmed, in
+/// document order.
+///
+/// Queries are absolute location paths: steps separated by `/` (children) or `//`
+/// (descendants), each a local element name, `*`, `@attribute`, `@*` or `text()`, optionally
+/// followed by predicates: a position such as `[1]`, `[last()]`, `[@attribute]`,
+/// `[@attribute='value']` or `[child='value']`. Namespace prefixes are ignored. For
+/// example, `/project/version` reads a Maven POM's version and `//dependency[1]/@version`
+/// the version attribute of the first `dependency` of each parent.
+///
+/// # Errors
+/// Returns [`DynamicError::Unparseable`] if the query is malformed or uses unsupported syntax.
+pub fn xpath(root: &Element, query: &str) -> Result<Vec<String>, DynamicError> {
+    let steps = parse_xpath(query).ok_or(DynamicError::Unparseable("xpath query"))?;
+    let mut nodes = vec![XPathNode::Document(root)];
+    for step in steps {
+        let mut next = Vec::new();
+        for node in nodes {
+            let mut contexts = vec![node];
+            if step.descendants {
+                let mut descendants = Vec::new();
+                for context in &contexts {
+                    collect_elements(context, &mut descendants);
+                }
+                contexts.extend(descendants.into_iter().map(XPathNode::Element));
+            }
+            for context in &contexts {
+                let mut selected = select_xml(context, &step.test);
+                for predicate in &step.predicates {
+                    selected = filter_xml(selected, predicate);
+                }
+                next.extend(selected);
+            }
+        }
+        nodes = next;
+    }
+    Ok(nodes
+        .into_iter()
+        .map(|node| match node {
+            XPathNode::Document(element) | XPathNode::Element(element) => {
+                let mut text = String::new();
+                collect_text(element, &mut text);
+                text.trim().to_string()
+            }
+            XPathNode::Text(text) => text.trim().to_string(),
+        })
+        .collect())
+}
+
+/// Child elements of a node.
+fn child_elements<'a>(node: &XPathNode<'a>) -> Vec<&'a Element> {
+    match node {
+        XPathNode::Document(root) => vec![root],
+        XPathNode::Element(element) => element
+            .children
+            .iter()
+            .filter_map(XMLNode::as_element)
+            .collect(),
+        XPathNode::Text(_) => Vec::new(),
+    }
+}
+
+/// Descendant elements of a node, in document order.
+fn collect_elements<'a>(node: &XPathNode<'a>, out: &mut Vec<&'a Element>) {
+    for child in child_elements(node) {
+        out.push(child);
+        collect_elements(&XPathNode::Element(child), out);
+    }
+}
+
+/// Concatenated text of an element and its descendants.
+fn collect_text(element: &Element, out: &mut String) {
+    for child in &element.children {
+        match child {
+            XMLNode::Element(child) => collect_text(child, out),
+            XMLNode::Text(text) | XMLNode::CData(text) => out.push_str(text),
+            _ => {}
+        }
+    }
+}
+
+fn select_xml<'a>(node: &XPathNode<'a>, test: &NodeTest) -> Vec<XPathNode<'a>> {
+    match test {
+        NodeTest::Element(name) => child_elements(node)
+            .into_iter()
+            .filter(|element| name.as_ref().is_none_or(|name| element.name == *name))
+            .map(XPathNode::Element)
+            .collect(),
+        NodeTest::Attribute(name) => match node {
+            XPathNode::Element(element) => element
+                .attributes
+                .iter()
+                .filter(|(key, _)| name.as_ref().is_none_or(|name| *key == name))
+                .map(|(_, value)| XPathNode::Text(Cow::Borrowed(value.as_str())))
+                .collect(),
+            _ => Vec::new(),
+        },
+        NodeTest::Text => match node {
+            XPathNode::Element(element) => element
+                .children
+                .iter()
+                .filter_map(|child| match child {
+                    XMLNode::Text(text) | XMLNode::CData(text) => {
+                        Some(XPathNode::Text(Cow::Borrowed(text.as_str())))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+fn filter_xml<'a>(nodes: Vec<XPathNode<'a>>, predicate: &XPathPredicate) -> Vec<XPathNode<'a>> {
+    let len = nodes.len();
+    nodes
+        .into_iter()
+        .enumerate()
+        .filter(|(i, node)| match predicate {
+            XPathPredicate::Position(position) => i + 1 == *position,
+            XPathPredicate::Last => i + 1 == len,
+            XPathPredicate::Attribute(name, value) => match node {
+                XPathNode::Element(element) => element
+                    .attributes
+                    .get(name)
+                    .is_some_and(|actual| value.as_ref().is_none_or(|value| actual == value)),
+                _ => false,
+            },
+            XPathPredicate::Child(name, value) => match node {
+                XPathNode::Element(element) => element
+                    .children
+                    .iter()
+                    .filter_map(XMLNode::as_element)
+                    .any(|child| {
+                        let mut text = String::new();
+                        collect_text(child, &mut text);
+                        child.name == *name && text == *value
+                    }),
+                _ => false,
+            },
+        })
+        .map(|(_, node)| node)
+        .collect()
+}
+
+/// Splits an XPath query into steps, or `None` if it is malformed.
+fn parse_xpath(query: &str) -> Option<Vec<XPathStep>> {
+    let mut rest = query.trim();
+    let mut steps = Vec::new();
+    while !rest.is_empty() {
+        let descendants = rest.starts_with("//");
+        rest = rest.strip_prefix("//").or_else(|| rest.strip_prefix('/'))?;
+        let end = step_end(rest)?;
+        let (step, after) = rest.split_at(end);
+        rest = after;
+        let (test, mut predicates) = step.split_at(step.find('[').unwrap_or(step.len()));
+        let test = match test {
+            "" => return None,
+            "text()" => NodeTest::Text,
+            "*" => NodeTest::Element(None),
+            "@*" => NodeTest::Attribute(None),
+            test => match test.strip_prefix('@') {
+                Some(name) => NodeTest::Attribute(Some(xml_name(name)?)),
+                None => NodeTest::Element(Some(xml_name(test)?)),
+            },
+        };
+        let mut parsed = Vec::new();
+        while let Some(after) = predicates.strip_prefix('[') {
+            let (inner, after) = split_bracket(after)?;
+            predicates = after;
+            parsed.push(parse_xpath_predicate(inner.trim())?);
+        }
+        if !predicates.is_empty() || (!parsed.is_empty() && !matches!(test, NodeTest::Element(_))) {
+            return None;
+        }
+        steps.push(XPathStep {
+            descendants,
+            test,
+            predicates: parsed,
+        });
+    }
+    (!steps.is_empty()).then_some(steps)
+}
+
+/// Length of the step at the start of `s`: up to the next `/` outside brackets and quotes.
+fn step_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, '/') if depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    (quote.is_none() && depth == 0).then_some(s.len())
+}
+
+/// Local part of an element or attribute name, without any namespace prefix.
+fn xml_name(name: &str) -> Option<String> {
+    let local = name.rsplit(':').next()?;
+    let valid = !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| local.to_string())
+}
+
+fn parse_xpath_predicate(predicate: &str) -> Option<XPathPredicate> {
+    if predicate == "last()" {
+        return Some(XPathPredicate::Last);
+    }
+    if let Ok(position) = predicate.parse() {
+        return (position > 0).then_some(XPathPredicate::Position(position));
+    }
+    let (name, value) = match predicate.split_once('=') {
+        Some((name, value)) => {
+            let value = value.trim();
+            let value = ['\'', '"']
+                .iter()
+                .find_map(|&q| value.strip_prefix(q)?.strip_suffix(q))?;
+            (name.trim(), Some(value.to_string()))
+        }
+        None => (predicate, None),
+    };
+    match name.strip_prefix('@') {
+        Some(attribute) => Some(XPathPredicate::Attribute(xml_name(attribute)?, value)),
+        None => Some(XPathPredicate::Child(xml_name(name)?, value?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BadgeStyle;
+
+    #[test]
+    fn test_json_path() {
+        let document: Value = serde_json::from_str(
+            r#"{"name": "app", "version": "1.2.0", "private": false,
+                "dependencies": {"serde": "1.0", "once-cell": "1.19"},
+                "releases": [{"tag": "v1.0"}, {"tag": "v1.1"}, {"tag": "v1.2"}]}"#,
+        )
+        .unwrap();
+        let query = |query: &str| {
+            json_path(&document, query).map(|values| {
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::String(s) => s.clone(),
+                        value => value.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(query("$.version").unwrap(), ["1.2.0"]);
+        assert_eq!(query("$['version']").unwrap(), ["1.2.0"]);
+        assert_eq!(query("$.private").unwrap(), ["false"]);
+        assert_eq!(query("$.dependencies['once-cell']").unwrap(), ["1.19"]);
+        assert_eq!(query("$.dependencies.*").unwrap(), ["1.19", "1.0"]);
+        assert_eq!(query("$.releases[-1].tag").unwrap(), ["v1.2"]);
+        assert_eq!(query("$.releases[0,2].tag").unwrap(), ["v1.0", "v1.2"]);
+        assert_eq!(query("$.releases[1:].tag").unwrap(), ["v1.1", "v1.2"]);
+        assert_eq!(query("$..tag").unwrap(), ["v1.0", "v1.1", "v1.2"]);
+        assert_eq!(query("$['name','version']").unwrap(), ["app", "1.2.0"]);
+        assert!(query("$.missing").unwrap().is_empty());
+        assert!(query("$.releases[7]").unwrap().is_empty());
+        for malformed in [
+            "version",
+            "$.",
+            "$[",
+            "$['version]",
+            "$[?(@.tag)]",
+            "$.a..",
+            "$x",
+        ] {
+            assert_eq!(
+                query(malformed),
+                Err(DynamicError::Unparseable("jsonpath query")),
+                "{}",
+                malformed
+            );
+        }
+    }
+
+    #[test]
+    fn test_dynamic_badge() {
+        let badge = DynamicBadge::from_query_str(
+            "?url=https%3A%2F%2Fexample.com%2Fpackage.json&query=$.releases[*].tag\
+             &prefix=[&suffix=]&style=flat-square&color=green",
+        )
+        .unwrap();
+        assert_eq!(badge.url, "https://example.com/package.json");
+        assert_eq!(badge.params.style, BadgeStyle::FlatSquare);
+        let document = r#"{"releases": [{"tag": "v1"}, {"tag": "v2"}]}"#;
+        let params = badge.render_json(document).unwrap();
+        assert_eq!(params.label.as_deref(), Some("custom badge"));
+        assert_eq!(params.message.as_deref(), Some("[v1, v2]"));
+        assert_eq!(params.message_color.as_deref(), Some("green"));
+
+        assert_eq!(
+            badge.render_json("<html>"),
+            Err(DynamicError::Unparseable("json response"))
+        );
+        assert_eq!(badge.render_json("{}"), Err(DynamicError::NoResult));
+        let badge = DynamicBadge::from_query_str("url=https://a.example&query=$.releases").unwrap();
+        assert_eq!(badge.render_json(document), Err(DynamicError::InvalidValue));
+        assert_eq!(
+            DynamicBadge::from_query_str("query=$.version"),
+            Err(DynamicError::MissingParameter("url"))
+        );
+    }
+
+    #[test]
+    fn test_xpath() {
+        let root = Element::parse(
+            r#"<?xml version="1.0"?>
+            <project xmlns="http://maven.apache.org/POM/4.0.0">
+              <version>2.1.0</version>
+              <dependencies>
+                <dependency scope="test"><artifactId>junit</artifactId><version>4.13</version></dependency>
+                <dependency><artifactId>guava</artifactId><version>33.0</version></dependency>
+              </dependencies>
+              <note><![CDATA[a <b>]]></note>
+            </project>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        let query = |query: &str| xpath(&root, query);
+        assert_eq!(query("/project/version").unwrap(), ["2.1.0"]);
+        assert_eq!(query("/project/version/text()").unwrap(), ["2.1.0"]);
+        assert_eq!(query("//dependency/version").unwrap(), ["4.13", "33.0"]);
+        assert_eq!(query("//dependency[last()]/artifactId").unwrap(), ["guava"]);
+        assert_eq!(query("//dependency[2]/version").unwrap(), ["33.0"]);
+        assert_eq!(
+            query("//dependency[@scope='test']/version").unwrap(),
+            ["4.13"]
+        );
+        assert_eq!(
+            query("//dependency[artifactId='guava']/version").unwrap(),
+            ["33.0"]
+        );
+        assert_eq!(query("//dependency/@scope").unwrap(), ["test"]);
+        assert_eq!(
+            query("/project/dependencies/*[1]/pom:artifactId").unwrap(),
+            ["junit"]
+        );
+        assert_eq!(query("//note").unwrap(), ["a <b>"]);
+        assert!(query("/version").unwrap().is_empty());
+        for malformed in [
+            "",
+            "project",
+            "/",
+            "/project/",
+            "//a[0]",
+            "//a[",
+            "/a[@b='c]",
+            "/@a[1]",
+        ] {
+            assert_eq!(
+                query(malformed),
+                Err(DynamicError::Unparseable("xpath query")),
+                "{}",
+                malformed
+            );
+        }
+
+        let badge =
+            DynamicBadge::from_query_str("url=https://a.example&query=//dependency/version")
+                .unwrap();
+        let document = "<deps><dependency><version>1</version></dependency></deps>";
+        let params = badge.render(DynamicFormat::Xml, document).unwrap();
+        assert_eq!(params.message.as_deref(), Some("1"));
+        assert_eq!(
+            badge.render(DynamicFormat::Xml, "{}"),
+            Err(DynamicError::Unparseable("xml response"))
+        );
+    }
+
+    #[cfg(feature = "dynamic-formats")]
+    #[test]
+    fn test_key_path_formats() {
+        let badge =
+            DynamicBadge::from_query_str("url=https://a.example&query=package.version").unwrap();
+        let cargo_toml = "[package]\nname = \"shields\"\nversion = \"1.0.0\"\n";
+        let params = badge.render(DynamicFormat::Toml, cargo_toml).unwrap();
+        assert_eq!(params.message.as_deref(), Some("1.0.0"));
+        let yaml = "package:\n  version: 2.3.1\n  tags: [a, b]\n";
+        let params = badge.render(DynamicFormat::Yaml, yaml).unwrap();
+        assert_eq!(params.message.as_deref(), Some("2.3.1"));
+
+        let badge =
+            DynamicBadge::from_query_str("url=https://a.example&query=$.package.tags[*]").unwrap();
+        let params = badge.render(DynamicFormat::Yaml, yaml).unwrap();
+        assert_eq!(params.message.as_deref(), Some("a, b"));
+        assert_eq!(
+            badge.render(DynamicFormat::Toml, "version ="),
+            Err(DynamicError::Unparseable("toml response"))
+        );
+
+        for format in DynamicFormat::ALL {
+            assert_eq!(format.as_str().parse(), Ok(*format));
+        }
+        assert_eq!(
+            "csv".parse::<DynamicFormat>(),
+            Err(DynamicError::UnknownFormat)
+        );
+    }
+
+    #[test]
+    fn test_error_badge() {
+        let params = error_badge(
+            "query=$.version&label=version&color=green&style=for-the-badge",
+            &DynamicError::MissingParameter("url"),
+        );
+        assert_eq!(params.label.as_deref(), Some("version"));
+        assert_eq!(
+            params.message.as_deref(),
+            Some("invalid query parameter: url")
+        );
+        assert_eq!(params.message_color.as_deref(), Some("red"));
+        assert_eq!(params.style, BadgeStyle::ForTheBadge);
+
+        let params = error_badge("", &DynamicError::Inaccessible);
+        assert_eq!(params.label.as_deref(), Some("custom badge"));
+        assert_eq!(params.message.as_deref(), Some("inaccessible"));
+        assert_eq!(params.message_color.as_deref(), Some("lightgrey"));
+    }
+}
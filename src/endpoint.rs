@@ -0,0 +1,175 @@
+//! shields.io endpoint badges: JSON documents describing a badge, served by a project and
+//! rendered by the badge service.
+//!
+//! See <https://shields.io/badges/endpoint-badge> for the schema. [`EndpointBadge::from_json`]
+//! parses a document and [`render_endpoint_badge`] renders it; [`EndpointBadge::with_query`]
+//! applies the query parameters a viewer added to the badge URL, as shields.io does.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(all(feature = "unstable", feature = "style-flat"))] {
+//! use shields::endpoint::{EndpointBadge, render_endpoint_badge};
+//!
+//! let badge = EndpointBadge::from_json(
+//!     r#"{"schemaVersion": 1, "label": "coverage", "message": "94%", "color": "green"}"#,
+//! )
+//! .unwrap();
+//! let svg = render_endpoint_badge(&badge);
+//! assert!(svg.contains("94%"));
+//! # }
+//! ```
+
+use crate::{BadgeParamsOwned, BadgeStyle, LogoSize, render_badge_svg};
+use serde::{Deserialize, Serialize};
+
+/// A badge described by shields.io's endpoint schema (version 1).
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointBadge {
+    /// Schema version; only `1` exists.
+    pub schema_version: u32,
+    /// Left-hand text; may be empty for a message-only badge.
+    pub label: String,
+    /// Right-hand text.
+    pub message: String,
+    /// Message color (default `lightgrey`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Label color (default `grey`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_color: Option<String>,
+    /// Marks the badge as an error, which keeps viewers from overriding its color.
+    #[serde(default)]
+    pub is_error: bool,
+    /// Logo slug, e.g. `rust`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub named_logo: Option<String>,
+    /// Custom logo as an SVG document; takes precedence over `named_logo`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_svg: Option<String>,
+    /// Logo color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_color: Option<String>,
+    /// Logo width in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_width: Option<u32>,
+    /// Logo sizing; `auto` keeps the logo's aspect ratio.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_size: Option<String>,
+    /// Badge style name; unknown styles fall back to flat.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// How long the badge service may cache the document, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_seconds: Option<u32>,
+}
+
+impl EndpointBadge {
+    /// Parses an endpoint document.
+    ///
+    /// # Errors
+    /// Returns an error if the JSON does not match the schema or `schemaVersion` is not `1`.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let badge: EndpointBadge = serde_json::from_str(json)?;
+        if badge.schema_version != 1 {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported schemaVersion {}",
+                badge.schema_version
+            )));
+        }
+        Ok(badge)
+    }
+
+    /// Converts the document into badge parameters.
+    pub fn to_params(&self) -> BadgeParamsOwned {
+        BadgeParamsOwned {
+            style: self
+                .style
+                .as_deref()
+                .and_then(|style| style.parse().ok())
+                .unwrap_or(BadgeStyle::Flat),
+            label: Some(self.label.clone()),
+            message: Some(self.message.clone()),
+            label_color: Some(self.label_color.as_deref().unwrap_or("grey").to_string()),
+            message_color: Some(self.color.as_deref().unwrap_or("lightgrey").to_string()),
+            logo: self.logo_svg.clone().or_else(|| self.named_logo.clone()),
+            logo_color: self.logo_color.clone(),
+            logo_width: self.logo_width,
+            logo_size: (self.logo_size.as_deref() == Some("auto")).then_some(LogoSize::Auto),
+            ..Default::default()
+        }
+    }
+
+    /// Converts the document into badge parameters overridden by a shields.io query string,
+    /// such as `style=for-the-badge&label=tests` (see
+    /// [`BadgeParamsOwned::from_query_str`]).
+    ///
+    /// Like shields.io, `color` and `labelColor` overrides are ignored for error badges.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # #[cfg(feature = "unstable")] {
+    /// use shields::BadgeStyle;
+    /// use shields::endpoint::EndpointBadge;
+    ///
+    /// let badge = EndpointBadge::from_json(
+    ///     r#"{"schemaVersion": 1, "label": "build", "message": "failing", "color": "red",
+    ///         "isError": true}"#,
+    /// )
+    /// .unwrap();
+    /// let params = badge.with_query("style=flat-square&color=green");
+    /// assert_eq!(params.style, BadgeStyle::FlatSquare);
+    /// assert_eq!(params.message_color.as_deref(), Some("red"));
+    /// # }
+    /// ```
+    pub fn with_query(&self, query: &str) -> BadgeParamsOwned {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        let mut params = self.to_params();
+        params.apply_query(form_urlencoded::parse(query.as_bytes()).filter(|(key, _)| {
+            !self.is_error || !matches!(key.as_ref(), "color" | "colorB" | "labelColor" | "colorA")
+        }));
+        params
+    }
+}
+
+/// Renders an endpoint badge as SVG.
+pub fn render_endpoint_badge(badge: &EndpointBadge) -> String {
+    render_badge_svg(&badge.to_params().as_params())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_badge() {
+        let badge = EndpointBadge::from_json(
+            r#"{"schemaVersion": 1, "label": "docs", "message": "passing",
+                "namedLogo": "rust", "logoColor": "white", "style": "for-the-badge",
+                "cacheSeconds": 300}"#,
+        )
+        .unwrap();
+        let params = badge.to_params();
+        assert_eq!(params.style, BadgeStyle::ForTheBadge);
+        assert_eq!(params.label_color.as_deref(), Some("grey"));
+        assert_eq!(params.message_color.as_deref(), Some("lightgrey"));
+        assert_eq!(params.logo.as_deref(), Some("rust"));
+        assert_eq!(badge.cache_seconds, Some(300));
+        assert_eq!(
+            render_endpoint_badge(&badge),
+            render_badge_svg(&params.as_params())
+        );
+
+        let params = badge.with_query("?style=flat&color=blue&logo=github");
+        assert_eq!(params.style, BadgeStyle::Flat);
+        assert_eq!(params.message_color.as_deref(), Some("blue"));
+        assert_eq!(params.logo.as_deref(), Some("github"));
+
+        assert!(
+            EndpointBadge::from_json(r#"{"schemaVersion": 2, "label": "", "message": "x"}"#)
+                .is_err()
+        );
+        assert!(EndpointBadge::from_json(r#"{"schemaVersion": 1, "label": "x"}"#).is_err());
+    }
+}
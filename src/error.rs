@@ -0,0 +1,85 @@
+//! Errors returned by the fallible rendering APIs.
+
+use crate::BadgeStyle;
+use std::fmt;
+
+/// Errors returned when a badge cannot be rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BadgeError {
+    /// A bound set in [`RenderLimits`](crate::RenderLimits) was exceeded and rendering was aborted.
+    Limit(LimitKind),
+    /// A color parameter is neither a known name or alias nor a valid CSS color.
+    InvalidColor {
+        /// Name of the [`BadgeParams`](crate::BadgeParams) field, e.g. `"label_color"`.
+        param: &'static str,
+        /// The rejected value.
+        value: String,
+    },
+    /// The logo is neither a known slug, an inline image nor a single symbol.
+    InvalidLogo(String),
+    /// A link contains whitespace, control characters, quotes or angle brackets, or its scheme
+    /// is not allowed by the [`LinkPolicy`](crate::LinkPolicy).
+    InvalidLink {
+        /// Name of the [`BadgeParams`](crate::BadgeParams) field, e.g. `"link"`.
+        param: &'static str,
+        /// The rejected value.
+        value: String,
+    },
+    /// The SVG template failed to render.
+    Template(String),
+    /// The style's `style-*` feature is disabled in this build.
+    StyleNotEnabled(BadgeStyle),
+    /// The badge could not be rasterized to PNG.
+    Raster(String),
+}
+
+/// Alias for [`BadgeError`].
+pub type ShieldsError = BadgeError;
+
+/// The [`RenderLimits`](crate::RenderLimits) bound that aborted a render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    /// The text parameters contain more characters than allowed.
+    Chars { max: usize, actual: usize },
+    /// The sparkline has more values than allowed.
+    SparklinePoints { max: usize, actual: usize },
+    /// The rendered SVG is larger than allowed.
+    OutputBytes { max: usize, actual: usize },
+    /// The deadline passed before rendering finished.
+    Deadline,
+}
+
+impl fmt::Display for BadgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BadgeError::Limit(LimitKind::Chars { max, actual }) => write!(
+                f,
+                "badge parameters have {} characters (limit {})",
+                actual, max
+            ),
+            BadgeError::Limit(LimitKind::SparklinePoints { max, actual }) => {
+                write!(f, "badge sparkline has {} points (limit {})", actual, max)
+            }
+            BadgeError::Limit(LimitKind::OutputBytes { max, actual }) => {
+                write!(f, "rendered badge is {} bytes (limit {})", actual, max)
+            }
+            BadgeError::Limit(LimitKind::Deadline) => {
+                write!(f, "badge rendering exceeded its deadline")
+            }
+            BadgeError::InvalidColor { param, value } => {
+                write!(f, "invalid {} `{}`", param, value)
+            }
+            BadgeError::InvalidLogo(logo) => write!(f, "unknown logo `{}`", logo),
+            BadgeError::InvalidLink { param, value } => {
+                write!(f, "invalid {} `{}`", param, value)
+            }
+            BadgeError::Template(e) => write!(f, "badge template failed to render: {}", e),
+            BadgeError::StyleNotEnabled(style) => {
+                write!(f, "badge style {:?} is not enabled in this build", style)
+            }
+            BadgeError::Raster(e) => write!(f, "badge rasterization failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BadgeError {}
@@ -0,0 +1,669 @@
+//! Number and date formatting helpers for badge messages.
+//!
+//! [`metric`], [`social_count`], [`ordinal`], [`percentage`] and [`thousands_separated`]
+//! follow shields.io's conventions for turning raw numbers into badge messages, and
+//! [`relative_date`] with [`age_color`] those of its date badges. [`bytes`] and [`duration`]
+//! give sizes and times a compact, locale-independent form. For the decimal
+//! formats, rounding works on the decimal digits a value prints as (`99.95` is treated as exactly
+//! 99.95, not as the nearest binary float), so results match what people expect when they
+//! read the input.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "unstable")] {
+//! use shields::format::{DecimalFormat, PercentFormat, Rounding};
+//!
+//! assert_eq!(PercentFormat::new(2).format(99.95), "99.95%");
+//! assert_eq!(PercentFormat::new(0).format(99.95), "100%");
+//! // Coverage badges often refuse to claim 100% for anything short of it
+//! assert_eq!(PercentFormat::new(0).exact_hundred_only(true).format(99.95), "99%");
+//! assert_eq!(DecimalFormat::new(1).rounding(Rounding::Floor).format(2.79), "2.7");
+//! # }
+//! ```
+
+#[cfg(feature = "unstable")]
+use std::time::{Duration, SystemTime};
+
+/// How digits beyond the requested precision are resolved.
+// Only the default mode is chosen outside of `unstable`, which exports the setter
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Round to nearest; ties go away from zero (`2.5` → `3`, `-2.5` → `-3`).
+    #[default]
+    HalfAwayFromZero,
+    /// Round to nearest; ties go to the even digit (`2.5` → `2`, `3.5` → `4`).
+    HalfEven,
+    /// Round toward negative infinity.
+    Floor,
+    /// Round toward positive infinity.
+    Ceil,
+    /// Drop extra digits.
+    TowardZero,
+}
+
+/// Fixed-precision decimal formatting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalFormat {
+    /// Digits after the decimal point.
+    pub decimals: u32,
+    /// Rounding applied to the remaining digits.
+    pub rounding: Rounding,
+    /// Remove trailing fractional zeros (`1.50` → `1.5`, `2.00` → `2`).
+    pub trim_zeros: bool,
+}
+
+impl DecimalFormat {
+    /// Formats with `decimals` digits, rounding half away from zero.
+    pub fn new(decimals: u32) -> Self {
+        DecimalFormat {
+            decimals,
+            rounding: Rounding::default(),
+            trim_zeros: false,
+        }
+    }
+
+    /// Sets the rounding mode.
+    #[cfg(feature = "unstable")]
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Sets whether trailing fractional zeros are removed.
+    #[cfg(feature = "unstable")]
+    pub fn trim_zeros(mut self, trim_zeros: bool) -> Self {
+        self.trim_zeros = trim_zeros;
+        self
+    }
+
+    /// Formats `value`. Non-finite values are printed as `NaN`, `inf` or `-inf`.
+    pub fn format(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let negative = value.is_sign_negative();
+        let repr = value.abs().to_string();
+        let (int, frac) = repr.split_once('.').unwrap_or((&repr, ""));
+        let decimals = self.decimals as usize;
+
+        let mut digits: Vec<u8> = int.bytes().map(|b| b - b'0').collect();
+        digits.extend(
+            frac.bytes()
+                .chain(std::iter::repeat(b'0'))
+                .take(decimals)
+                .map(|b| b - b'0'),
+        );
+        let rest = frac.as_bytes().get(decimals..).unwrap_or_default();
+        let any_rest = rest.iter().any(|&b| b != b'0');
+        let round_up = match self.rounding {
+            Rounding::HalfAwayFromZero => rest.first().is_some_and(|&b| b >= b'5'),
+            Rounding::HalfEven => match rest.first() {
+                Some(&b) if b > b'5' => true,
+                Some(&b'5') => {
+                    rest[1..].iter().any(|&b| b != b'0')
+                        || digits.last().is_some_and(|d| d % 2 == 1)
+                }
+                _ => false,
+            },
+            Rounding::Floor => negative && any_rest,
+            Rounding::Ceil => !negative && any_rest,
+            Rounding::TowardZero => false,
+        };
+        if round_up {
+            increment(&mut digits);
+        }
+
+        let split = digits.len() - decimals;
+        let mut out = String::with_capacity(digits.len() + 2);
+        if negative && digits.iter().any(|&d| d != 0) {
+            out.push('-');
+        }
+        out.extend(digits[..split].iter().map(|&d| char::from(b'0' + d)));
+        if decimals > 0 {
+            let mut frac: String = digits[split..]
+                .iter()
+                .map(|&d| char::from(b'0' + d))
+                .collect();
+            if self.trim_zeros {
+                frac.truncate(frac.trim_end_matches('0').len());
+            }
+            if !frac.is_empty() {
+                out.push('.');
+                out.push_str(&frac);
+            }
+        }
+        out
+    }
+}
+
+impl Default for DecimalFormat {
+    fn default() -> Self {
+        DecimalFormat::new(0)
+    }
+}
+
+/// Adds one unit in the last place, carrying into a new leading digit if needed.
+fn increment(digits: &mut Vec<u8>) {
+    for d in digits.iter_mut().rev() {
+        if *d == 9 {
+            *d = 0;
+        } else {
+            *d += 1;
+            return;
+        }
+    }
+    digits.insert(0, 1);
+}
+
+/// Percentage formatting (the value is already in percent, `0.0..=100.0`).
+#[cfg(feature = "unstable")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PercentFormat {
+    /// Precision and rounding of the number.
+    pub decimal: DecimalFormat,
+    /// Never print `100%` for a value below 100; show the largest value below it at the
+    /// chosen precision instead (`99.97` → `99.9%` with one decimal).
+    pub exact_hundred_only: bool,
+}
+
+#[cfg(feature = "unstable")]
+impl PercentFormat {
+    /// Formats with `decimals` digits, rounding half away from zero.
+    pub fn new(decimals: u32) -> Self {
+        PercentFormat {
+            decimal: DecimalFormat::new(decimals),
+            exact_hundred_only: false,
+        }
+    }
+
+    /// Sets the rounding mode.
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.decimal.rounding = rounding;
+        self
+    }
+
+    /// Sets whether trailing fractional zeros are removed.
+    pub fn trim_zeros(mut self, trim_zeros: bool) -> Self {
+        self.decimal.trim_zeros = trim_zeros;
+        self
+    }
+
+    /// Sets the "never show 100% unless exact" rule.
+    pub fn exact_hundred_only(mut self, exact_hundred_only: bool) -> Self {
+        self.exact_hundred_only = exact_hundred_only;
+        self
+    }
+
+    /// Formats `value` followed by `%`.
+    pub fn format(&self, value: f64) -> String {
+        let number = self.decimal.format(value);
+        if self.exact_hundred_only && value < 100.0 && number.parse::<f64>() == Ok(100.0) {
+            let below = 100.0 - 10f64.powi(-(self.decimal.decimals as i32));
+            let number = self.decimal.rounding(Rounding::TowardZero).format(below);
+            return format!("{}%", number);
+        }
+        format!("{}%", number)
+    }
+}
+
+/// Metric suffixes used by [`metric`], for powers of 1000 starting at 1000.
+const METRIC_PREFIXES: [&str; 8] = ["k", "M", "G", "T", "P", "E", "Z", "Y"];
+
+/// Abbreviates a number with a metric suffix like shields.io: `1234` → `1.2k`, `-2.5e6` →
+/// `-2.5M`, `999_999` → `1M`.
+///
+/// Values below 10 of a unit keep one decimal unless it is zero; larger ones are rounded to
+/// whole units. Values below 1000 are printed as they are.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::metric;
+/// assert_eq!(metric(12.5), "12.5");
+/// assert_eq!(metric(1234.0), "1.2k");
+/// assert_eq!(metric(-2_000_000.0), "-2M");
+/// # }
+/// ```
+pub fn metric(n: f64) -> String {
+    let sign = if n < 0.0 { "-" } else { "" };
+    let abs = n.abs();
+    for (i, prefix) in METRIC_PREFIXES.iter().enumerate().rev() {
+        let limit = 1000f64.powi(i as i32 + 1);
+        if abs < limit || !abs.is_finite() {
+            continue;
+        }
+        let scaled = abs / limit;
+        if scaled < 10.0 {
+            let one_decimal = DecimalFormat::new(1).format(scaled);
+            if !one_decimal.ends_with('0') {
+                return format!("{}{}{}", sign, one_decimal, prefix);
+            }
+        }
+        let rounded = DecimalFormat::new(0).format(scaled);
+        return match METRIC_PREFIXES.get(i + 1) {
+            Some(next) if rounded.len() > 3 => format!("{}1{}", sign, next),
+            _ => format!("{}{}{}", sign, rounded, prefix),
+        };
+    }
+    n.to_string()
+}
+
+/// Abbreviates a count like shields.io's social badges, see [`metric`].
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::social_count;
+/// assert_eq!(social_count(999), "999");
+/// assert_eq!(social_count(1234), "1.2k");
+/// assert_eq!(social_count(2_000_000), "2M");
+/// # }
+/// ```
+pub fn social_count(n: u64) -> String {
+    metric(n as f64)
+}
+
+/// Appends an English ordinal suffix in superscript letters, as shields.io does:
+/// `1` → `1ˢᵗ`, `12` → `12ᵗʰ`, `23` → `23ʳᵈ`.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::ordinal;
+/// assert_eq!(ordinal(2), "2ⁿᵈ");
+/// assert_eq!(ordinal(111), "111ᵗʰ");
+/// # }
+/// ```
+#[cfg(feature = "unstable")]
+pub fn ordinal(n: u64) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "ᵗʰ",
+        (1, _) => "ˢᵗ",
+        (2, _) => "ⁿᵈ",
+        (3, _) => "ʳᵈ",
+        _ => "ᵗʰ",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Formats a percentage (already in percent) with `decimals` digits, trailing fractional
+/// zeros removed: `percentage(87.50, 1)` → `87.5%`. Use [`PercentFormat`] for more control.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::percentage;
+/// assert_eq!(percentage(87.54, 1), "87.5%");
+/// assert_eq!(percentage(100.0, 2), "100%");
+/// # }
+/// ```
+#[cfg(feature = "unstable")]
+pub fn percentage(value: f64, decimals: u32) -> String {
+    PercentFormat::new(decimals).trim_zeros(true).format(value)
+}
+
+/// Groups the digits of an integer in threes with commas: `1234567` → `1,234,567`.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::thousands_separated;
+/// assert_eq!(thousands_separated(-1234567), "-1,234,567");
+/// # }
+/// ```
+#[cfg(feature = "unstable")]
+pub fn thousands_separated(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if n < 0 {
+        out.push('-');
+    }
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+/// Decimal byte units used by [`bytes`], for powers of 1000.
+#[cfg(feature = "unstable")]
+const BYTE_UNITS: [&str; 7] = ["B", "kB", "MB", "GB", "TB", "PB", "EB"];
+
+/// Formats a size in bytes with decimal (SI) units and at most one decimal:
+/// `512` → `512 B`, `1_430_000` → `1.4 MB`.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::bytes;
+/// assert_eq!(bytes(1_430_000), "1.4 MB");
+/// assert_eq!(bytes(2_000), "2 kB");
+/// # }
+/// ```
+#[cfg(feature = "unstable")]
+pub fn bytes(n: u64) -> String {
+    let format = DecimalFormat::new(1).trim_zeros(true);
+    let mut value = n as f64;
+    let mut unit = 0;
+    // Compare the rounded value, so 999_960 bytes become `1 MB` rather than `1000 kB`
+    while unit + 1 < BYTE_UNITS.len()
+        && format
+            .format(value)
+            .parse::<f64>()
+            .is_ok_and(|v| v >= 1000.0)
+    {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{} {}", format.format(value), BYTE_UNITS[unit])
+}
+
+/// Formats a duration with its two largest units, truncated: `222s` → `3m 42s`,
+/// `3725s` → `1h 2m`. Durations under a second are shown in milliseconds.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::duration;
+/// use std::time::Duration;
+///
+/// assert_eq!(duration(Duration::from_secs(222)), "3m 42s");
+/// assert_eq!(duration(Duration::from_millis(350)), "350ms");
+/// # }
+/// ```
+#[cfg(feature = "unstable")]
+pub fn duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs == 0 {
+        return format!("{}ms", duration.subsec_millis());
+    }
+    let parts = [
+        (secs / 86_400, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+    ];
+    let first = parts.iter().position(|(n, _)| *n > 0).unwrap_or(3);
+    parts[first..]
+        .iter()
+        .take(2)
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, unit)| format!("{}{}", n, unit))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Describes how long ago (or how far ahead) `time` is, the way shields.io date badges do:
+/// `a few seconds ago`, `2 days ago`, `in 3 months`.
+///
+/// Durations are rounded to the nearest unit, switching units at the same points as
+/// shields.io (e.g. 26 days become `a month`). See [`age_color`] for the matching color.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::relative_date;
+/// use std::time::{Duration, SystemTime};
+///
+/// let two_days_ago = SystemTime::now() - Duration::from_secs(2 * 86_400);
+/// assert_eq!(relative_date(two_days_ago), "2 days ago");
+/// # }
+/// ```
+#[cfg(feature = "unstable")]
+pub fn relative_date(time: SystemTime) -> String {
+    relative_date_at(time, SystemTime::now())
+}
+
+/// Color of a date badge for `time`: `brightgreen` within a week, then `green`,
+/// `yellowgreen`, `yellow`, `orange` from a year on and, from two years on, `red`. Dates
+/// in the future are `brightgreen`.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::format::age_color;
+/// use std::time::{Duration, SystemTime};
+///
+/// let stale = SystemTime::now() - Duration::from_secs(800 * 86_400);
+/// assert_eq!(age_color(stale), "red");
+/// # }
+/// ```
+#[cfg(feature = "unstable")]
+pub fn age_color(time: SystemTime) -> &'static str {
+    age_color_at(time, SystemTime::now())
+}
+
+#[cfg(feature = "unstable")]
+fn relative_date_at(time: SystemTime, now: SystemTime) -> String {
+    let (seconds, future) = match now.duration_since(time) {
+        Ok(elapsed) => (elapsed.as_secs_f64(), false),
+        Err(e) => (e.duration().as_secs_f64(), true),
+    };
+    let minutes = seconds / 60.0;
+    let hours = minutes / 60.0;
+    let days = hours / 24.0;
+    let months = days / 30.4375;
+    let years = days / 365.25;
+    let amount = |n: f64, unit: &str| format!("{} {}s", n.round(), unit);
+    let span = if seconds < 45.0 {
+        "a few seconds".to_string()
+    } else if seconds < 90.0 {
+        "a minute".to_string()
+    } else if minutes < 45.0 {
+        amount(minutes, "minute")
+    } else if minutes < 90.0 {
+        "an hour".to_string()
+    } else if hours < 22.0 {
+        amount(hours, "hour")
+    } else if hours < 36.0 {
+        "a day".to_string()
+    } else if days < 26.0 {
+        amount(days, "day")
+    } else if days < 46.0 {
+        "a month".to_string()
+    } else if days < 320.0 {
+        amount(months, "month")
+    } else if days < 548.0 {
+        "a year".to_string()
+    } else {
+        amount(years, "year")
+    };
+    if future {
+        format!("in {}", span)
+    } else {
+        format!("{} ago", span)
+    }
+}
+
+#[cfg(feature = "unstable")]
+fn age_color_at(time: SystemTime, now: SystemTime) -> &'static str {
+    let days = now
+        .duration_since(time)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400);
+    match days {
+        0..7 => "brightgreen",
+        7..30 => "green",
+        30..180 => "yellowgreen",
+        180..365 => "yellow",
+        365..730 => "orange",
+        _ => "red",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_rounding_modes() {
+        let cases = [
+            (2.5, Rounding::HalfAwayFromZero, "3"),
+            (-2.5, Rounding::HalfAwayFromZero, "-3"),
+            (2.5, Rounding::HalfEven, "2"),
+            (3.5, Rounding::HalfEven, "4"),
+            (2.51, Rounding::HalfEven, "3"),
+            (2.1, Rounding::Ceil, "3"),
+            (-2.1, Rounding::Ceil, "-2"),
+            (2.9, Rounding::Floor, "2"),
+            (-2.1, Rounding::Floor, "-3"),
+            (-2.9, Rounding::TowardZero, "-2"),
+            (-0.4, Rounding::HalfAwayFromZero, "0"),
+            (999.5, Rounding::HalfAwayFromZero, "1000"),
+        ];
+        for (value, rounding, expected) in cases {
+            let format = DecimalFormat::new(0).rounding(rounding);
+            assert_eq!(format.format(value), expected, "{} {:?}", value, rounding);
+        }
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_decimal_digits() {
+        // 1.005 is stored as 1.00499999…, but is rounded as written
+        assert_eq!(DecimalFormat::new(2).format(1.005), "1.01");
+        assert_eq!(DecimalFormat::new(3).format(1.5), "1.500");
+        assert_eq!(DecimalFormat::new(3).trim_zeros(true).format(1.5), "1.5");
+        assert_eq!(DecimalFormat::new(2).trim_zeros(true).format(2.0), "2");
+        assert_eq!(DecimalFormat::new(1).format(0.0000001), "0.0");
+        assert_eq!(DecimalFormat::new(1).format(f64::NAN), "NaN");
+    }
+
+    #[test]
+    fn test_social_count() {
+        let cases = [
+            (0, "0"),
+            (999, "999"),
+            (1000, "1k"),
+            (1049, "1k"),
+            (1050, "1.1k"),
+            (1234, "1.2k"),
+            (9960, "10k"),
+            (12_345, "12k"),
+            (999_499, "999k"),
+            (999_500, "1M"),
+            (2_000_000, "2M"),
+            (u64::MAX, "18E"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(social_count(n), expected, "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_metric() {
+        assert_eq!(metric(0.0), "0");
+        assert_eq!(metric(999.5), "999.5");
+        assert_eq!(metric(-1234.0), "-1.2k");
+        assert_eq!(metric(1e24), "1Y");
+        assert_eq!(metric(2.5e27), "2500Y");
+        assert_eq!(metric(f64::NAN), "NaN");
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_ordinal() {
+        let cases = [
+            (0, "0ᵗʰ"),
+            (1, "1ˢᵗ"),
+            (2, "2ⁿᵈ"),
+            (3, "3ʳᵈ"),
+            (4, "4ᵗʰ"),
+            (11, "11ᵗʰ"),
+            (12, "12ᵗʰ"),
+            (13, "13ᵗʰ"),
+            (21, "21ˢᵗ"),
+            (112, "112ᵗʰ"),
+            (1003, "1003ʳᵈ"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(ordinal(n), expected);
+        }
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_percentage_and_thousands() {
+        assert_eq!(percentage(99.95, 1), "100%");
+        assert_eq!(percentage(12.0, 0), "12%");
+        assert_eq!(thousands_separated(0), "0");
+        assert_eq!(thousands_separated(999), "999");
+        assert_eq!(thousands_separated(1000), "1,000");
+        assert_eq!(thousands_separated(i64::MIN), "-9,223,372,036,854,775,808");
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_bytes_and_duration() {
+        assert_eq!(bytes(0), "0 B");
+        assert_eq!(bytes(999), "999 B");
+        assert_eq!(bytes(1000), "1 kB");
+        assert_eq!(bytes(1_430_000), "1.4 MB");
+        assert_eq!(bytes(999_960), "1 MB");
+        assert_eq!(bytes(15_300_000_000), "15.3 GB");
+        assert_eq!(bytes(u64::MAX), "18.4 EB");
+
+        assert_eq!(duration(Duration::ZERO), "0ms");
+        assert_eq!(duration(Duration::from_millis(1500)), "1s");
+        assert_eq!(duration(Duration::from_secs(42)), "42s");
+        assert_eq!(duration(Duration::from_secs(180)), "3m");
+        assert_eq!(duration(Duration::from_secs(3725)), "1h 2m");
+        assert_eq!(duration(Duration::from_secs(2 * 86_400 + 59)), "2d");
+        assert_eq!(duration(Duration::from_secs(90_061)), "1d 1h");
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_relative_date() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000_000_000);
+        let ago = |secs: u64| relative_date_at(now - Duration::from_secs(secs), now);
+        assert_eq!(ago(10), "a few seconds ago");
+        assert_eq!(ago(60), "a minute ago");
+        assert_eq!(ago(5 * 60), "5 minutes ago");
+        assert_eq!(ago(60 * 60), "an hour ago");
+        assert_eq!(ago(3 * 3600), "3 hours ago");
+        assert_eq!(ago(24 * 3600), "a day ago");
+        assert_eq!(ago(2 * 86_400), "2 days ago");
+        assert_eq!(ago(30 * 86_400), "a month ago");
+        assert_eq!(ago(95 * 86_400), "3 months ago");
+        assert_eq!(ago(400 * 86_400), "a year ago");
+        assert_eq!(ago(3 * 366 * 86_400), "3 years ago");
+        assert_eq!(
+            relative_date_at(now + Duration::from_secs(92 * 86_400), now),
+            "in 3 months"
+        );
+
+        let color = |days: u64| age_color_at(now - Duration::from_secs(days * 86_400), now);
+        assert_eq!(color(6), "brightgreen");
+        assert_eq!(color(7), "green");
+        assert_eq!(color(30), "yellowgreen");
+        assert_eq!(color(200), "yellow");
+        assert_eq!(color(365), "orange");
+        assert_eq!(color(400), "orange");
+        assert_eq!(color(730), "red");
+        assert_eq!(
+            age_color_at(now + Duration::from_secs(86_400), now),
+            "brightgreen"
+        );
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_percent() {
+        assert_eq!(PercentFormat::new(1).format(99.95), "100.0%");
+        assert_eq!(PercentFormat::new(1).trim_zeros(true).format(99.95), "100%");
+        let strict = PercentFormat::new(1).exact_hundred_only(true);
+        assert_eq!(strict.format(99.95), "99.9%");
+        assert_eq!(strict.format(99.9999), "99.9%");
+        assert_eq!(strict.format(100.0), "100.0%");
+        assert_eq!(strict.format(87.25), "87.3%");
+        assert_eq!(
+            PercentFormat::new(0).exact_hundred_only(true).format(99.5),
+            "99%"
+        );
+    }
+}
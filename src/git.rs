@@ -0,0 +1,319 @@
+//! Badges read from a local git repository: latest tag, commit count, last commit date and
+//! number of contributors.
+//!
+//! [`GitBadges`] works offline through libgit2, so docs builds and release scripts can
+//! produce badges without calling a hosting service's API. In a monorepo,
+//! [`scope`](GitBadges::scope) restricts the history to commits touching one directory and
+//! [`tag_prefix`](GitBadges::tag_prefix) to tags of one package, such as `shields-v1.2.0`.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use shields::git::GitBadges;
+//! use shields::render_badge_svg;
+//!
+//! let git = GitBadges::open(".")?.scope("crates/shields").tag_prefix("shields-");
+//! for params in [git.latest_tag()?, git.commit_count()?, git.last_commit()?, git.contributors()?] {
+//!     println!("{}", render_badge_svg(&params.as_params()));
+//! }
+//! # Ok::<(), shields::git::GitError>(())
+//! ```
+
+use crate::format::{age_color, metric, relative_date};
+use crate::{BadgeParamsOwned, presets};
+use git2::{Commit, Oid, Repository, Sort};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Error returned when a badge cannot be read from a repository.
+#[derive(Debug)]
+pub enum GitError {
+    /// The repository could not be opened or read.
+    Git(git2::Error),
+    /// No tag matches the tag prefix.
+    NoTags,
+    /// The history has no commits, or none touching the scope.
+    NoCommits,
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitError::Git(error) => write!(f, "{}", error.message()),
+            GitError::NoTags => f.write_str("no tags"),
+            GitError::NoCommits => f.write_str("no commits"),
+        }
+    }
+}
+
+impl std::error::Error for GitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GitError::Git(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<git2::Error> for GitError {
+    fn from(error: git2::Error) -> Self {
+        GitError::Git(error)
+    }
+}
+
+/// Reads badges from the history of a local repository's `HEAD`.
+pub struct GitBadges {
+    repo: Repository,
+    scope: Option<PathBuf>,
+    tag_prefix: String,
+}
+
+impl GitBadges {
+    /// Opens the repository containing `path`, searching parent directories like `git` does.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GitError> {
+        Ok(Self {
+            repo: Repository::discover(path)?,
+            scope: None,
+            tag_prefix: String::new(),
+        })
+    }
+
+    /// Only counts commits changing `path`, relative to the repository root, following the
+    /// first parent of merges.
+    pub fn scope(mut self, path: impl Into<PathBuf>) -> Self {
+        self.scope = Some(path.into());
+        self
+    }
+
+    /// Only considers tags starting with `prefix`, which is stripped from the badge message.
+    pub fn tag_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.tag_prefix = prefix.into();
+        self
+    }
+
+    /// Most recent tag by commit date, e.g. `tag | v1.4.2`. Version tags are colored like
+    /// [`presets::version`]; other tags are shown as is, in blue.
+    pub fn latest_tag(&self) -> Result<BadgeParamsOwned, GitError> {
+        let pattern = format!("{}*", self.tag_prefix);
+        let mut latest: Option<(i64, String)> = None;
+        for name in self.repo.tag_names(Some(&pattern))?.iter().flatten() {
+            let reference = self.repo.find_reference(&format!("refs/tags/{}", name))?;
+            let Ok(commit) = reference.peel_to_commit() else {
+                // Tags of trees and blobs have no date
+                continue;
+            };
+            let candidate = (commit.time().seconds(), name.to_string());
+            if latest.as_ref().is_none_or(|latest| candidate > *latest) {
+                latest = Some(candidate);
+            }
+        }
+        let (_, name) = latest.ok_or(GitError::NoTags)?;
+        let tag = &name[self.tag_prefix.len()..];
+        let number = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+        if number.starts_with(|c: char| c.is_ascii_digit()) {
+            return Ok(presets::version("tag", tag));
+        }
+        Ok(BadgeParamsOwned {
+            label: Some("tag".to_string()),
+            message: Some(tag.to_string()),
+            message_color: Some("blue".to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Number of commits, e.g. `commits | 1.2k`.
+    pub fn commit_count(&self) -> Result<BadgeParamsOwned, GitError> {
+        let mut count = 0u64;
+        for commit in self.commits()? {
+            commit?;
+            count += 1;
+        }
+        if count == 0 {
+            return Err(GitError::NoCommits);
+        }
+        Ok(BadgeParamsOwned {
+            label: Some("commits".to_string()),
+            message: Some(metric(count as f64)),
+            message_color: Some("blue".to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Date of the most recent commit, e.g. `last commit | 2 days ago`, colored by age like
+    /// shields.io (see [`age_color`]).
+    pub fn last_commit(&self) -> Result<BadgeParamsOwned, GitError> {
+        let commit = self.commits()?.next().ok_or(GitError::NoCommits)??;
+        let seconds = commit.time().seconds().max(0) as u64;
+        let time = UNIX_EPOCH + Duration::from_secs(seconds);
+        Ok(BadgeParamsOwned {
+            label: Some("last commit".to_string()),
+            message: Some(relative_date(time)),
+            message_color: Some(age_color(time).to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Number of distinct commit authors, e.g. `contributors | 12`. Authors are told apart by
+    /// email, case-insensitively, after applying the repository's `.mailmap`.
+    pub fn contributors(&self) -> Result<BadgeParamsOwned, GitError> {
+        let mailmap = self.repo.mailmap()?;
+        let mut authors = HashSet::new();
+        for commit in self.commits()? {
+            let author = commit?.author_with_mailmap(&mailmap)?;
+            authors.insert(String::from_utf8_lossy(author.email_bytes()).to_lowercase());
+        }
+        if authors.is_empty() {
+            return Err(GitError::NoCommits);
+        }
+        Ok(BadgeParamsOwned {
+            label: Some("contributors".to_string()),
+            message: Some(metric(authors.len() as f64)),
+            message_color: Some("blue".to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Commits reachable from `HEAD` and touching the scope, newest first.
+    fn commits(&self) -> Result<impl Iterator<Item = Result<Commit<'_>, GitError>>, GitError> {
+        if self.repo.is_empty()? {
+            return Err(GitError::NoCommits);
+        }
+        let mut walk = self.repo.revwalk()?;
+        walk.set_sorting(Sort::TIME)?;
+        walk.push_head()?;
+        Ok(walk.filter_map(move |oid| {
+            let commit = match oid.and_then(|oid| self.repo.find_commit(oid)) {
+                Ok(commit) => commit,
+                Err(error) => return Some(Err(error.into())),
+            };
+            match self.touches_scope(&commit) {
+                Ok(true) => Some(Ok(commit)),
+                Ok(false) => None,
+                Err(error) => Some(Err(error.into())),
+            }
+        }))
+    }
+
+    /// Whether `commit` changes the scope compared to its first parent.
+    fn touches_scope(&self, commit: &Commit<'_>) -> Result<bool, git2::Error> {
+        let Some(scope) = &self.scope else {
+            return Ok(true);
+        };
+        let entry = |commit: &Commit<'_>| -> Result<Option<Oid>, git2::Error> {
+            Ok(commit.tree()?.get_path(scope).ok().map(|entry| entry.id()))
+        };
+        let parent = match commit.parent(0) {
+            Ok(parent) => entry(&parent)?,
+            Err(_) => None,
+        };
+        Ok(entry(commit)? != parent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, Time};
+    use std::fs;
+
+    fn temp_repo(name: &str) -> (PathBuf, Repository) {
+        let dir = std::env::temp_dir().join(format!("shields-git-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        (dir, repo)
+    }
+
+    /// Commits `contents` to `path` on `HEAD`, authored by `email` at `seconds`.
+    fn commit(repo: &Repository, path: &str, contents: &str, email: &str, seconds: i64) -> Oid {
+        let file = repo.workdir().unwrap().join(path);
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, contents).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::new("Dev", email, &Time::new(seconds, 0)).unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<&Commit<'_>> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "commit",
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_git_badges() {
+        let (dir, repo) = temp_repo("badges");
+        let first = commit(&repo, "a.txt", "1", "alice@example.com", 1_600_000_000);
+        let second = commit(
+            &repo,
+            "crates/foo/lib.rs",
+            "",
+            "bob@example.com",
+            1_600_100_000,
+        );
+        let third = commit(&repo, "a.txt", "2", "Alice@Example.com", 1_600_200_000);
+        repo.tag_lightweight("v0.1.0", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        let signature = Signature::new("Dev", "bob@example.com", &Time::new(0, 0)).unwrap();
+        repo.tag(
+            "foo-v1.2.0",
+            &repo.find_object(second, None).unwrap(),
+            &signature,
+            "release",
+            false,
+        )
+        .unwrap();
+        repo.tag_lightweight("v0.2.0", &repo.find_object(third, None).unwrap(), false)
+            .unwrap();
+
+        let git = GitBadges::open(dir.join("crates")).unwrap();
+        let params = git.latest_tag().unwrap();
+        assert_eq!(params.label.as_deref(), Some("tag"));
+        assert_eq!(params.message.as_deref(), Some("v0.2.0"));
+        assert_eq!(params.message_color.as_deref(), Some("orange"));
+        let params = git.commit_count().unwrap();
+        assert_eq!(params.message.as_deref(), Some("3"));
+        let params = git.contributors().unwrap();
+        assert_eq!(params.message.as_deref(), Some("2"));
+        let time = UNIX_EPOCH + Duration::from_secs(1_600_200_000);
+        let params = git.last_commit().unwrap();
+        assert_eq!(params.label.as_deref(), Some("last commit"));
+        assert_eq!(params.message, Some(relative_date(time)));
+        assert_eq!(params.message_color.as_deref(), Some(age_color(time)));
+
+        let git = git.scope("crates/foo").tag_prefix("foo-");
+        let params = git.latest_tag().unwrap();
+        assert_eq!(params.message.as_deref(), Some("v1.2.0"));
+        assert_eq!(params.message_color.as_deref(), Some("blue"));
+        assert_eq!(git.commit_count().unwrap().message.as_deref(), Some("1"));
+        assert_eq!(git.contributors().unwrap().message.as_deref(), Some("1"));
+        let time = UNIX_EPOCH + Duration::from_secs(1_600_100_000);
+        assert_eq!(
+            git.last_commit().unwrap().message,
+            Some(relative_date(time))
+        );
+
+        let git = git.scope("missing").tag_prefix("nightly-");
+        assert!(matches!(git.latest_tag(), Err(GitError::NoTags)));
+        assert!(matches!(git.commit_count(), Err(GitError::NoCommits)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_empty_repository() {
+        let (dir, _repo) = temp_repo("empty");
+        let git = GitBadges::open(&dir).unwrap();
+        assert!(matches!(git.last_commit(), Err(GitError::NoCommits)));
+        assert!(matches!(git.contributors(), Err(GitError::NoCommits)));
+        assert!(matches!(git.latest_tag(), Err(GitError::NoTags)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+//! HTTP caching helpers for badge servers: strong `ETag`s, conditional request checks,
+//! `Cache-Control` values and `Last-Modified` dates.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use shields::BadgeParams;
+//! use shields::http_cache::{cache_control, etag_for, is_modified};
+//!
+//! let params = BadgeParams {
+//!     label: Some("build"),
+//!     message: Some("passing"),
+//!     ..Default::default()
+//! };
+//! let etag = etag_for(&params);
+//! // A client revalidating its copy sends the ETag back in `If-None-Match`
+//! let request_header = Some(etag.as_str());
+//! if !is_modified(request_header, &etag) {
+//!     // 304 Not Modified with the same `ETag` and `Cache-Control` headers
+//! }
+//! assert_eq!(cache_control(300), "max-age=300, s-maxage=300");
+//! ```
+
+use crate::{BadgeParams, content_hash, logos};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long caches may keep a badge unless told otherwise, in seconds.
+pub const DEFAULT_MAX_AGE: u32 = 300;
+
+/// Strong `ETag` for the badge rendered from `params`, quotes included.
+///
+/// The tag is a digest of the parameters, the crate version and the logo dataset (see
+/// [`logos::dataset_version`]), so it changes whenever the rendered bytes may change. Servers
+/// rendering with non-default [`RenderOptions`](crate::RenderOptions) should serve each set
+/// of options under its own URL.
+pub fn etag_for(params: &BadgeParams) -> String {
+    // Debug output covers every parameter and does not change between runs
+    let key = format!(
+        "{}\n{}\n{:?}",
+        env!("CARGO_PKG_VERSION"),
+        logos::dataset_version(),
+        params
+    );
+    format!("\"{}\"", content_hash(key.as_bytes()))
+}
+
+/// Whether the badge tagged `etag` differs from the client's copy, given the request's
+/// `If-None-Match` header.
+///
+/// # Returns
+/// `false` when the header lists `etag` (compared weakly, as RFC 9110 requires for
+/// `If-None-Match`) or is `*`, meaning the client's copy is current and a `304 Not Modified`
+/// should be sent; `true` when the full badge should be sent, including when the header is
+/// absent.
+pub fn is_modified(header: Option<&str>, etag: &str) -> bool {
+    let Some(header) = header else {
+        return true;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    !header
+        .split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == etag)
+}
+
+/// `Cache-Control` value letting browsers and shared caches keep a badge for `max_age`
+/// seconds. Zero gives `no-cache`, for badges that must be revalidated on every use.
+pub fn cache_control(max_age: u32) -> String {
+    if max_age == 0 {
+        "no-cache".to_string()
+    } else {
+        format!("max-age={0}, s-maxage={0}", max_age)
+    }
+}
+
+const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formats `time` as an HTTP date (e.g. `Sun, 06 Nov 1994 08:49:37 GMT`) for
+/// `Last-Modified` headers. Times before 1970 are clamped to the epoch.
+pub fn http_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let days = secs / 86_400;
+    let (year, month, day) = civil_from_days(days as i64);
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        DAYS[(days % 7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        secs % 86_400 / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Parses an HTTP date in the preferred `Sun, 06 Nov 1994 08:49:37 GMT` format, as sent in
+/// `If-Modified-Since`.
+///
+/// # Returns
+/// The time, or `None` if the date is malformed or uses an obsolete format.
+pub fn parse_http_date(date: &str) -> Option<SystemTime> {
+    let mut parts = date.trim().split(' ');
+    let weekday = parts.next()?.strip_suffix(',')?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u32 + 1;
+    let year: i64 = parts.next()?.parse().ok()?;
+    let mut clock = parts.next()?.split(':').map(|n| n.parse::<u64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    if parts.next()? != "GMT" || parts.next().is_some() || clock.next().is_some() {
+        return None;
+    }
+    if !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if days < 0 || !DAYS[(days % 7) as usize].eq(weekday) {
+        return None;
+    }
+    let secs = days as u64 * 86_400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Evaluates an `If-Modified-Since` request header against the badge's `last_modified` time.
+///
+/// # Returns
+/// `false` when the badge has not changed since the date in the header, meaning a
+/// `304 Not Modified` should be sent; `true` otherwise, including when the header is absent
+/// or malformed. HTTP dates have whole-second precision, so `last_modified` is compared
+/// truncated to the second.
+pub fn if_modified_since(header: Option<&str>, last_modified: SystemTime) -> bool {
+    let Some(since) = header.and_then(parse_http_date) else {
+        return true;
+    };
+    let last_modified = last_modified
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let since = since
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    last_modified > since
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) date in the proleptic Gregorian
+/// calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a proleptic Gregorian date to days since 1970-01-01.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_for() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let etag = etag_for(&params);
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+        assert_eq!(etag.len(), 18);
+        assert_eq!(etag, etag_for(&params.clone()));
+        let failing = BadgeParams {
+            message: Some("failing"),
+            ..params
+        };
+        assert_ne!(etag, etag_for(&failing));
+    }
+
+    #[test]
+    fn test_is_modified() {
+        let etag = "\"0123456789abcdef\"";
+        assert!(is_modified(None, etag));
+        assert!(!is_modified(Some(etag), etag));
+        assert!(!is_modified(Some("W/\"0123456789abcdef\""), etag));
+        assert!(!is_modified(
+            Some("\"other\", \"0123456789abcdef\""),
+            etag
+        ));
+        assert!(!is_modified(Some("*"), etag));
+        assert!(is_modified(Some("\"other\""), etag));
+        assert_eq!(cache_control(0), "no-cache");
+        assert_eq!(cache_control(3600), "max-age=3600, s-maxage=3600");
+    }
+
+    #[test]
+    fn test_http_date() {
+        let time = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        assert_eq!(http_date(time), "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(http_date(UNIX_EPOCH), "Thu, 01 Jan 1970 00:00:00 GMT");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_210_096);
+        assert_eq!(http_date(leap_day), "Thu, 29 Feb 2024 12:34:56 GMT");
+        for time in [time, UNIX_EPOCH, leap_day] {
+            assert_eq!(parse_http_date(&http_date(time)), Some(time));
+        }
+        assert_eq!(parse_http_date("Mon, 06 Nov 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37"), None);
+
+        let header = Some("Sun, 06 Nov 1994 08:49:37 GMT");
+        assert!(!if_modified_since(header, time));
+        assert!(!if_modified_since(
+            header,
+            time + Duration::from_millis(500)
+        ));
+        assert!(if_modified_since(header, time + Duration::from_secs(1)));
+        assert!(if_modified_since(Some("yesterday"), time));
+        assert!(if_modified_since(None, time));
+    }
+}
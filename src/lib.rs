@@ -8,7 +8,8 @@ This crate provides flexible APIs for creating customizable status badges for CI
 ## Features
 
 - Generate SVG badge strings with custom label, message, color, logo, and links.
-- Multiple badge styles: flat, flat-square, plastic, social, for-the-badge.
+- Multiple badge styles: flat, flat-square, plastic, social, for-the-badge, for-the-badge-two-line, pill,
+  outline, monospace.
 - Accurate text width calculation using embedded font width tables.
 - Builder pattern and parameter struct APIs.
 - Color normalization and aliasing (e.g., "critical" → red).
@@ -17,6 +18,7 @@ This crate provides flexible APIs for creating customizable status badges for CI
 ### Example
 
 ```rust
+# #[cfg(feature = "style-flat")] {
 use shields::{BadgeStyle, BadgeParams, render_badge_svg};
 
 let params = BadgeParams {
@@ -29,14 +31,17 @@ let params = BadgeParams {
     extra_link: None,
     logo: None,
     logo_color: None,
+    ..Default::default()
 };
 let svg = render_badge_svg(&params);
 assert!(svg.contains("passing"));
+# }
 ```
 
 Or use the builder API:
 
 ```rust
+# #[cfg(feature = "style-plastic")] {
 use shields::{BadgeStyle};
 use shields::builder::Badge;
 
@@ -46,32 +51,147 @@ let svg = Badge::style(BadgeStyle::Plastic)
     .logo("github")
     .build();
 assert!(svg.contains("version"));
+# }
 ```
 
 See [`BadgeParams`](crate::BadgeParams), [`BadgeStyle`](crate::BadgeStyle), and [`BadgeBuilder`](crate::builder::BadgeBuilder) for details.
 
+## Stability
+
+The stable core follows semver: badge parameters ([`BadgeParams`](crate::BadgeParams), [`BadgeParamsOwned`](crate::BadgeParamsOwned)), styles ([`BadgeStyle`](crate::BadgeStyle)), rendering ([`render_badge_svg`](crate::render_badge_svg), [`try_render_badge_svg`](crate::try_render_badge_svg), the [`builder`](crate::builder)) and measurement ([`get_text_width`](crate::get_text_width), [`measurer`](crate::measurer)).
+
+Experimental subsystems (`a11y`, `actix`, `badge_maker`, `cargo`, `color`, `color_scale`, `dynamic`, `endpoint`, `format`, `git`, `http_cache`, `logos`, `manifest`, `output`, `presets`, `renderer`, `server`, `service`, `services`, `sheet`, `snippet` and `stack`) are only compiled with the `unstable` feature and may change in any release:
+
+```toml
+[dependencies]
+shields = { version = "2", features = ["unstable"] }
+```
+
 "#]
-use askama::{Template, filters::capitalize};
+#[cfg(any_style)]
+use askama::Template;
+#[cfg(feature = "style-social")]
+use askama::filters::capitalize;
 use std::str::FromStr;
+mod bidi;
 pub mod builder;
+mod cache;
+mod compat;
+pub mod error;
 pub mod measurer;
+mod optimize;
+pub use optimize::render_badge_svg_optimized;
+mod pretty;
+#[cfg(feature = "raster")]
+mod raster;
+mod subset;
+#[cfg(feature = "raster")]
+pub use raster::render_badge_png;
+#[cfg(feature = "compression")]
+mod svgz;
+#[cfg(feature = "compression")]
+pub use svgz::render_badge_svgz;
+
+// Experimental subsystems; their APIs may change in minor releases.
+#[cfg(feature = "unstable")]
+pub mod a11y;
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg(feature = "unstable")]
+pub mod badge_maker;
+#[cfg(feature = "cargo")]
+pub mod cargo;
+#[cfg(feature = "unstable")]
+pub mod color;
+#[cfg(feature = "unstable")]
+pub mod color_scale;
+#[cfg(feature = "unstable")]
+pub mod dynamic;
+#[cfg(feature = "unstable")]
+pub mod endpoint;
+#[cfg(feature = "unstable")]
+pub mod format;
+#[cfg(not(feature = "unstable"))]
+mod format;
+#[cfg(feature = "git")]
+pub mod git;
+#[cfg(feature = "unstable")]
+pub mod http_cache;
+#[cfg(feature = "unstable")]
+pub mod logos;
+#[cfg(not(feature = "unstable"))]
+mod logos;
+#[cfg(feature = "unstable")]
+pub mod manifest;
+#[cfg(feature = "unstable")]
+pub mod output;
+#[cfg(feature = "unstable")]
+pub mod presets;
+#[cfg(feature = "unstable")]
+pub mod renderer;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "tower")]
+pub mod service;
+#[cfg(feature = "services")]
+pub mod services;
+#[cfg(feature = "unstable")]
+pub mod sheet;
+#[cfg(feature = "unstable")]
+pub mod snippet;
+#[cfg(feature = "unstable")]
+pub mod stack;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+use arc_swap::ArcSwap;
 use base64::Engine;
+use cache::ShardedLru;
 use color_util::to_svg_color;
 use csscolorparser::Color;
-use serde::Deserialize;
+pub use error::{BadgeError, LimitKind, ShieldsError};
+use measurer::CharWidthMeasurer;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// SVG rendering template context, fields must correspond to variables in badge_svg_template_askama.svg
+#[cfg(feature = "style-flat")]
 #[derive(Template)]
 #[template(path = "flat_badge_template.min.svg", escape = "none")]
 struct FlatBadgeSvgTemplateContext<'a> {
     total_width: i32,
     badge_height: i32,
     accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
+    /// Appended to every element `id` and its references.
+    id_suffix: &'a str,
+    /// Leave out the label section, for a badge showing nothing but its logo.
+    logo_only: bool,
     left_width: i32,
     right_width: i32,
     label_color: &'a str,
     message_color: &'a str,
     font_family: &'a str,
+    /// Set the text in bold, for families with only a bold width table.
+    bold_text: bool,
     font_size_scaled: i32,
 
     label: &'a str,
@@ -84,23 +204,228 @@ struct FlatBadgeSvgTemplateContext<'a> {
     message_x: f32,
     message_shadow_color: &'a str,
     message_text_color: &'a str,
+    /// Points of the sparkline polyline, empty for none.
+    sparkline: &'a str,
     message_width_scaled: i32,
 
     link: &'a str,
     extra_link: &'a str,
 
     logo: &'a str,
+    logo_x: u32,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
     rect_offset: i32,
 
     message_link_x: i32,
 }
 /// flat-square SVG rendering template context
+#[cfg(feature = "style-flat-square")]
 #[derive(Template)]
 #[template(path = "flat_square_badge_template.min.svg", escape = "none")]
 struct FlatSquareBadgeSvgTemplateContext<'a> {
     total_width: i32,
     badge_height: i32,
     accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
+    /// Leave out the label section, for a badge showing nothing but its logo.
+    logo_only: bool,
+    left_width: i32,
+    right_width: i32,
+    label_color: &'a str,
+    message_color: &'a str,
+    font_family: &'a str,
+    /// Set the text in bold, for families with only a bold width table.
+    bold_text: bool,
+    font_size_scaled: i32,
+
+    label: &'a str,
+    label_x: f32,
+    label_width_scaled: i32,
+    label_text_color: &'a str,
+
+    message: &'a str,
+    message_x: f32,
+    message_text_color: &'a str,
+    /// Points of the sparkline polyline, empty for none.
+    sparkline: &'a str,
+    message_width_scaled: i32,
+
+    link: &'a str,
+    extra_link: &'a str,
+    logo: &'a str,
+    logo_x: u32,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
+    rect_offset: i32,
+
+    message_link_x: i32,
+}
+/// pill SVG rendering template context
+#[cfg(feature = "style-pill")]
+#[derive(Template)]
+#[template(path = "pill_badge_template.min.svg", escape = "none")]
+struct PillBadgeSvgTemplateContext<'a> {
+    total_width: i32,
+    badge_height: i32,
+    /// Corner radius, half the height for fully rounded ends.
+    radius: i32,
+    accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
+    /// Appended to every element `id` and its references.
+    id_suffix: &'a str,
+    /// Leave out the label section, for a badge showing nothing but its logo.
+    logo_only: bool,
+    left_width: i32,
+    right_width: i32,
+    label_color: &'a str,
+    message_color: &'a str,
+    font_family: &'a str,
+    /// Set the text in bold, for families with only a bold width table.
+    bold_text: bool,
+    font_size_scaled: i32,
+
+    label: &'a str,
+    label_x: f32,
+    label_width_scaled: i32,
+    label_text_color: &'a str,
+
+    message: &'a str,
+    message_x: f32,
+    message_text_color: &'a str,
+    /// Points of the sparkline polyline, empty for none.
+    sparkline: &'a str,
+    message_width_scaled: i32,
+
+    link: &'a str,
+    extra_link: &'a str,
+    logo: &'a str,
+    logo_x: u32,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
+    rect_offset: i32,
+
+    message_link_x: i32,
+}
+/// outline SVG rendering template context
+#[cfg(feature = "style-outline")]
+#[derive(Template)]
+#[template(path = "outline_badge_template.min.svg", escape = "none")]
+struct OutlineBadgeSvgTemplateContext<'a> {
+    total_width: i32,
+    badge_height: i32,
+    accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
+    left_width: i32,
+    right_width: i32,
+    label_color: &'a str,
+    message_color: &'a str,
+    /// Path of the label segment's border, empty without a label.
+    label_border: &'a str,
+    /// Path of the message segment's border.
+    message_border: &'a str,
+    font_family: &'a str,
+    /// Set the text in bold, for families with only a bold width table.
+    bold_text: bool,
+    font_size_scaled: i32,
+
+    label: &'a str,
+    label_x: f32,
+    label_width_scaled: i32,
+    label_text_color: &'a str,
+
+    message: &'a str,
+    message_x: f32,
+    message_text_color: &'a str,
+    /// Points of the sparkline polyline, empty for none.
+    sparkline: &'a str,
+    message_width_scaled: i32,
+
+    link: &'a str,
+    extra_link: &'a str,
+    logo: &'a str,
+    logo_x: u32,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
+    rect_offset: i32,
+
+    message_link_x: i32,
+}
+/// monospace SVG rendering template context
+#[cfg(feature = "style-monospace")]
+#[derive(Template)]
+#[template(path = "monospace_badge_template.min.svg", escape = "none")]
+struct MonospaceBadgeSvgTemplateContext<'a> {
+    total_width: i32,
+    badge_height: i32,
+    accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
+    /// Leave out the label section, for a badge showing nothing but its logo.
+    logo_only: bool,
     left_width: i32,
     right_width: i32,
     label_color: &'a str,
@@ -116,24 +441,56 @@ struct FlatSquareBadgeSvgTemplateContext<'a> {
     message: &'a str,
     message_x: f32,
     message_text_color: &'a str,
+    /// Points of the sparkline polyline, empty for none.
+    sparkline: &'a str,
     message_width_scaled: i32,
 
     link: &'a str,
     extra_link: &'a str,
     logo: &'a str,
+    logo_x: u32,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
     rect_offset: i32,
 
     message_link_x: i32,
 }
 /// plastic SVG rendering template context
+#[cfg(feature = "style-plastic")]
 #[derive(Template)]
 #[template(path = "plastic_badge_template.min.svg", escape = "none")]
 struct PlasticBadgeSvgTemplateContext<'a> {
     total_width: i32,
     accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
+    /// Appended to every element `id` and its references.
+    id_suffix: &'a str,
+    /// Leave out the label section, for a badge showing nothing but its logo.
+    logo_only: bool,
     left_width: i32,
     right_width: i32,
+    font_family: &'a str,
+    /// Set the text in bold, for families with only a bold width table.
+    bold_text: bool,
     // gradient
+    /// `<stop>` elements of the gloss gradient.
+    gradient_stops: &'a str,
     label: &'a str,
     label_x: f32,
     label_text_length: i32,
@@ -143,6 +500,8 @@ struct PlasticBadgeSvgTemplateContext<'a> {
     message_x: f32,
     message_text_length: i32,
     message_text_color: &'a str,
+    /// Points of the sparkline polyline, empty for none.
+    sparkline: &'a str,
     message_shadow_color: &'a str,
     label_color: &'a str,
     message_color: &'a str,
@@ -151,12 +510,19 @@ struct PlasticBadgeSvgTemplateContext<'a> {
     extra_link: &'a str,
 
     logo: &'a str,
+    logo_x: u32,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
     rect_offset: i32,
 
     message_link_x: i32,
 }
 
 /// social SVG rendering template context
+#[cfg(feature = "style-social")]
 #[derive(Template)]
 #[template(path = "social_badge_template.min.svg", escape = "none")]
 struct SocialBadgeSvgTemplateContext<'a> {
@@ -164,6 +530,23 @@ struct SocialBadgeSvgTemplateContext<'a> {
     total_height: i32,
     internal_height: u32,
     accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
+    /// Appended to every element `id` and its references.
+    id_suffix: &'a str,
+    font_family: &'a str,
     label_rect_width: i32,
     message_bubble_main_x: f32,
     message_rect_width: u32,
@@ -174,14 +557,23 @@ struct SocialBadgeSvgTemplateContext<'a> {
     message_text_x: f32,
     message_text_length: u32,
     message: &'a str,
+    bubble_icon_x: f32,
+    bubble_icon_path: &'a str,
 
     link: &'a str,
     extra_link: &'a str,
 
     logo: &'a str,
+    logo_x: u32,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
 }
 
 /// for-the-badge SVG rendering template context
+#[cfg(feature = "style-for-the-badge")]
 #[derive(Template)]
 #[template(path = "for_the_badge_template.min.svg", escape = "none")]
 struct ForTheBadgeSvgTemplateContext<'a> {
@@ -190,8 +582,24 @@ struct ForTheBadgeSvgTemplateContext<'a> {
 
     // Accessibility
     accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
 
     // Layout dimensions
+    /// Leave out the label section, for a badge showing nothing but its logo.
+    logo_only: bool,
     left_width: i32,
     right_width: i32,
 
@@ -201,6 +609,8 @@ struct ForTheBadgeSvgTemplateContext<'a> {
 
     // Font settings
     font_family: &'a str,
+    /// Set the text in bold, for families with only a bold width table.
+    bold_text: bool,
     font_size: i32,
 
     // Label (left side)
@@ -220,21 +630,201 @@ struct ForTheBadgeSvgTemplateContext<'a> {
     extra_link: &'a str,
 
     // Logo
+    logo: &'a str,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
+    logo_x: i32,
+}
+
+/// two-line for-the-badge SVG rendering template context
+#[cfg(feature = "style-for-the-badge-two-line")]
+#[derive(Template)]
+#[template(path = "for_the_badge_two_line_template.min.svg", escape = "none")]
+struct ForTheBadgeTwoLineSvgTemplateContext<'a> {
+    total_width: i32,
+    accessible_text: &'a str,
+    /// Tooltip for a `<title>` referenced by `aria-labelledby`, empty to use `accessible_text`.
+    title: &'a str,
+    /// `id` of the tooltip `<title>`.
+    title_id: &'a str,
+    /// Direction attributes of the label texts, empty for left-to-right text.
+    label_bidi: &'a str,
+    /// Direction attributes of the message texts, empty for left-to-right text.
+    message_bidi: &'a str,
+    /// Emit `shields-*` classes on the rects, texts and logo.
+    class_hooks: bool,
+    /// Leave out the `fill` of elements with class hooks.
+    unstyled: bool,
+    /// CSS for an embedded `<style>` element, empty for none.
+    extra_style: &'a str,
+    color: &'a str,
+    font_family: &'a str,
+    /// Set the text in bold, for families with only a bold width table.
+    bold_text: bool,
+
+    // Both lines share a left edge and text color
+    text_x: i32,
+    text_color: &'a str,
+    label: &'a str,
+    label_width_scaled: i32,
+    message: &'a str,
+    message_y: i32,
+    message_width_scaled: i32,
+
+    link: &'a str,
+    extra_link: &'a str,
+
     logo: &'a str,
     logo_x: i32,
+    logo_width: u32,
+    logo_glyph: &'a str,
+    logo_glyph_x: f32,
+    logo_glyph_width_scaled: u32,
+    logo_glyph_color: &'a str,
+}
+
+/// Geometry of a filled-in badge template, in pixels.
+#[cfg(any_style)]
+trait Geometry {
+    fn layout(&self) -> BadgeLayout;
+}
+
+/// The box of an image logo at `x`, or of a symbol logo centered on `glyph_x` (in tenths of a
+/// pixel, like the template's text positions).
+#[cfg(any_style)]
+fn logo_box(
+    logo: &str,
+    glyph: &str,
+    x: f32,
+    glyph_x: f32,
+    width: u32,
+    y: f32,
+    height: f32,
+) -> Option<LogoBox> {
+    let width = width as f32;
+    let x = match (logo.is_empty(), glyph.is_empty()) {
+        (false, _) => x,
+        (true, false) => glyph_x / 10.0 - width / 2.0,
+        (true, true) => return None,
+    };
+    Some(LogoBox {
+        x,
+        y,
+        width,
+        height,
+    })
+}
+
+/// Implements [`Geometry`] for the two-part templates with `left_width`, `right_width`,
+/// `label_x` and `message_x`.
+macro_rules! two_part_geometry {
+    ($(#[$cfg:meta] $template:ident => height: $height:expr, logo_y: $logo_y:expr;)*) => {$(
+        #[$cfg]
+        impl Geometry for $template<'_> {
+            fn layout(&self) -> BadgeLayout {
+                #[allow(clippy::redundant_closure_call)]
+                let height = ($height)(self);
+                BadgeLayout {
+                    width: self.total_width.max(0) as u32,
+                    height,
+                    label_width: self.left_width.max(0) as u32,
+                    message_width: self.right_width.max(0) as u32,
+                    label_x: self.label_x / 10.0,
+                    message_x: self.message_x / 10.0,
+                    logo: logo_box(
+                        self.logo,
+                        self.logo_glyph,
+                        self.logo_x as f32,
+                        self.logo_glyph_x,
+                        self.logo_width,
+                        $logo_y,
+                        LOGO_HEIGHT as f32,
+                    ),
+                }
+            }
+        }
+    )*};
+}
+
+two_part_geometry! {
+    #[cfg(feature = "style-flat")]
+    FlatBadgeSvgTemplateContext => height: |t: &Self| t.badge_height as u32, logo_y: 3.0;
+    #[cfg(feature = "style-flat-square")]
+    FlatSquareBadgeSvgTemplateContext => height: |t: &Self| t.badge_height as u32, logo_y: 3.0;
+    #[cfg(feature = "style-pill")]
+    PillBadgeSvgTemplateContext => height: |t: &Self| t.badge_height as u32, logo_y: 3.0;
+    #[cfg(feature = "style-outline")]
+    OutlineBadgeSvgTemplateContext => height: |t: &Self| t.badge_height as u32, logo_y: 3.0;
+    #[cfg(feature = "style-monospace")]
+    MonospaceBadgeSvgTemplateContext => height: |t: &Self| t.badge_height as u32, logo_y: 3.0;
+    #[cfg(feature = "style-plastic")]
+    PlasticBadgeSvgTemplateContext => height: |_: &Self| 18, logo_y: 2.0;
+    #[cfg(feature = "style-for-the-badge")]
+    ForTheBadgeSvgTemplateContext => height: |_: &Self| 28, logo_y: 7.0;
+}
+
+#[cfg(feature = "style-social")]
+impl Geometry for SocialBadgeSvgTemplateContext<'_> {
+    fn layout(&self) -> BadgeLayout {
+        let width = self.total_width.max(0) as u32;
+        let label_width = (self.label_rect_width.max(0) as u32).min(width);
+        BadgeLayout {
+            width,
+            height: self.total_height.max(0) as u32,
+            label_width,
+            message_width: width - label_width,
+            label_x: self.label_text_x / 10.0,
+            message_x: self.message_text_x / 10.0,
+            logo: logo_box(
+                self.logo,
+                self.logo_glyph,
+                self.logo_x as f32,
+                self.logo_glyph_x,
+                self.logo_width,
+                3.0,
+                LOGO_HEIGHT as f32,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "style-for-the-badge-two-line")]
+impl Geometry for ForTheBadgeTwoLineSvgTemplateContext<'_> {
+    fn layout(&self) -> BadgeLayout {
+        let width = self.total_width.max(0) as u32;
+        BadgeLayout {
+            width,
+            height: 40,
+            label_width: width,
+            message_width: width,
+            label_x: self.text_x as f32 / 10.0,
+            message_x: self.text_x as f32 / 10.0,
+            logo: logo_box(
+                self.logo,
+                self.logo_glyph,
+                self.logo_x as f32,
+                self.logo_glyph_x,
+                self.logo_width,
+                8.0,
+                24.0,
+            ),
+        }
+    }
 }
 
 // --- Color processing utility module ---
 // Supports standardization and SVG output of named colors, aliases, hex, and CSS color inputs
 
 mod color_util {
+    use crate::cache::ShardedLru;
+    use arc_swap::ArcSwap;
     use csscolorparser::Color;
-    use lru::LruCache;
     use once_cell::sync::Lazy;
     use std::collections::HashMap;
-    use std::num::NonZeroUsize;
     use std::str::FromStr;
-    use std::sync::Mutex;
 
     // Named color mapping
     pub static NAMED_COLORS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
@@ -276,20 +866,83 @@ mod color_util {
         Color::from_str(s).is_ok()
     }
 
+    /// User-registered names mapped to the SVG color they resolve to.
+    pub static CUSTOM_COLORS: Lazy<ArcSwap<HashMap<String, String>>> = Lazy::new(ArcSwap::default);
+
+    static NORMALIZE_CACHE: Lazy<ShardedLru<String, Option<String>>> =
+        Lazy::new(|| ShardedLru::new(crate::CacheCapacity::default().colors));
+
+    static SVG_COLOR_CACHE: Lazy<ShardedLru<String, Option<String>>> =
+        Lazy::new(|| ShardedLru::new(crate::CacheCapacity::default().colors));
+
+    /// Resizes both color caches to hold about `capacity` entries each.
+    pub fn set_cache_capacity(capacity: usize) {
+        NORMALIZE_CACHE.set_capacity(capacity);
+        SVG_COLOR_CACHE.set_capacity(capacity);
+    }
+
+    /// Registers `name` as a color resolving to `color`, which may itself be a named color,
+    /// alias, hex or CSS color. Returns `false`, registering nothing, if `color` is not a
+    /// recognized color or `name` is empty or a built-in name or alias.
+    #[cfg(feature = "unstable")]
+    pub fn register_color(name: &str, color: &str) -> bool {
+        let name = name.trim().to_ascii_lowercase();
+        if name.is_empty()
+            || NAMED_COLORS.contains_key(name.as_str())
+            || ALIASES.contains_key(name.as_str())
+        {
+            return false;
+        }
+        let Some(color) = to_svg_color(color) else {
+            return false;
+        };
+        CUSTOM_COLORS.rcu(|colors| {
+            let mut colors = HashMap::clone(colors);
+            colors.insert(name.clone(), color.clone());
+            colors
+        });
+        clear_caches();
+        true
+    }
+
+    /// Removes a registered color name, returning whether it was registered.
+    #[cfg(feature = "unstable")]
+    pub fn unregister_color(name: &str) -> bool {
+        let name = name.trim().to_ascii_lowercase();
+        if !CUSTOM_COLORS.load().contains_key(&name) {
+            return false;
+        }
+        CUSTOM_COLORS.rcu(|colors| {
+            let mut colors = HashMap::clone(colors);
+            colors.remove(&name);
+            colors
+        });
+        clear_caches();
+        true
+    }
+
+    /// Removes all registered color names.
+    #[cfg(feature = "unstable")]
+    pub fn clear_colors() {
+        CUSTOM_COLORS.store(Default::default());
+        clear_caches();
+    }
+
+    #[cfg(feature = "unstable")]
+    fn clear_caches() {
+        NORMALIZE_CACHE.clear();
+        SVG_COLOR_CACHE.clear();
+    }
+
     /// Standardizes color input, returning a string usable in SVG or None
     pub fn normalize_color(color: &str) -> Option<String> {
-        static CACHE: Lazy<Mutex<LruCache<String, Option<String>>>> =
-            Lazy::new(|| Mutex::new(LruCache::new(NonZeroUsize::new(512).unwrap())));
         let color = color.trim();
         if color.is_empty() {
             return None;
         }
         let key = color.to_ascii_lowercase();
         // Check cache first
-        if let Some(cached) = {
-            let mut cache = CACHE.lock().unwrap();
-            cache.get(&key).cloned()
-        } {
+        if let Some(cached) = NORMALIZE_CACHE.get(&key) {
             return cached;
         }
         // Allocate only if there are uppercase letters
@@ -298,6 +951,8 @@ mod color_util {
             Some(lower.to_string())
         } else if let Some(&alias) = ALIASES.get(lower.as_str()) {
             Some(alias.to_string())
+        } else if CUSTOM_COLORS.load().contains_key(lower.as_str()) {
+            Some(lower.to_string())
         } else if is_valid_hex(lower.as_str()) {
             let hex = lower.trim_start_matches('#');
             Some(format!("#{}", hex))
@@ -306,20 +961,14 @@ mod color_util {
         } else {
             None
         };
-        let mut cache = CACHE.lock().unwrap();
-        cache.put(key, result.clone());
+        NORMALIZE_CACHE.put(key, result.clone());
         result
     }
 
     /// Outputs SVG-compatible color (hex string), prioritizing named colors and aliases, otherwise original
     pub fn to_svg_color(color: &str) -> Option<String> {
-        static CACHE: Lazy<Mutex<LruCache<String, Option<String>>>> =
-            Lazy::new(|| Mutex::new(LruCache::new(NonZeroUsize::new(256).unwrap())));
         let key = color.to_ascii_lowercase();
-        if let Some(cached) = {
-            let mut cache = CACHE.lock().unwrap();
-            cache.get(&key).cloned()
-        } {
+        if let Some(cached) = SVG_COLOR_CACHE.get(&key) {
             return cached;
         }
         let normalized = normalize_color(color)?;
@@ -327,11 +976,12 @@ mod color_util {
             Some(hex.to_string())
         } else if let Some(&alias) = ALIASES.get(normalized.as_str()) {
             NAMED_COLORS.get(alias).map(|&h| h.to_string())
+        } else if let Some(custom) = CUSTOM_COLORS.load().get(normalized.as_str()) {
+            Some(custom.clone())
         } else {
             Some(normalized)
         };
-        let mut cache = CACHE.lock().unwrap();
-        cache.put(key, result.clone());
+        SVG_COLOR_CACHE.put(key, result.clone());
         result
     }
 }
@@ -352,6 +1002,220 @@ pub enum Font {
     VerdanaNormal10,
     /// Verdana 10px Bold
     VerdanaBold10,
+    /// Monospace 11px Normal, with DejaVu Sans Mono metrics; East Asian wide characters the
+    /// font lacks take two cells, as in terminals
+    MonospaceNormal11,
+    /// Verdana 11px Bold, scaled from the 10px table
+    VerdanaBold11,
+    /// Helvetica 10px Bold, scaled from the 11px table
+    HelveticaBold10,
+    /// DejaVu Sans 11px Normal
+    DejaVuSansNormal11,
+    /// DejaVu Sans 11px Bold
+    DejaVuSansBold11,
+    /// DejaVu Sans 10px Normal
+    DejaVuSansNormal10,
+    /// DejaVu Sans 10px Bold
+    DejaVuSansBold10,
+}
+
+impl Font {
+    /// Every embedded font, e.g. for warming up width tables.
+    pub const ALL: [Font; 11] = [
+        Font::VerdanaNormal11,
+        Font::HelveticaBold11,
+        Font::VerdanaNormal10,
+        Font::VerdanaBold10,
+        Font::MonospaceNormal11,
+        Font::VerdanaBold11,
+        Font::HelveticaBold10,
+        Font::DejaVuSansNormal11,
+        Font::DejaVuSansBold11,
+        Font::DejaVuSansNormal10,
+        Font::DejaVuSansBold10,
+    ];
+}
+
+/// Font family used for a badge's text, selected with [`BadgeParams::font`].
+///
+/// Each family brings its own width tables, so the badge is sized for the font it names. Only
+/// a bold Helvetica table is embedded, so Helvetica text is always set in bold.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum FontFamily {
+    /// Verdana, the default of every style but `social`.
+    Verdana,
+    /// Helvetica (bold), the default of the `social` style.
+    Helvetica,
+    /// DejaVu Sans, the default sans-serif font of many Linux systems.
+    DejaVuSans,
+}
+
+impl FontFamily {
+    /// Every font family.
+    pub const ALL: [FontFamily; 3] = [
+        FontFamily::Verdana,
+        FontFamily::Helvetica,
+        FontFamily::DejaVuSans,
+    ];
+
+    /// Name used in queries and on the command line, e.g. `dejavu-sans`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FontFamily::Verdana => "verdana",
+            FontFamily::Helvetica => "helvetica",
+            FontFamily::DejaVuSans => "dejavu-sans",
+        }
+    }
+
+    /// CSS `font-family` list written into the badge, falling back to similar fonts.
+    fn css(self) -> &'static str {
+        match self {
+            FontFamily::Verdana => FONT_FAMILY,
+            FontFamily::Helvetica => HELVETICA_FONT_FAMILY,
+            FontFamily::DejaVuSans => DEJAVU_SANS_FONT_FAMILY,
+        }
+    }
+
+    /// Whether the text must be set in bold because the family has no other embedded weight.
+    #[cfg(proportional_style)]
+    fn bold_only(self) -> bool {
+        self == FontFamily::Helvetica
+    }
+
+    /// Width table for text in this family at `size` px (10 or 11).
+    #[cfg(proportional_style)]
+    fn font(self, bold: bool, size: u32) -> Font {
+        match (self, bold || self.bold_only(), size) {
+            (FontFamily::Verdana, false, 10) => Font::VerdanaNormal10,
+            (FontFamily::Verdana, true, 10) => Font::VerdanaBold10,
+            (FontFamily::Verdana, false, _) => Font::VerdanaNormal11,
+            (FontFamily::Verdana, true, _) => Font::VerdanaBold11,
+            (FontFamily::Helvetica, _, 10) => Font::HelveticaBold10,
+            (FontFamily::Helvetica, _, _) => Font::HelveticaBold11,
+            (FontFamily::DejaVuSans, false, 10) => Font::DejaVuSansNormal10,
+            (FontFamily::DejaVuSans, true, 10) => Font::DejaVuSansBold10,
+            (FontFamily::DejaVuSans, false, _) => Font::DejaVuSansNormal11,
+            (FontFamily::DejaVuSans, true, _) => Font::DejaVuSansBold11,
+        }
+    }
+}
+
+impl fmt::Display for FontFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FontFamily {
+    type Err = ParseFontFamilyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FontFamily::ALL
+            .into_iter()
+            .find(|family| family.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseFontFamilyError(s.to_string()))
+    }
+}
+
+/// Error returned when parsing an unknown [`FontFamily`] name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFontFamilyError(String);
+
+impl fmt::Display for ParseFontFamilyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown font family `{}` (expected verdana, helvetica or dejavu-sans)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseFontFamilyError {}
+
+/// Width tables installed with [`set_font_table`], keyed by the font they replace.
+static FONT_TABLES: Lazy<ArcSwap<HashMap<Font, Arc<CharWidthMeasurer>>>> =
+    Lazy::new(ArcSwap::default);
+
+/// Text widths keyed by (text, font); cleared whenever a width table changes.
+static WIDTH_CACHE: Lazy<ShardedLru<(String, Font), u32>> =
+    Lazy::new(|| ShardedLru::new(CacheCapacity::default().widths));
+
+/// Number of entries kept by each of the crate's global caches.
+///
+/// Every cache is split into independently locked shards, so rendering from many threads
+/// rarely contends on them. A capacity of zero disables that cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheCapacity {
+    /// Measured text widths, keyed by text and font.
+    pub widths: usize,
+    /// Normalized colors; the normalized and SVG forms are cached separately, each this large.
+    pub colors: usize,
+    /// Encoded logos, keyed by slug and fill color.
+    pub logos: usize,
+}
+
+impl Default for CacheCapacity {
+    fn default() -> Self {
+        CacheCapacity {
+            widths: 1024,
+            colors: 512,
+            logos: 256,
+        }
+    }
+}
+
+/// Resizes the crate's global caches, evicting least recently used entries to fit.
+///
+/// Raise the capacities for services rendering many distinct badges; lower them (or disable
+/// caching with zero) where memory matters more than render time.
+///
+/// ## Example
+/// ```rust
+/// use shields::{CacheCapacity, set_cache_capacity};
+///
+/// set_cache_capacity(CacheCapacity {
+///     widths: 16_384,
+///     ..Default::default()
+/// });
+/// ```
+pub fn set_cache_capacity(capacity: CacheCapacity) {
+    WIDTH_CACHE.set_capacity(capacity.widths);
+    color_util::set_cache_capacity(capacity.colors);
+    logos::set_cache_capacity(capacity.logos);
+}
+
+/// Installs a width table used for `font` by every render instead of the embedded one, or
+/// restores the embedded table with `None`.
+///
+/// Use this for fonts whose metrics differ from the bundled tables, e.g. a table generated for
+/// your own renderer and loaded with [`CharWidthMeasurer::load_sync`]. To override fonts for a
+/// single renderer only, see `RendererConfig::with_font` (`unstable` feature).
+///
+/// ## Example
+/// ```rust
+/// use shields::measurer::CharWidthMeasurer;
+/// use shields::{Font, get_text_width, set_font_table};
+///
+/// let wide = CharWidthMeasurer::from_data(vec![(32, 126, 10.0)]);
+/// set_font_table(Font::VerdanaNormal11, Some(wide));
+/// assert_eq!(get_text_width("abc", Font::VerdanaNormal11), 30.0);
+///
+/// set_font_table(Font::VerdanaNormal11, None);
+/// assert!(get_text_width("abc", Font::VerdanaNormal11) < 30.0);
+/// ```
+pub fn set_font_table(font: Font, table: Option<CharWidthMeasurer>) {
+    let table = table.map(Arc::new);
+    FONT_TABLES.rcu(|tables| {
+        let mut tables = HashMap::clone(tables);
+        match &table {
+            Some(table) => tables.insert(font.clone(), table.clone()),
+            None => tables.remove(&font),
+        };
+        tables
+    });
+    WIDTH_CACHE.clear();
 }
 
 /// Calculates the width of text in Verdana 11px (in pixels)
@@ -359,41 +1223,117 @@ pub enum Font {
 /// - Only the text needs to be passed in, the width table is loaded and reused internally
 /// - Efficient lazy initialization to avoid repeated IO
 /// - Can be directly used in scenarios like SVG badges
+/// - Tables of fonts no enabled `style-*` feature uses are not embedded; such fonts are
+///   measured with the Verdana 11px table unless one is installed with [`set_font_table`]
 pub fn get_text_width(text: &str, font: Font) -> f64 {
-    use crate::measurer::CharWidthMeasurer;
-    use once_cell::sync::Lazy;
-
-    // 在编译时直接将 JSON 文件内容作为字符串嵌入
-    const VERDANA_11_N_JSON_DATA: &str = include_str!("../assets/fonts/verdana-11px-normal.json");
-    const HELVETICA_11_B_JSON_DATA: &str = include_str!("../assets/fonts/helvetica-11px-bold.json");
-    const VERDANA_10_N_JSON_DATA: &str = include_str!("../assets/fonts/verdana-10px-normal.json");
-    const VERDANA_10_B_JSON_DATA: &str = include_str!("../assets/fonts/verdana-10px-bold.json");
+    // Binary tables generated by build.rs from assets/fonts, loaded without parsing or copying
+    const VERDANA_11_N_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/verdana-11px-normal.bin"));
+    #[cfg(width_table = "helvetica-11px-bold")]
+    const HELVETICA_11_B_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/helvetica-11px-bold.bin"));
+    #[cfg(width_table = "verdana-10px-normal")]
+    const VERDANA_10_N_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/verdana-10px-normal.bin"));
+    #[cfg(width_table = "verdana-10px-bold")]
+    const VERDANA_10_B_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/verdana-10px-bold.bin"));
+    #[cfg(width_table = "monospace-11px-normal")]
+    const MONOSPACE_11_N_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/monospace-11px-normal.bin"));
+    #[cfg(width_table = "dejavu-sans-11px-normal")]
+    const DEJAVU_11_N_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/dejavu-sans-11px-normal.bin"));
+    #[cfg(width_table = "dejavu-sans-11px-bold")]
+    const DEJAVU_11_B_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/dejavu-sans-11px-bold.bin"));
+    #[cfg(width_table = "dejavu-sans-10px-normal")]
+    const DEJAVU_10_N_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/dejavu-sans-10px-normal.bin"));
+    #[cfg(width_table = "dejavu-sans-10px-bold")]
+    const DEJAVU_10_B_TABLE: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/dejavu-sans-10px-bold.bin"));
     static VERDANA_11_N_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
-        // 从嵌入的字符串加载数据，而不是从文件系统
-        CharWidthMeasurer::load_from_str(VERDANA_11_N_JSON_DATA)
+        CharWidthMeasurer::from_binary(VERDANA_11_N_TABLE)
             .expect("Unable to parse Verdana 11px width table")
     });
 
+    #[cfg(width_table = "helvetica-11px-bold")]
     static HELVETICA_11_B_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
-        // 从嵌入的字符串加载数据
-        CharWidthMeasurer::load_from_str(HELVETICA_11_B_JSON_DATA)
+        CharWidthMeasurer::from_binary(HELVETICA_11_B_TABLE)
             .expect("Unable to parse Helvetica Bold width table")
     });
+    #[cfg(width_table = "verdana-10px-normal")]
     static VERDANA_10_N_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
-        CharWidthMeasurer::load_from_str(VERDANA_10_N_JSON_DATA)
+        CharWidthMeasurer::from_binary(VERDANA_10_N_TABLE)
             .expect("Unable to parse Verdana 10px width table")
     });
 
+    #[cfg(width_table = "verdana-10px-bold")]
     static VERDANA_10_B_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
-        CharWidthMeasurer::load_from_str(VERDANA_10_B_JSON_DATA)
+        CharWidthMeasurer::from_binary(VERDANA_10_B_TABLE)
             .expect("Unable to parse Verdana 10px Bold width table")
     });
 
+    #[cfg(width_table = "monospace-11px-normal")]
+    static MONOSPACE_11_N_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
+        CharWidthMeasurer::from_binary(MONOSPACE_11_N_TABLE)
+            .expect("Unable to parse monospace 11px width table")
+    });
+
+    #[cfg(width_table = "dejavu-sans-11px-normal")]
+    static DEJAVU_11_N_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
+        CharWidthMeasurer::from_binary(DEJAVU_11_N_TABLE)
+            .expect("Unable to parse DejaVu Sans 11px width table")
+    });
+
+    #[cfg(width_table = "dejavu-sans-11px-bold")]
+    static DEJAVU_11_B_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
+        CharWidthMeasurer::from_binary(DEJAVU_11_B_TABLE)
+            .expect("Unable to parse DejaVu Sans 11px Bold width table")
+    });
+
+    #[cfg(width_table = "dejavu-sans-10px-normal")]
+    static DEJAVU_10_N_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
+        CharWidthMeasurer::from_binary(DEJAVU_10_N_TABLE)
+            .expect("Unable to parse DejaVu Sans 10px width table")
+    });
+
+    #[cfg(width_table = "dejavu-sans-10px-bold")]
+    static DEJAVU_10_B_WIDTH_TABLE: Lazy<CharWidthMeasurer> = Lazy::new(|| {
+        CharWidthMeasurer::from_binary(DEJAVU_10_B_TABLE)
+            .expect("Unable to parse DejaVu Sans 10px Bold width table")
+    });
+
+    if let Some(table) = FONT_TABLES.load().get(&font) {
+        return table.width_of(text, true);
+    }
     match font {
         Font::VerdanaNormal11 => VERDANA_11_N_WIDTH_TABLE.width_of(text, true),
+        #[cfg(width_table = "helvetica-11px-bold")]
         Font::HelveticaBold11 => HELVETICA_11_B_WIDTH_TABLE.width_of(text, true),
+        #[cfg(width_table = "verdana-10px-normal")]
         Font::VerdanaNormal10 => VERDANA_10_N_WIDTH_TABLE.width_of(text, true),
+        #[cfg(width_table = "verdana-10px-bold")]
         Font::VerdanaBold10 => VERDANA_10_B_WIDTH_TABLE.width_of(text, true),
+        #[cfg(width_table = "monospace-11px-normal")]
+        Font::MonospaceNormal11 => MONOSPACE_11_N_WIDTH_TABLE.width_of(text, true),
+        // Both faces scale linearly between sizes, hinting aside
+        #[cfg(width_table = "verdana-10px-bold")]
+        Font::VerdanaBold11 => VERDANA_10_B_WIDTH_TABLE.width_of(text, true) * 1.1,
+        #[cfg(width_table = "helvetica-11px-bold")]
+        Font::HelveticaBold10 => HELVETICA_11_B_WIDTH_TABLE.width_of(text, true) * 10.0 / 11.0,
+        #[cfg(width_table = "dejavu-sans-11px-normal")]
+        Font::DejaVuSansNormal11 => DEJAVU_11_N_WIDTH_TABLE.width_of(text, true),
+        #[cfg(width_table = "dejavu-sans-11px-bold")]
+        Font::DejaVuSansBold11 => DEJAVU_11_B_WIDTH_TABLE.width_of(text, true),
+        #[cfg(width_table = "dejavu-sans-10px-normal")]
+        Font::DejaVuSansNormal10 => DEJAVU_10_N_WIDTH_TABLE.width_of(text, true),
+        #[cfg(width_table = "dejavu-sans-10px-bold")]
+        Font::DejaVuSansBold10 => DEJAVU_10_B_WIDTH_TABLE.width_of(text, true),
+        // Tables no enabled style measures with are not embedded
+        #[allow(unreachable_patterns)]
+        _ => VERDANA_11_N_WIDTH_TABLE.width_of(text, true),
     }
 }
 macro_rules! round_up_to_odd_float {
@@ -410,11 +1350,43 @@ macro_rules! round_up_to_odd_float {
 }
 
 round_up_to_odd_float!(round_up_to_odd_f64, f64);
+#[cfg(any(flat_layout, feature = "style-social"))]
 const BADGE_HEIGHT: u32 = 20;
 const HORIZONTAL_PADDING: u32 = 5;
+/// Extra space at both ends of pill badges, keeping text clear of the rounded ends.
+#[cfg(feature = "style-pill")]
+const PILL_END_PADDING: i32 = 4;
+/// Height of logos, and their default width, in pixels.
+const LOGO_HEIGHT: u32 = 14;
+/// Width of sparklines drawn after the message, in pixels.
+#[cfg(classic_layout)]
+const SPARKLINE_WIDTH: u32 = 40;
 const FONT_FAMILY: &str = "Verdana,Geneva,DejaVu Sans,sans-serif";
+const HELVETICA_FONT_FAMILY: &str = "Helvetica Neue,Helvetica,Arial,sans-serif";
+const DEJAVU_SANS_FONT_FAMILY: &str = "DejaVu Sans,Verdana,Geneva,sans-serif";
+const MONOSPACE_FONT_FAMILY: &str = "DejaVu Sans Mono,Menlo,Consolas,Liberation Mono,monospace";
+#[cfg(flat_layout)]
 const FONT_SIZE_SCALED: u32 = 110;
+#[cfg(any_style)]
 const FONT_SCALE_UP_FACTOR: u32 = 10;
+/// Normalizes a color the way badges interpret it.
+///
+/// Accepts the named colors and aliases shields.io understands (e.g. `brightgreen`, `critical`),
+/// names registered with `color::register` (`unstable` feature), 3 or 6 digit hex with or
+/// without `#`, and CSS color syntax. Returns `None` for anything else; badges fall back to
+/// their default color in that case.
+///
+/// ## Example
+/// ```rust
+/// use shields::normalize_color;
+/// assert_eq!(normalize_color("Critical").as_deref(), Some("red"));
+/// assert_eq!(normalize_color("4c1").as_deref(), Some("#4c1"));
+/// assert_eq!(normalize_color("not-a-color"), None);
+/// ```
+pub fn normalize_color(color: &str) -> Option<String> {
+    color_util::normalize_color(color)
+}
+
 /// Dynamically calculates foreground and shadow colors based on background color (equivalent to JS colorsForBackground)
 ///
 /// - Input: hex color string (supports 3/6 digits, e.g. "#4c1", "#007ec6")
@@ -422,7 +1394,18 @@ const FONT_SCALE_UP_FACTOR: u32 = 10;
 ///   1. Parses hex to RGB
 ///   2. Calculates brightness = (0.299*R + 0.587*G + 0.114*B) / 255
 ///   3. If brightness ≤ 0.69, returns ("#fff", "#010101"), otherwise ("#333", "#ccc")
+///
+/// Rendering can use another threshold and colors through [`RenderOptions::text_colors`].
 pub fn colors_for_background(hex: &str) -> (&'static str, &'static str) {
+    if background_brightness(hex) <= 0.69 {
+        ("#fff", "#010101")
+    } else {
+        ("#333", "#ccc")
+    }
+}
+
+/// Brightness of a 3 or 6 digit hex color from 0.0 to 1.0, or 0.0 for anything else.
+fn background_brightness(hex: &str) -> f32 {
     // Remove leading #
     let hex = hex.trim_start_matches('#');
     // Parse RGB
@@ -467,44 +1450,186 @@ pub fn colors_for_background(hex: &str) -> (&'static str, &'static str) {
         _ => (0, 0, 0), // Invalid input, return black
     };
     // W3C recommended brightness formula
-    let brightness = (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0;
-    if brightness <= 0.69 {
-        ("#fff", "#010101")
-    } else {
-        ("#333", "#ccc")
-    }
+    (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) / 255.0
 }
-pub(crate) fn preferred_width_of(text: &str, font: Font) -> u32 {
-    use lru::LruCache;
-    use once_cell::sync::Lazy;
-    use std::num::NonZeroUsize;
-    use std::sync::Mutex;
 
-    // Create a cache that includes font information in the key
-    static WIDTH_CACHE: Lazy<Mutex<LruCache<(String, Font), u32>>> =
-        Lazy::new(|| Mutex::new(LruCache::new(NonZeroUsize::new(1024).unwrap())));
-
-    let cache_key = (text.to_string(), font.clone());
+/// Text and shadow colors chosen by background brightness, as [`colors_for_background`]
+/// does with its fixed values.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, RenderOptions, TextColors, render_badge_svg_with_options};
+/// let params = BadgeParams {
+///     message: Some("beta"),
+///     message_color: Some("#ffb000"),
+///     ..Default::default()
+/// };
+/// // Keep light text on this orange, which is brighter than the default threshold
+/// let colors = TextColors {
+///     threshold: 0.8,
+///     ..Default::default()
+/// };
+/// let svg = render_badge_svg_with_options(&params, &RenderOptions::new().text_colors(colors));
+/// assert!(svg.contains(r##"fill="#fff" textLength"##));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextColors {
+    /// Brightness from 0.0 to 1.0 up to which a background gets the light text. Defaults to
+    /// 0.69.
+    pub threshold: f32,
+    /// Text color on backgrounds up to the threshold. Defaults to `#fff`.
+    pub light_text: String,
+    /// Shadow color under the light text. Defaults to `#010101`.
+    pub light_shadow: String,
+    /// Text color on backgrounds brighter than the threshold. Defaults to `#333`.
+    pub dark_text: String,
+    /// Shadow color under the dark text. Defaults to `#ccc`.
+    pub dark_shadow: String,
+}
 
-    {
-        let mut cache = WIDTH_CACHE.lock().unwrap();
-        if let Some(&cached) = cache.get(&cache_key) {
-            return cached;
+impl TextColors {
+    /// Returns the text and shadow colors for text on `hex` (3 or 6 digit hex).
+    pub fn for_background(&self, hex: &str) -> (&str, &str) {
+        if background_brightness(hex) <= self.threshold {
+            (&self.light_text, &self.light_shadow)
+        } else {
+            (&self.dark_text, &self.dark_shadow)
         }
     }
+}
 
-    let width = get_text_width(text, font);
-    let rounded = round_up_to_odd_f64(width);
-
-    if text.len() <= 1024 {
-        let mut cache = WIDTH_CACHE.lock().unwrap();
-        cache.put(cache_key, rounded);
+impl Default for TextColors {
+    fn default() -> Self {
+        TextColors {
+            threshold: 0.69,
+            light_text: "#fff".to_string(),
+            light_shadow: "#010101".to_string(),
+            dark_text: "#333".to_string(),
+            dark_shadow: "#ccc".to_string(),
+        }
+    }
+}
+
+/// A stop of the gloss gradient drawn over [`BadgeStyle::Plastic`] badges, from the top
+/// (offset 0) to the bottom (offset 1) of the badge.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(all(feature = "style-flat", feature = "style-plastic"))] {
+/// use shields::{BadgeParams, BadgeStyle, GradientStop, RenderOptions, render_badge_svg_with_options};
+/// let params = BadgeParams {
+///     style: BadgeStyle::Plastic,
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// // A faint highlight, without the dark bottom edge
+/// let gloss = vec![
+///     GradientStop::new(0.0, "#fff", 0.3),
+///     GradientStop::new(0.5, "#fff", 0.0),
+/// ];
+/// let options = RenderOptions::new().plastic_gradient(gloss);
+/// let svg = render_badge_svg_with_options(&params, &options);
+/// assert!(svg.contains(r##"<stop offset=".5" stop-color="#fff" stop-opacity="0"/>"##));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientStop {
+    /// Position from 0.0 to 1.0.
+    pub offset: f32,
+    /// Color, in any form badge colors accept. Black if it is not a valid color.
+    pub color: String,
+    /// Opacity from 0.0 to 1.0.
+    pub opacity: f32,
+}
+
+impl GradientStop {
+    /// A stop of `color` at `offset` with `opacity`.
+    pub fn new(offset: f32, color: impl Into<String>, opacity: f32) -> Self {
+        GradientStop {
+            offset,
+            color: color.into(),
+            opacity,
+        }
+    }
+
+    /// The plastic style's standard gloss: a white highlight along the top and a darkened
+    /// bottom edge.
+    pub fn plastic() -> Vec<GradientStop> {
+        vec![
+            GradientStop::new(0.0, "#fff", 0.7),
+            GradientStop::new(0.1, "#aaa", 0.1),
+            GradientStop::new(0.9, "#000", 0.3),
+            GradientStop::new(1.0, "#000", 0.5),
+        ]
+    }
+}
+
+/// The default plastic gloss, and its `<stop>` elements.
+#[cfg(feature = "style-plastic")]
+static PLASTIC_GRADIENT: Lazy<(Vec<GradientStop>, String)> = Lazy::new(|| {
+    let stops = GradientStop::plastic();
+    let elements = write_gradient_stops(&stops);
+    (stops, elements)
+});
+
+/// `<stop>` elements for `stops`, written once for the default plastic gloss.
+#[cfg(feature = "style-plastic")]
+fn gradient_stops(stops: &[GradientStop]) -> Cow<'static, str> {
+    let (plastic, elements) = &*PLASTIC_GRADIENT;
+    if stops == plastic.as_slice() {
+        Cow::Borrowed(elements)
+    } else {
+        Cow::Owned(write_gradient_stops(stops))
+    }
+}
+
+/// `<stop>` elements for `stops`, with offsets and opacities clamped to 0..=1.
+#[cfg(feature = "style-plastic")]
+fn write_gradient_stops(stops: &[GradientStop]) -> String {
+    // Written like the SVG attributes they replace: `.5` rather than `0.5`
+    let number = |n: f32| {
+        let n = if n.is_finite() {
+            n.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let n = n.to_string();
+        match n.strip_prefix('0') {
+            Some(fraction) if !fraction.is_empty() => fraction.to_string(),
+            _ => n,
+        }
+    };
+    let mut out = String::new();
+    for stop in stops {
+        out.push_str(&format!(r#"<stop offset="{}""#, number(stop.offset)));
+        if let Some(color) = to_svg_color(&stop.color) {
+            out.push_str(&format!(r#" stop-color="{}""#, color));
+        }
+        out.push_str(&format!(r#" stop-opacity="{}"/>"#, number(stop.opacity)));
+    }
+    out
+}
+
+pub(crate) fn preferred_width_of(text: &str, font: Font) -> u32 {
+    let cache_key = (text.to_string(), font.clone());
+
+    if let Some(cached) = WIDTH_CACHE.get(&cache_key) {
+        return cached;
+    }
+
+    let width = get_text_width(text, font);
+    let rounded = round_up_to_odd_f64(width);
+
+    if text.len() <= 1024 {
+        WIDTH_CACHE.put(cache_key, rounded);
     }
 
     rounded
 }
 
-#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 /// Badge style variants supported by the shields crate.
 ///
@@ -513,6 +1638,10 @@ pub(crate) fn preferred_width_of(text: &str, font: Font) -> u32 {
 /// - `Plastic`: Classic plastic style.
 /// - `Social`: Social badge style (e.g., GitHub social).
 /// - `ForTheBadge`: All-caps, bold, attention-grabbing style.
+/// - `ForTheBadgeTwoLine`: For-the-badge with the label above the message.
+/// - `Pill`: Flat with fully rounded ends.
+/// - `Outline`: Transparent with colored text and border.
+/// - `Monospace`: Flat-square with monospace text, for command-line tools.
 ///
 /// ## Example
 /// ```rust
@@ -530,6 +1659,15 @@ pub enum BadgeStyle {
     Social,
     /// For-the-badge style, which is bold and all-caps.
     ForTheBadge,
+    /// Taller for-the-badge style with the label stacked above a larger message in one block,
+    /// for call-to-action badges such as "GET IT ON / GITHUB RELEASES".
+    ForTheBadgeTwoLine,
+    /// Flat colors with fully rounded ends and no gradient.
+    Pill,
+    /// Transparent background with the text and a 1px border in the badge colors.
+    Outline,
+    /// Square corners and text in a monospace font, optionally after a `$` prompt.
+    Monospace,
 }
 
 impl Default for BadgeStyle {
@@ -539,6 +1677,320 @@ impl Default for BadgeStyle {
     }
 }
 
+impl BadgeStyle {
+    /// Every style, in declaration order.
+    pub const ALL: [BadgeStyle; 9] = [
+        BadgeStyle::Flat,
+        BadgeStyle::FlatSquare,
+        BadgeStyle::Plastic,
+        BadgeStyle::Social,
+        BadgeStyle::ForTheBadge,
+        BadgeStyle::ForTheBadgeTwoLine,
+        BadgeStyle::Pill,
+        BadgeStyle::Outline,
+        BadgeStyle::Monospace,
+    ];
+
+    /// Returns the kebab-case name used in URLs and serialized params, e.g. `for-the-badge`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BadgeStyle::Flat => "flat",
+            BadgeStyle::FlatSquare => "flat-square",
+            BadgeStyle::Plastic => "plastic",
+            BadgeStyle::Social => "social",
+            BadgeStyle::ForTheBadge => "for-the-badge",
+            BadgeStyle::ForTheBadgeTwoLine => "for-the-badge-two-line",
+            BadgeStyle::Pill => "pill",
+            BadgeStyle::Outline => "outline",
+            BadgeStyle::Monospace => "monospace",
+        }
+    }
+}
+
+impl fmt::Display for BadgeStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing a [`BadgeStyle`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBadgeStyleError(String);
+
+impl fmt::Display for ParseBadgeStyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown style `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseBadgeStyleError {}
+
+impl FromStr for BadgeStyle {
+    type Err = ParseBadgeStyleError;
+
+    /// Parses a style name as written in URLs, e.g. `flat-square`, ignoring case and
+    /// surrounding whitespace.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use shields::BadgeStyle;
+    /// assert_eq!("for-the-badge".parse(), Ok(BadgeStyle::ForTheBadge));
+    /// assert_eq!(BadgeStyle::FlatSquare.to_string(), "flat-square");
+    /// assert!("glossy".parse::<BadgeStyle>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        BadgeStyle::ALL
+            .into_iter()
+            .find(|style| style.as_str().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseBadgeStyleError(s.to_string()))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+/// Small icons that can be drawn inside the message bubble of a [`BadgeStyle::Social`] badge.
+///
+/// Mirrors the star/fork/watch counters on GitHub's repository widgets.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(all(feature = "style-flat", feature = "style-social"))] {
+/// use shields::{BadgeParams, BadgeStyle, SocialIcon, render_badge_svg};
+/// let params = BadgeParams {
+///     style: BadgeStyle::Social,
+///     label: Some("stars"),
+///     message: Some("1.2k"),
+///     bubble_icon: Some(SocialIcon::Star),
+///     ..Default::default()
+/// };
+/// let svg = render_badge_svg(&params);
+/// assert!(svg.contains("1.2k"));
+/// # }
+/// ```
+pub enum SocialIcon {
+    /// Star (stargazers).
+    Star,
+    /// Fork (forks).
+    Fork,
+    /// Eye (watchers).
+    Eye,
+}
+
+impl SocialIcon {
+    /// Width and height of the icon inside the bubble, in pixels.
+    pub const SIZE: u32 = 10;
+
+    /// Returns the SVG path data of the icon, drawn on a 16x16 viewBox.
+    pub fn path(&self) -> &'static str {
+        match self {
+            SocialIcon::Star => {
+                "M8 .25a.75.75 0 0 1 .673.418l1.882 3.815 4.21.612a.75.75 0 0 1 .416 1.279l-3.046 2.97.719 4.192a.751.751 0 0 1-1.088.791L8 12.347l-3.766 1.98a.75.75 0 0 1-1.088-.79l.72-4.194L.818 6.374a.75.75 0 0 1 .416-1.28l4.21-.611L7.327.668A.75.75 0 0 1 8 .25Z"
+            }
+            SocialIcon::Fork => {
+                "M5 5.372v.878c0 .414.336.75.75.75h4.5a.75.75 0 0 0 .75-.75v-.878a2.25 2.25 0 1 1 1.5 0v.878a2.25 2.25 0 0 1-2.25 2.25h-1.5v2.128a2.251 2.251 0 1 1-1.5 0V8.5h-1.5A2.25 2.25 0 0 1 3.5 6.25v-.878a2.25 2.25 0 1 1 1.5 0ZM5 3.25a.75.75 0 1 0-1.5 0 .75.75 0 0 0 1.5 0Zm6.75.75a.75.75 0 1 0 0-1.5.75.75 0 0 0 0 1.5Zm-3 8.75a.75.75 0 1 0-1.5 0 .75.75 0 0 0 1.5 0Z"
+            }
+            SocialIcon::Eye => {
+                "M8 2c1.981 0 3.671.992 4.933 2.078 1.27 1.091 2.187 2.345 2.637 3.023a1.62 1.62 0 0 1 0 1.798c-.45.678-1.367 1.932-2.637 3.023C11.67 13.008 9.981 14 8 14c-1.981 0-3.671-.992-4.933-2.078C1.797 10.83.88 9.576.43 8.898a1.62 1.62 0 0 1 0-1.798c.45-.677 1.367-1.931 2.637-3.022C4.33 2.992 6.019 2 8 2ZM1.679 7.932a.12.12 0 0 0 0 .136c.411.622 1.241 1.75 2.366 2.717C5.176 11.758 6.527 12.5 8 12.5c1.473 0 2.825-.742 3.955-1.715 1.124-.967 1.954-2.096 2.366-2.717a.12.12 0 0 0 0-.136c-.412-.621-1.242-1.75-2.366-2.717C10.824 4.242 9.473 3.5 8 3.5c-1.473 0-2.825.742-3.955 1.715-1.124.967-1.954 2.096-2.366 2.717ZM8 10a2 2 0 1 1-.001-3.999A2 2 0 0 1 8 10Z"
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+/// How an image logo is sized when no explicit `logo_width` is given.
+///
+/// Logos are drawn 14px tall (24px in [`BadgeStyle::ForTheBadgeTwoLine`]) and, by default,
+/// squeezed into a square box.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, LogoSize, render_badge_svg};
+/// let wordmark = "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 48 12\"/>";
+/// let svg = render_badge_svg(&BadgeParams {
+///     label: Some("made with"),
+///     message: Some("love"),
+///     logo: Some(wordmark),
+///     logo_size: Some(LogoSize::Auto),
+///     ..Default::default()
+/// });
+/// assert!(svg.contains(r#"width="56" height="14""#));
+/// # }
+/// ```
+pub enum LogoSize {
+    /// Keep the logo's natural aspect ratio, read from its SVG `viewBox` (or `width` and
+    /// `height`) or its PNG or GIF header.
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Outcome of a build or check, with the message and color shields.io uses for it.
+///
+/// Converts into a `(message, color)` pair.
+///
+/// ## Example
+/// ```rust
+/// use shields::Status;
+/// let (message, color): (&str, &str) = Status::Failing.into();
+/// assert_eq!((message, color), ("failing", "red"));
+/// ```
+pub enum Status {
+    /// `passing`, bright green.
+    Passing,
+    /// `failing`, red.
+    Failing,
+    /// `error`, red.
+    Error,
+    /// `pending`, light grey.
+    Pending,
+    /// `unknown`, light grey.
+    Unknown,
+    /// `skipped`, light grey.
+    Skipped,
+}
+
+impl From<Status> for (&'static str, &'static str) {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Passing => ("passing", "brightgreen"),
+            Status::Failing => ("failing", "red"),
+            Status::Error => ("error", "red"),
+            Status::Pending => ("pending", "lightgrey"),
+            Status::Unknown => ("unknown", "lightgrey"),
+            Status::Skipped => ("skipped", "lightgrey"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The named colors shields.io defines, checked at compile time.
+///
+/// ## Example
+/// ```rust
+/// use shields::NamedColor;
+/// assert_eq!(NamedColor::BrightGreen.hex(), "#4c1");
+/// assert_eq!(NamedColor::BrightGreen.name(), "brightgreen");
+/// ```
+pub enum NamedColor {
+    /// `brightgreen`, `#4c1` (alias `success`).
+    BrightGreen,
+    /// `green`, `#97ca00`.
+    Green,
+    /// `yellow`, `#dfb317`.
+    Yellow,
+    /// `yellowgreen`, `#a4a61d`.
+    YellowGreen,
+    /// `orange`, `#fe7d37` (alias `important`).
+    Orange,
+    /// `red`, `#e05d44` (alias `critical`).
+    Red,
+    /// `blue`, `#007ec6` (alias `informational`).
+    Blue,
+    /// `grey`, `#555` (alias `gray`).
+    Grey,
+    /// `lightgrey`, `#9f9f9f` (aliases `lightgray` and `inactive`).
+    LightGrey,
+}
+
+impl NamedColor {
+    /// Returns the name badges accept for this color.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NamedColor::BrightGreen => "brightgreen",
+            NamedColor::Green => "green",
+            NamedColor::Yellow => "yellow",
+            NamedColor::YellowGreen => "yellowgreen",
+            NamedColor::Orange => "orange",
+            NamedColor::Red => "red",
+            NamedColor::Blue => "blue",
+            NamedColor::Grey => "grey",
+            NamedColor::LightGrey => "lightgrey",
+        }
+    }
+
+    /// Returns the hex value rendered for this color.
+    pub fn hex(&self) -> &'static str {
+        match self {
+            NamedColor::BrightGreen => "#4c1",
+            NamedColor::Green => "#97ca00",
+            NamedColor::Yellow => "#dfb317",
+            NamedColor::YellowGreen => "#a4a61d",
+            NamedColor::Orange => "#fe7d37",
+            NamedColor::Red => "#e05d44",
+            NamedColor::Blue => "#007ec6",
+            NamedColor::Grey => "#555",
+            NamedColor::LightGrey => "#9f9f9f",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+/// A badge color, accepted by the [`builder`] wherever a color string is.
+///
+/// Named and hex colors are resolved without parsing; strings convert to
+/// [`BadgeColor::Css`] and are interpreted like any color parameter, so names, aliases and
+/// hex strings keep working.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::builder::Badge;
+/// use shields::{BadgeColor, BadgeStyle, NamedColor};
+///
+/// let svg = Badge::style(BadgeStyle::Flat)
+///     .label("build")
+///     .message("passing")
+///     .label_color(BadgeColor::Hex(0x0f62fe))
+///     .message_color(NamedColor::BrightGreen)
+///     .build();
+/// assert!(svg.contains(r##"fill="#0f62fe""##));
+/// assert!(svg.contains(r##"fill="#4c1""##));
+/// # }
+/// ```
+pub enum BadgeColor {
+    /// One of the shields.io named colors.
+    Named(NamedColor),
+    /// A `0xRRGGBB` color.
+    Hex(u32),
+    /// Any color string a badge accepts: a name, alias, hex or CSS color.
+    Css(String),
+    /// The default color of the badge part.
+    #[default]
+    Default,
+}
+
+impl BadgeColor {
+    /// Returns the color string passed to rendering, or `None` for [`BadgeColor::Default`].
+    pub fn as_str(&self) -> Option<Cow<'_, str>> {
+        match self {
+            BadgeColor::Named(color) => Some(Cow::Borrowed(color.hex())),
+            BadgeColor::Hex(rgb) => Some(Cow::Owned(format!("#{:06x}", rgb & 0xff_ffff))),
+            BadgeColor::Css(color) => Some(Cow::Borrowed(color)),
+            BadgeColor::Default => None,
+        }
+    }
+}
+
+impl From<NamedColor> for BadgeColor {
+    fn from(color: NamedColor) -> Self {
+        BadgeColor::Named(color)
+    }
+}
+
+impl From<&str> for BadgeColor {
+    fn from(color: &str) -> Self {
+        BadgeColor::Css(color.to_string())
+    }
+}
+
+impl From<String> for BadgeColor {
+    fn from(color: String) -> Self {
+        BadgeColor::Css(color)
+    }
+}
+
 /// Returns the default message color hex string (`#007ec6`).
 pub fn default_message_color() -> &'static str {
     "#007ec6"
@@ -549,7 +2001,7 @@ pub fn default_label_color() -> &'static str {
     "#555"
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
 /// Parameters for generating a badge SVG.
 ///
 /// This struct is used to configure all aspects of a badge, including style, label, message, colors, links, and logo.
@@ -564,9 +2016,15 @@ pub fn default_label_color() -> &'static str {
 /// - `extra_link`: Optional secondary link URL.
 /// - `logo`: Optional logo name or SVG data.
 /// - `logo_color`: Optional logo color.
+/// - `bubble_icon`: Optional icon drawn next to the count in social badges.
+/// - `logo_width`: Optional logo width in pixels, for wide logos such as wordmarks.
+/// - `logo_size`: Optional logo sizing (see [`LogoSize`]).
+/// - `title`: Optional tooltip shown when hovering the badge.
+/// - `font`: Optional font family of the text (see [`FontFamily`]).
 ///
 /// ## Example
 /// ```rust
+/// # #[cfg(feature = "style-flat")] {
 /// use shields::{BadgeParams, BadgeStyle, render_badge_svg};
 /// let params = BadgeParams {
 ///     style: BadgeStyle::Flat,
@@ -578,9 +2036,11 @@ pub fn default_label_color() -> &'static str {
 ///     extra_link: None,
 ///     logo: None,
 ///     logo_color: None,
+///     ..Default::default()
 /// };
 /// let svg = render_badge_svg(&params);
 /// assert!(svg.contains("passing"));
+/// # }
 /// ```
 pub struct BadgeParams<'a> {
     #[serde(default)]
@@ -588,20 +2048,229 @@ pub struct BadgeParams<'a> {
     pub style: BadgeStyle,
     /// Optional label text (left side).
     pub label: Option<&'a str>,
-    /// Optional message text (right side).
+    /// Optional message text (right side). Without one, the label is drawn as a single
+    /// section in the label color.
     pub message: Option<&'a str>,
     /// Optional label color, defaults to `#555` (dark gray).
     pub label_color: Option<&'a str>,
     /// Optional message color, defaults to `#007ec6` (blue).
     pub message_color: Option<&'a str>,
-    /// Optional main link, used for linking the badge to a URL.
+    /// Optional main link, used for linking the badge to a URL. With an `extra_link` it only
+    /// covers the label. Without any link the badge has no `<a>` elements at all.
     pub link: Option<&'a str>,
-    /// Optional secondary link, used for social badges or additional information.
+    /// Optional secondary link, covering the message, used for social badges or additional
+    /// information.
     pub extra_link: Option<&'a str>,
-    /// Optional logo name (e.g., "github", "rust") or SVG data.
+    /// Optional logo: a slug (e.g., "github", "rust"), a comma-separated list of slugs shown side
+    /// by side (e.g., "rust,webassembly"), a single symbol, a base64 `data:image/...` URI or a
+    /// literal `<svg>` document.
     pub logo: Option<&'a str>,
     /// Optional logo color, defaults to `#000000` for social badges, otherwise `whitesmoke`.
+    /// `auto` uses the slug's brand color from the logo dataset, which is also used when no
+    /// color is given and the logo sits on a light background.
     pub logo_color: Option<&'a str>,
+    #[serde(default)]
+    /// Optional icon drawn inside the message bubble, only used by the social style.
+    pub bubble_icon: Option<SocialIcon>,
+    #[serde(default)]
+    /// Optional logo width in pixels at the standard 14px logo height (scaled along with the
+    /// logo in two-line badges); takes precedence over `logo_size`. Defaults to 14.
+    pub logo_width: Option<u32>,
+    #[serde(default)]
+    /// Optional logo sizing; [`LogoSize::Auto`] keeps the logo's aspect ratio.
+    pub logo_size: Option<LogoSize>,
+    #[serde(skip)]
+    /// Optional series drawn as a sparkline after the message, e.g. daily downloads. Only
+    /// finite values are plotted; ignored by the social and for-the-badge styles.
+    pub sparkline: Option<&'a [f64]>,
+    /// Optional tooltip shown when hovering the badge, in place of the `label: message` text.
+    /// It becomes the badge's accessible name as well, through `aria-labelledby`.
+    pub title: Option<&'a str>,
+    #[serde(default)]
+    /// Optional font family of the text, sized with that family's width tables. Defaults to
+    /// Helvetica for social badges and Verdana otherwise; monospace badges ignore it.
+    pub font: Option<FontFamily>,
+}
+
+impl BadgeParams<'_> {
+    /// Reads badge parameters from environment variables, for CI steps that render badges
+    /// without any argument plumbing.
+    ///
+    /// Each field is read from `{prefix}_{NAME}`: `LABEL`, `MESSAGE`, `COLOR` (message color),
+    /// `LABEL_COLOR`, `STYLE` (e.g. `for-the-badge`), `LOGO`, `LOGO_COLOR`, `LINK`,
+    /// `EXTRA_LINK`, `TITLE` and `FONT` (e.g. `dejavu-sans`). With an empty prefix the bare names
+    /// are used. Unset variables stay `None`, an unknown style falls back to the default style
+    /// and an unknown font is ignored.
+    ///
+    /// Environment values are owned, so this returns a [`BadgeParamsOwned`]; borrow it with
+    /// [`BadgeParamsOwned::as_params`] to render.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # #[cfg(feature = "style-flat")] {
+    /// use shields::{BadgeParams, render_badge_svg};
+    /// // e.g. SHIELDS_LABEL=coverage SHIELDS_MESSAGE=93% SHIELDS_COLOR=green
+    /// let params = BadgeParams::from_env("SHIELDS");
+    /// let svg = render_badge_svg(&params.as_params());
+    /// assert!(svg.starts_with("<svg"));
+    /// # }
+    /// ```
+    pub fn from_env(prefix: &str) -> BadgeParamsOwned {
+        BadgeParamsOwned::from_lookup(prefix, |key| std::env::var(key).ok())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq)]
+/// Owned counterpart of [`BadgeParams`], for values that don't outlive a borrow
+/// (environment variables, config files, request bodies).
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParamsOwned, render_badge_svg};
+/// let params = BadgeParamsOwned {
+///     label: Some(format!("v{}", 3)),
+///     message: Some("stable".to_string()),
+///     ..Default::default()
+/// };
+/// let svg = render_badge_svg(&params.as_params());
+/// assert!(svg.contains("v3"));
+/// # }
+/// ```
+pub struct BadgeParamsOwned {
+    #[serde(default)]
+    /// Badge style variant (default is `Flat`).
+    pub style: BadgeStyle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional label text (left side).
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional message text (right side).
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional label color.
+    pub label_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional message color.
+    pub message_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional main link.
+    pub link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional secondary link.
+    pub extra_link: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional logo name or SVG data.
+    pub logo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional logo color.
+    pub logo_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional icon drawn inside the message bubble, only used by the social style.
+    pub bubble_icon: Option<SocialIcon>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional logo width in pixels.
+    pub logo_width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional logo sizing.
+    pub logo_size: Option<LogoSize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional series drawn as a sparkline after the message.
+    pub sparkline: Option<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional tooltip shown when hovering the badge.
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Optional font family of the text.
+    pub font: Option<FontFamily>,
+}
+
+impl BadgeParamsOwned {
+    /// Borrows these parameters as a [`BadgeParams`] ready for rendering.
+    pub fn as_params(&self) -> BadgeParams<'_> {
+        BadgeParams {
+            style: self.style,
+            label: self.label.as_deref(),
+            message: self.message.as_deref(),
+            label_color: self.label_color.as_deref(),
+            message_color: self.message_color.as_deref(),
+            link: self.link.as_deref(),
+            extra_link: self.extra_link.as_deref(),
+            logo: self.logo.as_deref(),
+            logo_color: self.logo_color.as_deref(),
+            bubble_icon: self.bubble_icon,
+            logo_width: self.logo_width,
+            logo_size: self.logo_size,
+            sparkline: self.sparkline.as_deref(),
+            title: self.title.as_deref(),
+            font: self.font,
+        }
+    }
+
+    /// Builds parameters from `{prefix}_{NAME}` keys resolved by `lookup`.
+    fn from_lookup(prefix: &str, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| {
+            if prefix.is_empty() {
+                lookup(name)
+            } else {
+                lookup(&format!("{}_{}", prefix, name))
+            }
+        };
+        let style = var("STYLE")
+            .and_then(|style| style.parse().ok())
+            .unwrap_or_default();
+        BadgeParamsOwned {
+            style,
+            label: var("LABEL"),
+            message: var("MESSAGE"),
+            label_color: var("LABEL_COLOR"),
+            message_color: var("COLOR"),
+            link: var("LINK"),
+            extra_link: var("EXTRA_LINK"),
+            logo: var("LOGO"),
+            logo_color: var("LOGO_COLOR"),
+            bubble_icon: None,
+            logo_width: None,
+            logo_size: None,
+            sparkline: None,
+            title: var("TITLE"),
+            font: var("FONT").and_then(|font| font.parse().ok()),
+        }
+    }
+}
+
+impl From<&BadgeParams<'_>> for BadgeParamsOwned {
+    fn from(params: &BadgeParams<'_>) -> Self {
+        BadgeParamsOwned {
+            style: params.style,
+            label: params.label.map(str::to_string),
+            message: params.message.map(str::to_string),
+            label_color: params.label_color.map(str::to_string),
+            message_color: params.message_color.map(str::to_string),
+            link: params.link.map(str::to_string),
+            extra_link: params.extra_link.map(str::to_string),
+            logo: params.logo.map(str::to_string),
+            logo_color: params.logo_color.map(str::to_string),
+            bubble_icon: params.bubble_icon,
+            logo_width: params.logo_width,
+            logo_size: params.logo_size,
+            sparkline: params.sparkline.map(<[f64]>::to_vec),
+            title: params.title.map(str::to_string),
+            font: params.font,
+        }
+    }
+}
+
+impl From<BadgeParams<'_>> for BadgeParamsOwned {
+    fn from(params: BadgeParams<'_>) -> Self {
+        BadgeParamsOwned::from(&params)
+    }
+}
+
+impl<'a> From<&'a BadgeParamsOwned> for BadgeParams<'a> {
+    fn from(params: &'a BadgeParamsOwned) -> Self {
+        params.as_params()
+    }
 }
 
 /// Generate an SVG badge string from [`BadgeParams`].
@@ -614,6 +2283,7 @@ pub struct BadgeParams<'a> {
 ///
 /// ## Example
 /// ```rust
+/// # #[cfg(feature = "style-flat")] {
 /// use shields::{BadgeParams, BadgeStyle, render_badge_svg};
 /// let params = BadgeParams {
 ///     style: BadgeStyle::Flat,
@@ -625,680 +2295,4818 @@ pub struct BadgeParams<'a> {
 ///     extra_link: None,
 ///     logo: None,
 ///     logo_color: None,
+///     ..Default::default()
 /// };
 /// let svg = render_badge_svg(&params);
 /// assert!(svg.contains("passing"));
+/// # }
 /// ```
 pub fn render_badge_svg(params: &BadgeParams) -> String {
-    let BadgeParams {
-        style,
-        label,
-        message,
-        label_color,
-        message_color,
-        link,
-        extra_link,
-        logo,
-        logo_color,
-    } = params;
-    let label = *label;
-    let default_logo_color = if *style == BadgeStyle::Social {
-        "#000000"
-    } else {
-        "whitesmoke"
-    };
+    render_badge_svg_with(params, &RenderContext::default())
+}
 
-    let logo_color = logo_color.unwrap_or(default_logo_color);
-    let logo_color = to_svg_color(logo_color).unwrap_or(default_logo_color.to_string());
-    let icon_svg = match logo {
-        Some(logo) => {
-            let logo = logo.trim();
-            if logo.is_empty() {
-                ""
-            } else {
-                // let logo_color = logo_color.unwrap_or("#555");
-                // let icon = to_svg_color(logo_color).unwrap_or("#555".to_string());
-                let icon = logo;
-                let svg = simpleicons::Icon::get_svg(icon);
-                svg.unwrap_or_default()
-            }
-        }
-        None => "",
-    };
-    // 如果 logo 为 <svg 开头，则需要获取 base64 编码
-    // 通过 cargo add base64 来引入 base64 crate
-    let logo = if icon_svg.starts_with("<svg") {
-        let logo_svg = icon_svg.replace("<svg", format!("<svg fill=\"{}\"", logo_color).as_str());
-        let base64_logo = base64::engine::general_purpose::STANDARD.encode(logo_svg);
-        format!("data:image/svg+xml;base64,{}", base64_logo)
-    } else {
-        icon_svg.to_string()
-    };
-    let has_logo = !logo.is_empty();
-    let logo_width = 14;
-    let mut logo_padding = 3;
-    if label.is_some() && label.unwrap().is_empty() {
-        logo_padding = 0;
+/// Render a badge as SVG into an existing [`fmt::Write`] buffer, such as a response body
+/// reused across badges, without allocating the intermediate `String`.
+///
+/// Like [`render_badge_svg`], a badge that fails to render is written as an SVG comment; if
+/// this happens after output started, the comment follows the partial output.
+///
+/// # Arguments
+/// * `params` - Badge parameters (see [`BadgeParams`]).
+/// * `out` - Buffer the SVG is appended to.
+///
+/// # Returns
+/// An error only if `out` fails.
+///
+/// ## Example
+/// ```rust
+/// use shields::{BadgeParams, render_badge_svg, render_badge_svg_to};
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// let mut body = String::with_capacity(4096);
+/// render_badge_svg_to(&params, &mut body).unwrap();
+/// assert_eq!(body, render_badge_svg(&params));
+/// ```
+pub fn render_badge_svg_to(params: &BadgeParams, out: &mut impl fmt::Write) -> fmt::Result {
+    match write_svg(
+        params,
+        &RenderContext::default(),
+        &mut Scratch::default(),
+        out,
+    ) {
+        Ok(()) => Ok(()),
+        Err(e) => out.write_str(&error_comment(&e)),
     }
+}
 
-    let total_logo_width = if has_logo {
-        logo_width + logo_padding
-    } else {
-        0
-    };
+/// Render a badge as SVG into an [`io::Write`] sink, e.g. a socket or file.
+///
+/// See [`render_badge_svg_to`]; small writes are passed straight to `out`, so wrap unbuffered
+/// sinks in a [`std::io::BufWriter`].
+///
+/// # Returns
+/// The first error returned by `out`.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, render_badge_svg_to_io};
+/// let params = BadgeParams {
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// let mut bytes = Vec::new();
+/// render_badge_svg_to_io(&params, &mut bytes).unwrap();
+/// assert!(bytes.starts_with(b"<svg"));
+/// # }
+/// ```
+pub fn render_badge_svg_to_io(params: &BadgeParams, out: &mut impl io::Write) -> io::Result<()> {
+    let mut adapter = IoAdapter { out, error: None };
+    match render_badge_svg_to(params, &mut adapter) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(adapter
+            .error
+            .unwrap_or_else(|| io::Error::other("badge formatting failed"))),
+    }
+}
 
-    let has_label_color = !label_color.unwrap_or("").is_empty();
-    let message_color = message_color.unwrap_or(default_message_color());
-    let message_color = to_svg_color(message_color).unwrap_or("#007ec6".to_string());
+/// Forwards formatted output to an [`io::Write`], keeping the first I/O error.
+struct IoAdapter<'a, W: io::Write> {
+    out: &'a mut W,
+    error: Option<io::Error>,
+}
 
-    let label_color = match (
-        label.unwrap_or("").is_empty(),
-        label_color.unwrap_or("").is_empty(),
-    ) {
-        (true, true) if has_logo => "#555",
-        (true, true) => message_color.as_str(),
-        (_, _) => label_color.unwrap_or(default_label_color()),
+impl<W: io::Write> fmt::Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_all(s.as_bytes()).map_err(|e| {
+            self.error.get_or_insert(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Render a badge as SVG with custom size and spacing.
+///
+/// # Arguments
+/// * `params` - Badge parameters (see [`BadgeParams`]).
+/// * `options` - Output height, scale and padding (see [`RenderOptions`]).
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, RenderOptions, render_badge_svg_with_options};
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// let svg = render_badge_svg_with_options(&params, &RenderOptions::new().height(40));
+/// assert!(svg.contains(r#"height="40" viewBox="0 0 "#));
+/// # }
+/// ```
+pub fn render_badge_svg_with_options(params: &BadgeParams, options: &RenderOptions) -> String {
+    let ctx = RenderContext {
+        options: Cow::Borrowed(options),
+        ..Default::default()
     };
+    render_badge_svg_with(params, &ctx)
+}
 
-    let binding = to_svg_color(label_color).unwrap_or("#555".to_string());
-    let label_color = binding.as_str();
+/// Geometry of a badge as [`render_badge_svg`] would lay it out, in pixels.
+///
+/// Text positions are the `x` of the text anchor: the center of the text, except for
+/// [`BadgeStyle::ForTheBadgeTwoLine`], whose lines start there. That style stacks label and
+/// message, so both span the full width.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BadgeLayout {
+    /// Total width.
+    pub width: u32,
+    /// Total height.
+    pub height: u32,
+    /// Width of the label (left) part, including the logo when it sits there.
+    pub label_width: u32,
+    /// Width of the message (right) part.
+    pub message_width: u32,
+    /// Horizontal position of the label text.
+    pub label_x: f32,
+    /// Horizontal position of the message text.
+    pub message_x: f32,
+    /// Box of the logo, if the badge has one.
+    pub logo: Option<LogoBox>,
+}
 
-    let message_color = message_color.as_str();
-    let message = message.unwrap_or("");
-    let link = link.unwrap_or("");
-    let extra_link_not_empty_str = extra_link.is_none() || !extra_link.unwrap().is_empty();
-    let extra_link = extra_link.unwrap_or("");
-    let logo = logo.as_str();
-    match style {
-        BadgeStyle::Flat => {
-            let accessible_text = create_accessible_text(label, message);
-            let has_label_content = label.is_some() && !label.unwrap().is_empty();
-            let has_label = has_label_content || has_label_color;
-            let label_margin = total_logo_width + 1;
+/// Position and size of a badge logo, in pixels from the badge's top-left corner.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LogoBox {
+    /// Left edge.
+    pub x: f32,
+    /// Top edge.
+    pub y: f32,
+    /// Width.
+    pub width: f32,
+    /// Height.
+    pub height: f32,
+}
 
-            let label_width = if has_label && label.is_some() {
-                preferred_width_of(label.unwrap_or_default(), Font::VerdanaNormal11)
-            } else {
-                0
-            };
+/// Compute the layout of a badge without rendering its SVG.
+///
+/// Useful to reserve space in HTML, build image maps over the label and message, or test
+/// sizing. The layout matches [`render_badge_svg`] exactly; when the style's feature is
+/// disabled it is all zero.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, compute_layout, render_badge_svg};
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// let layout = compute_layout(&params);
+/// assert_eq!(layout.label_width + layout.message_width, layout.width);
+/// assert!(render_badge_svg(&params).contains(&format!(r#"width="{}""#, layout.width)));
+/// # }
+/// ```
+pub fn compute_layout(params: &BadgeParams) -> BadgeLayout {
+    let mut layout = None;
+    let _ = write_template(
+        params,
+        &RenderContext::default(),
+        &mut Scratch::default(),
+        &mut layout,
+    );
+    layout.unwrap_or_default()
+}
 
-            let mut left_width = if has_label {
-                (label_width + 2 * HORIZONTAL_PADDING + total_logo_width) as i32
-            } else {
-                0
-            };
+/// Width and height of a badge as [`render_badge_svg`] would render it, in pixels.
+///
+/// Set these on `<img>` tags so pages don't shift as badges load. This measures the badge
+/// without rendering its SVG (see [`compute_layout`]).
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(all(feature = "style-flat", feature = "style-for-the-badge"))] {
+/// use shields::{BadgeParams, BadgeStyle, badge_dimensions};
+/// let params = BadgeParams {
+///     style: BadgeStyle::ForTheBadge,
+///     label: Some("build"),
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// let (width, height) = badge_dimensions(&params);
+/// assert_eq!(height, 28);
+/// println!(r#"<img src="build.svg" width="{}" height="{}">"#, width, height);
+/// # }
+/// ```
+pub fn badge_dimensions(params: &BadgeParams) -> (u32, u32) {
+    let layout = compute_layout(params);
+    (layout.width, layout.height)
+}
 
-            if has_label && label.is_some() {
-                let label = label.unwrap();
-                if label.is_empty() {
-                    left_width -= 1;
-                }
-            }
-            let message_width = preferred_width_of(message, Font::VerdanaNormal11);
+/// Output size and spacing applied by [`render_badge_svg_with_options`].
+///
+/// Height and scale resize the finished SVG through its `viewBox`, so a 28px or 40px badge
+/// stays sharp at any size instead of being scaled up as an image. The default renders the
+/// standard 20px badge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Output height in pixels; the badge is scaled uniformly to it. Takes precedence over
+    /// `scale`.
+    pub height: Option<u32>,
+    /// Factor applied to the output width and height. Non-positive or non-finite values are
+    /// ignored.
+    pub scale: f32,
+    /// Space in pixels between the badge edges, logo and text of the flat, flat-square and
+    /// plastic styles. Defaults to 5.
+    pub horizontal_padding: u32,
+    /// Colors used when the viewer prefers a dark color scheme; the badge's own colors are
+    /// used otherwise.
+    pub dark: Option<DarkColors>,
+    /// Add stable `class` attributes for styling badges inlined in HTML: `shields-label` and
+    /// `shields-message` on the backgrounds, `shields-label-text`, `shields-message-text`
+    /// and their `-shadow` variants on the texts, `shields-logo` on the logo and
+    /// `shields-sparkline` on the sparkline.
+    pub class_hooks: bool,
+    /// Leave out the `fill` attributes of the elements with class hooks, so they take their
+    /// colors from external CSS only. Implies `class_hooks`.
+    pub unstyled: bool,
+    /// Suffix for the gradient, clip path and link `id`s of the flat, plastic and social
+    /// styles, so several badges inlined in one HTML document do not share them. Defaults to
+    /// [`IdSuffix::Auto`]; [`IdSuffix::Plain`] keeps the ids shields.io uses.
+    pub id_suffix: IdSuffix,
+    /// Prefix the message of monospace badges with a `$ ` shell prompt.
+    pub prompt: bool,
+    /// Restore a WCAG AA text contrast ratio (4.5:1) where the badge colors fall short of it.
+    /// Unset by default, rendering the colors as given.
+    pub contrast: Option<ContrastFix>,
+    /// Text and shadow colors, and the background brightness at which they switch.
+    pub text_colors: TextColors,
+    /// Emit indented SVG with one element per line instead of a single minified line, for
+    /// debugging and reviewing golden files. Text content is unchanged.
+    pub pretty: bool,
+    /// Which links are emitted as `href`s; other links are left out of the badge.
+    pub link_policy: LinkPolicy,
+    /// Exact badge width in pixels, before any resize, so badges line up in columns. Narrower
+    /// badges get a wider message section; wider ones have their message cut short with an
+    /// ellipsis, keeping the full text as the title. Unset by default.
+    pub fixed_width: Option<u32>,
+    /// When the label and message swap sides, for right-to-left scripts. Defaults to
+    /// [`Mirror::Never`].
+    pub mirror: Mirror,
+    /// Keep the text as given in the for-the-badge styles, which uppercase it, and the social
+    /// style, which capitalizes the label, so names like `npm` or `iOS` stay intact.
+    pub preserve_case: bool,
+    /// Font embedded in an `@font-face` rule, so the text renders with the metrics the badge
+    /// was sized for on systems without the font, such as Linux CI screenshots and PDF
+    /// exports. Unset by default, relying on the viewer's installed fonts.
+    pub embed_font: Option<EmbeddedFont>,
+    /// Gloss gradient drawn over plastic badges. Defaults to [`GradientStop::plastic`]; no
+    /// stops leave the badge colors flat.
+    pub plastic_gradient: Vec<GradientStop>,
+    /// Color of a stroke drawn inside the edge of the whole badge, following its rounded
+    /// corners, e.g. to set it apart from a background of the same color. Unset by default,
+    /// drawing no border.
+    pub border_color: Option<String>,
+    /// Width of the border in pixels. Defaults to 1.
+    pub border_width: f32,
+}
 
-            let offset = if label.is_none() && has_logo {
-                -3i32
-            } else {
-                0
-            };
+/// A font embedded into badges with [`RenderOptions::embed_font`].
+///
+/// The `@font-face` rule takes the name of the first font in the badge's `font-family` list
+/// (e.g. `Verdana`), so it replaces that font within the badge without changing its markup.
+/// It declares no weight, so bold text uses a synthesized bold of the embedded face.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, EmbeddedFont, RenderOptions, render_badge_svg_with_options};
+/// let options = RenderOptions::new().embed_font(EmbeddedFont::Url(
+///     "https://fonts.example.com/verdana.woff2".to_string(),
+/// ));
+/// let svg = render_badge_svg_with_options(
+///     &BadgeParams {
+///         message: Some("passing"),
+///         ..Default::default()
+///     },
+///     &options,
+/// );
+/// assert!(svg.contains(r#"@font-face{font-family:"Verdana";src:url("https://fonts.example.com/verdana.woff2")}"#));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmbeddedFont {
+    /// A TrueType font file (`.ttf`), embedded as a base64 `data:` URI holding only the glyphs
+    /// the badge draws, along with their `kern` table pairs. Fonts that cannot be subset, such as CFF-based OpenType fonts, are left out.
+    Subset(Arc<[u8]>),
+    /// A font loaded from a URL, e.g. a hosted WOFF2 file.
+    Url(String),
+}
 
-            let left_width = left_width + offset as i32;
-            let mut message_margin: i32 =
-                left_width as i32 - if message.is_empty() { 0 } else { 1 };
-            if !has_label {
-                if has_logo {
-                    message_margin += (total_logo_width + HORIZONTAL_PADDING) as i32
-                } else {
-                    message_margin += 1
+impl EmbeddedFont {
+    /// The `@font-face` rule for text in `font_family` drawing `texts`, empty when the font
+    /// cannot be subset.
+    fn font_face(&self, font_family: &str, texts: &[&str]) -> String {
+        let name = font_family.split(',').next().unwrap_or(font_family);
+        let src = match self {
+            EmbeddedFont::Url(url) => format!("\"{}\"", css_url_escape(url)),
+            EmbeddedFont::Subset(data) => {
+                // Case changes and truncation happen while rendering, so cover them all
+                let mut chars = std::collections::BTreeSet::from(['…', '$', ' ']);
+                for text in texts {
+                    chars.extend(text.chars());
+                    chars.extend(text.to_uppercase().chars());
+                }
+                match subset::subset_ttf(data, chars) {
+                    Some(font) => format!(
+                        "data:font/ttf;base64,{}",
+                        base64::engine::general_purpose::STANDARD.encode(font)
+                    ),
+                    None => return String::new(),
                 }
             }
+        };
+        format!("@font-face{{font-family:\"{}\";src:url({})}}", name, src)
+    }
+}
 
-            let mut right_width = (message_width + 2 * HORIZONTAL_PADDING) as i32;
-            if has_logo && !has_label {
-                right_width += total_logo_width as i32
-                    + if !message.is_empty() {
-                        (HORIZONTAL_PADDING - 1) as i32
-                    } else {
-                        0i32
-                    };
+/// Percent-encodes the characters of `url` that would end a quoted CSS string or the
+/// surrounding `<style>` element.
+fn css_url_escape(url: &str) -> Cow<'_, str> {
+    if !url
+        .chars()
+        .any(|c| matches!(c, '"' | '\\' | '<' | '>' | '&') || c.is_control())
+    {
+        return Cow::Borrowed(url);
+    }
+    let mut escaped = String::with_capacity(url.len() + 8);
+    for c in url.chars() {
+        if matches!(c, '"' | '\\' | '<' | '>' | '&') || c.is_control() {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
             }
+        } else {
+            escaped.push(c);
+        }
+    }
+    Cow::Owned(escaped)
+}
 
-            let label_x = 10.0
-                * (label_margin as f32 + (0.5 * label_width as f32) + HORIZONTAL_PADDING as f32)
-                + offset as f32;
-            let label_width_scaled = label_width * 10;
-            let total_width = left_width + right_width as i32;
+/// When [`RenderOptions::mirror`] puts the message on the left and the label on the right,
+/// the reading order of right-to-left scripts.
+///
+/// Only the flat, flat-square, plastic and pill styles are mirrored, and only badges with
+/// both a label and a message. The message keeps its color and the full `label: message`
+/// text stays the badge's title.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, Mirror, RenderOptions, render_badge_svg_with_options};
+/// let params = BadgeParams {
+///     label: Some("גרסה"),
+///     message: Some("2.0"),
+///     ..Default::default()
+/// };
+/// let svg = render_badge_svg_with_options(&params, &RenderOptions::new().mirror(Mirror::Auto));
+/// assert!(svg.find(">2.0<").unwrap() < svg.find(">גרסה<").unwrap());
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Mirror {
+    /// Keep the label on the left.
+    #[default]
+    Never,
+    /// Mirror badges whose label is right-to-left text.
+    Auto,
+    /// Mirror every badge.
+    Always,
+}
 
-            let right_width = right_width + if !has_label_color { offset } else { 0 };
-            let hex_label_color = Color::from_str(label_color)
-                .unwrap_or(Color::from_str("#555").unwrap())
-                .to_css_hex();
-            let hex_label_color = hex_label_color.as_str();
-            let hex_message_color = Color::from_str(message_color)
-                .unwrap_or(Color::from_str("#007ec6").unwrap())
-                .to_css_hex();
-            let hex_message_color = hex_message_color.as_str();
-            let (label_text_color, label_shadow_color) = colors_for_background(hex_label_color);
-            let (message_text_color, message_shadow_color) =
-                colors_for_background(hex_message_color);
-            let rect_offset = if has_logo { 19 } else { 0 };
+impl Mirror {
+    fn applies(self, params: &BadgeParams) -> bool {
+        let label = params.label.unwrap_or("");
+        matches!(
+            params.style,
+            BadgeStyle::Flat | BadgeStyle::FlatSquare | BadgeStyle::Plastic | BadgeStyle::Pill
+        ) && !label.is_empty()
+            && !params.message.unwrap_or("").is_empty()
+            && match self {
+                Mirror::Never => false,
+                Mirror::Auto => bidi::is_rtl(label),
+                Mirror::Always => true,
+            }
+    }
+}
 
-            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
-                total_logo_width as i32 + HORIZONTAL_PADDING as i32
-            } else {
-                left_width
-            };
+/// How [`RenderOptions::contrast`] makes text readable on a low-contrast background.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, ContrastFix, RenderOptions, render_badge_svg_with_options};
+/// let params = BadgeParams {
+///     label: Some("coverage"),
+///     message: Some("71%"),
+///     message_color: Some("yellowgreen"),
+///     ..Default::default()
+/// };
+/// let options = RenderOptions::new().contrast(ContrastFix::Text);
+/// let svg = render_badge_svg_with_options(&params, &options);
+/// assert!(svg.contains(r##"fill="#000""##));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContrastFix {
+    /// Switch the text to black or white, whichever contrasts more with the background.
+    Text,
+    /// Keep the text color and change the background's lightness just enough: darker under
+    /// light text, lighter under dark text. Not applied to the social and outline styles.
+    Background,
+}
 
-            let has_extra_link = !extra_link.is_empty();
-            let message_x = 10.0
-                * (message_margin as f32
-                    + (0.5 * message_width as f32)
-                    + HORIZONTAL_PADDING as f32);
-            let message_link_x = message_link_x
-                + if !has_label && has_extra_link {
-                    offset
-                } else {
-                    0
-                } as i32;
-            let message_width_scaled = message_width * 10;
-            let left_width = if left_width < 0 { 0 } else { left_width };
-            FlatBadgeSvgTemplateContext {
-                font_family: FONT_FAMILY,
+/// Suffix appended, after a `-`, to the element `id`s of a badge.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum IdSuffix {
+    /// A hash of the badge parameters and render options, so different badges get different
+    /// ids while the same badge always renders the same output.
+    #[default]
+    Auto,
+    /// A fixed suffix; characters other than ASCII letters, digits, `-` and `_` are dropped.
+    Fixed(String),
+    /// No suffix, keeping the ids shields.io uses (`s`, `r`, ...). Badges inlined together
+    /// then share their gradients and clip paths. Use it to compare output byte for byte
+    /// with shields.io.
+    Plain,
+}
 
-                accessible_text: accessible_text.as_str(),
-                badge_height: BADGE_HEIGHT as i32,
+impl IdSuffix {
+    fn resolve(&self, params: &BadgeParams, options: &RenderOptions) -> String {
+        let suffix = match self {
+            IdSuffix::Auto => format!("{:08x}", badge_hash(params, options) >> 32),
+            IdSuffix::Fixed(suffix) => suffix
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+                .collect(),
+            IdSuffix::Plain => String::new(),
+        };
+        if suffix.is_empty() {
+            suffix
+        } else {
+            format!("-{}", suffix)
+        }
+    }
+}
 
-                left_width: left_width as i32,
-                right_width: right_width as i32,
-                total_width: total_width as i32,
+/// URL schemes allowed in badge links.
+///
+/// [`render_badge_svg`] leaves out links the policy rejects, and [`try_render_badge_svg`]
+/// reports them as [`BadgeError::InvalidLink`]. Schemes are compared case-insensitively after
+/// removing the tabs, newlines and leading control characters that browsers ignore, so
+/// `java\tscript:` counts as `javascript:`.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, LinkPolicy, RenderOptions, render_badge_svg_with_options};
+/// let params = BadgeParams {
+///     message: Some("chat"),
+///     link: Some("irc://irc.libera.chat/rust"),
+///     ..Default::default()
+/// };
+/// assert!(!LinkPolicy::default().allows("irc://irc.libera.chat/rust"));
+///
+/// let policy = LinkPolicy {
+///     schemes: vec!["https".to_string(), "irc".to_string()],
+///     ..Default::default()
+/// };
+/// let svg = render_badge_svg_with_options(&params, &RenderOptions::new().link_policy(policy));
+/// assert!(svg.contains(r#"href="irc://irc.libera.chat/rust""#));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkPolicy {
+    /// Allowed schemes, without the `:`. Defaults to `http`, `https` and `mailto`.
+    pub schemes: Vec<String>,
+    /// Allow links without a scheme, such as `/docs` or `#install`. Defaults to true.
+    pub allow_relative: bool,
+}
 
-                label_color,
-                message_color,
+impl LinkPolicy {
+    /// Returns whether `link` may be used as an `href`.
+    pub fn allows(&self, link: &str) -> bool {
+        // Browsers strip these before parsing a URL
+        let link = link.trim_matches(|c: char| c <= ' ');
+        let link: Cow<str> = if link.contains(['\t', '\n', '\r']) {
+            Cow::Owned(link.replace(['\t', '\n', '\r'], ""))
+        } else {
+            Cow::Borrowed(link)
+        };
+        match url_scheme(&link) {
+            Some(scheme) => self.schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)),
+            None => self.allow_relative,
+        }
+    }
+}
 
-                font_size_scaled: FONT_SIZE_SCALED as i32,
+impl Default for LinkPolicy {
+    fn default() -> Self {
+        LinkPolicy {
+            schemes: vec![
+                "http".to_string(),
+                "https".to_string(),
+                "mailto".to_string(),
+            ],
+            allow_relative: true,
+        }
+    }
+}
 
-                label: label.unwrap_or(""),
-                label_x,
-                label_width_scaled: label_width_scaled as i32,
-                label_text_color,
-                label_shadow_color,
+/// The scheme of an absolute URL, `None` for a relative one.
+fn url_scheme(link: &str) -> Option<&str> {
+    let end = link.find([':', '/', '?', '#'])?;
+    let scheme = &link[..end];
+    let valid = link[end..].starts_with(':')
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
+}
 
-                message_x,
-                message_shadow_color,
-                message_text_color,
-                message_width_scaled: message_width_scaled as i32,
-                message,
+/// A link as it is written into an `href`: empty when the policy rejects it, with `&`, quotes
+/// and angle brackets escaped so it stays well-formed and cannot end the attribute.
+fn href<'a>(link: Option<&'a str>, policy: &LinkPolicy) -> Option<Cow<'a, str>> {
+    let link = link.filter(|link| link.is_empty() || policy.allows(link))?;
+    if link.contains(['&', '"', '\'', '<', '>']) {
+        Some(Cow::Owned(
+            link.replace('&', "&amp;")
+                .replace('"', "&quot;")
+                .replace('\'', "&#39;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+        ))
+    } else {
+        Some(Cow::Borrowed(link))
+    }
+}
 
-                link,
-                extra_link,
-                logo,
+/// Background colors swapped in by a `prefers-color-scheme: dark` media query.
+///
+/// Text and shadow colors follow the backgrounds as usual. Unset colors keep their light
+/// value.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, DarkColors, RenderOptions, render_badge_svg_with_options};
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     label_color: Some("#eee"),
+///     ..Default::default()
+/// };
+/// let dark = DarkColors {
+///     label_color: Some("#333".to_string()),
+///     ..Default::default()
+/// };
+/// let svg = render_badge_svg_with_options(&params, &RenderOptions::new().dark(dark));
+/// assert!(svg.contains("@media (prefers-color-scheme:dark)"));
+/// assert!(svg.contains(r#"class="shields-label""#));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DarkColors {
+    /// Label background color in dark mode.
+    pub label_color: Option<String>,
+    /// Message background color in dark mode.
+    pub message_color: Option<String>,
+}
 
-                rect_offset,
-                message_link_x,
+impl DarkColors {
+    /// Builds the media query applying these colors through the template class hooks.
+    ///
+    /// The two-line style draws both texts on the message color.
+    fn style(&self, style: BadgeStyle, text_colors: &TextColors) -> String {
+        let hex = |color: &Option<String>| {
+            let color = to_svg_color(color.as_deref()?)?;
+            Some(Color::from_str(&color).ok()?.to_css_hex())
+        };
+        let label = hex(&self.label_color);
+        let message = hex(&self.message_color);
+        let label_text = if style == BadgeStyle::ForTheBadgeTwoLine {
+            &message
+        } else {
+            &label
+        };
+        let mut rules = String::new();
+        for (class, color) in [("label", &label), ("message", &message)] {
+            if let Some(color) = color {
+                rules.push_str(&format!(".shields-{}{{fill:{}}}", class, color));
             }
-            .render()
-            .unwrap_or_else(|e| format!("<!-- Askama render error: {} -->", e))
         }
-        BadgeStyle::FlatSquare => {
-            let accessible_text = create_accessible_text(label, message);
-            let has_label_content = label.is_some() && !label.unwrap().is_empty();
-            let has_label = has_label_content || has_label_color;
-            let label_margin = total_logo_width + 1;
+        for (class, color) in [("label", label_text), ("message", &message)] {
+            if let Some(color) = color {
+                let (text, shadow) = text_colors.for_background(color);
+                rules.push_str(&format!(
+                    ".shields-{0}-text{{fill:{1}}}.shields-{0}-shadow{{fill:{2}}}",
+                    class, text, shadow
+                ));
+            }
+        }
+        if let Some(color) = &message {
+            let (text, _) = text_colors.for_background(color);
+            rules.push_str(&format!(".shields-sparkline{{stroke:{}}}", text));
+        }
+        if rules.is_empty() {
+            return rules;
+        }
+        format!("@media (prefers-color-scheme:dark){{{}}}", rules)
+    }
+}
 
-            let label_width = if has_label && label.is_some() {
-                preferred_width_of(label.unwrap_or_default(), Font::VerdanaNormal11)
-            } else {
-                0
-            };
+impl RenderOptions {
+    /// Options producing the standard badge.
+    pub fn new() -> Self {
+        RenderOptions {
+            height: None,
+            scale: 1.0,
+            horizontal_padding: HORIZONTAL_PADDING,
+            dark: None,
+            class_hooks: false,
+            unstyled: false,
+            id_suffix: IdSuffix::Auto,
+            prompt: false,
+            contrast: None,
+            text_colors: TextColors::default(),
+            pretty: false,
+            link_policy: LinkPolicy::default(),
+            fixed_width: None,
+            mirror: Mirror::Never,
+            preserve_case: false,
+            embed_font: None,
+            plastic_gradient: GradientStop::plastic(),
+            border_color: None,
+            border_width: 1.0,
+        }
+    }
 
-            let mut left_width = if has_label {
-                (label_width + 2 * HORIZONTAL_PADDING + total_logo_width) as i32
-            } else {
-                0
-            };
+    /// Sets the output height in pixels.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
 
-            if has_label && label.is_some() {
-                let label = label.unwrap();
-                if label.is_empty() {
-                    left_width -= 1;
-                }
-            }
-            let message_width = preferred_width_of(message, Font::VerdanaNormal11);
+    /// Sets the output scale factor.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
 
-            let offset = if label.is_none() && has_logo {
-                -3i32
-            } else {
-                0
-            };
+    /// Sets the horizontal padding in pixels.
+    pub fn horizontal_padding(mut self, horizontal_padding: u32) -> Self {
+        self.horizontal_padding = horizontal_padding;
+        self
+    }
 
-            let left_width = left_width + offset as i32;
-            let mut message_margin: i32 =
-                left_width as i32 - if message.is_empty() { 0 } else { 1 };
-            if !has_label {
-                if has_logo {
-                    message_margin += (total_logo_width + HORIZONTAL_PADDING) as i32
-                } else {
-                    message_margin += 1
-                }
-            }
+    /// Sets the colors used in dark mode.
+    pub fn dark(mut self, dark: DarkColors) -> Self {
+        self.dark = Some(dark);
+        self
+    }
 
-            let mut right_width = (message_width + 2 * HORIZONTAL_PADDING) as i32;
-            if has_logo && !has_label {
-                right_width += total_logo_width as i32
-                    + if !message.is_empty() {
-                        (HORIZONTAL_PADDING - 1) as i32
-                    } else {
-                        0i32
-                    };
-            }
+    /// Sets whether `shields-*` classes are added.
+    pub fn class_hooks(mut self, class_hooks: bool) -> Self {
+        self.class_hooks = class_hooks;
+        self
+    }
 
-            let label_x = 10.0
-                * (label_margin as f32 + (0.5 * label_width as f32) + HORIZONTAL_PADDING as f32)
-                + offset as f32;
-            let label_width_scaled = label_width * 10;
-            let total_width = left_width + right_width as i32;
+    /// Sets whether fills of elements with class hooks are left out.
+    pub fn unstyled(mut self, unstyled: bool) -> Self {
+        self.unstyled = unstyled;
+        self
+    }
 
-            let right_width = right_width + if !has_label_color { offset } else { 0 };
-            let hex_label_color = Color::from_str(label_color)
-                .unwrap_or(Color::from_str("#555").unwrap())
-                .to_css_hex();
-            let hex_label_color = hex_label_color.as_str();
-            let hex_message_color = Color::from_str(message_color)
-                .unwrap_or(Color::from_str("#007ec6").unwrap())
-                .to_css_hex();
-            let hex_message_color = hex_message_color.as_str();
-            let (label_text_color, _) = colors_for_background(hex_label_color);
-            let (message_text_color, _) = colors_for_background(hex_message_color);
-            let rect_offset = if has_logo { 19 } else { 0 };
+    /// Sets the suffix appended to element ids.
+    pub fn id_suffix(mut self, id_suffix: IdSuffix) -> Self {
+        self.id_suffix = id_suffix;
+        self
+    }
 
-            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
-                total_logo_width as i32 + HORIZONTAL_PADDING as i32
-            } else {
-                left_width
-            };
+    /// Sets whether monospace badges show a `$ ` prompt before the message.
+    pub fn prompt(mut self, prompt: bool) -> Self {
+        self.prompt = prompt;
+        self
+    }
 
-            let has_extra_link = !extra_link.is_empty();
-            let message_x = 10.0
-                * (message_margin as f32
-                    + (0.5 * message_width as f32)
-                    + HORIZONTAL_PADDING as f32);
-            let message_link_x = message_link_x
-                + if !has_label && has_extra_link {
-                    offset
-                } else {
-                    0
-                } as i32;
-            let message_width_scaled = message_width * 10;
-            let left_width = if left_width < 0 { 0 } else { left_width };
-            FlatSquareBadgeSvgTemplateContext {
-                font_family: FONT_FAMILY,
-                accessible_text: accessible_text.as_str(),
-                badge_height: BADGE_HEIGHT as i32,
-                left_width,
-                right_width,
-                total_width,
-                label_color,
-                message_color,
-                font_size_scaled: FONT_SIZE_SCALED as i32,
-                label: label.unwrap_or(""),
-                label_x,
-                label_width_scaled: label_width_scaled as i32,
-                label_text_color,
-                message_x,
-                message_text_color,
-                message_width_scaled: message_width_scaled as i32,
-                message,
+    /// Sets how low text contrast is corrected.
+    pub fn contrast(mut self, contrast: ContrastFix) -> Self {
+        self.contrast = Some(contrast);
+        self
+    }
+
+    /// Sets which links are emitted.
+    pub fn link_policy(mut self, link_policy: LinkPolicy) -> Self {
+        self.link_policy = link_policy;
+        self
+    }
+
+    /// Sets the exact badge width in pixels.
+    pub fn fixed_width(mut self, width: u32) -> Self {
+        self.fixed_width = Some(width);
+        self
+    }
+
+    /// Sets when the label and message swap sides.
+    pub fn mirror(mut self, mirror: Mirror) -> Self {
+        self.mirror = mirror;
+        self
+    }
+
+    /// Sets whether the text keeps its case in every style.
+    pub fn preserve_case(mut self, preserve_case: bool) -> Self {
+        self.preserve_case = preserve_case;
+        self
+    }
+
+    /// Sets the font embedded into the badge.
+    pub fn embed_font(mut self, font: EmbeddedFont) -> Self {
+        self.embed_font = Some(font);
+        self
+    }
+
+    /// Sets the text and shadow colors.
+    pub fn text_colors(mut self, text_colors: TextColors) -> Self {
+        self.text_colors = text_colors;
+        self
+    }
+
+    /// Sets the gloss gradient of plastic badges.
+    pub fn plastic_gradient(mut self, plastic_gradient: Vec<GradientStop>) -> Self {
+        self.plastic_gradient = plastic_gradient;
+        self
+    }
+
+    /// Sets the border color.
+    pub fn border_color(mut self, border_color: impl Into<String>) -> Self {
+        self.border_color = Some(border_color.into());
+        self
+    }
+
+    /// Sets the border width in pixels.
+    pub fn border_width(mut self, border_width: f32) -> Self {
+        self.border_width = border_width;
+        self
+    }
+
+    /// Sets whether the SVG is indented.
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// The border's SVG color and width, if one is drawn.
+    fn border(&self) -> Option<(String, f32)> {
+        let color = to_svg_color(self.border_color.as_deref()?)?;
+        let width = self.border_width;
+        (width.is_finite() && width > 0.0).then_some((color, width))
+    }
+
+    /// Adds the border to a rendered badge of `style`, inset by half its width so the stroke
+    /// stays within the badge.
+    fn add_border(&self, svg: String, style: BadgeStyle) -> String {
+        let (Some((color, width)), Some((total_width, height))) = (self.border(), svg_size(&svg))
+        else {
+            return svg;
+        };
+        let Some(end) = svg.rfind("</svg>") else {
+            return svg;
+        };
+        let radius = match style {
+            BadgeStyle::Flat | BadgeStyle::Outline => 3.0,
+            BadgeStyle::Plastic => 4.0,
+            BadgeStyle::Social => 2.5,
+            BadgeStyle::Pill => height as f32 / 2.0,
+            _ => 0.0,
+        };
+        let inset = width / 2.0;
+        let rect = format!(
+            r#"<rect x="{0}" y="{0}" width="{1}" height="{2}" rx="{3}" fill="none" stroke="{4}" stroke-width="{5}"/>"#,
+            inset,
+            (total_width as f32 - width).max(0.0),
+            (height as f32 - width).max(0.0),
+            (radius - inset).max(0.0),
+            color,
+            width
+        );
+        let mut svg = svg;
+        svg.insert_str(end, &rect);
+        svg
+    }
+
+    /// Whether [`resize`](RenderOptions::resize) may change the rendered badge.
+    fn resizes(&self) -> bool {
+        self.height.is_some() || (self.scale.is_finite() && self.scale > 0.0 && self.scale != 1.0)
+    }
+
+    /// Rewrites the root `width` and `height` of a rendered badge to the requested size,
+    /// keeping the original geometry as the `viewBox`.
+    fn resize(&self, svg: String) -> String {
+        let Some((width, height)) = svg_size(&svg) else {
+            return svg;
+        };
+        let scale = match self.height {
+            Some(target) => target as f64 / height,
+            None if self.scale.is_finite() && self.scale > 0.0 => self.scale as f64,
+            None => return svg,
+        };
+        if scale == 1.0 || !scale.is_finite() || scale <= 0.0 {
+            return svg;
+        }
+        let round = |v: f64| (v * 100.0).round() / 100.0;
+        let root_end = svg.find('>').unwrap_or(svg.len());
+        let root = svg[..root_end]
+            .replacen(
+                &format!(" width=\"{}\"", width),
+                &format!(" width=\"{}\"", round(width * scale)),
+                1,
+            )
+            .replacen(
+                &format!(" height=\"{}\"", height),
+                &format!(
+                    " height=\"{}\" viewBox=\"0 0 {} {}\"",
+                    round(height * scale),
+                    width,
+                    height
+                ),
+                1,
+            );
+        root + &svg[root_end..]
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions::new()
+    }
+}
+
+/// 64-bit FNV-1a digest as 16 hex digits; stable across platforms and releases.
+#[cfg(feature = "unstable")]
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Fnv1a::default();
+    hasher.update(bytes);
+    format!("{:016x}", hasher.0)
+}
+
+/// 64-bit FNV-1a, stable across runs and platforms unlike the std hashers.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+impl Fnv1a {
+    /// Feeds an optional text, length-prefixed so neighbouring fields cannot run together.
+    fn text(&mut self, text: Option<&str>) {
+        match text {
+            Some(text) => {
+                self.update(&(text.len() as u64).to_le_bytes());
+                self.update(text.as_bytes());
+            }
+            None => self.update(&[0xff; 8]),
+        }
+    }
+
+    /// Feeds an optional number.
+    fn number(&mut self, number: Option<f64>) {
+        match number {
+            Some(number) => {
+                self.update(&[1]);
+                self.update(&number.to_le_bytes());
+            }
+            None => self.update(&[0]),
+        }
+    }
+}
+
+/// Hash of everything that changes how a badge is drawn: its parameters and the render
+/// options other than pretty-printing, the link policy and the id suffix itself. Each field is
+/// written out explicitly, so the hash only changes when a release changes this encoding.
+fn badge_hash(params: &BadgeParams, options: &RenderOptions) -> u64 {
+    let mut hasher = Fnv1a::default();
+    let BadgeParams {
+        style,
+        label,
+        message,
+        label_color,
+        message_color,
+        link,
+        extra_link,
+        logo,
+        logo_color,
+        bubble_icon,
+        logo_width,
+        logo_size,
+        sparkline,
+        title,
+        font,
+    } = params;
+    hasher.text(Some(style.as_str()));
+    for text in [
+        label,
+        message,
+        label_color,
+        message_color,
+        link,
+        extra_link,
+        logo,
+        logo_color,
+        title,
+    ] {
+        hasher.text(*text);
+    }
+    hasher.text(bubble_icon.as_ref().map(SocialIcon::path));
+    hasher.number(logo_width.map(f64::from));
+    hasher.text(logo_size.map(|LogoSize::Auto| "auto"));
+    hasher.number(sparkline.map(|values| values.len() as f64));
+    for value in sparkline.unwrap_or_default() {
+        hasher.number(Some(*value));
+    }
+    hasher.text(font.map(FontFamily::as_str));
+
+    let RenderOptions {
+        height,
+        scale,
+        horizontal_padding,
+        dark,
+        class_hooks,
+        unstyled,
+        id_suffix: _,
+        prompt,
+        contrast,
+        text_colors,
+        pretty: _,
+        link_policy: _,
+        fixed_width,
+        mirror,
+        preserve_case,
+        embed_font,
+        plastic_gradient,
+        border_color,
+        border_width,
+    } = options;
+    hasher.number(height.map(f64::from));
+    hasher.number(Some(f64::from(*scale)));
+    hasher.number(Some(f64::from(*horizontal_padding)));
+    hasher.text(dark.as_ref().and_then(|dark| dark.label_color.as_deref()));
+    hasher.text(dark.as_ref().and_then(|dark| dark.message_color.as_deref()));
+    for flag in [class_hooks, unstyled, prompt, preserve_case] {
+        hasher.number(Some(f64::from(u8::from(*flag))));
+    }
+    hasher.text(contrast.map(|contrast| match contrast {
+        ContrastFix::Text => "text",
+        ContrastFix::Background => "background",
+    }));
+    hasher.number(Some(f64::from(text_colors.threshold)));
+    for color in [
+        &text_colors.light_text,
+        &text_colors.light_shadow,
+        &text_colors.dark_text,
+        &text_colors.dark_shadow,
+    ] {
+        hasher.text(Some(color));
+    }
+    hasher.number(fixed_width.map(f64::from));
+    hasher.text(Some(match mirror {
+        Mirror::Never => "never",
+        Mirror::Auto => "auto",
+        Mirror::Always => "always",
+    }));
+    match embed_font {
+        Some(EmbeddedFont::Url(url)) => hasher.text(Some(url)),
+        Some(EmbeddedFont::Subset(data)) => hasher.update(data),
+        None => hasher.text(None),
+    }
+    hasher.number(Some(plastic_gradient.len() as f64));
+    for stop in plastic_gradient {
+        hasher.number(Some(f64::from(stop.offset)));
+        hasher.text(Some(&stop.color));
+        hasher.number(Some(f64::from(stop.opacity)));
+    }
+    hasher.text(border_color.as_deref());
+    hasher.number(Some(f64::from(*border_width)));
+    hasher.0
+}
+
+impl fmt::Write for Fnv1a {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Reads the root `width` and `height` attributes of a rendered badge, or `None` when either
+/// is missing or not a number.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, render_badge_svg, svg_size};
+/// let svg = render_badge_svg(&BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     ..Default::default()
+/// });
+/// assert_eq!(svg_size(&svg).map(|(_, height)| height), Some(20.0));
+/// assert_eq!(svg_size("<svg>"), None);
+/// # }
+/// ```
+pub fn svg_size(svg: &str) -> Option<(f64, f64)> {
+    let root = &svg[..svg.find('>')?];
+    let attr = |name: &str| {
+        let start = root.find(&format!(" {}=\"", name))? + name.len() + 3;
+        let end = start + root[start..].find('"')?;
+        root[start..end].parse().ok()
+    };
+    Some((attr("width")?, attr("height")?))
+}
+
+/// Bounds applied by [`try_render_badge_svg_with_limits`] to a single render.
+///
+/// Services rendering badges from untrusted input can use these to reject pathological
+/// parameters instead of spending unbounded CPU and memory on them. Unset bounds are not
+/// checked; the default applies none.
+#[derive(Debug, Clone, Default)]
+pub struct RenderLimits {
+    /// Maximum number of characters across all text parameters (label, message, colors,
+    /// links, logo and title).
+    pub max_chars: Option<usize>,
+    /// Maximum number of sparkline values.
+    pub max_sparkline_points: Option<usize>,
+    /// Maximum size of the rendered SVG in bytes.
+    pub max_output_bytes: Option<usize>,
+    /// Point in time after which rendering is abandoned. Checked before and after layout.
+    pub deadline: Option<Instant>,
+}
+
+impl RenderLimits {
+    fn check_input(&self, params: &BadgeParams) -> Result<(), BadgeError> {
+        if let Some(max) = self.max_chars {
+            let actual = [
+                params.label,
+                params.message,
+                params.label_color,
+                params.message_color,
+                params.link,
+                params.extra_link,
+                params.logo,
+                params.logo_color,
+                params.title,
+            ]
+            .iter()
+            .flatten()
+            .map(|s| s.chars().count())
+            .sum();
+            if actual > max {
+                return Err(BadgeError::Limit(LimitKind::Chars { max, actual }));
+            }
+        }
+        if let (Some(max), Some(values)) = (self.max_sparkline_points, params.sparkline)
+            && values.len() > max
+        {
+            return Err(BadgeError::Limit(LimitKind::SparklinePoints {
+                max,
+                actual: values.len(),
+            }));
+        }
+        self.check_deadline()
+    }
+
+    fn check_output(&self, svg: &str) -> Result<(), BadgeError> {
+        self.check_deadline()?;
+        match self.max_output_bytes {
+            Some(max) if svg.len() > max => Err(BadgeError::Limit(LimitKind::OutputBytes {
+                max,
+                actual: svg.len(),
+            })),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_deadline(&self) -> Result<(), BadgeError> {
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                Err(BadgeError::Limit(LimitKind::Deadline))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Generate an SVG badge string from [`BadgeParams`], reporting problems instead of hiding them.
+///
+/// Where [`render_badge_svg`] falls back to default colors, drops unknown logos and embeds
+/// template failures as an SVG comment, this returns an error so callers can surface the
+/// problem instead of shipping a broken badge.
+///
+/// # Arguments
+/// * `params` - Badge parameters (see [`BadgeParams`]).
+///
+/// # Returns
+/// SVG string representing the badge, or the [`BadgeError`] describing the first problem found.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeError, BadgeParams, try_render_badge_svg};
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     message_color: Some("brightgreen"),
+///     ..Default::default()
+/// };
+/// assert!(try_render_badge_svg(&params).is_ok());
+///
+/// let params = BadgeParams {
+///     message_color: Some("greenish"),
+///     ..params
+/// };
+/// assert!(matches!(
+///     try_render_badge_svg(&params),
+///     Err(BadgeError::InvalidColor { param: "message_color", .. })
+/// ));
+/// # }
+/// ```
+pub fn try_render_badge_svg(params: &BadgeParams) -> Result<String, BadgeError> {
+    try_render_badge_svg_with_limits(params, &RenderLimits::default())
+}
+
+/// Like [`try_render_badge_svg`], additionally aborting when a [`RenderLimits`] bound is
+/// exceeded.
+///
+/// # Arguments
+/// * `params` - Badge parameters (see [`BadgeParams`]).
+/// * `limits` - Bounds checked before and after rendering.
+///
+/// # Returns
+/// SVG string representing the badge, or a [`BadgeError`]; [`BadgeError::Limit`] names the
+/// exceeded bound.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeError, BadgeParams, LimitKind, RenderLimits, try_render_badge_svg_with_limits};
+/// let limits = RenderLimits {
+///     max_chars: Some(16),
+///     ..Default::default()
+/// };
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// assert!(try_render_badge_svg_with_limits(&params, &limits).is_ok());
+///
+/// let params = BadgeParams {
+///     message: Some("a message far longer than sixteen characters"),
+///     ..Default::default()
+/// };
+/// assert!(matches!(
+///     try_render_badge_svg_with_limits(&params, &limits),
+///     Err(BadgeError::Limit(LimitKind::Chars { max: 16, .. }))
+/// ));
+/// # }
+/// ```
+pub fn try_render_badge_svg_with_limits(
+    params: &BadgeParams,
+    limits: &RenderLimits,
+) -> Result<String, BadgeError> {
+    try_render_badge_svg_with(params, &RenderContext::default(), limits)
+}
+
+pub(crate) fn try_render_badge_svg_with(
+    params: &BadgeParams,
+    ctx: &RenderContext,
+    limits: &RenderLimits,
+) -> Result<String, BadgeError> {
+    limits.check_input(params)?;
+    validate_params(params, &ctx.options.link_policy)?;
+    let svg = render_svg(params, ctx)?;
+    limits.check_output(&svg)?;
+    Ok(svg)
+}
+
+/// Rendering state shared by all style branches of a single render call.
+pub(crate) struct RenderContext<'a> {
+    /// Width tables that replace the embedded ones for specific fonts.
+    pub(crate) fonts: Option<&'a HashMap<Font, Arc<CharWidthMeasurer>>>,
+    /// Output size and spacing.
+    pub(crate) options: Cow<'a, RenderOptions>,
+}
+
+/// Options of [`render_badge_svg`], built once rather than on every render.
+static DEFAULT_OPTIONS: Lazy<RenderOptions> = Lazy::new(RenderOptions::new);
+
+impl Default for RenderContext<'_> {
+    fn default() -> Self {
+        RenderContext {
+            fonts: None,
+            options: Cow::Borrowed(&DEFAULT_OPTIONS),
+        }
+    }
+}
+
+/// Buffers reused from one render to the next, so batch rendering doesn't allocate them per
+/// badge.
+#[derive(Default)]
+pub(crate) struct Scratch {
+    /// Encoded `href` of the last single logo.
+    logo: String,
+    /// Slug, fill and [`logos::generation`] `logo` was encoded for, skipping the logo cache
+    /// when they repeat.
+    logo_key: Option<(String, String, usize)>,
+    /// Accessible text of the badge.
+    #[cfg(any_style)]
+    accessible_text: String,
+    /// Template output awaiting a resize.
+    svg: String,
+}
+
+impl Scratch {
+    /// Encodes `slug` filled with `fill` into `self.logo`, leaving it empty if unknown.
+    fn encode_logo(&mut self, slug: &str, fill: &str) {
+        let generation = logos::generation();
+        if let Some((last_slug, last_fill, last_generation)) = &self.logo_key
+            && last_slug == slug
+            && last_fill == fill
+            && *last_generation == generation
+        {
+            return;
+        }
+        self.logo.clear();
+        logos::encode_into(slug, fill, &mut self.logo);
+        let (last_slug, last_fill, last_generation) = self.logo_key.get_or_insert_default();
+        last_slug.clear();
+        last_slug.push_str(slug);
+        last_fill.clear();
+        last_fill.push_str(fill);
+        *last_generation = generation;
+    }
+}
+
+impl RenderContext<'_> {
+    fn font_override(&self, font: &Font) -> Option<&CharWidthMeasurer> {
+        self.fonts
+            .and_then(|fonts| fonts.get(font))
+            .map(Arc::as_ref)
+    }
+
+    #[cfg(for_the_badge_layout)]
+    fn text_width(&self, text: &str, font: Font) -> f64 {
+        match self.font_override(&font) {
+            Some(measurer) => measurer.width_of(text, true),
+            None => get_text_width(text, font),
+        }
+    }
+
+    fn preferred_width_of(&self, text: &str, font: Font) -> u32 {
+        match self.font_override(&font) {
+            Some(measurer) => round_up_to_odd_f64(measurer.width_of(text, true)),
+            None => preferred_width_of(text, font),
+        }
+    }
+
+    /// Text and shadow colors for text on `hex`, switched to black or white when
+    /// [`ContrastFix::Text`] is set and the usual pair is not readable enough.
+    #[cfg(any(feature = "unstable", colored_style))]
+    pub(crate) fn text_colors(&self, hex: &str) -> (&str, &str) {
+        let colors = self.options.text_colors.for_background(hex);
+        if self.options.contrast != Some(ContrastFix::Text) {
+            return colors;
+        }
+        let Ok(background) = Color::from_str(hex) else {
+            return colors;
+        };
+        let ratio =
+            |text: &str| parse_color(text).map_or(0.0, |text| contrast_ratio(&background, &text));
+        if ratio(colors.0) >= MIN_CONTRAST_RATIO {
+            colors
+        } else if ratio("#fff") >= ratio("#000") {
+            ("#fff", "#010101")
+        } else {
+            ("#000", "#ccc")
+        }
+    }
+
+    /// Background replacing `color` when [`ContrastFix::Background`] is set and `color` is
+    /// not readable enough.
+    #[cfg(any(feature = "unstable", colored_style))]
+    pub(crate) fn contrast_background(&self, color: &str) -> Option<String> {
+        match self.options.contrast {
+            Some(ContrastFix::Background) => contrast_background(color, &self.options.text_colors),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn render_badge_svg_with(params: &BadgeParams, ctx: &RenderContext) -> String {
+    match render_svg(params, ctx) {
+        Ok(svg) => svg,
+        Err(e) => error_comment(&e),
+    }
+}
+
+/// Appends a badge to `out` like [`render_badge_svg_with`], reusing the buffers in `scratch`.
+#[cfg(feature = "unstable")]
+pub(crate) fn render_badge_svg_into(
+    params: &BadgeParams,
+    ctx: &RenderContext,
+    scratch: &mut Scratch,
+    out: &mut String,
+) {
+    let start = out.len();
+    if let Err(e) = write_svg(params, ctx, scratch, out) {
+        out.truncate(start);
+        out.push_str(&error_comment(&e));
+    }
+}
+
+/// The SVG comment rendered in place of a badge that failed to render.
+fn error_comment(e: &BadgeError) -> String {
+    match e {
+        BadgeError::Template(e) => format!("<!-- Askama render error: {} -->", e),
+        BadgeError::StyleNotEnabled(style) => {
+            format!("<!-- Badge style {:?} is not enabled -->", style)
+        }
+        e => format!("<!-- {} -->", e),
+    }
+}
+
+fn render_svg(params: &BadgeParams, ctx: &RenderContext) -> Result<String, BadgeError> {
+    let mut svg = String::new();
+    write_svg(params, ctx, &mut Scratch::default(), &mut svg)?;
+    Ok(svg)
+}
+
+/// Renders a badge into `out`, buffering only when the output has to be resized.
+pub(crate) fn write_svg<W: fmt::Write + ?Sized>(
+    params: &BadgeParams,
+    ctx: &RenderContext,
+    scratch: &mut Scratch,
+    out: &mut W,
+) -> Result<(), BadgeError> {
+    let borders = ctx.options.border().is_some();
+    if ctx.options.resizes() || ctx.options.pretty || borders {
+        let mut svg = std::mem::take(&mut scratch.svg);
+        svg.clear();
+        write_template(params, ctx, scratch, &mut RenderTo(&mut svg))?;
+        if borders {
+            svg = ctx.options.add_border(svg, params.style);
+        }
+        let mut svg = ctx.options.resize(svg);
+        if ctx.options.pretty {
+            svg = pretty::indent_svg(&svg);
+        }
+        let written = out.write_str(&svg);
+        scratch.svg = svg;
+        return written.map_err(|e| BadgeError::Template(e.to_string()));
+    }
+    write_template(params, ctx, scratch, &mut RenderTo(out))
+}
+
+/// Receives the filled-in template of a badge.
+trait Emit {
+    #[cfg(any_style)]
+    fn emit<T: Template + Geometry>(&mut self, template: T) -> Result<(), BadgeError>;
+
+    /// Whether the markup is rendered, rather than only the layout kept.
+    fn renders(&self) -> bool {
+        true
+    }
+}
+
+/// Renders templates into a writer.
+struct RenderTo<'w, W: ?Sized>(&'w mut W);
+
+impl<W: fmt::Write + ?Sized> Emit for RenderTo<'_, W> {
+    #[cfg(any_style)]
+    fn emit<T: Template + Geometry>(&mut self, template: T) -> Result<(), BadgeError> {
+        template
+            .render_into(self.0)
+            .map_err(|e| BadgeError::Template(e.to_string()))
+    }
+}
+
+/// Keeps only the layout of the template, skipping rendering.
+impl Emit for Option<BadgeLayout> {
+    #[cfg(any_style)]
+    fn emit<T: Template + Geometry>(&mut self, template: T) -> Result<(), BadgeError> {
+        *self = Some(template.layout());
+        Ok(())
+    }
+
+    fn renders(&self) -> bool {
+        false
+    }
+}
+
+fn write_template<E: Emit>(
+    params: &BadgeParams,
+    ctx: &RenderContext,
+    scratch: &mut Scratch,
+    out: &mut E,
+) -> Result<(), BadgeError> {
+    let Some(fixed_width) = ctx.options.fixed_width else {
+        return write_badge(params, ctx, scratch, 0, out);
+    };
+    let mut measure = |params: &BadgeParams| {
+        let mut layout = None;
+        write_badge(params, ctx, scratch, 0, &mut layout)?;
+        Ok::<_, BadgeError>(layout.unwrap_or_default().width)
+    };
+    let width = measure(params)?;
+    let message = params.message.unwrap_or("");
+    if width <= fixed_width || message.is_empty() {
+        let fill = fixed_width.saturating_sub(width);
+        return write_badge(params, ctx, scratch, fill, out);
+    }
+
+    // Keep the longest start of the message that fits with an ellipsis; widths only grow
+    // with more characters, so a binary search finds it
+    let ends: Vec<usize> = message.char_indices().map(|(i, _)| i).collect();
+    let truncate = |chars: usize| format!("{}…", message[..ends[chars]].trim_end());
+    let (mut fits, mut too_long) = (0, ends.len());
+    while too_long - fits > 1 {
+        let mid = (fits + too_long) / 2;
+        let message = truncate(mid);
+        let width = measure(&BadgeParams {
+            message: Some(&message),
+            ..params.clone()
+        })?;
+        if width <= fixed_width {
+            fits = mid;
+        } else {
+            too_long = mid;
+        }
+    }
+    // The full text stays available as the tooltip
+    let mut full_text = String::new();
+    write_accessible_text(&mut full_text, params.label, message);
+    let message = truncate(fits);
+    let truncated = BadgeParams {
+        message: Some(&message),
+        title: params.title.or(Some(&full_text)),
+        ..params.clone()
+    };
+    let fill = fixed_width.saturating_sub(measure(&truncated)?);
+    write_badge(&truncated, ctx, scratch, fill, out)
+}
+
+/// The text of for-the-badge styles: uppercase unless the case is preserved.
+#[cfg(for_the_badge_layout)]
+fn uppercase(text: &str, preserve_case: bool) -> String {
+    if preserve_case {
+        text.to_string()
+    } else {
+        text.to_uppercase()
+    }
+}
+
+/// Swaps the label and message of a badge, with their colors and links, keeping `full_text`
+/// as its title. A link on the message alone ends up covering the whole mirrored badge.
+fn mirror_params<'a>(params: &BadgeParams<'a>, full_text: &'a str) -> BadgeParams<'a> {
+    let section_links = params.extra_link.is_some_and(|link| !link.is_empty());
+    BadgeParams {
+        label: params.message,
+        message: params.label,
+        label_color: Some(params.message_color.unwrap_or(default_message_color())),
+        message_color: Some(params.label_color.unwrap_or(default_label_color())),
+        link: if section_links {
+            params.extra_link
+        } else {
+            params.link
+        },
+        extra_link: if section_links {
+            params.link
+        } else {
+            params.extra_link
+        },
+        title: params.title.or(Some(full_text)),
+        ..params.clone()
+    }
+}
+
+/// Parameters drawing a badge without a message as a single section: its label takes the
+/// place of a message-only badge's text, in the label color, so no empty message block is
+/// left over. An empty `Some("")` message keeps its block, as on shields.io. Social and
+/// two-line badges already draw a lone label as one block, and a sparkline keeps its message
+/// section.
+fn label_only_params<'a>(params: &BadgeParams<'a>) -> Option<BadgeParams<'a>> {
+    let label = params.label.filter(|label| !label.is_empty())?;
+    if params.message.is_some()
+        || params.sparkline.is_some()
+        || matches!(
+            params.style,
+            BadgeStyle::Social | BadgeStyle::ForTheBadgeTwoLine
+        )
+    {
+        return None;
+    }
+    let color = params.label_color.unwrap_or(default_label_color());
+    let has_logo = params.logo.is_some_and(|logo| !logo.trim().is_empty());
+    Some(BadgeParams {
+        label: Some(""),
+        message: Some(label),
+        // A logo sits in the label block, which takes the same color
+        label_color: has_logo.then_some(color),
+        message_color: Some(color),
+        link: [params.link, params.extra_link]
+            .into_iter()
+            .flatten()
+            .find(|link| !link.is_empty()),
+        extra_link: None,
+        ..params.clone()
+    })
+}
+
+/// Fills in the template of a badge whose message section is widened by `message_fill`
+/// pixels.
+// Without any `style-*` feature there is no template for the values worked out here
+#[cfg_attr(not(any_style), allow(unused_variables))]
+fn write_badge<E: Emit>(
+    params: &BadgeParams,
+    ctx: &RenderContext,
+    scratch: &mut Scratch,
+    message_fill: u32,
+    out: &mut E,
+) -> Result<(), BadgeError> {
+    let label_only;
+    let params = match label_only_params(params) {
+        Some(single) => {
+            label_only = single;
+            &label_only
+        }
+        None => params,
+    };
+    let mut full_text = String::new();
+    let mirrored;
+    let params = if ctx.options.mirror.applies(params) {
+        write_accessible_text(&mut full_text, params.label, params.message.unwrap_or(""));
+        mirrored = mirror_params(params, &full_text);
+        &mirrored
+    } else {
+        params
+    };
+    let BadgeParams {
+        style,
+        label,
+        message,
+        label_color,
+        message_color,
+        link,
+        extra_link,
+        logo,
+        logo_color,
+        // Read by the social style only
+        bubble_icon: _,
+        logo_width,
+        logo_size,
+        sparkline,
+        title,
+        font,
+    } = params;
+    let label = *label;
+    let logo_name = logo.map(str::trim).unwrap_or("");
+    // A logo without any text is drawn alone on a single block, in the message color or,
+    // failing that, the label color
+    let logo_only = !logo_name.is_empty()
+        && label.unwrap_or("").is_empty()
+        && message.unwrap_or("").is_empty()
+        && sparkline.is_none();
+    let block_color = message_color
+        .or(*label_color)
+        .unwrap_or(default_label_color());
+    let (label_color, message_color) = if logo_only {
+        (Some(block_color), Some(block_color))
+    } else {
+        (*label_color, *message_color)
+    };
+    // Social badges keep shields.io's Helvetica unless another family is asked for
+    let family = font.unwrap_or(match style {
+        BadgeStyle::Social => FontFamily::Helvetica,
+        _ => FontFamily::Verdana,
+    });
+    let default_logo_color = match style {
+        BadgeStyle::Social => "#000000".to_string(),
+        // Outline badges have no background, so the logo follows the label text
+        BadgeStyle::Outline => label_color
+            .and_then(to_svg_color)
+            .unwrap_or(default_label_color().to_string()),
+        _ => "whitesmoke".to_string(),
+    };
+
+    // `auto`, or no color over a light background, uses the logo's brand color when known
+    let logo_background = match style {
+        BadgeStyle::Social | BadgeStyle::Outline => None,
+        BadgeStyle::ForTheBadgeTwoLine => Some(message_color.unwrap_or(default_message_color())),
+        _ if label.unwrap_or("").is_empty() && label_color.unwrap_or("").is_empty() => None,
+        _ => Some(label_color.unwrap_or(default_label_color())),
+    };
+    let brand_logo = match logo_color.map(str::trim) {
+        Some(color) => color.eq_ignore_ascii_case("auto"),
+        None => logo_background.is_some_and(is_light_color),
+    };
+    let brand_color = logo.filter(|_| brand_logo).and_then(logos::brand_color);
+    let logo_color = match brand_color {
+        Some(color) => color,
+        None => logo_color
+            .and_then(to_svg_color)
+            .unwrap_or(default_logo_color),
+    };
+    // A single symbol such as ✔ is drawn as text in the logo slot instead of an icon
+    let logo_glyph = logo.map(str::trim).and_then(logos::glyph).unwrap_or("");
+    // Several slugs are combined into one image laid out like a single wide logo
+    let logo_list = logo.and_then(logos::split_list);
+    let logo = match logo.map(str::trim) {
+        Some(logo) if !logo.is_empty() && logo_glyph.is_empty() => {
+            if let Some(slugs) = &logo_list {
+                let auto_size = *logo_size == Some(LogoSize::Auto);
+                scratch.logo_key = None;
+                scratch.logo = logos::encode_list(slugs, &logo_color, brand_logo, auto_size)
+                    .unwrap_or_default();
+            } else {
+                scratch.encode_logo(logo, &logo_color);
+            }
+            scratch.logo.as_str()
+        }
+        _ => "",
+    };
+    let has_logo = !logo.is_empty() || !logo_glyph.is_empty();
+    // The accessible name of a lone logo, which never spells out inline image data
+    let logo_only = (logo_only && has_logo).then(|| logos::accessible_name(logo_name));
+    let logo_width: u32 = match (logo_glyph.chars().next(), logo_width, logo_size) {
+        (Some(c), _, _) => {
+            ctx.preferred_width_of(c.encode_utf8(&mut [0; 4]), Font::VerdanaNormal11)
+        }
+        (None, Some(width), _) => *width,
+        (None, None, size)
+            if !logo.is_empty() && (*size == Some(LogoSize::Auto) || logo_list.is_some()) =>
+        {
+            logos::aspect_ratio(logo).map_or(LOGO_HEIGHT, |ratio| {
+                (LOGO_HEIGHT as f64 * ratio).round() as u32
+            })
+        }
+        _ => LOGO_HEIGHT,
+    };
+    #[cfg(any(label_color_style, feature = "style-social"))]
+    let logo_glyph_width_scaled = logo_width * FONT_SCALE_UP_FACTOR;
+    #[cfg(classic_layout)]
+    let padding = ctx.options.horizontal_padding;
+    let mut extra_style = match &ctx.options.dark {
+        Some(dark) => dark.style(*style, &ctx.options.text_colors),
+        None => String::new(),
+    };
+    let unstyled = ctx.options.unstyled;
+    let id_suffix = ctx.options.id_suffix.resolve(params, &ctx.options);
+    let title = title.unwrap_or("");
+    let title_id = if title.is_empty() {
+        String::new()
+    } else {
+        format!("title{}", id_suffix)
+    };
+    let class_hooks = ctx.options.class_hooks || unstyled || !extra_style.is_empty();
+    if let Some(embed_font) = ctx.options.embed_font.as_ref().filter(|_| out.renders()) {
+        let font_family = match style {
+            BadgeStyle::Monospace => MONOSPACE_FONT_FAMILY,
+            _ => family.css(),
+        };
+        let texts = [label.unwrap_or(""), message.unwrap_or("")];
+        extra_style.push_str(&embed_font.font_face(font_family, &texts));
+    }
+    #[cfg(any(classic_layout, feature = "style-social"))]
+    let logo_padding = if label.is_some() && label.unwrap().is_empty() {
+        0
+    } else {
+        3
+    };
+
+    #[cfg(any(classic_layout, feature = "style-social"))]
+    let total_logo_width = if has_logo {
+        logo_width + logo_padding
+    } else {
+        0
+    };
+
+    #[cfg(classic_layout)]
+    let has_label_color = !label_color.unwrap_or("").is_empty();
+    #[cfg(colored_style)]
+    let message_color = message_color.unwrap_or(default_message_color());
+    #[cfg(colored_style)]
+    let message_color = to_svg_color(message_color).unwrap_or("#007ec6".to_string());
+
+    #[cfg(label_color_style)]
+    let label_color = match (
+        label.unwrap_or("").is_empty(),
+        label_color.unwrap_or("").is_empty(),
+    ) {
+        (true, true) if has_logo => "#555",
+        (true, true) => message_color.as_str(),
+        (_, _) => label_color.unwrap_or(default_label_color()),
+    };
+
+    #[cfg(label_color_style)]
+    let binding = to_svg_color(label_color).unwrap_or("#555".to_string());
+    #[cfg(label_color_style)]
+    let label_color = binding.as_str();
+
+    #[cfg(colored_style)]
+    let message_color = message_color.as_str();
+    // Social and outline badges draw no text over these colors
+    #[cfg(colored_style)]
+    let contrast_background = |color: &str| match style {
+        BadgeStyle::Social | BadgeStyle::Outline => None,
+        _ => ctx.contrast_background(color),
+    };
+    #[cfg(label_color_style)]
+    let label_binding = contrast_background(label_color);
+    #[cfg(label_color_style)]
+    let label_color = label_binding.as_deref().unwrap_or(label_color);
+    #[cfg(colored_style)]
+    let message_binding = contrast_background(message_color);
+    #[cfg(colored_style)]
+    let message_color = message_binding.as_deref().unwrap_or(message_color);
+    let message = message.unwrap_or("");
+    let (label_bidi, message_bidi) = match style {
+        BadgeStyle::ForTheBadgeTwoLine => (
+            bidi::left_anchored_text_attributes(label.unwrap_or("")),
+            bidi::left_anchored_text_attributes(message),
+        ),
+        _ => (
+            bidi::text_attributes(label.unwrap_or("")),
+            bidi::text_attributes(message),
+        ),
+    };
+    #[cfg(classic_layout)]
+    let sparkline_values = sparkline.filter(|values| values.iter().any(|v| v.is_finite()));
+    #[cfg(classic_layout)]
+    let sparkline_width = match sparkline_values {
+        Some(_) if message.is_empty() => SPARKLINE_WIDTH as i32,
+        Some(_) => (SPARKLINE_WIDTH + padding) as i32,
+        None => 0,
+    };
+    let link = href(*link, &ctx.options.link_policy);
+    let link = link.as_deref().unwrap_or("");
+    let extra_link = href(*extra_link, &ctx.options.link_policy);
+    #[cfg(classic_layout)]
+    let extra_link_not_empty_str = extra_link.as_ref().is_none_or(|link| !link.is_empty());
+    let extra_link = extra_link.as_deref().unwrap_or("");
+    match style {
+        #[cfg(feature = "style-flat")]
+        BadgeStyle::Flat => {
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, label, message);
+            let has_label_content = label.is_some() && !label.unwrap().is_empty();
+            let has_label = has_label_content || has_label_color;
+            let label_margin = total_logo_width + 1;
+
+            let label_width = if has_label && label.is_some() {
+                ctx.preferred_width_of(label.unwrap_or_default(), family.font(false, 11))
+            } else {
+                0
+            };
+
+            let mut left_width = if has_label {
+                (label_width + 2 * padding + total_logo_width) as i32
+            } else {
+                0
+            };
+
+            if has_label && label.is_some_and(str::is_empty) {
+                left_width -= 1;
+            }
+            let message_width = ctx.preferred_width_of(message, family.font(false, 11));
+
+            let offset = if label.is_none() && has_logo {
+                -3i32
+            } else {
+                0
+            };
+
+            let left_width = left_width + offset;
+            let mut message_margin: i32 = left_width - if message.is_empty() { 0 } else { 1 };
+            if !has_label {
+                if has_logo {
+                    message_margin += (total_logo_width + padding) as i32
+                } else {
+                    message_margin += 1
+                }
+            }
+
+            let mut right_width = (message_width + 2 * padding) as i32;
+            if has_logo && !has_label {
+                right_width += total_logo_width as i32
+                    + if !message.is_empty() {
+                        padding.saturating_sub(1) as i32
+                    } else {
+                        0i32
+                    };
+            }
+            right_width += sparkline_width + message_fill as i32;
+
+            let label_x = 10.0
+                * (label_margin as f32 + (0.5 * label_width as f32) + padding as f32)
+                + offset as f32;
+            let label_width_scaled = label_width * 10;
+            let total_width = left_width + right_width;
+
+            let right_width = right_width + if !has_label_color { offset } else { 0 };
+            let hex_label_color = Color::from_str(label_color)
+                .unwrap_or(Color::from_str("#555").unwrap())
+                .to_css_hex();
+            let hex_label_color = hex_label_color.as_str();
+            let hex_message_color = Color::from_str(message_color)
+                .unwrap_or(Color::from_str("#007ec6").unwrap())
+                .to_css_hex();
+            let hex_message_color = hex_message_color.as_str();
+            let (label_text_color, label_shadow_color) = ctx.text_colors(hex_label_color);
+            let (message_text_color, message_shadow_color) = ctx.text_colors(hex_message_color);
+            let rect_offset = if has_logo {
+                (padding + logo_width) as i32
+            } else {
+                0
+            };
+
+            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
+                total_logo_width as i32 + padding as i32
+            } else {
+                left_width
+            };
+
+            let has_extra_link = !extra_link.is_empty();
+            let message_x = 10.0
+                * (message_margin as f32
+                    + 0.5 * (message_width + message_fill) as f32
+                    + padding as f32);
+            let message_link_x = message_link_x
+                + if !has_label && has_extra_link {
+                    offset
+                } else {
+                    0
+                };
+            let message_width_scaled = message_width * 10;
+            let left_width = if left_width < 0 { 0 } else { left_width };
+            let frame = Frame::new(left_width, right_width, total_width, padding).or_logo_only(
+                logo_only.is_some(),
+                BADGE_HEIGHT,
+                logo_width,
+                LOGO_HEIGHT,
+            );
+            let sparkline = sparkline_points(
+                sparkline_values,
+                (frame.total_width - (padding + SPARKLINE_WIDTH) as i32) as f32,
+                BADGE_HEIGHT,
+            );
+            out.emit(FlatBadgeSvgTemplateContext {
+                font_family: family.css(),
+                bold_text: family.bold_only(),
+
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                id_suffix: id_suffix.as_str(),
+                badge_height: BADGE_HEIGHT as i32,
+
+                logo_only: logo_only.is_some(),
+                left_width: frame.left_width,
+                right_width: frame.right_width,
+                total_width: frame.total_width,
+
+                label_color,
+                message_color,
+
+                font_size_scaled: FONT_SIZE_SCALED as i32,
+
+                label: label.unwrap_or(""),
+                label_x,
+                label_width_scaled: label_width_scaled as i32,
+                label_text_color,
+                label_shadow_color,
+
+                message_x,
+                message_shadow_color,
+                message_text_color,
+                sparkline: sparkline.as_str(),
+                message_width_scaled: message_width_scaled as i32,
+                message,
+
+                link,
+                extra_link,
+                logo,
+                logo_width,
+                logo_glyph,
+                logo_x: frame.logo_x,
+                logo_glyph_x: frame.logo_glyph_x(logo_width),
+                logo_glyph_width_scaled,
+                logo_glyph_color: logo_color.as_str(),
+
+                rect_offset,
+                message_link_x,
+            })
+        }
+        #[cfg(feature = "style-flat-square")]
+        BadgeStyle::FlatSquare => {
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, label, message);
+            let has_label_content = label.is_some() && !label.unwrap().is_empty();
+            let has_label = has_label_content || has_label_color;
+            let label_margin = total_logo_width + 1;
+
+            let label_width = if has_label && label.is_some() {
+                ctx.preferred_width_of(label.unwrap_or_default(), family.font(false, 11))
+            } else {
+                0
+            };
+
+            let mut left_width = if has_label {
+                (label_width + 2 * padding + total_logo_width) as i32
+            } else {
+                0
+            };
+
+            if has_label && label.is_some_and(str::is_empty) {
+                left_width -= 1;
+            }
+            let message_width = ctx.preferred_width_of(message, family.font(false, 11));
+
+            let offset = if label.is_none() && has_logo {
+                -3i32
+            } else {
+                0
+            };
+
+            let left_width = left_width + offset;
+            let mut message_margin: i32 = left_width - if message.is_empty() { 0 } else { 1 };
+            if !has_label {
+                if has_logo {
+                    message_margin += (total_logo_width + padding) as i32
+                } else {
+                    message_margin += 1
+                }
+            }
+
+            let mut right_width = (message_width + 2 * padding) as i32;
+            if has_logo && !has_label {
+                right_width += total_logo_width as i32
+                    + if !message.is_empty() {
+                        padding.saturating_sub(1) as i32
+                    } else {
+                        0i32
+                    };
+            }
+            right_width += sparkline_width + message_fill as i32;
+
+            let label_x = 10.0
+                * (label_margin as f32 + (0.5 * label_width as f32) + padding as f32)
+                + offset as f32;
+            let label_width_scaled = label_width * 10;
+            let total_width = left_width + right_width;
+
+            let right_width = right_width + if !has_label_color { offset } else { 0 };
+            let hex_label_color = Color::from_str(label_color)
+                .unwrap_or(Color::from_str("#555").unwrap())
+                .to_css_hex();
+            let hex_label_color = hex_label_color.as_str();
+            let hex_message_color = Color::from_str(message_color)
+                .unwrap_or(Color::from_str("#007ec6").unwrap())
+                .to_css_hex();
+            let hex_message_color = hex_message_color.as_str();
+            let (label_text_color, _) = ctx.text_colors(hex_label_color);
+            let (message_text_color, _) = ctx.text_colors(hex_message_color);
+            let rect_offset = if has_logo {
+                (padding + logo_width) as i32
+            } else {
+                0
+            };
+
+            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
+                total_logo_width as i32 + padding as i32
+            } else {
+                left_width
+            };
+
+            let has_extra_link = !extra_link.is_empty();
+            let message_x = 10.0
+                * (message_margin as f32
+                    + 0.5 * (message_width + message_fill) as f32
+                    + padding as f32);
+            let message_link_x = message_link_x
+                + if !has_label && has_extra_link {
+                    offset
+                } else {
+                    0
+                };
+            let message_width_scaled = message_width * 10;
+            let left_width = if left_width < 0 { 0 } else { left_width };
+            let frame = Frame::new(left_width, right_width, total_width, padding).or_logo_only(
+                logo_only.is_some(),
+                BADGE_HEIGHT,
+                logo_width,
+                LOGO_HEIGHT,
+            );
+            let sparkline = sparkline_points(
+                sparkline_values,
+                (frame.total_width - (padding + SPARKLINE_WIDTH) as i32) as f32,
+                BADGE_HEIGHT,
+            );
+            out.emit(FlatSquareBadgeSvgTemplateContext {
+                font_family: family.css(),
+                bold_text: family.bold_only(),
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                badge_height: BADGE_HEIGHT as i32,
+                logo_only: logo_only.is_some(),
+                left_width: frame.left_width,
+                right_width: frame.right_width,
+                total_width: frame.total_width,
+                label_color,
+                message_color,
+                font_size_scaled: FONT_SIZE_SCALED as i32,
+                label: label.unwrap_or(""),
+                label_x,
+                label_width_scaled: label_width_scaled as i32,
+                label_text_color,
+                message_x,
+                message_text_color,
+                sparkline: sparkline.as_str(),
+                message_width_scaled: message_width_scaled as i32,
+                message,
+                link,
+                extra_link,
+                logo,
+                logo_width,
+                logo_glyph,
+                logo_x: frame.logo_x,
+                logo_glyph_x: frame.logo_glyph_x(logo_width),
+                logo_glyph_width_scaled,
+                logo_glyph_color: logo_color.as_str(),
+                rect_offset,
+                message_link_x,
+            })
+        }
+        #[cfg(feature = "style-monospace")]
+        BadgeStyle::Monospace => {
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, label, message);
+            let prompted;
+            let message = if ctx.options.prompt && !message.is_empty() {
+                prompted = format!("$ {}", message);
+                prompted.as_str()
+            } else {
+                message
+            };
+            let has_label_content = label.is_some() && !label.unwrap().is_empty();
+            let has_label = has_label_content || has_label_color;
+            let label_margin = total_logo_width + 1;
+
+            let label_width = if has_label && label.is_some() {
+                ctx.preferred_width_of(label.unwrap_or_default(), Font::MonospaceNormal11)
+            } else {
+                0
+            };
+
+            let mut left_width = if has_label {
+                (label_width + 2 * padding + total_logo_width) as i32
+            } else {
+                0
+            };
+
+            if has_label && label.is_some_and(str::is_empty) {
+                left_width -= 1;
+            }
+            let message_width = ctx.preferred_width_of(message, Font::MonospaceNormal11);
+
+            let offset = if label.is_none() && has_logo {
+                -3i32
+            } else {
+                0
+            };
+
+            let left_width = left_width + offset;
+            let mut message_margin: i32 = left_width - if message.is_empty() { 0 } else { 1 };
+            if !has_label {
+                if has_logo {
+                    message_margin += (total_logo_width + padding) as i32
+                } else {
+                    message_margin += 1
+                }
+            }
+
+            let mut right_width = (message_width + 2 * padding) as i32;
+            if has_logo && !has_label {
+                right_width += total_logo_width as i32
+                    + if !message.is_empty() {
+                        padding.saturating_sub(1) as i32
+                    } else {
+                        0i32
+                    };
+            }
+            right_width += sparkline_width + message_fill as i32;
+
+            let label_x = 10.0
+                * (label_margin as f32 + (0.5 * label_width as f32) + padding as f32)
+                + offset as f32;
+            let label_width_scaled = label_width * 10;
+            let total_width = left_width + right_width;
+
+            let right_width = right_width + if !has_label_color { offset } else { 0 };
+            let hex_label_color = Color::from_str(label_color)
+                .unwrap_or(Color::from_str("#555").unwrap())
+                .to_css_hex();
+            let hex_label_color = hex_label_color.as_str();
+            let hex_message_color = Color::from_str(message_color)
+                .unwrap_or(Color::from_str("#007ec6").unwrap())
+                .to_css_hex();
+            let hex_message_color = hex_message_color.as_str();
+            let (label_text_color, _) = ctx.text_colors(hex_label_color);
+            let (message_text_color, _) = ctx.text_colors(hex_message_color);
+            let rect_offset = if has_logo {
+                (padding + logo_width) as i32
+            } else {
+                0
+            };
+
+            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
+                total_logo_width as i32 + padding as i32
+            } else {
+                left_width
+            };
+
+            let has_extra_link = !extra_link.is_empty();
+            let message_x = 10.0
+                * (message_margin as f32
+                    + 0.5 * (message_width + message_fill) as f32
+                    + padding as f32);
+            let message_link_x = message_link_x
+                + if !has_label && has_extra_link {
+                    offset
+                } else {
+                    0
+                };
+            let message_width_scaled = message_width * 10;
+            let left_width = if left_width < 0 { 0 } else { left_width };
+            let frame = Frame::new(left_width, right_width, total_width, padding).or_logo_only(
+                logo_only.is_some(),
+                BADGE_HEIGHT,
+                logo_width,
+                LOGO_HEIGHT,
+            );
+            let sparkline = sparkline_points(
+                sparkline_values,
+                (frame.total_width - (padding + SPARKLINE_WIDTH) as i32) as f32,
+                BADGE_HEIGHT,
+            );
+            out.emit(MonospaceBadgeSvgTemplateContext {
+                font_family: MONOSPACE_FONT_FAMILY,
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                badge_height: BADGE_HEIGHT as i32,
+                logo_only: logo_only.is_some(),
+                left_width: frame.left_width,
+                right_width: frame.right_width,
+                total_width: frame.total_width,
+                label_color,
+                message_color,
+                font_size_scaled: FONT_SIZE_SCALED as i32,
+                label: label.unwrap_or(""),
+                label_x,
+                label_width_scaled: label_width_scaled as i32,
+                label_text_color,
+                message_x,
+                message_text_color,
+                sparkline: sparkline.as_str(),
+                message_width_scaled: message_width_scaled as i32,
+                message,
+                link,
+                extra_link,
+                logo,
+                logo_width,
+                logo_glyph,
+                logo_x: frame.logo_x,
+                logo_glyph_x: frame.logo_glyph_x(logo_width),
+                logo_glyph_width_scaled,
+                logo_glyph_color: logo_color.as_str(),
+                rect_offset,
+                message_link_x,
+            })
+        }
+        #[cfg(feature = "style-outline")]
+        BadgeStyle::Outline => {
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, label, message);
+            let has_label_content = label.is_some() && !label.unwrap().is_empty();
+            let has_label = has_label_content || has_label_color;
+            let label_margin = total_logo_width + 1;
+
+            let label_width = if has_label && label.is_some() {
+                ctx.preferred_width_of(label.unwrap_or_default(), family.font(false, 11))
+            } else {
+                0
+            };
+
+            let mut left_width = if has_label {
+                (label_width + 2 * padding + total_logo_width) as i32
+            } else {
+                0
+            };
+
+            if has_label && label.is_some_and(str::is_empty) {
+                left_width -= 1;
+            }
+            let message_width = ctx.preferred_width_of(message, family.font(false, 11));
+
+            let offset = if label.is_none() && has_logo {
+                -3i32
+            } else {
+                0
+            };
+
+            let left_width = left_width + offset;
+            let mut message_margin: i32 = left_width - if message.is_empty() { 0 } else { 1 };
+            if !has_label {
+                if has_logo {
+                    message_margin += (total_logo_width + padding) as i32
+                } else {
+                    message_margin += 1
+                }
+            }
+
+            let mut right_width = (message_width + 2 * padding) as i32;
+            if has_logo && !has_label {
+                right_width += total_logo_width as i32
+                    + if !message.is_empty() {
+                        padding.saturating_sub(1) as i32
+                    } else {
+                        0i32
+                    };
+            }
+            right_width += sparkline_width + message_fill as i32;
+
+            let label_x = 10.0
+                * (label_margin as f32 + (0.5 * label_width as f32) + padding as f32)
+                + offset as f32;
+            let label_width_scaled = label_width * 10;
+            let total_width = left_width + right_width;
+
+            let right_width = right_width + if !has_label_color { offset } else { 0 };
+            // Text takes the badge colors; there is no background to contrast with
+            let label_text_color = label_color;
+            let message_text_color = message_color;
+            let rect_offset = if has_logo {
+                (padding + logo_width) as i32
+            } else {
+                0
+            };
+
+            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
+                total_logo_width as i32 + padding as i32
+            } else {
+                left_width
+            };
+
+            let has_extra_link = !extra_link.is_empty();
+            let message_x = 10.0
+                * (message_margin as f32
+                    + 0.5 * (message_width + message_fill) as f32
+                    + padding as f32);
+            let message_link_x = message_link_x
+                + if !has_label && has_extra_link {
+                    offset
+                } else {
+                    0
+                };
+            let message_width_scaled = message_width * 10;
+            let left_width = if left_width < 0 { 0 } else { left_width };
+            let frame = Frame::new(left_width, right_width, total_width, padding).or_logo_only(
+                logo_only.is_some(),
+                BADGE_HEIGHT,
+                logo_width,
+                LOGO_HEIGHT,
+            );
+            let (label_border, message_border) =
+                outline_borders(frame.left_width, frame.total_width);
+            let sparkline = sparkline_points(
+                sparkline_values,
+                (frame.total_width - (padding + SPARKLINE_WIDTH) as i32) as f32,
+                BADGE_HEIGHT,
+            );
+            out.emit(OutlineBadgeSvgTemplateContext {
+                font_family: family.css(),
+                bold_text: family.bold_only(),
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                badge_height: BADGE_HEIGHT as i32,
+                left_width: frame.left_width,
+                right_width: frame.right_width,
+                total_width: frame.total_width,
+                label_color,
+                message_color,
+                label_border: label_border.as_str(),
+                message_border: message_border.as_str(),
+                font_size_scaled: FONT_SIZE_SCALED as i32,
+                label: label.unwrap_or(""),
+                label_x,
+                label_width_scaled: label_width_scaled as i32,
+                label_text_color,
+                message_x,
+                message_text_color,
+                sparkline: sparkline.as_str(),
+                message_width_scaled: message_width_scaled as i32,
+                message,
+                link,
+                extra_link,
+                logo,
+                logo_width,
+                logo_glyph,
+                logo_x: frame.logo_x,
+                logo_glyph_x: frame.logo_glyph_x(logo_width),
+                logo_glyph_width_scaled,
+                logo_glyph_color: logo_color.as_str(),
+                rect_offset,
+                message_link_x,
+            })
+        }
+        #[cfg(feature = "style-pill")]
+        BadgeStyle::Pill => {
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, label, message);
+            let has_label_content = label.is_some() && !label.unwrap().is_empty();
+            let has_label = has_label_content || has_label_color;
+            let label_margin = total_logo_width + 1;
+
+            let label_width = if has_label && label.is_some() {
+                ctx.preferred_width_of(label.unwrap_or_default(), family.font(false, 11))
+            } else {
+                0
+            };
+
+            let mut left_width = if has_label {
+                (label_width + 2 * padding + total_logo_width) as i32
+            } else {
+                0
+            };
+
+            if has_label && label.is_some_and(str::is_empty) {
+                left_width -= 1;
+            }
+            let message_width = ctx.preferred_width_of(message, family.font(false, 11));
+
+            let offset = if label.is_none() && has_logo {
+                -3i32
+            } else {
+                0
+            };
+
+            let left_width = left_width + offset;
+            let mut message_margin: i32 = left_width - if message.is_empty() { 0 } else { 1 };
+            if !has_label {
+                if has_logo {
+                    message_margin += (total_logo_width + padding) as i32
+                } else {
+                    message_margin += 1
+                }
+            }
+
+            let mut right_width = (message_width + 2 * padding) as i32;
+            if has_logo && !has_label {
+                right_width += total_logo_width as i32
+                    + if !message.is_empty() {
+                        padding.saturating_sub(1) as i32
+                    } else {
+                        0i32
+                    };
+            }
+            right_width += sparkline_width + message_fill as i32;
+
+            let label_x = 10.0
+                * (label_margin as f32 + (0.5 * label_width as f32) + padding as f32)
+                + offset as f32;
+            let label_width_scaled = label_width * 10;
+            let total_width = left_width + right_width;
+
+            let right_width = right_width + if !has_label_color { offset } else { 0 };
+            let hex_label_color = Color::from_str(label_color)
+                .unwrap_or(Color::from_str("#555").unwrap())
+                .to_css_hex();
+            let hex_label_color = hex_label_color.as_str();
+            let hex_message_color = Color::from_str(message_color)
+                .unwrap_or(Color::from_str("#007ec6").unwrap())
+                .to_css_hex();
+            let hex_message_color = hex_message_color.as_str();
+            let (label_text_color, _) = ctx.text_colors(hex_label_color);
+            let (message_text_color, _) = ctx.text_colors(hex_message_color);
+            let rect_offset = if has_logo {
+                (padding + logo_width) as i32
+            } else {
+                0
+            };
+
+            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
+                total_logo_width as i32 + padding as i32
+            } else {
+                left_width
+            };
+
+            let has_extra_link = !extra_link.is_empty();
+            let message_x = 10.0
+                * (message_margin as f32
+                    + 0.5 * (message_width + message_fill) as f32
+                    + padding as f32);
+            let message_link_x = message_link_x
+                + if !has_label && has_extra_link {
+                    offset
+                } else {
+                    0
+                };
+            let message_width_scaled = message_width * 10;
+            let left_width = if left_width < 0 { 0 } else { left_width };
+
+            // The flat-square layout, widened at both ends for the rounded caps
+            let end = PILL_END_PADDING;
+            let total_width = total_width + 2 * end;
+            let (left_width, right_width) = if left_width > 0 {
+                (left_width + end, right_width + end)
+            } else {
+                (0, right_width + 2 * end)
+            };
+            let label_x = label_x + (end * 10) as f32;
+            let message_x = message_x + (end * 10) as f32;
+            let rect_offset = rect_offset + end;
+            let message_link_x = message_link_x + end;
+            // A lone logo is centered on a circle
+            let frame = Frame::new(left_width, right_width, total_width, padding + end as u32)
+                .or_logo_only(logo_only.is_some(), BADGE_HEIGHT, logo_width, LOGO_HEIGHT);
+            let sparkline = sparkline_points(
+                sparkline_values,
+                (frame.total_width - end - (padding + SPARKLINE_WIDTH) as i32) as f32,
+                BADGE_HEIGHT,
+            );
+            out.emit(PillBadgeSvgTemplateContext {
+                font_family: family.css(),
+                bold_text: family.bold_only(),
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                id_suffix: id_suffix.as_str(),
+                badge_height: BADGE_HEIGHT as i32,
+                radius: BADGE_HEIGHT as i32 / 2,
+                logo_only: logo_only.is_some(),
+                left_width: frame.left_width,
+                right_width: frame.right_width,
+                total_width: frame.total_width,
+                label_color,
+                message_color,
+                font_size_scaled: FONT_SIZE_SCALED as i32,
+                label: label.unwrap_or(""),
+                label_x,
+                label_width_scaled: label_width_scaled as i32,
+                label_text_color,
+                message_x,
+                message_text_color,
+                sparkline: sparkline.as_str(),
+                message_width_scaled: message_width_scaled as i32,
+                message,
+                link,
+                extra_link,
+                logo,
+                logo_width,
+                logo_glyph,
+                logo_x: frame.logo_x,
+                logo_glyph_x: frame.logo_glyph_x(logo_width),
+                logo_glyph_width_scaled,
+                logo_glyph_color: logo_color.as_str(),
+                rect_offset,
+                message_link_x,
+            })
+        }
+        #[cfg(feature = "style-plastic")]
+        BadgeStyle::Plastic => {
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, label, message);
+            let has_label_content = label.is_some() && !label.unwrap().is_empty();
+            let has_label = has_label_content || has_label_color;
+            let label_margin = total_logo_width + 1;
+
+            let label_width = if has_label && label.is_some() {
+                ctx.preferred_width_of(label.unwrap_or_default(), family.font(false, 11))
+            } else {
+                0
+            };
+
+            let mut left_width = if has_label {
+                (label_width + 2 * padding + total_logo_width) as i32
+            } else {
+                0
+            };
+
+            if has_label && label.is_some_and(str::is_empty) {
+                left_width -= 1;
+            }
+            let message_width = ctx.preferred_width_of(message, family.font(false, 11));
+
+            let offset = if label.is_none() && has_logo {
+                -3i32
+            } else {
+                0
+            };
+
+            let left_width = left_width + offset;
+            let mut message_margin: i32 = left_width - if message.is_empty() { 0 } else { 1 };
+            if !has_label {
+                if has_logo {
+                    message_margin += (total_logo_width + padding) as i32;
+                } else {
+                    message_margin += 1
+                }
+            }
+
+            let mut right_width = (message_width + 2 * padding) as i32;
+            if has_logo && !has_label {
+                right_width += total_logo_width as i32
+                    + if !message.is_empty() {
+                        padding.saturating_sub(1) as i32
+                    } else {
+                        0i32
+                    };
+            }
+            right_width += sparkline_width + message_fill as i32;
+
+            let label_x = 10.0
+                * (label_margin as f32 + (0.5 * label_width as f32) + padding as f32)
+                + offset as f32;
+            let label_width_scaled = label_width * 10;
+            let total_width = left_width + right_width;
+
+            let right_width = right_width + if !has_label_color { offset } else { 0 };
+            let hex_label_color = Color::from_str(label_color)
+                .unwrap_or(Color::from_str("#555").unwrap())
+                .to_css_hex();
+            let hex_label_color = hex_label_color.as_str();
+            let hex_message_color = Color::from_str(message_color)
+                .unwrap_or(Color::from_str("#007ec6").unwrap())
+                .to_css_hex();
+            let hex_message_color = hex_message_color.as_str();
+            let (label_text_color, label_shadow_color) = ctx.text_colors(hex_label_color);
+            let (message_text_color, message_shadow_color) = ctx.text_colors(hex_message_color);
+            let rect_offset = if has_logo {
+                (padding + logo_width) as i32
+            } else {
+                0
+            };
+
+            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
+                total_logo_width as i32 + padding as i32
+            } else {
+                left_width
+            };
+
+            let has_extra_link = !extra_link.is_empty();
+            let message_x = 10.0
+                * (message_margin as f32
+                    + 0.5 * (message_width + message_fill) as f32
+                    + padding as f32);
+            let message_link_x = message_link_x
+                + if !has_label && has_extra_link {
+                    offset
+                } else {
+                    0
+                };
+            let message_width_scaled = message_width * 10;
+            let left_width = if left_width < 0 { 0 } else { left_width };
+            let frame = Frame::new(left_width, right_width, total_width, padding).or_logo_only(
+                logo_only.is_some(),
+                18,
+                logo_width,
+                LOGO_HEIGHT,
+            );
+            let sparkline = sparkline_points(
+                sparkline_values,
+                (frame.total_width - (padding + SPARKLINE_WIDTH) as i32) as f32,
+                18,
+            );
+            let gradient_stops = gradient_stops(&ctx.options.plastic_gradient);
+            out.emit(PlasticBadgeSvgTemplateContext {
+                font_family: family.css(),
+                bold_text: family.bold_only(),
+                total_width: frame.total_width,
+                logo_only: logo_only.is_some(),
+                left_width: frame.left_width,
+                right_width: frame.right_width,
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                id_suffix: id_suffix.as_str(),
+                gradient_stops: &gradient_stops,
+                label: label.unwrap_or(""),
+                label_x,
+                label_text_length: label_width_scaled as i32,
+                label_text_color,
+                label_shadow_color,
+                message,
+                message_x,
+                message_text_length: message_width_scaled as i32,
+                message_text_color,
+                sparkline: sparkline.as_str(),
+                message_shadow_color,
+                label_color,
+                message_color,
+                link,
+                extra_link,
+                logo,
+                logo_width,
+                logo_glyph,
+                logo_x: frame.logo_x,
+                logo_glyph_x: frame.logo_glyph_x(logo_width),
+                logo_glyph_width_scaled,
+                logo_glyph_color: logo_color.as_str(),
+                rect_offset,
+                message_link_x,
+            })
+        }
+        #[cfg(feature = "style-social")]
+        BadgeStyle::Social => {
+            let label_is_none = label.is_none();
+
+            let offset = if label_is_none && has_logo {
+                -3i32
+            } else {
+                0i32
+            };
+
+            let label = label.unwrap_or("");
+            let label = if ctx.options.preserve_case {
+                label.to_string()
+            } else {
+                capitalize(label).unwrap().to_string()
+            };
+            let label_str = label.as_str();
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, Some(label_str), message);
+            let internal_height = 19;
+            let label_horizontal_padding = 5;
+            let message_horizontal_padding = 4;
+            let horizontal_gutter = 6;
+
+            let label_text_width = ctx.preferred_width_of(label_str, family.font(true, 11));
+
+            let label_rect_width =
+                (label_text_width + total_logo_width + 2 * label_horizontal_padding) as i32
+                    + offset;
+
+            let message_text_width = ctx.preferred_width_of(message, family.font(true, 11));
+
+            let bubble_icon = params.bubble_icon;
+            // The bubble icon sits left of the count, followed by a small gap
+            let bubble_icon_gutter = 3;
+            let bubble_icon_width = if bubble_icon.is_some() {
+                SocialIcon::SIZE + bubble_icon_gutter
+            } else {
+                0
+            };
+            let message_rect_width = message_text_width
+                + 2 * message_horizontal_padding
+                + bubble_icon_width
+                + message_fill;
+            let has_message = !message.is_empty();
+
+            let message_bubble_main_x = label_rect_width as f32 + horizontal_gutter as f32 + 0.5;
+            let message_bubble_notch_x = label_rect_width + horizontal_gutter;
+            let bubble_icon_x = message_bubble_main_x - 0.5 + message_horizontal_padding as f32;
+            let label_text_x = FONT_SCALE_UP_FACTOR as f32
+                * (total_logo_width as f32
+                    + label_text_width as f32 / 2.0
+                    + label_horizontal_padding as f32
+                    + offset as f32);
+            let message_text_x = FONT_SCALE_UP_FACTOR as f32
+                * (label_rect_width as f32
+                    + horizontal_gutter as f32
+                    + (message_rect_width + bubble_icon_width) as f32 / 2.0);
+            let message_text_length = FONT_SCALE_UP_FACTOR * message_text_width;
+            let label_text_length = FONT_SCALE_UP_FACTOR * label_text_width;
+
+            let left_width = label_rect_width + 1;
+            let right_width = if has_message {
+                horizontal_gutter + message_rect_width as i32
+            } else {
+                0
+            };
+
+            let total_width = left_width + right_width;
+            let frame = Frame::new(left_width, right_width, total_width, HORIZONTAL_PADDING)
+                .or_logo_only(logo_only.is_some(), BADGE_HEIGHT, logo_width, LOGO_HEIGHT);
+            // A lone logo sits on the label section, which then spans the whole badge
+            let label_rect_width = match logo_only {
+                Some(_) => frame.total_width - 1,
+                None => label_rect_width,
+            };
+
+            out.emit(SocialBadgeSvgTemplateContext {
+                font_family: family.css(),
+                total_width: frame.total_width,
+                total_height: BADGE_HEIGHT as i32,
+                internal_height,
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                id_suffix: id_suffix.as_str(),
+                message_rect_width,
+                message_bubble_main_x,
+                message_bubble_notch_x,
+                label_text_length,
+                label: label_str,
+                message,
+                label_text_x,
+                message_text_x,
+                message_text_length,
+                bubble_icon_x,
+                bubble_icon_path: bubble_icon.as_ref().map_or("", SocialIcon::path),
+                label_rect_width,
                 link,
                 extra_link,
                 logo,
-                rect_offset,
-                message_link_x,
+                logo_x: frame.logo_x,
+                logo_width,
+                logo_glyph,
+                logo_glyph_x: frame.logo_glyph_x(logo_width),
+                logo_glyph_width_scaled,
+                logo_glyph_color: logo_color.as_str(),
+            })
+        }
+        #[cfg(feature = "style-for-the-badge")]
+        BadgeStyle::ForTheBadge => {
+            // label to uppercase
+            let label = uppercase(label.unwrap_or(""), ctx.options.preserve_case);
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, Some(label.as_str()), message);
+            let message = uppercase(message, ctx.options.preserve_case);
+            let font_size = 10;
+            let letter_spacing = 1.25;
+            let logo_text_gutter = 6i32;
+            let logo_margin = 9i32;
+            let logo_width = logo_width as i32;
+            let label_text_width = if !label.is_empty() {
+                (ctx.text_width(&label, family.font(false, 10))
+                    + letter_spacing * label.len() as f64) as i32
+            } else {
+                0
+            };
+            let message_text_width = if !message.is_empty() {
+                (ctx.text_width(&message, family.font(true, 10))
+                    + letter_spacing * message.len() as f64) as i32
+            } else {
+                0
+            };
+            let has_label = !label.is_empty();
+            let no_text = !has_label && message.is_empty();
+            let need_label_rect = has_label || (has_logo && !label_color.is_empty());
+            let gutter = if no_text {
+                logo_text_gutter - logo_margin
+            } else {
+                logo_text_gutter
+            };
+            let text_margin = 12;
+
+            // Logo positioning
+            let (logo_min_x, label_text_min_x) = if has_logo {
+                (logo_margin, logo_margin + logo_width + gutter)
+            } else {
+                (0, text_margin)
+            };
+
+            // Handle label and message rectangles
+            let (label_rect_width, message_text_min_x, message_rect_width) = if need_label_rect {
+                if has_label {
+                    (
+                        label_text_min_x + label_text_width + text_margin,
+                        label_text_min_x + label_text_width + text_margin + text_margin,
+                        2 * text_margin + message_text_width,
+                    )
+                } else {
+                    (
+                        2 * logo_margin + logo_width,
+                        2 * logo_margin + logo_width + text_margin,
+                        2 * text_margin + message_text_width,
+                    )
+                }
+            } else if has_logo {
+                (
+                    0,
+                    text_margin + logo_width + gutter,
+                    2 * text_margin + logo_width + gutter + message_text_width,
+                )
+            } else {
+                (0, text_margin, 2 * text_margin + message_text_width)
+            };
+            let message_rect_width = message_rect_width + message_fill as i32;
+            let left_width = label_rect_width;
+            let right_width = message_rect_width;
+            let total_width = left_width + right_width;
+            let frame = Frame::new(left_width, right_width, total_width, logo_min_x as u32)
+                .or_logo_only(logo_only.is_some(), 28, logo_width as u32, LOGO_HEIGHT);
+
+            let hex_label_color = Color::from_str(label_color)
+                .unwrap_or(Color::from_str("#555").unwrap())
+                .to_css_hex();
+            let hex_label_color = hex_label_color.as_str();
+            let hex_message_color = Color::from_str(message_color)
+                .unwrap_or(Color::from_str("#007ec6").unwrap())
+                .to_css_hex();
+            let hex_message_color = hex_message_color.as_str();
+
+            let message_mid_x =
+                message_text_min_x as f32 + 0.5 * (message_text_width + message_fill as i32) as f32;
+            let label_mid_x = label_text_min_x as f32 + 0.5 * label_text_width as f32;
+
+            let (label_text_color, _) = ctx.text_colors(hex_label_color);
+            let (message_text_color, _) = ctx.text_colors(hex_message_color);
+
+            out.emit(ForTheBadgeSvgTemplateContext {
+                total_width: frame.total_width,
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                logo_only: logo_only.is_some(),
+                left_width: frame.left_width,
+                right_width: frame.right_width,
+                label_color,
+                message_color,
+                font_family: family.css(),
+                bold_text: family.bold_only(),
+                font_size: font_size * FONT_SCALE_UP_FACTOR as i32,
+                label: label.as_str(),
+                label_x: label_mid_x * FONT_SCALE_UP_FACTOR as f32,
+                label_width_scaled: label_text_width * FONT_SCALE_UP_FACTOR as i32,
+                label_text_color,
+                message: message.as_str(),
+                message_x: message_mid_x * FONT_SCALE_UP_FACTOR as f32,
+                message_text_color,
+                message_width_scaled: message_text_width * FONT_SCALE_UP_FACTOR as i32,
+                link,
+                extra_link,
+                logo,
+                logo_x: frame.logo_x as i32,
+                logo_width: logo_width as u32,
+                logo_glyph,
+                logo_glyph_x: frame.logo_glyph_x(logo_width as u32),
+                logo_glyph_width_scaled,
+                logo_glyph_color: logo_color.as_str(),
+            })
+        }
+        #[cfg(feature = "style-for-the-badge-two-line")]
+        BadgeStyle::ForTheBadgeTwoLine => {
+            // Screen readers get the text as written, not the uppercased rendering
+            let accessible_text =
+                write_accessible_text(&mut scratch.accessible_text, label, message);
+            let label = uppercase(label.unwrap_or(""), ctx.options.preserve_case);
+            let message = uppercase(message, ctx.options.preserve_case);
+            let margin = 12;
+            let logo_gutter = 8;
+            // Icons are drawn 24px tall; glyphs at twice the 11px size they were measured at
+            let logo_size = if logo_glyph.is_empty() {
+                (24.0 * logo_width as f64 / LOGO_HEIGHT as f64).round() as i32
+            } else {
+                2 * logo_width as i32
+            };
+            // The message line is set at 14px, measured by scaling the 10px bold table
+            let message_scale = 1.4;
+            let letter_spacing = 1.25;
+            let label_text_width = if !label.is_empty() {
+                (ctx.text_width(&label, family.font(false, 10))
+                    + letter_spacing * label.chars().count() as f64)
+                    .ceil() as i32
+            } else {
+                0
+            };
+            let message_text_width = if !message.is_empty() {
+                (message_scale
+                    * (ctx.text_width(&message, family.font(true, 10))
+                        + letter_spacing * message.chars().count() as f64))
+                    .ceil() as i32
+            } else {
+                0
+            };
+            let text_x = if has_logo {
+                margin + logo_size + logo_gutter
+            } else {
+                margin
+            };
+            let text_width = label_text_width.max(message_text_width);
+            let total_width = if text_width > 0 {
+                text_x + text_width + margin
+            } else {
+                2 * margin + logo_size
+            } + message_fill as i32;
+            let frame = Frame::new(0, total_width, total_width, margin as u32).or_logo_only(
+                logo_only.is_some(),
+                40,
+                logo_size as u32,
+                24,
+            );
+            // A lone message is centered vertically
+            let message_y = if label.is_empty() { 250 } else { 330 };
+
+            let hex_color = Color::from_str(message_color)
+                .unwrap_or(Color::from_str("#007ec6").unwrap())
+                .to_css_hex();
+            let (text_color, _) = ctx.text_colors(hex_color.as_str());
+
+            out.emit(ForTheBadgeTwoLineSvgTemplateContext {
+                total_width: frame.total_width,
+                accessible_text: logo_only.unwrap_or(accessible_text),
+                title,
+                title_id: &title_id,
+                label_bidi,
+                message_bidi,
+                class_hooks,
+                unstyled,
+                extra_style: extra_style.as_str(),
+                color: message_color,
+                font_family: family.css(),
+                bold_text: family.bold_only(),
+                text_x: text_x * FONT_SCALE_UP_FACTOR as i32,
+                text_color,
+                label: label.as_str(),
+                label_width_scaled: label_text_width * FONT_SCALE_UP_FACTOR as i32,
+                message: message.as_str(),
+                message_y,
+                message_width_scaled: message_text_width * FONT_SCALE_UP_FACTOR as i32,
+                link,
+                extra_link,
+                logo,
+                logo_x: frame.logo_x as i32,
+                logo_width: logo_size as u32,
+                logo_glyph,
+                logo_glyph_x: frame.logo_glyph_x(logo_size as u32),
+                logo_glyph_width_scaled: logo_size as u32 * FONT_SCALE_UP_FACTOR,
+                logo_glyph_color: logo_color.as_str(),
+            })
+        }
+        // Reached only when the style's `style-*` feature is disabled
+        #[allow(unreachable_patterns)]
+        _ => Err(BadgeError::StyleNotEnabled(*style)),
+    }
+}
+
+/// Section widths and logo position of a badge.
+#[cfg(any_style)]
+struct Frame {
+    // Social and two-line badges lay out their sections without these
+    #[cfg_attr(not(label_color_style), allow(dead_code))]
+    left_width: i32,
+    #[cfg_attr(not(label_color_style), allow(dead_code))]
+    right_width: i32,
+    total_width: i32,
+    logo_x: u32,
+}
+
+#[cfg(any_style)]
+impl Frame {
+    fn new(left_width: i32, right_width: i32, total_width: i32, logo_x: u32) -> Self {
+        Frame {
+            left_width,
+            right_width,
+            total_width,
+            logo_x,
+        }
+    }
+
+    /// Returns this frame, or with `logo_only` the frame of a badge `height` pixels tall showing
+    /// nothing but a logo `logo_width` by `logo_height` pixels. The logo is centered on a single
+    /// message section, square unless a wide logo stretches it keeping the same margins.
+    fn or_logo_only(self, logo_only: bool, height: u32, logo_width: u32, logo_height: u32) -> Self {
+        if !logo_only {
+            return self;
+        }
+        let margin = height.saturating_sub(logo_height) / 2;
+        let width = height.max(logo_width + 2 * margin);
+        Frame::new(0, width as i32, width as i32, (width - logo_width) / 2)
+    }
+
+    /// Scaled x position of the middle of a glyph logo `logo_width` pixels wide.
+    fn logo_glyph_x(&self, logo_width: u32) -> f32 {
+        FONT_SCALE_UP_FACTOR as f32 * (self.logo_x as f32 + logo_width as f32 / 2.0)
+    }
+}
+
+/// Returns whether `color` is a background that dark text is drawn on.
+fn is_light_color(color: &str) -> bool {
+    let hex = match parse_color(color) {
+        Some(color) => color.to_css_hex(),
+        None => return false,
+    };
+    colors_for_background(&hex).0 == "#333"
+}
+
+/// Resolves a badge color to its rendered value.
+fn parse_color(color: &str) -> Option<Color> {
+    Color::from_str(&to_svg_color(color)?).ok()
+}
+
+/// Minimum text contrast ratio (WCAG 2 level AA for normal-size text).
+#[cfg(any(feature = "unstable", colored_style))]
+pub(crate) const MIN_CONTRAST_RATIO: f64 = 4.5;
+
+/// Contrast ratio between two colors, from 1 to 21 (WCAG 2 formula).
+#[cfg(any(feature = "unstable", colored_style))]
+pub(crate) fn contrast_ratio(a: &Color, b: &Color) -> f64 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+#[cfg(any(feature = "unstable", colored_style))]
+fn relative_luminance(color: &Color) -> f64 {
+    let channel = |c: f32| {
+        let c = c as f64;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(color.r) + 0.7152 * channel(color.g) + 0.0722 * channel(color.b)
+}
+
+/// Background shifted in lightness until its usual text color reaches [`MIN_CONTRAST_RATIO`]:
+/// darkened under light text, lightened under dark text. `None` if it is readable already.
+#[cfg(any(feature = "unstable", colored_style))]
+fn contrast_background(color: &str, text_colors: &TextColors) -> Option<String> {
+    let mut background = Color::from_str(color).ok()?;
+    let (text, _) = text_colors.for_background(&background.to_css_hex());
+    let text = parse_color(text)?;
+    if contrast_ratio(&background, &text) >= MIN_CONTRAST_RATIO {
+        return None;
+    }
+    let step = if relative_luminance(&text) > 0.5 {
+        -0.02
+    } else {
+        0.02
+    };
+    let [h, s, mut l, a] = background.to_hsla();
+    let h = if h.is_nan() { 0.0 } else { h };
+    while contrast_ratio(&background, &text) < MIN_CONTRAST_RATIO && (0.0..=1.0).contains(&l) {
+        l += step;
+        background = Color::from_hsla(h, s, l.clamp(0.0, 1.0), a);
+    }
+    Some(background.to_css_hex())
+}
+
+/// Polyline points plotting `values` in a [`SPARKLINE_WIDTH`] wide box starting at `x`,
+/// vertically inset by 4px in a badge `height` pixels tall. Non-finite values are skipped and
+/// a constant series is drawn as a flat line through the middle.
+#[cfg(classic_layout)]
+fn sparkline_points(values: Option<&[f64]>, x: f32, height: u32) -> String {
+    let Some(values) = values else {
+        return String::new();
+    };
+    let values: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+    let top = 4.0;
+    let bottom = height as f32 - 4.0;
+    let step = SPARKLINE_WIDTH as f32 / (values.len().max(2) - 1) as f32;
+    let mut points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let ratio = if max > min {
+                ((v - min) / (max - min)) as f32
+            } else {
+                0.5
+            };
+            let y = bottom - ratio * (bottom - top);
+            format!(
+                "{},{}",
+                round_to_tenth(x + i as f32 * step),
+                round_to_tenth(y)
+            )
+        })
+        .collect();
+    if points.len() == 1 {
+        let y = points[0].split(',').nth(1).unwrap_or_default().to_string();
+        points.push(format!(
+            "{},{}",
+            round_to_tenth(x + SPARKLINE_WIDTH as f32),
+            y
+        ));
+    }
+    points.join(" ")
+}
+
+#[cfg(classic_layout)]
+fn round_to_tenth(n: f32) -> f32 {
+    (n * 10.0).round() / 10.0
+}
+
+/// Border paths of an outline badge's label and message segments, 1px wide and centered on
+/// the half pixel. The label path is empty without a label; the message path closes over the
+/// divider between the segments.
+#[cfg(feature = "style-outline")]
+fn outline_borders(left_width: i32, total_width: i32) -> (String, String) {
+    let right = total_width - 3;
+    let message_end = format!("H{}a2.5 2.5 0 0 1 2.5 2.5v14a2.5 2.5 0 0 1-2.5 2.5", right);
+    if left_width == 0 {
+        let message = format!(
+            "M3 .5{}H3a2.5 2.5 0 0 1-2.5-2.5V3A2.5 2.5 0 0 1 3 .5z",
+            message_end
+        );
+        return (String::new(), message);
+    }
+    let divider = left_width as f32 + 0.5;
+    let label = format!(
+        "M{0} .5H3a2.5 2.5 0 0 0-2.5 2.5v14a2.5 2.5 0 0 0 2.5 2.5H{0}",
+        divider
+    );
+    let message = format!("M{0} .5{1}H{0}z", divider, message_end);
+    (label, message)
+}
+
+/// Rejects color and logo parameters that [`render_badge_svg`] would silently replace, and
+/// links that cannot be used as an `href` as they are or that `link_policy` rejects.
+fn validate_params(params: &BadgeParams, link_policy: &LinkPolicy) -> Result<(), BadgeError> {
+    for (param, color) in [
+        ("label_color", params.label_color),
+        ("message_color", params.message_color),
+        ("logo_color", params.logo_color),
+    ] {
+        if let Some(color) = color
+            && !color.is_empty()
+            && !(param == "logo_color" && color.trim().eq_ignore_ascii_case("auto"))
+            && to_svg_color(color).is_none()
+        {
+            return Err(BadgeError::InvalidColor {
+                param,
+                value: color.to_string(),
+            });
+        }
+    }
+    for (param, link) in [("link", params.link), ("extra_link", params.extra_link)] {
+        if let Some(link) = link
+            && (link
+                .chars()
+                .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '"' | '<' | '>'))
+                || !link_policy.allows(link))
+        {
+            return Err(BadgeError::InvalidLink {
+                param,
+                value: link.to_string(),
+            });
+        }
+    }
+    if let Some(logo) = params.logo.map(str::trim)
+        && !logo.is_empty()
+        && logos::glyph(logo).is_none()
+    {
+        let slugs = logos::split_list(logo).unwrap_or_else(|| vec![logo]);
+        if let Some(slug) = slugs
+            .into_iter()
+            .find(|slug| logos::inline(slug).is_none() && logos::get_svg(slug).is_none())
+        {
+            return Err(BadgeError::InvalidLogo(slug.to_string()));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn write_accessible_text<'a>(
+    buf: &'a mut String,
+    label: Option<&str>,
+    message: &str,
+) -> &'a str {
+    buf.clear();
+    if let Some(label) = label.filter(|l| !l.is_empty()) {
+        buf.push_str(label);
+        if !message.is_empty() {
+            buf.push_str(": ");
+        }
+    }
+    buf.push_str(message);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use csscolorparser::Color;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    use super::*;
+    #[test]
+    fn test_svg() {
+        // Test SVG rendering
+        let params = BadgeParams {
+            style: BadgeStyle::FlatSquare,
+            label: Some("build"),
+            message: Some("passing"),
+            label_color: Some("#333"),
+            message_color: Some("#4c1"),
+            link: None,
+            extra_link: None,
+            logo: None,
+            logo_color: None,
+            ..Default::default()
+        };
+        let svg = render_badge_svg(&params);
+        assert!(!svg.is_empty(), "SVG rendering failed");
+    }
+
+    #[test]
+    fn test_style_names() {
+        for style in BadgeStyle::ALL {
+            assert_eq!(style.to_string().parse(), Ok(style));
+            // Names match the serde representation
+            assert_eq!(
+                serde_json::to_string(&style).unwrap(),
+                format!("\"{}\"", style)
+            );
+        }
+        assert_eq!(" Flat-Square ".parse(), Ok(BadgeStyle::FlatSquare));
+        assert_eq!(
+            "flat_square".parse::<BadgeStyle>().unwrap_err().to_string(),
+            "unknown style `flat_square`"
+        );
+    }
+
+    #[cfg(all_styles)]
+    #[test]
+    fn test_compute_layout() {
+        let attr = |svg: &str, name: &str| -> f32 {
+            let at = svg.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+            svg[at..at + svg[at..].find('"').unwrap()].parse().unwrap()
+        };
+        for style in BadgeStyle::ALL {
+            let params = BadgeParams {
+                style,
+                label: Some("build"),
+                message: Some("passing"),
+                logo: Some("rust"),
+                ..Default::default()
+            };
+            let layout = compute_layout(&params);
+            let svg = render_badge_svg(&params);
+            assert_eq!(layout.width as f32, attr(&svg, "width"), "{:?}", style);
+            assert_eq!(layout.height as f32, attr(&svg, "height"), "{:?}", style);
+            let image = &svg[svg.find("<image").unwrap()..];
+            let logo = layout.logo.unwrap();
+            assert_eq!(logo.x, attr(image, "x"), "{:?}", style);
+            assert_eq!(logo.y, attr(image, "y"), "{:?}", style);
+            assert_eq!(logo.width, attr(image, "width"), "{:?}", style);
+        }
+
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let layout = compute_layout(&params);
+        assert_eq!(badge_dimensions(&params), (layout.width, 20));
+        assert_eq!(layout.logo, None);
+        assert_eq!(layout.label_width + layout.message_width, layout.width);
+        assert!(layout.label_x < layout.label_width as f32);
+        assert!(layout.message_x > layout.label_width as f32);
+    }
+
+    #[cfg(all_styles)]
+    #[test]
+    fn test_label_only() {
+        for style in BadgeStyle::ALL {
+            let label_only = BadgeParams {
+                style,
+                label: Some("docs"),
+                label_color: Some("purple"),
+                ..Default::default()
+            };
+            let svg = render_badge_svg(&label_only);
+            let aria_label = svg.to_lowercase().contains("aria-label=\"docs\"");
+            assert!(aria_label, "{:?}", style);
+            if matches!(style, BadgeStyle::Social | BadgeStyle::ForTheBadgeTwoLine) {
+                continue;
             }
-            .render()
-            .unwrap_or_else(|e| format!("<!-- Askama render error: {} -->", e))
+            // Same layout as a message-only badge, with nothing in the message color
+            let message_only = BadgeParams {
+                style,
+                label: Some(""),
+                message: Some("docs"),
+                message_color: Some("purple"),
+                ..Default::default()
+            };
+            assert_eq!(
+                compute_layout(&label_only),
+                compute_layout(&message_only),
+                "{:?}",
+                style
+            );
+            assert!(!svg.contains("#007ec6"), "{:?}", style);
+            // An empty message keeps its block
+            assert_ne!(
+                compute_layout(&BadgeParams {
+                    message: Some(""),
+                    ..label_only.clone()
+                }),
+                compute_layout(&label_only),
+                "{:?}",
+                style
+            );
         }
-        BadgeStyle::Plastic => {
-            let accessible_text = create_accessible_text(label, message);
-            let has_label_content = label.is_some() && !label.unwrap().is_empty();
-            let has_label = has_label_content || has_label_color;
-            let label_margin = total_logo_width + 1;
+        // The single section links to whichever link is set
+        let svg = render_badge_svg(&BadgeParams {
+            label: Some("docs"),
+            link: Some(""),
+            extra_link: Some("https://docs.example.com"),
+            ..Default::default()
+        });
+        assert!(svg.contains(r#"href="https://docs.example.com""#));
+    }
+
+    #[cfg(all_styles)]
+    #[test]
+    fn test_logo_only() {
+        for (style, size, logo_x) in [
+            (BadgeStyle::Flat, 20, 3.0),
+            (BadgeStyle::FlatSquare, 20, 3.0),
+            (BadgeStyle::Plastic, 18, 2.0),
+            (BadgeStyle::Social, 20, 3.0),
+            (BadgeStyle::ForTheBadge, 28, 7.0),
+            (BadgeStyle::ForTheBadgeTwoLine, 40, 8.0),
+            (BadgeStyle::Pill, 20, 3.0),
+            (BadgeStyle::Outline, 20, 3.0),
+            (BadgeStyle::Monospace, 20, 3.0),
+        ] {
+            let params = BadgeParams {
+                style,
+                logo: Some("github"),
+                message_color: Some("#181717"),
+                ..Default::default()
+            };
+            let layout = compute_layout(&params);
+            assert_eq!((layout.width, layout.height), (size, size), "{:?}", style);
+            let logo = layout.logo.unwrap();
+            assert_eq!(logo.x, logo_x, "{:?}", style);
+            assert_eq!(2.0 * logo.x + logo.width, size as f32, "{:?}", style);
+            let svg = render_badge_svg(&params);
+            assert!(svg.contains("aria-label=\"github\""), "{:?}", style);
+            assert!(!svg.contains("#555"), "{:?}", style);
+            assert!(!svg.contains("width=\"0\""), "{:?}", style);
+            let labeled = BadgeParams {
+                label: Some(""),
+                ..params.clone()
+            };
+            let labeled = compute_layout(&labeled);
+            assert_eq!(
+                (labeled.width, labeled.logo),
+                (size, layout.logo),
+                "{:?}",
+                style
+            );
+        }
+        // Only the label color set, or a wide logo
+        let params = BadgeParams {
+            logo: Some("github"),
+            label_color: Some("purple"),
+            ..Default::default()
+        };
+        assert!(render_badge_svg(&params).contains("fill=\"purple\""));
+        let layout = compute_layout(&BadgeParams {
+            logo_width: Some(30),
+            ..params.clone()
+        });
+        assert_eq!((layout.width, layout.logo.unwrap().x), (36, 3.0));
+        // Inline images are named generically rather than by their markup
+        let svg = render_badge_svg(&BadgeParams {
+            logo: Some("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 1 1\"/>"),
+            ..params
+        });
+        assert!(svg.contains("aria-label=\"logo\""));
+        assert!(svg.contains("<title>logo</title>"));
+    }
 
-            let label_width = if has_label && label.is_some() {
-                preferred_width_of(label.unwrap_or_default(), Font::VerdanaNormal11)
-            } else {
-                0
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_pretty_output() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let svg = render_badge_svg(&params);
+        let pretty = render_badge_svg_with_options(&params, &RenderOptions::new().pretty(true));
+        assert!(pretty.starts_with("<svg"));
+        assert!(pretty.contains("\n  <title>build: passing</title>\n"));
+        let unindented: String = pretty.lines().map(str::trim_start).collect();
+        assert_eq!(unindented, svg);
+    }
+
+    #[test]
+    fn test_render_to_writers() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            logo: Some("rust"),
+            ..Default::default()
+        };
+        let expected = render_badge_svg(&params);
+        let mut out = String::from("<!-- badge -->");
+        render_badge_svg_to(&params, &mut out).unwrap();
+        assert_eq!(out, format!("<!-- badge -->{}", expected));
+
+        let mut bytes = Vec::new();
+        render_badge_svg_to_io(&params, &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), expected);
+
+        let mut full = [0u8; 16];
+        let err = render_badge_svg_to_io(&params, &mut &mut full[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_owned_params_conversions() {
+        let params = BadgeParams {
+            style: BadgeStyle::Social,
+            label: Some("stars"),
+            message: Some("1.2k"),
+            link: Some("https://github.com/Jannchie/shields.rs"),
+            bubble_icon: Some(SocialIcon::Star),
+            ..Default::default()
+        };
+        let owned = BadgeParamsOwned::from(&params);
+        assert_eq!(BadgeParams::from(&owned), params);
+        assert_eq!(BadgeParamsOwned::from(params), owned);
+
+        let json = serde_json::to_string(&owned).unwrap();
+        assert_eq!(
+            serde_json::from_str::<BadgeParamsOwned>(&json).unwrap(),
+            owned
+        );
+        let parsed: BadgeParamsOwned =
+            serde_json::from_str(r#"{"label": "build", "message": "passing"}"#).unwrap();
+        assert_eq!(parsed.style, BadgeStyle::Flat);
+        assert_eq!(parsed.message.as_deref(), Some("passing"));
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_render_limits() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let unlimited =
+            try_render_badge_svg_with_limits(&params, &RenderLimits::default()).unwrap();
+        assert_eq!(unlimited, render_badge_svg(&params));
+
+        let chars = RenderLimits {
+            max_chars: Some(11),
+            ..Default::default()
+        };
+        assert_eq!(
+            try_render_badge_svg_with_limits(&params, &chars),
+            Err(BadgeError::Limit(LimitKind::Chars {
+                max: 11,
+                actual: 12
+            }))
+        );
+        // The tooltip counts like any other text
+        let titled = BadgeParams {
+            label: None,
+            message: Some("ok"),
+            title: Some("a long explanation"),
+            ..params
+        };
+        assert!(matches!(
+            try_render_badge_svg_with_limits(&titled, &chars),
+            Err(BadgeError::Limit(LimitKind::Chars { actual: 20, .. }))
+        ));
+
+        let points = RenderLimits {
+            max_sparkline_points: Some(3),
+            ..Default::default()
+        };
+        let sparkline = BadgeParams {
+            sparkline: Some(&[1.0, 2.0, 3.0, 4.0]),
+            ..params
+        };
+        assert_eq!(
+            try_render_badge_svg_with_limits(&sparkline, &points),
+            Err(BadgeError::Limit(LimitKind::SparklinePoints {
+                max: 3,
+                actual: 4
+            }))
+        );
+        assert!(try_render_badge_svg_with_limits(&params, &points).is_ok());
+
+        let bytes = RenderLimits {
+            max_output_bytes: Some(100),
+            ..Default::default()
+        };
+        assert!(matches!(
+            try_render_badge_svg_with_limits(&params, &bytes),
+            Err(BadgeError::Limit(LimitKind::OutputBytes { max: 100, .. }))
+        ));
+
+        let expired = RenderLimits {
+            deadline: Some(Instant::now()),
+            ..Default::default()
+        };
+        assert_eq!(
+            try_render_badge_svg_with_limits(&params, &expired),
+            Err(BadgeError::Limit(LimitKind::Deadline))
+        );
+    }
+
+    #[cfg(all_styles)]
+    #[test]
+    fn test_fixed_width() {
+        let options = RenderOptions::new().fixed_width(120);
+        for style in BadgeStyle::ALL {
+            let params = BadgeParams {
+                style,
+                label: Some("v"),
+                message: Some("1.0"),
+                ..Default::default()
             };
+            let svg = render_badge_svg_with_options(&params, &options);
+            assert_eq!(svg_size(&svg).unwrap().0, 120.0, "{:?}: {}", style, svg);
 
-            let mut left_width = if has_label {
-                (label_width + 2 * HORIZONTAL_PADDING + total_logo_width) as i32
-            } else {
-                0
+            let long = BadgeParams {
+                message: Some("a release name far too long for the column"),
+                ..params
             };
+            let svg = render_badge_svg_with_options(&long, &options);
+            assert_eq!(svg_size(&svg).unwrap().0, 120.0, "{:?}: {}", style, svg);
+            assert!(svg.contains('…'));
+            assert!(svg.contains("column</title>"));
+        }
+    }
 
-            if has_label && label.is_some() {
-                let label = label.unwrap();
-                if label.is_empty() {
-                    left_width -= 1;
-                }
-            }
-            let message_width = preferred_width_of(message, Font::VerdanaNormal11);
+    #[cfg(all(feature = "style-flat", feature = "style-for-the-badge-two-line"))]
+    #[test]
+    fn test_rtl_text() {
+        let params = BadgeParams {
+            label: Some("גרסה"),
+            message: Some("2.0"),
+            ..Default::default()
+        };
+        let svg = render_badge_svg(&params);
+        assert!(svg.contains(r#"direction="rtl" unicode-bidi="embed">גרסה</text>"#));
+        assert!(!svg.contains(r#"direction="rtl" unicode-bidi="embed">2.0</text>"#));
+        let two_line = render_badge_svg(&BadgeParams {
+            style: BadgeStyle::ForTheBadgeTwoLine,
+            ..params
+        });
+        assert!(two_line.contains(r#"text-anchor="end">גרסה</text>"#));
+
+        // Mirroring swaps the sides and colors but keeps the reading of the badge
+        let mirrored = render_badge_svg_with_options(
+            &BadgeParams {
+                message_color: Some("green"),
+                ..params
+            },
+            &RenderOptions::new()
+                .mirror(Mirror::Auto)
+                .id_suffix(IdSuffix::Plain),
+        );
+        assert!(mirrored.find(">2.0<").unwrap() < mirrored.find(">גרסה<").unwrap());
+        assert!(mirrored.contains(r##"<rect width="27" height="20" fill="#97ca00"/>"##));
+        assert!(mirrored.contains("<title id=\"title\">גרסה: 2.0</title>"));
+        assert_eq!(
+            svg_size(&mirrored).unwrap(),
+            svg_size(&svg).unwrap(),
+            "{}",
+            mirrored
+        );
+        let ltr = BadgeParams {
+            label: Some("version"),
+            ..params
+        };
+        let plain = RenderOptions::new().id_suffix(IdSuffix::Plain);
+        assert_eq!(
+            render_badge_svg_with_options(&ltr, &plain.clone().mirror(Mirror::Auto)),
+            render_badge_svg_with_options(&ltr, &plain)
+        );
 
-            let offset = if label.is_none() && has_logo {
-                -3i32
-            } else {
-                0
+        // Vowel marks and bidi controls take no space
+        assert_eq!(
+            preferred_width_of("\u{200F}שָׁלוֹם", Font::VerdanaNormal11),
+            preferred_width_of("שלום", Font::VerdanaNormal11)
+        );
+    }
+
+    #[cfg(all(
+        feature = "style-flat",
+        feature = "style-social",
+        feature = "style-for-the-badge",
+        feature = "style-for-the-badge-two-line"
+    ))]
+    #[test]
+    fn test_preserve_case() {
+        let preserve = RenderOptions::new().preserve_case(true);
+        for style in [BadgeStyle::ForTheBadge, BadgeStyle::ForTheBadgeTwoLine] {
+            let params = BadgeParams {
+                style,
+                label: Some("npm"),
+                message: Some("iOS"),
+                ..Default::default()
             };
+            let svg = render_badge_svg(&params);
+            assert!(svg.contains(">NPM</text>") && svg.contains(">IOS</text>"));
+            let svg = render_badge_svg_with_options(&params, &preserve);
+            assert!(svg.contains(">npm</text>"), "{}", svg);
+            assert!(svg.contains(">iOS</text>"), "{}", svg);
+        }
+        let social = BadgeParams {
+            style: BadgeStyle::Social,
+            label: Some("npm"),
+            message: Some("42"),
+            ..Default::default()
+        };
+        assert!(render_badge_svg(&social).contains(">Npm</text>"));
+        assert!(render_badge_svg_with_options(&social, &preserve).contains(">npm</text>"));
+    }
 
-            let left_width = left_width + offset as i32;
-            let mut message_margin: i32 =
-                left_width as i32 - if message.is_empty() { 0 } else { 1 };
-            if !has_label {
-                if has_logo {
-                    message_margin += (total_logo_width + HORIZONTAL_PADDING) as i32;
-                } else {
-                    message_margin += 1
+    #[cfg(all(feature = "style-flat", feature = "style-monospace"))]
+    #[test]
+    fn test_embed_font() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let font: Arc<[u8]> =
+            Arc::from(&include_bytes!("../tests/fixtures/dejavu-sans-ascii.ttf")[..]);
+        let options = RenderOptions::new().embed_font(EmbeddedFont::Subset(font.clone()));
+        let svg = render_badge_svg_with_options(&params, &options);
+        xmltree::Element::parse(svg.as_bytes()).unwrap();
+        assert!(
+            svg.contains(
+                r#"<style>@font-face{font-family:"Verdana";src:url(data:font/ttf;base64,"#
+            )
+        );
+        assert!(!svg.contains("class="));
+        let encoded = svg
+            .split("base64,")
+            .nth(1)
+            .unwrap()
+            .split(')')
+            .next()
+            .unwrap();
+        let subset = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        assert!(subset.len() < font.len() / 2);
+
+        let svg = render_badge_svg_with_options(
+            &BadgeParams {
+                style: BadgeStyle::Monospace,
+                ..params
+            },
+            &options,
+        );
+        assert!(svg.contains(r#"@font-face{font-family:"DejaVu Sans Mono";"#));
+
+        let options = RenderOptions::new().embed_font(EmbeddedFont::Url(
+            "https://fonts.example.com/a\"b</style>.woff2".to_string(),
+        ));
+        let svg = render_badge_svg_with_options(&params, &options);
+        assert!(svg.contains(r#"src:url("https://fonts.example.com/a%22b%3C/style%3E.woff2")"#));
+
+        // Unusable font data is left out
+        let options = RenderOptions::new().embed_font(EmbeddedFont::Subset(Arc::from(&b"x"[..])));
+        assert!(!render_badge_svg_with_options(&params, &options).contains("<style>"));
+    }
+
+    #[cfg(all_styles)]
+    #[test]
+    fn test_font_family() {
+        for style in BadgeStyle::ALL {
+            let params = BadgeParams {
+                style,
+                label: Some("build"),
+                message: Some("passing"),
+                ..Default::default()
+            };
+            let default_family = match style {
+                BadgeStyle::Social => FontFamily::Helvetica,
+                _ => FontFamily::Verdana,
+            };
+            let plain = RenderOptions::new().id_suffix(IdSuffix::Plain);
+            assert_eq!(
+                render_badge_svg_with_options(&params, &plain),
+                render_badge_svg_with_options(
+                    &BadgeParams {
+                        font: Some(default_family),
+                        ..params
+                    },
+                    &plain
+                ),
+                "{:?}",
+                style
+            );
+            for family in FontFamily::ALL {
+                let svg = render_badge_svg(&BadgeParams {
+                    font: Some(family),
+                    ..params
+                });
+                xmltree::Element::parse(svg.as_bytes()).unwrap();
+                if style != BadgeStyle::Monospace {
+                    assert!(
+                        svg.contains(&format!(r#"font-family="{}""#, family.css())),
+                        "{:?} {:?}",
+                        style,
+                        family
+                    );
                 }
             }
+        }
 
-            let mut right_width = (message_width + 2 * HORIZONTAL_PADDING) as i32;
-            if has_logo && !has_label {
-                right_width += total_logo_width as i32
-                    + if !message.is_empty() {
-                        (HORIZONTAL_PADDING - 1) as i32
-                    } else {
-                        0i32
-                    };
-            }
+        // Each family is sized with its own widths
+        let width = |font| {
+            badge_dimensions(&BadgeParams {
+                label: Some("build"),
+                message: Some("passing"),
+                font: Some(font),
+                ..Default::default()
+            })
+            .0
+        };
+        assert_ne!(width(FontFamily::Verdana), width(FontFamily::DejaVuSans));
+        assert_ne!(width(FontFamily::Verdana), width(FontFamily::Helvetica));
+        let svg = render_badge_svg(&BadgeParams {
+            message: Some("ok"),
+            font: Some(FontFamily::Helvetica),
+            ..Default::default()
+        });
+        assert!(svg.contains(r#" font-weight="bold""#));
+
+        assert_eq!("DejaVu-Sans".parse(), Ok(FontFamily::DejaVuSans));
+        assert!("comic-sans".parse::<FontFamily>().is_err());
+        let params = BadgeParamsOwned::from_query_str("message=ok&font=helvetica");
+        assert_eq!(params.font, Some(FontFamily::Helvetica));
+    }
 
-            let label_x = 10.0
-                * (label_margin as f32 + (0.5 * label_width as f32) + HORIZONTAL_PADDING as f32)
-                + offset as f32;
-            let label_width_scaled = label_width * 10;
-            let total_width = left_width + right_width as i32;
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_link_policy() {
+        let policy = LinkPolicy::default();
+        for link in [
+            "https://a.example",
+            "HTTP://a.example",
+            "mailto:a@b.example",
+            "/docs",
+            "#x",
+        ] {
+            assert!(policy.allows(link), "{}", link);
+        }
+        for link in [
+            "javascript:alert(1)",
+            " JavaScript:alert(1)",
+            "java\tscript:alert(1)",
+            "data:text/html,<b>",
+            "vbscript:x",
+        ] {
+            assert!(!policy.allows(link), "{}", link);
+        }
+        let strict = LinkPolicy {
+            allow_relative: false,
+            ..Default::default()
+        };
+        assert!(!strict.allows("/docs"));
 
-            let right_width = right_width + if !has_label_color { offset } else { 0 };
-            let hex_label_color = Color::from_str(label_color)
-                .unwrap_or(Color::from_str("#555").unwrap())
-                .to_css_hex();
-            let hex_label_color = hex_label_color.as_str();
-            let hex_message_color = Color::from_str(message_color)
-                .unwrap_or(Color::from_str("#007ec6").unwrap())
-                .to_css_hex();
-            let hex_message_color = hex_message_color.as_str();
-            let (label_text_color, label_shadow_color) = colors_for_background(hex_label_color);
-            let (message_text_color, message_shadow_color) =
-                colors_for_background(hex_message_color);
-            let rect_offset = if has_logo { 19 } else { 0 };
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            link: Some("javascript:alert(1)"),
+            extra_link: Some("https://ci.example.com"),
+            ..Default::default()
+        };
+        assert!(!render_badge_svg(&params).contains("javascript"));
+        // A rejected message link leaves the label link covering the whole badge
+        let swapped = BadgeParams {
+            link: params.extra_link,
+            extra_link: params.link,
+            ..params
+        };
+        let svg = render_badge_svg(&swapped);
+        assert!(!svg.contains("javascript"));
+        assert_eq!(svg.matches(r#"href="https://ci.example.com""#).count(), 1);
+        assert_eq!(
+            try_render_badge_svg(&params),
+            Err(BadgeError::InvalidLink {
+                param: "link",
+                value: "javascript:alert(1)".to_string(),
+            })
+        );
 
-            let message_link_x = if has_logo && !has_label && extra_link_not_empty_str {
-                total_logo_width as i32 + HORIZONTAL_PADDING as i32
-            } else {
-                left_width
-            };
+        // Quotes cannot end the attribute even when the link is rendered
+        let svg = render_badge_svg(&BadgeParams {
+            link: Some("https://a.example/\" onclick=\"x"),
+            extra_link: None,
+            ..params
+        });
+        assert!(svg.contains(r#"href="https://a.example/&quot; onclick=&quot;x""#));
+        // Query strings keep their ampersands escaped
+        let params = BadgeParams {
+            link: Some("https://a.example/?a=1&b='2'"),
+            ..params
+        };
+        let svg = try_render_badge_svg(&params).unwrap();
+        assert!(svg.contains(r#"href="https://a.example/?a=1&amp;b=&#39;2&#39;""#));
+    }
 
-            let has_extra_link = !extra_link.is_empty();
-            let message_x = 10.0
-                * (message_margin as f32
-                    + (0.5 * message_width as f32)
-                    + HORIZONTAL_PADDING as f32);
-            let message_link_x = message_link_x
-                + if !has_label && has_extra_link {
-                    offset
-                } else {
-                    0
-                } as i32;
-            let message_width_scaled = message_width * 10;
-            let left_width = if left_width < 0 { 0 } else { left_width };
-            PlasticBadgeSvgTemplateContext {
-                total_width,
-                left_width,
-                right_width,
-                accessible_text: accessible_text.as_str(),
-                label: label.unwrap_or(""),
-                label_x,
-                label_text_length: label_width_scaled as i32,
-                label_text_color,
-                label_shadow_color,
-                message,
-                message_x,
-                message_text_length: message_width_scaled as i32,
-                message_text_color,
-                message_shadow_color,
-                label_color,
-                message_color,
-                link,
-                extra_link,
-                logo,
-                rect_offset,
-                message_link_x,
+    #[cfg(all_styles)]
+    #[test]
+    fn test_anchors_only_for_set_links() {
+        for style in BadgeStyle::ALL {
+            let params = BadgeParams {
+                style,
+                label: Some("build"),
+                message: Some("passing"),
+                ..Default::default()
+            };
+            for (link, extra_link) in [(None, None), (Some(""), Some("")), (None, Some(""))] {
+                let svg = render_badge_svg(&BadgeParams {
+                    link,
+                    extra_link,
+                    ..params.clone()
+                });
+                assert!(!svg.contains("<a "), "{:?}", style);
+                assert!(!svg.contains("rgba(0,0,0,0)"), "{:?}", style);
             }
-            .render()
-            .unwrap_or_else(|e| format!("<!-- Askama render error: {} -->", e))
+            // A message link alone leaves the label unlinked
+            let svg = render_badge_svg(&BadgeParams {
+                extra_link: Some("https://ci.example.com"),
+                ..params.clone()
+            });
+            assert_eq!(svg.matches("<a ").count(), 1, "{:?}", style);
+            assert!(!svg.contains("href=\"\""), "{:?}", style);
+            let linked = svg.split("<a ").nth(1).unwrap();
+            assert!(linked.contains("https://ci.example.com"), "{:?}", style);
+            assert!(!linked.to_lowercase().contains(">build<"), "{:?}", style);
         }
-        BadgeStyle::Social => {
-            let label_is_none = label.is_none();
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_try_render_reports_errors() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            label_color: Some(""),
+            message_color: Some("#4c1"),
+            logo: Some("rust"),
+            ..Default::default()
+        };
+        assert_eq!(
+            try_render_badge_svg(&params).unwrap(),
+            render_badge_svg(&params)
+        );
+        let glyph = BadgeParams {
+            logo: Some("✔"),
+            ..params
+        };
+        assert!(try_render_badge_svg(&glyph).is_ok());
+
+        // `auto` picks the brand color, and falls back to the default without one
+        let auto = BadgeParams {
+            logo_color: Some("Auto"),
+            ..params
+        };
+        assert!(try_render_badge_svg(&auto).is_ok());
+        let unsuffixed = RenderOptions::new().id_suffix(IdSuffix::Plain);
+        assert_eq!(
+            render_badge_svg_with_options(&auto, &unsuffixed),
+            render_badge_svg_with_options(
+                &BadgeParams {
+                    logo_color: Some("#000000"),
+                    ..params
+                },
+                &unsuffixed
+            )
+        );
+        let unbranded = BadgeParams {
+            logo: Some("data:image/svg+xml;base64,PHN2Zy8+"),
+            ..params
+        };
+        assert_eq!(
+            render_badge_svg_with_options(
+                &BadgeParams {
+                    logo_color: Some("auto"),
+                    ..unbranded
+                },
+                &unsuffixed
+            ),
+            render_badge_svg_with_options(&unbranded, &unsuffixed)
+        );
+        let light = BadgeParams {
+            label_color: Some("white"),
+            ..params
+        };
+        assert!(try_render_badge_svg(&light).is_ok());
+
+        let bad_color = BadgeParams {
+            logo_color: Some("not-a-color"),
+            ..params
+        };
+        assert_eq!(
+            try_render_badge_svg(&bad_color),
+            Err(BadgeError::InvalidColor {
+                param: "logo_color",
+                value: "not-a-color".to_string()
+            })
+        );
+        let bad_logo = BadgeParams {
+            logo: Some(" no-such-logo "),
+            ..params
+        };
+        assert_eq!(
+            try_render_badge_svg(&bad_logo),
+            Err(BadgeError::InvalidLogo("no-such-logo".to_string()))
+        );
+    }
+
+    #[cfg(all(
+        feature = "style-flat",
+        feature = "style-flat-square",
+        feature = "style-plastic",
+        feature = "style-social",
+        feature = "style-for-the-badge",
+        feature = "style-for-the-badge-two-line"
+    ))]
+    #[test]
+    fn test_logo_width_adjusts_layout() {
+        let width_of = |svg: &str| -> u32 {
+            let start = svg.find("width=\"").unwrap() + 7;
+            svg[start..start + svg[start..].find('"').unwrap()]
+                .parse()
+                .unwrap()
+        };
+        for style in [
+            BadgeStyle::Flat,
+            BadgeStyle::FlatSquare,
+            BadgeStyle::Plastic,
+            BadgeStyle::Social,
+            BadgeStyle::ForTheBadge,
+        ] {
+            let params = BadgeParams {
+                style,
+                label: Some("built with"),
+                message: Some("rust"),
+                logo: Some("rust"),
+                ..Default::default()
+            };
+            let default = render_badge_svg(&params);
+            let wide = render_badge_svg(&BadgeParams {
+                logo_width: Some(40),
+                ..params.clone()
+            });
+            assert_eq!(width_of(&wide), width_of(&default) + 26, "{:?}", style);
+            assert!(wide.contains(r#"width="40" height="14""#), "{:?}", style);
+        }
+
+        let params = BadgeParams {
+            style: BadgeStyle::ForTheBadgeTwoLine,
+            label: Some("get it on"),
+            message: Some("github"),
+            logo: Some("rust"),
+            logo_width: Some(28),
+            ..Default::default()
+        };
+        assert!(render_badge_svg(&params).contains(r#"width="48" height="24""#));
+
+        // Slug icons are square, so `auto` keeps the default box
+        let auto = BadgeParams {
+            logo_width: None,
+            logo_size: Some(LogoSize::Auto),
+            style: BadgeStyle::Flat,
+            ..params
+        };
+        assert!(render_badge_svg(&auto).contains(r#"width="14" height="14""#));
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_render_options() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            logo: Some("rust"),
+            ..Default::default()
+        };
+        let standard = render_badge_svg(&params);
+        assert_eq!(
+            render_badge_svg_with_options(&params, &RenderOptions::default()),
+            standard
+        );
+        let (width, height) = svg_size(&standard).unwrap();
+        assert_eq!(height, 20.0);
+
+        let tall = render_badge_svg_with_options(&params, &RenderOptions::new().height(40));
+        assert_eq!(svg_size(&tall), Some((width * 2.0, 40.0)));
+        assert!(tall.contains(&format!(r#"viewBox="0 0 {} 20""#, width)));
+        let scaled = render_badge_svg_with_options(&params, &RenderOptions::new().scale(1.4));
+        assert_eq!(
+            svg_size(&scaled),
+            Some(((width * 1.4 * 100.0).round() / 100.0, 28.0))
+        );
+        // Ids carry a hash of the options, so compare with plain ids
+        let plain = RenderOptions::new().id_suffix(IdSuffix::Plain);
+        assert_eq!(
+            render_badge_svg_with_options(&params, &plain.clone().scale(-1.0)),
+            render_badge_svg_with_options(&params, &plain)
+        );
+
+        // Padding is applied on both sides of the label and the message
+        let padded =
+            render_badge_svg_with_options(&params, &RenderOptions::new().horizontal_padding(8));
+        assert_eq!(svg_size(&padded), Some((width + 12.0, 20.0)));
+        assert!(padded.contains(r#"<image x="8""#));
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_contrast_fix() {
+        let params = BadgeParams {
+            label: Some("coverage"),
+            message: Some("71%"),
+            message_color: Some("yellowgreen"),
+            ..Default::default()
+        };
+        let standard = render_badge_svg(&params);
+        let text = render_badge_svg_with_options(
+            &params,
+            &RenderOptions::new().contrast(ContrastFix::Text),
+        );
+        assert!(!standard.contains(r##"fill="#000""##));
+        assert!(text.contains(r##"fill="#000""##));
+        // The readable label keeps its white text
+        assert!(text.contains(r##"fill="#fff" textLength"##));
+
+        let background = render_badge_svg_with_options(
+            &params,
+            &RenderOptions::new().contrast(ContrastFix::Background),
+        );
+        assert!(!background.contains(r##"fill="#a4a61d""##));
+        assert!(background.contains(r##"fill="#555""##));
+        let fixed = contrast_background("#a4a61d", &TextColors::default()).unwrap();
+        let ratio = contrast_ratio(
+            &Color::from_str(&fixed).unwrap(),
+            &Color::from_str("#fff").unwrap(),
+        );
+        assert!(ratio >= MIN_CONTRAST_RATIO, "{} {}", fixed, ratio);
+        assert_eq!(contrast_background("#555", &TextColors::default()), None);
+        assert_eq!(contrast_background("#c0c0c0", &TextColors::default()), None);
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_text_colors() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            message_color: Some("#ffb000"),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_badge_svg_with_options(
+                &params,
+                &RenderOptions::new().text_colors(TextColors::default())
+            ),
+            render_badge_svg(&params)
+        );
+        let colors = TextColors {
+            threshold: 0.4,
+            light_text: "#eee".to_string(),
+            dark_text: "#000".to_string(),
+            dark_shadow: "#fff".to_string(),
+            ..Default::default()
+        };
+        let svg = render_badge_svg_with_options(&params, &RenderOptions::new().text_colors(colors));
+        // #555 is darker than the threshold, the orange is brighter
+        assert!(svg.contains(r##"fill="#eee" textLength"##), "{}", svg);
+        assert!(svg.contains(r##"fill="#000" textLength"##), "{}", svg);
+        assert!(
+            svg.contains(r##"fill="#fff" fill-opacity=".3""##),
+            "{}",
+            svg
+        );
+    }
+
+    #[cfg(feature = "style-plastic")]
+    #[test]
+    fn test_plastic_gradient() {
+        let params = BadgeParams {
+            style: BadgeStyle::Plastic,
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let svg = render_badge_svg(&params);
+        assert!(svg.contains(concat!(
+            r##"<stop offset="0" stop-color="#fff" stop-opacity=".7"/>"##,
+            r##"<stop offset=".1" stop-color="#aaa" stop-opacity=".1"/>"##,
+            r##"<stop offset=".9" stop-color="#000" stop-opacity=".3"/>"##,
+            r##"<stop offset="1" stop-color="#000" stop-opacity=".5"/>"##,
+        )));
+        let stops = vec![
+            GradientStop::new(-1.0, "gold", 0.25),
+            GradientStop::new(2.0, "not a color", f32::NAN),
+        ];
+        let options = RenderOptions::new().plastic_gradient(stops);
+        let svg = render_badge_svg_with_options(&params, &options);
+        assert!(
+            svg.contains(concat!(
+                r##"<stop offset="0" stop-color="gold" stop-opacity=".25"/>"##,
+                r##"<stop offset="1" stop-opacity="0"/>"##,
+            )),
+            "{}",
+            svg
+        );
+        let flat = RenderOptions::new().plastic_gradient(Vec::new());
+        let svg = render_badge_svg_with_options(&params, &flat);
+        assert!(!svg.contains("linearGradient"), "{}", svg);
+        assert!(!svg.contains("url(#s"), "{}", svg);
+    }
 
-            let offset = if label_is_none && has_logo {
-                -3i32
-            } else {
-                0i32
+    #[cfg(all_styles)]
+    #[test]
+    fn test_border() {
+        for style in BadgeStyle::ALL {
+            let params = BadgeParams {
+                style,
+                label: Some("build"),
+                message: Some("passing"),
+                link: Some("https://ci.example.com"),
+                ..Default::default()
             };
+            let unbordered = RenderOptions::new().id_suffix(IdSuffix::Plain);
+            let plain = render_badge_svg_with_options(&params, &unbordered);
+            let options = unbordered.border_color("#f00").border_width(2.0);
+            let svg = render_badge_svg_with_options(&params, &options);
+            let (width, height) = svg_size(&plain).unwrap();
+            assert_eq!(svg_size(&svg), Some((width, height)), "{:?}", style);
+            let border = format!(
+                r#"<rect x="1" y="1" width="{}" height="{}" "#,
+                width - 2.0,
+                height - 2.0
+            );
+            assert!(svg.contains(&border), "{:?}: {}", style, svg);
+            assert!(svg.ends_with(r##"stroke="#f00" stroke-width="2"/></svg>"##));
+            assert_eq!(
+                svg.replace(&svg[svg.rfind("<rect").unwrap()..], "</svg>"),
+                plain
+            );
+        }
 
-            let label = label.unwrap_or("");
-            let label = capitalize(label).unwrap().to_string();
-            let label_str = label.as_str();
-            let accessible_text = create_accessible_text(Some(label_str), message);
-            let internal_height = 19;
-            let label_horizontal_padding = 5;
-            let message_horizontal_padding = 4;
-            let horizontal_gutter = 6;
+        let params = BadgeParams {
+            style: BadgeStyle::Pill,
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let options = RenderOptions::new().border_color("#d0d7de");
+        let svg = render_badge_svg_with_options(&params, &options);
+        assert!(svg.contains(r##"rx="9.5" fill="none" stroke="#d0d7de" stroke-width="1"/>"##));
+        // Scaled with the rest of the badge
+        let svg = render_badge_svg_with_options(&params, &options.clone().height(40));
+        assert!(svg.contains(r#"height="40" viewBox="0 0 "#), "{}", svg);
+        assert!(svg.contains(r#"stroke-width="1"/>"#), "{}", svg);
+        // Borders that draw nothing leave the badge as it is, apart from its hashed ids
+        let plain = RenderOptions::new().id_suffix(IdSuffix::Plain);
+        for options in [
+            plain.clone().border_width(2.0),
+            plain.clone().border_color("not a color"),
+            options.id_suffix(IdSuffix::Plain).border_width(0.0),
+        ] {
+            let svg = render_badge_svg_with_options(&params, &options);
+            assert_eq!(svg, render_badge_svg_with_options(&params, &plain));
+        }
+    }
 
-            let label_text_width = preferred_width_of(label_str, Font::HelveticaBold11);
+    #[cfg(all(
+        feature = "style-flat",
+        feature = "style-flat-square",
+        feature = "style-plastic",
+        feature = "style-social",
+        feature = "style-for-the-badge",
+        feature = "style-for-the-badge-two-line"
+    ))]
+    #[test]
+    fn test_dark_colors() {
+        let dark = DarkColors {
+            label_color: Some("#eee".to_string()),
+            message_color: Some("black".to_string()),
+        };
+        let options = RenderOptions::new().dark(dark);
+        for style in [
+            BadgeStyle::Flat,
+            BadgeStyle::FlatSquare,
+            BadgeStyle::Plastic,
+            BadgeStyle::Social,
+            BadgeStyle::ForTheBadge,
+            BadgeStyle::ForTheBadgeTwoLine,
+        ] {
+            let params = BadgeParams {
+                style,
+                label: Some("build"),
+                message: Some("passing"),
+                logo: Some("rust"),
+                ..Default::default()
+            };
+            let svg = render_badge_svg_with_options(&params, &options);
+            assert!(
+                svg.contains(".shields-message{fill:#000000}"),
+                "{:?}",
+                style
+            );
+            assert!(svg.contains(r#"class="shields-message""#), "{:?}", style);
+            assert!(
+                svg.contains(r#"class="shields-message-text""#),
+                "{:?}",
+                style
+            );
+            assert!(
+                svg.contains(r#"<image class="shields-logo""#),
+                "{:?}",
+                style
+            );
+            if style == BadgeStyle::ForTheBadgeTwoLine {
+                // Both lines sit on the message color
+                assert!(svg.contains(".shields-label-text{fill:#fff}"));
+            } else {
+                assert!(svg.contains(r#"class="shields-label""#), "{:?}", style);
+                assert!(
+                    svg.contains(".shields-label-text{fill:#333}"),
+                    "{:?}",
+                    style
+                );
+            }
+            assert!(!render_badge_svg(&params).contains("class="));
+        }
+        let empty = RenderOptions::new().dark(DarkColors::default());
+        let params = BadgeParams {
+            message: Some("passing"),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_badge_svg_with_options(&params, &empty),
+            render_badge_svg(&params)
+        );
+    }
 
-            let label_rect_width =
-                (label_text_width + total_logo_width + 2 * label_horizontal_padding) as i32
-                    + offset;
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_class_hooks() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            logo: Some("✔"),
+            ..Default::default()
+        };
+        let hooked =
+            render_badge_svg_with_options(&params, &RenderOptions::new().class_hooks(true));
+        assert!(
+            hooked
+                .contains(r##"<rect class="shields-label" width="49" height="20" fill="#555"/>"##)
+        );
+        assert!(hooked.contains(r#"<text class="shields-logo" aria-hidden="true""#));
+        assert!(!hooked.contains("<style>"));
+
+        let unstyled = render_badge_svg_with_options(&params, &RenderOptions::new().unstyled(true));
+        assert!(unstyled.contains(r#"<rect class="shields-label" width="49" height="20"/>"#));
+        assert!(!unstyled.contains("#555"));
+        assert!(!unstyled.contains("whitesmoke"));
+        assert!(unstyled.contains(r#"<text class="shields-message-text" x="#));
+    }
 
-            let message_text_width = preferred_width_of(message, Font::HelveticaBold11);
+    #[cfg(all(
+        feature = "style-flat",
+        feature = "style-plastic",
+        feature = "style-social"
+    ))]
+    #[test]
+    fn test_id_suffix() {
+        let params = BadgeParams {
+            style: BadgeStyle::Plastic,
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let fixed = IdSuffix::Fixed("b1\"/>".to_string());
+        let svg = render_badge_svg_with_options(&params, &RenderOptions::new().id_suffix(fixed));
+        assert!(svg.contains(r#"<linearGradient id="s-b1""#));
+        assert!(svg.contains(r#"<clipPath id="r-b1">"#));
+        assert!(svg.contains(r#"clip-path="url(#r-b1)""#));
+        assert!(svg.contains(r#"fill="url(#s-b1)""#));
+
+        let auto = RenderOptions::new().id_suffix(IdSuffix::Auto);
+        let first = render_badge_svg_with_options(&params, &auto);
+        assert_eq!(first, render_badge_svg(&params));
+        let other = BadgeParams {
+            message: Some("failing"),
+            ..params
+        };
+        let second = render_badge_svg_with_options(&other, &auto);
+        let id = |svg: &str| svg[svg.find("id=\"s-").unwrap()..][..14].to_string();
+        assert_ne!(id(&first), id(&second));
+        // Options that change the drawing change the ids too
+        let restyled = render_badge_svg_with_options(
+            &params,
+            &auto
+                .clone()
+                .plastic_gradient(vec![GradientStop::new(0.0, "#fff", 0.5)]),
+        );
+        assert_ne!(id(&first), id(&restyled));
+        let dark = render_badge_svg_with_options(
+            &params,
+            &auto.clone().dark(DarkColors {
+                label_color: Some("#000".to_string()),
+                ..Default::default()
+            }),
+        );
+        assert_ne!(id(&first), id(&dark));
 
-            let message_rect_width = message_text_width + 2 * message_horizontal_padding;
-            let has_message = !message.is_empty();
+        let plain = render_badge_svg_with_options(
+            &params,
+            &RenderOptions::new().id_suffix(IdSuffix::Plain),
+        );
+        assert!(plain.contains(r#"<clipPath id="r">"#));
+        assert!(plain.contains(r#"fill="url(#s)""#));
 
-            let message_bubble_main_x = label_rect_width as f32 + horizontal_gutter as f32 + 0.5;
-            let message_bubble_notch_x = label_rect_width + horizontal_gutter;
-            let label_text_x = FONT_SCALE_UP_FACTOR as f32
-                * (total_logo_width as f32
-                    + label_text_width as f32 / 2.0
-                    + label_horizontal_padding as f32
-                    + offset as f32);
-            let message_text_x = FONT_SCALE_UP_FACTOR as f32
-                * (label_rect_width as f32
-                    + horizontal_gutter as f32
-                    + message_rect_width as f32 / 2.0);
-            let message_text_length = FONT_SCALE_UP_FACTOR * message_text_width;
-            let label_text_length = FONT_SCALE_UP_FACTOR * label_text_width;
+        let social = BadgeParams {
+            style: BadgeStyle::Social,
+            ..params
+        };
+        let svg = render_badge_svg_with_options(
+            &social,
+            &RenderOptions::new().id_suffix(IdSuffix::Fixed("x".to_string())),
+        );
+        assert!(svg.contains("a:hover #llink-x{fill:url(#b-x)"));
+        assert!(!svg.contains(r#"id="a""#));
+    }
 
-            let left_width = label_rect_width + 1;
-            let right_width = if has_message {
-                horizontal_gutter + message_rect_width as i32
-            } else {
-                0
+    #[cfg(all_styles)]
+    #[test]
+    fn test_title() {
+        for style in BadgeStyle::ALL {
+            let params = BadgeParams {
+                style,
+                label: Some("coverage"),
+                message: Some("94%"),
+                title: Some("Line coverage"),
+                ..Default::default()
             };
-
-            let total_width = left_width + right_width as i32;
-
-            SocialBadgeSvgTemplateContext {
-                total_width,
-                total_height: BADGE_HEIGHT as i32,
-                internal_height,
-                accessible_text: accessible_text.as_str(),
-                message_rect_width,
-                message_bubble_main_x,
-                message_bubble_notch_x,
-                label_text_length,
-                label: label_str,
-                message,
-                label_text_x,
-                message_text_x,
-                message_text_length,
-                label_rect_width,
-                link,
-                extra_link,
-                logo,
-            }
-            .render()
-            .unwrap_or_else(|e| format!("<!-- Askama render error: {} -->", e))
+            let svg = render_badge_svg_with_options(
+                &params,
+                &RenderOptions::new().id_suffix(IdSuffix::Fixed("c".to_string())),
+            );
+            assert!(
+                svg.contains(r#" role="img" aria-labelledby="title-c">"#),
+                "{:?}: {}",
+                style,
+                svg
+            );
+            assert!(svg.contains(r#"<title id="title-c">Line coverage</title>"#));
+            assert!(!svg.contains("coverage: 94%"));
+
+            // Two-link badges get the tooltip without becoming a single image
+            let svg = render_badge_svg(&BadgeParams {
+                link: Some("https://a.example"),
+                extra_link: Some("https://b.example"),
+                ..params
+            });
+            assert!(svg.contains("Line coverage</title>"), "{:?}", style);
+            assert!(!svg.contains(r#"role="img""#));
         }
-        BadgeStyle::ForTheBadge => {
-            // label to uppercase
-            let label = label.unwrap_or("").to_uppercase();
-            let accessible_text = create_accessible_text(Some(label.as_str()), message);
-            let message = message.to_uppercase();
-            let font_size = 10;
-            let letter_spacing = 1.25;
-            let logo_text_gutter = 6i32;
-            let logo_margin = 9i32;
-            let logo_width = logo_width as i32;
-            let label_text_width = if !label.is_empty() {
-                (get_text_width(&label, Font::VerdanaNormal10)
-                    + letter_spacing * label.len() as f64) as i32
-            } else {
-                0
-            };
-            let message_text_width = if !message.is_empty() {
-                (get_text_width(&message, Font::VerdanaBold10)
-                    + letter_spacing * message.len() as f64) as i32
-            } else {
-                0
-            };
-            let has_label = !label.is_empty();
-            let no_text = !has_label && message.is_empty();
-            let need_label_rect = has_label || (!logo.is_empty() && !label_color.is_empty());
-            let gutter = if no_text {
-                logo_text_gutter - logo_margin
-            } else {
-                logo_text_gutter
-            };
-            let text_margin = 12;
-
-            // Logo positioning
-            let (logo_min_x, label_text_min_x) = if !logo.is_empty() {
-                (logo_margin, logo_margin + logo_width + gutter)
-            } else {
-                (0, text_margin)
-            };
+        let params = BadgeParamsOwned::from_query_str("message=ok&title=All%20good");
+        assert_eq!(params.title.as_deref(), Some("All good"));
+    }
 
-            // Handle label and message rectangles
-            let (label_rect_width, message_text_min_x, message_rect_width) = if need_label_rect {
-                if has_label {
-                    (
-                        label_text_min_x + label_text_width + text_margin,
-                        label_text_min_x + label_text_width + text_margin + text_margin,
-                        2 * text_margin + message_text_width,
-                    )
-                } else {
-                    (
-                        2 * logo_margin + logo_width,
-                        2 * logo_margin + logo_width + text_margin,
-                        2 * text_margin + message_text_width,
-                    )
-                }
-            } else if !logo.is_empty() {
-                (
-                    0,
-                    text_margin + logo_width + gutter,
-                    2 * text_margin + logo_width + gutter + message_text_width,
-                )
-            } else {
-                (0, text_margin, 2 * text_margin + message_text_width)
+    #[cfg(all(
+        feature = "style-flat",
+        feature = "style-flat-square",
+        feature = "style-plastic",
+        feature = "style-social",
+        feature = "style-for-the-badge"
+    ))]
+    #[test]
+    fn test_logo_list() {
+        for style in [
+            BadgeStyle::Flat,
+            BadgeStyle::FlatSquare,
+            BadgeStyle::Plastic,
+            BadgeStyle::Social,
+            BadgeStyle::ForTheBadge,
+        ] {
+            let params = BadgeParams {
+                style,
+                label: Some("built with"),
+                message: Some("rust"),
+                logo: Some("rust,github"),
+                ..Default::default()
             };
-            let left_width = label_rect_width;
-            let right_width = message_rect_width;
-            let total_width = left_width + right_width;
-
-            let hex_label_color = Color::from_str(label_color)
-                .unwrap_or(Color::from_str("#555").unwrap())
-                .to_css_hex();
-            let hex_label_color = hex_label_color.as_str();
-            let hex_message_color = Color::from_str(message_color)
-                .unwrap_or(Color::from_str("#007ec6").unwrap())
-                .to_css_hex();
-            let hex_message_color = hex_message_color.as_str();
-
-            let message_mid_x = message_text_min_x as f32 + 0.5 * message_text_width as f32;
-            let label_mid_x = label_text_min_x as f32 + 0.5 * label_text_width as f32;
+            // Two 14px logos and a 3px gap
+            let svg = try_render_badge_svg(&params).unwrap();
+            assert!(svg.contains(r#"width="31" height="14""#), "{:?}", style);
+            assert_eq!(
+                try_render_badge_svg(&BadgeParams {
+                    logo: Some("rust,no-such-logo"),
+                    ..params
+                }),
+                Err(BadgeError::InvalidLogo("no-such-logo".to_string()))
+            );
+        }
+    }
 
-            let (label_text_color, _) = colors_for_background(hex_label_color);
-            let (message_text_color, _) = colors_for_background(hex_message_color);
+    #[cfg(all(
+        feature = "style-flat",
+        feature = "style-plastic",
+        feature = "style-social",
+        feature = "style-for-the-badge"
+    ))]
+    #[test]
+    fn test_logo_glyph() {
+        let svg = render_badge_svg(&BadgeParams {
+            label: Some("tests"),
+            message: Some("passing"),
+            logo: Some(" ✔ "),
+            logo_color: Some("brightgreen"),
+            ..Default::default()
+        });
+        assert!(!svg.contains("<image"), "{}", svg);
+        // ✔ is 9px wide in Verdana 11px, centered 5px in
+        assert!(
+            svg.contains(r##"x="95" y="140" transform="scale(.1)" fill="#4c1""##),
+            "{}",
+            svg
+        );
+        assert!(svg.contains(r#"textLength="90">✔</text>"#), "{}", svg);
+        for style in [
+            BadgeStyle::Plastic,
+            BadgeStyle::Social,
+            BadgeStyle::ForTheBadge,
+        ] {
+            let svg = render_badge_svg(&BadgeParams {
+                style,
+                label: Some("tests"),
+                logo: Some("✖"),
+                ..Default::default()
+            });
+            assert!(svg.contains(">✖</text>"), "{}", svg);
+        }
+    }
 
-            ForTheBadgeSvgTemplateContext {
-                total_width,
-                accessible_text: accessible_text.as_str(),
-                left_width: label_rect_width,
-                right_width: message_rect_width,
-                label_color,
-                message_color,
-                font_family: FONT_FAMILY,
-                font_size: font_size * FONT_SCALE_UP_FACTOR as i32,
-                label: label.as_str(),
-                label_x: label_mid_x * FONT_SCALE_UP_FACTOR as f32,
-                label_width_scaled: label_text_width * FONT_SCALE_UP_FACTOR as i32,
-                label_text_color,
-                message: message.as_str(),
-                message_x: message_mid_x * FONT_SCALE_UP_FACTOR as f32,
-                message_text_color,
-                message_width_scaled: message_text_width * FONT_SCALE_UP_FACTOR as i32,
-                link,
-                extra_link,
+    #[cfg(all(feature = "style-flat-square", feature = "style-pill"))]
+    #[test]
+    fn test_pill_matches_flat_square_layout() {
+        let attr = |svg: &str, prefix: &str| -> f32 {
+            let start = svg.find(prefix).unwrap() + prefix.len();
+            svg[start..start + svg[start..].find('"').unwrap()]
+                .parse()
+                .unwrap()
+        };
+        for (label, logo) in [
+            (Some("build"), None),
+            (None, Some("rust")),
+            (Some("ci"), Some("rust")),
+        ] {
+            let params = BadgeParams {
+                style: BadgeStyle::FlatSquare,
+                label,
+                message: Some("passing"),
                 logo,
-                logo_x: logo_min_x,
+                ..Default::default()
+            };
+            let square = render_badge_svg(&params);
+            let pill = render_badge_svg(&BadgeParams {
+                style: BadgeStyle::Pill,
+                ..params
+            });
+            assert_eq!(attr(&pill, "width=\""), attr(&square, "width=\"") + 8.0);
+            assert!(pill.contains(r#"rx="10""#));
+            assert!(!pill.contains("linearGradient"));
+            // Text keeps its flat-square position, moved past the rounded left end
+            let message_x = |svg: &str| {
+                let at = svg.rfind("<text x=\"").unwrap();
+                attr(&svg[at..], "x=\"")
+            };
+            assert_eq!(message_x(&pill), message_x(&square) + 40.0, "{}", pill);
+            if logo.is_some() {
+                assert_eq!(
+                    attr(&pill, "<image x=\""),
+                    attr(&square, "<image x=\"") + 4.0
+                );
             }
-            .render()
-            .unwrap_or_else(|e| format!("<!-- Askama render error: {} -->", e))
         }
     }
-}
 
-fn create_accessible_text(label: Option<&str>, message: &str) -> String {
-    let use_label = match label {
-        Some(l) if !l.is_empty() => Some(l),
-        _ => None,
-    };
-    let label_len = use_label.map_or(0, |l| l.len() + 2); // +2 for ": "
-    let mut buf = String::with_capacity(label_len + message.len());
-    if let Some(label) = use_label {
-        buf.push_str(label);
-        buf.push_str(": ");
+    #[cfg(all(
+        feature = "style-flat",
+        feature = "style-flat-square",
+        feature = "style-plastic",
+        feature = "style-pill",
+        feature = "style-outline",
+        feature = "style-monospace"
+    ))]
+    #[test]
+    fn test_sparkline() {
+        let values = [1.0, 3.0, f64::NAN, 2.0, 5.0];
+        let params = BadgeParams {
+            label: Some("downloads"),
+            message: Some("30d"),
+            sparkline: Some(&values),
+            ..Default::default()
+        };
+        let plain = render_badge_svg(&BadgeParams {
+            sparkline: None,
+            ..params
+        });
+        for style in [
+            BadgeStyle::Flat,
+            BadgeStyle::FlatSquare,
+            BadgeStyle::Plastic,
+            BadgeStyle::Pill,
+            BadgeStyle::Outline,
+            BadgeStyle::Monospace,
+        ] {
+            let svg = render_badge_svg(&BadgeParams { style, ..params });
+            assert_eq!(svg.matches("<polyline").count(), 1, "{}", svg);
+        }
+        let svg = render_badge_svg(&params);
+        // The message section grows by the sparkline and a gap; the text keeps its position
+        assert_eq!(
+            svg_size(&svg).unwrap().0,
+            svg_size(&plain).unwrap().0 + (SPARKLINE_WIDTH + HORIZONTAL_PADDING) as f64
+        );
+        let message_x = |svg: &str| svg[svg.rfind("<text x=\"").unwrap()..][..20].to_string();
+        assert_eq!(message_x(&svg), message_x(&plain));
+        let (width, _) = svg_size(&svg).unwrap();
+        let right = width - HORIZONTAL_PADDING as f64;
+        let left = right - SPARKLINE_WIDTH as f64;
+        assert!(
+            svg.contains(&format!(
+                r#"points="{},16 {},10 {},13 {},4""#,
+                left,
+                left + 13.3,
+                left + 26.7,
+                right
+            )),
+            "{}",
+            svg
+        );
+        assert!(svg.contains(r##"stroke="#fff""##));
+
+        // A constant or single value is a flat line; no finite value means no sparkline
+        assert!(sparkline_points(Some(&[2.0]), 0.0, 20).ends_with("40,10"));
+        let unsuffixed = RenderOptions::new().id_suffix(IdSuffix::Plain);
+        let svg = render_badge_svg_with_options(
+            &BadgeParams {
+                sparkline: Some(&[f64::NAN]),
+                ..params
+            },
+            &unsuffixed,
+        );
+        let without = BadgeParams {
+            sparkline: None,
+            ..params
+        };
+        assert_eq!(svg, render_badge_svg_with_options(&without, &unsuffixed));
     }
-    buf.push_str(message);
-    buf
-}
 
-#[cfg(test)]
-mod tests {
-    use csscolorparser::Color;
-    use pretty_assertions::assert_eq;
-    use std::str::FromStr;
+    #[cfg(feature = "style-monospace")]
+    #[test]
+    fn test_monospace_style() {
+        let params = BadgeParams {
+            style: BadgeStyle::Monospace,
+            label: Some("install"),
+            message: Some("cargo install shields"),
+            ..Default::default()
+        };
+        let svg = render_badge_svg(&params);
+        assert!(svg.contains(MONOSPACE_FONT_FAMILY), "{}", svg);
+        assert!(svg.contains(r#"shape-rendering="crispEdges""#));
+        // Every character has the same width
+        assert_eq!(
+            get_text_width("iiiii", Font::MonospaceNormal11),
+            get_text_width("WWWWW", Font::MonospaceNormal11)
+        );
+        assert!(svg.contains(&format!(
+            r#"textLength="{}""#,
+            preferred_width_of("cargo install shields", Font::MonospaceNormal11) * 10
+        )));
 
-    use super::*;
+        let prompted = render_badge_svg_with_options(&params, &RenderOptions::new().prompt(true));
+        assert!(
+            prompted.contains(">$ cargo install shields</text>"),
+            "{}",
+            prompted
+        );
+        assert!(prompted.contains(r#"aria-label="install: cargo install shields""#));
+        assert!(svg_size(&prompted).unwrap().0 > svg_size(&svg).unwrap().0);
+    }
+
+    #[cfg(all(feature = "style-flat-square", feature = "style-outline"))]
     #[test]
-    fn test_svg() {
-        // Test SVG rendering
+    fn test_outline_style() {
         let params = BadgeParams {
-            style: BadgeStyle::FlatSquare,
+            style: BadgeStyle::Outline,
             label: Some("build"),
             message: Some("passing"),
-            label_color: Some("#333"),
-            message_color: Some("#4c1"),
-            link: None,
-            extra_link: None,
-            logo: None,
-            logo_color: None,
+            label_color: Some("black"),
+            message_color: Some("green"),
+            ..Default::default()
         };
         let svg = render_badge_svg(&params);
-        assert!(!svg.is_empty(), "SVG rendering failed");
+        let square = render_badge_svg(&BadgeParams {
+            style: BadgeStyle::FlatSquare,
+            ..params
+        });
+        let width = |svg: &str| {
+            svg[..svg.find('>').unwrap()]
+                .split('"')
+                .nth(5)
+                .map(str::to_owned)
+        };
+        assert_eq!(width(&svg), width(&square));
+        assert!(!svg.contains("<rect"), "{}", svg);
+        assert!(svg.contains(r#"stroke="black""#), "{}", svg);
+        assert!(svg.contains(r##"stroke="#97ca00""##), "{}", svg);
+        assert!(svg.contains(r##"fill="#97ca00""##), "{}", svg);
+
+        // Without a label a single rounded border surrounds the message
+        let svg = render_badge_svg(&BadgeParams {
+            label: None,
+            label_color: None,
+            ..params
+        });
+        assert_eq!(svg.matches("<path").count(), 1, "{}", svg);
+    }
+
+    #[cfg(feature = "style-for-the-badge-two-line")]
+    #[test]
+    fn test_for_the_badge_two_line() {
+        let svg = render_badge_svg(&BadgeParams {
+            style: BadgeStyle::ForTheBadgeTwoLine,
+            label: Some("Get it on"),
+            message: Some("GitHub Releases"),
+            message_color: Some("#24292f"),
+            logo: Some("github"),
+            ..Default::default()
+        });
+        assert!(svg.contains(r#"height="40" role="img" aria-label="Get it on: GitHub Releases""#));
+        assert!(
+            svg.contains(r##"<rect width="229" height="40" fill="#24292f"/>"##),
+            "{}",
+            svg
+        );
+        // Both lines start right of the 24px logo
+        assert!(
+            svg.contains(r#"<image x="12" y="8" width="24" height="24""#),
+            "{}",
+            svg
+        );
+        assert!(
+            svg.contains(r#"x="440" y="160" font-size="100""#),
+            "{}",
+            svg
+        );
+        assert!(
+            svg.contains(r#"x="440" y="330" font-size="140""#),
+            "{}",
+            svg
+        );
+        assert!(svg.contains(">GITHUB RELEASES</text>"), "{}", svg);
+
+        let svg = render_badge_svg(&BadgeParams {
+            style: BadgeStyle::ForTheBadgeTwoLine,
+            message: Some("download"),
+            ..Default::default()
+        });
+        assert!(
+            svg.contains(r#"x="120" y="250" font-size="140""#),
+            "{}",
+            svg
+        );
+    }
+
+    #[cfg(feature = "style-social")]
+    #[test]
+    fn test_social_bubble_icon() {
+        let plain = render_badge_svg(&BadgeParams {
+            style: BadgeStyle::Social,
+            label: Some("stars"),
+            message: Some("1.2k"),
+            ..Default::default()
+        });
+        let with_icon = render_badge_svg(&BadgeParams {
+            style: BadgeStyle::Social,
+            label: Some("stars"),
+            message: Some("1.2k"),
+            bubble_icon: Some(SocialIcon::Star),
+            ..Default::default()
+        });
+        assert!(!plain.contains("<path d=\"M8 .25"));
+        assert!(with_icon.contains(SocialIcon::Star.path()));
+        // The bubble grows by the icon plus its gutter
+        assert!(plain.contains(r#"width="73""#), "{}", plain);
+        assert!(with_icon.contains(r#"width="86""#), "{}", with_icon);
+    }
+
+    #[cfg(all(feature = "style-flat", feature = "style-for-the-badge"))]
+    #[test]
+    fn test_params_from_env_lookup() {
+        let vars = std::collections::HashMap::from([
+            ("CI_BADGE_LABEL", "coverage"),
+            ("CI_BADGE_MESSAGE", "93%"),
+            ("CI_BADGE_COLOR", "green"),
+            ("CI_BADGE_STYLE", "for-the-badge"),
+            ("LABEL", "unprefixed"),
+        ]);
+        let lookup = |key: &str| vars.get(key).map(|v| v.to_string());
+
+        let params = BadgeParamsOwned::from_lookup("CI_BADGE", lookup);
+        assert_eq!(params.style, BadgeStyle::ForTheBadge);
+        assert_eq!(params.label.as_deref(), Some("coverage"));
+        assert_eq!(params.message.as_deref(), Some("93%"));
+        assert_eq!(params.message_color.as_deref(), Some("green"));
+        assert_eq!(params.logo, None);
+        let svg = render_badge_svg(&params.as_params());
+        assert!(svg.contains("COVERAGE"));
+
+        let params = BadgeParamsOwned::from_lookup("", lookup);
+        assert_eq!(params.label.as_deref(), Some("unprefixed"));
+        assert_eq!(params.style, BadgeStyle::Flat);
     }
 
+    #[cfg(feature = "style-for-the-badge")]
     #[test]
     fn text_for_the_badge() {
         // Test ForTheBadge style rendering
@@ -1312,6 +7120,7 @@ mod tests {
             extra_link: Some("https://example.com"),
             logo: Some("rust"),
             logo_color: Some("blue"),
+            ..Default::default()
         };
         let svg = render_badge_svg(&params);
         println!("{}", svg);
@@ -1325,6 +7134,7 @@ mod tests {
         assert!(!svg.is_empty(), "SVG rendering for ForTheBadge failed");
     }
 
+    #[cfg(feature = "style-flat-square")]
     #[test]
     fn test_named_color() {
         let params = BadgeParams {
@@ -1337,6 +7147,7 @@ mod tests {
             extra_link: None,
             logo: None,
             logo_color: None,
+            ..Default::default()
         };
         let svg = render_badge_svg(&params);
         assert!(
@@ -1349,6 +7160,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "style-flat-square")]
     #[test]
     fn test_alias_color() {
         let params = BadgeParams {
@@ -1361,6 +7173,7 @@ mod tests {
             extra_link: None,
             logo: None,
             logo_color: None,
+            ..Default::default()
         };
         let svg = render_badge_svg(&params);
         assert!(
@@ -1373,6 +7186,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "style-flat-square")]
     #[test]
     fn test_hex_color() {
         let params = BadgeParams {
@@ -1385,6 +7199,7 @@ mod tests {
             extra_link: None,
             logo: None,
             logo_color: None,
+            ..Default::default()
         };
         let svg = render_badge_svg(&params);
         assert!(
@@ -1397,6 +7212,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "style-flat-square")]
     #[test]
     fn test_css_color() {
         let params = BadgeParams {
@@ -1409,6 +7225,7 @@ mod tests {
             extra_link: None,
             logo: None,
             logo_color: None,
+            ..Default::default()
         };
         let svg = render_badge_svg(&params);
         assert!(
@@ -1421,6 +7238,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "style-flat-square")]
     #[test]
     fn test_invalid_color_fallback() {
         let params = BadgeParams {
@@ -1433,6 +7251,7 @@ mod tests {
             extra_link: None,
             logo: None,
             logo_color: None,
+            ..Default::default()
         };
         let svg = render_badge_svg(&params);
         assert!(
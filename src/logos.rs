@@ -0,0 +1,634 @@
+//! Logo dataset used to resolve `logo` slugs.
+//!
+//! By default slugs are looked up in the [simple-icons](https://simpleicons.org/) dataset bundled
+//! with the `simpleicons` crate, which this crate pins to an exact version. Upgrading that
+//! dependency can change icon artwork, and with it the bytes of every badge showing a logo. Use
+//! [`dataset_version`] to key caches on the dataset in use, and [`set_dataset`] to render from a
+//! fixed snapshot instead, so cached badges keep their appearance across upgrades.
+//! Applications can resolve their own slugs (company logos, internal services) ahead of the
+//! dataset by implementing [`IconProvider`] and calling [`register_provider`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "unstable")] {
+//! use shields::logos::{self, LogoDataset};
+//!
+//! let snapshot = LogoDataset::from_json(
+//!     r#"{"version": "acme-2024.06", "icons": {"acme": "<svg viewBox=\"0 0 24 24\"><path d=\"M0 0h24v24H0z\"/></svg>"}, "colors": {"acme": "FF5500"}}"#,
+//! )
+//! .unwrap();
+//! logos::set_dataset(Some(snapshot));
+//! assert_eq!(logos::dataset_version(), "acme-2024.06");
+//! assert!(logos::get_svg("acme").is_some());
+//! assert_eq!(logos::brand_color("acme").as_deref(), Some("#ff5500"));
+//! assert!(logos::get_svg("rust").is_none()); // a snapshot replaces simple-icons entirely
+//!
+//! logos::set_dataset(None);
+//! assert_eq!(logos::dataset_version(), logos::SIMPLE_ICONS_VERSION);
+//! # }
+//! ```
+
+use crate::cache::ShardedLru;
+use arc_swap::{ArcSwap, ArcSwapOption};
+use base64::Engine;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Version identifier of the bundled simple-icons dataset (the pinned `simpleicons` crate).
+#[cfg(feature = "unstable")]
+pub const SIMPLE_ICONS_VERSION: &str = "simpleicons-0.3.0";
+
+static DATASET: ArcSwapOption<LogoDataset> = ArcSwapOption::const_empty();
+
+/// Brand colors of the bundled simple-icons as `(title, slug, hex)`, generated by `build.rs`
+/// from the dataset's `simple-icons.json`. The slug is only set where it is not derived from
+/// the title.
+static SIMPLE_ICONS_DATA: &[(&str, Option<&str>, &str)] =
+    include!(concat!(env!("OUT_DIR"), "/brand_colors.rs"));
+
+/// Hex brand colors of the bundled simple-icons, keyed by slug.
+static SIMPLE_ICONS_COLORS: Lazy<HashMap<String, &'static str>> = Lazy::new(|| {
+    SIMPLE_ICONS_DATA
+        .iter()
+        .map(|&(title, slug, hex)| {
+            let slug = slug.map_or_else(|| simpleicons::title_to_slug(title), str::to_string);
+            (slug, hex)
+        })
+        .collect()
+});
+
+/// Encoded logos keyed by (slug, fill color); cleared whenever the dataset changes.
+type EncodedCache = ShardedLru<(String, String), Option<String>>;
+
+static ENCODED_CACHE: Lazy<EncodedCache> =
+    Lazy::new(|| ShardedLru::new(crate::CacheCapacity::default().logos));
+
+/// Bumped whenever [`ENCODED_CACHE`] is cleared, so encodings held elsewhere can be dropped.
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "unstable")]
+fn clear_encoded() {
+    ENCODED_CACHE.clear();
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Resizes the encoded logo cache to hold about `capacity` entries.
+pub(crate) fn set_cache_capacity(capacity: usize) {
+    ENCODED_CACHE.set_capacity(capacity);
+}
+
+/// Returns a counter that changes whenever the artwork behind a slug may have changed.
+pub(crate) fn generation() -> usize {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// A snapshot of logo artwork keyed by slug.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LogoDataset {
+    /// Identifier reported by [`dataset_version`] while this snapshot is active.
+    pub version: String,
+    /// SVG documents keyed by lowercase slug.
+    pub icons: HashMap<String, String>,
+    /// Brand colors (`#rrggbb` or `rrggbb`) keyed by lowercase slug, used for
+    /// `logo_color="auto"`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub colors: HashMap<String, String>,
+}
+
+impl LogoDataset {
+    /// Parses a snapshot from JSON of the form `{"version": "...", "icons": {"slug": "<svg ...>"}}`,
+    /// with an optional `"colors": {"slug": "#rrggbb"}` map of brand colors.
+    ///
+    /// # Errors
+    /// Returns an error if the JSON does not match that shape.
+    #[cfg(feature = "unstable")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let mut dataset: LogoDataset = serde_json::from_str(json)?;
+        let lowercase = |map: HashMap<String, String>| {
+            map.into_iter()
+                .map(|(slug, value)| (slug.to_ascii_lowercase(), value))
+                .collect()
+        };
+        dataset.icons = lowercase(dataset.icons);
+        dataset.colors = lowercase(dataset.colors);
+        Ok(dataset)
+    }
+}
+
+/// Returns the version identifier of the logo dataset currently used for rendering.
+///
+/// This is [`SIMPLE_ICONS_VERSION`] unless a snapshot was installed with [`set_dataset`].
+#[cfg(feature = "unstable")]
+pub fn dataset_version() -> String {
+    match DATASET.load().as_ref() {
+        Some(dataset) => dataset.version.clone(),
+        None => SIMPLE_ICONS_VERSION.to_string(),
+    }
+}
+
+/// Installs a dataset snapshot used instead of the bundled simple-icons, or restores the
+/// bundled dataset with `None`.
+#[cfg(feature = "unstable")]
+pub fn set_dataset(dataset: Option<LogoDataset>) {
+    DATASET.store(dataset.map(Arc::new));
+    clear_encoded();
+}
+
+/// A source of logo artwork resolved by slug, such as company logos or internal services.
+///
+/// Providers added with [`register_provider`] are consulted in registration order before the
+/// active dataset.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::logos::{self, IconProvider};
+/// use std::borrow::Cow;
+///
+/// struct Internal;
+///
+/// impl IconProvider for Internal {
+///     fn get_svg(&self, slug: &str) -> Option<Cow<'static, str>> {
+///         (slug == "deploy-bot").then_some(Cow::Borrowed("<svg viewBox=\"0 0 24 24\"/>"))
+///     }
+/// }
+///
+/// logos::register_provider(Internal);
+/// assert!(logos::get_svg("deploy-bot").is_some());
+/// # }
+/// ```
+pub trait IconProvider: Send + Sync {
+    /// Returns the SVG document for `slug`, or `None` if this provider does not have it.
+    fn get_svg(&self, slug: &str) -> Option<Cow<'static, str>>;
+
+    /// Returns the brand color for `slug` (`#rrggbb` or `rrggbb`), used by `logo_color="auto"`.
+    fn brand_color(&self, _slug: &str) -> Option<String> {
+        None
+    }
+}
+
+impl IconProvider for LogoDataset {
+    fn get_svg(&self, slug: &str) -> Option<Cow<'static, str>> {
+        self.icons
+            .get(&slug.to_ascii_lowercase())
+            .map(|svg| Cow::Owned(svg.clone()))
+    }
+
+    fn brand_color(&self, slug: &str) -> Option<String> {
+        self.colors.get(&slug.to_ascii_lowercase()).cloned()
+    }
+}
+
+static PROVIDERS: Lazy<ArcSwap<Vec<Arc<dyn IconProvider>>>> = Lazy::new(ArcSwap::default);
+
+/// Registers an icon provider, consulted after previously registered ones and before the
+/// active dataset. Providers are not part of [`dataset_version`]; include their own version
+/// in cache keys if their artwork can change.
+#[cfg(feature = "unstable")]
+pub fn register_provider(provider: impl IconProvider + 'static) {
+    let provider: Arc<dyn IconProvider> = Arc::new(provider);
+    PROVIDERS.rcu(|providers| {
+        let mut providers = Vec::clone(providers);
+        providers.push(provider.clone());
+        providers
+    });
+    clear_encoded();
+}
+
+/// Removes all registered icon providers.
+#[cfg(feature = "unstable")]
+pub fn clear_providers() {
+    PROVIDERS.store(Arc::default());
+    clear_encoded();
+}
+
+/// Looks up the SVG document for a logo slug in the registered providers, then the active
+/// dataset.
+pub fn get_svg(slug: &str) -> Option<Cow<'static, str>> {
+    let providers = PROVIDERS.load();
+    if let Some(svg) = providers.iter().find_map(|p| p.get_svg(slug)) {
+        return Some(svg);
+    }
+    match DATASET.load().as_ref() {
+        Some(dataset) => dataset.get_svg(slug),
+        None => simpleicons::Icon::get_svg(slug).map(Cow::Borrowed),
+    }
+}
+
+/// Returns the brand color of a logo slug as `#rrggbb`, if a registered provider or the
+/// active dataset has one.
+///
+/// Without a snapshot installed with [`set_dataset`], colors come from the simple-icons
+/// release whose artwork is bundled. Invalid hex values are ignored.
+pub fn brand_color(slug: &str) -> Option<String> {
+    let slug = slug.trim();
+    let providers = PROVIDERS.load();
+    if let Some(hex) = providers.iter().find_map(|p| p.brand_color(slug)) {
+        return normalize_hex(&hex);
+    }
+    match DATASET.load().as_ref() {
+        Some(dataset) => normalize_hex(&dataset.brand_color(slug)?),
+        None => normalize_hex(SIMPLE_ICONS_COLORS.get(&simpleicons::title_to_slug(slug))?),
+    }
+}
+
+/// Normalizes `rrggbb` or `#rrggbb` to lowercase `#rrggbb`.
+fn normalize_hex(hex: &str) -> Option<String> {
+    let hex = hex.trim().trim_start_matches('#');
+    (hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| format!("#{}", hex.to_ascii_lowercase()))
+}
+
+/// Returns `logo` when it is a single non-ASCII symbol (such as ✔, ✖ or ⚠, optionally followed
+/// by an emoji presentation selector), which badges draw as a text glyph instead of looking it
+/// up in the dataset.
+pub fn glyph(logo: &str) -> Option<&str> {
+    let mut chars = logo.chars();
+    let symbol = chars.next()?;
+    let is_symbol = !symbol.is_ascii() && !symbol.is_alphanumeric() && !symbol.is_whitespace();
+    let rest = chars.as_str();
+    (is_symbol && (rest.is_empty() || rest == "\u{FE0F}")).then_some(logo)
+}
+
+/// Image types accepted in inline `data:` logos.
+const DATA_URI_TYPES: [&str; 5] = ["svg+xml", "png", "jpeg", "gif", "webp"];
+
+/// Returns the `href` for a logo given inline rather than as a slug.
+///
+/// Accepts base64 data URIs (`data:image/svg+xml;base64,...`, `data:image/png;base64,...`,
+/// also JPEG, GIF and WebP) and literal SVG documents (`<svg ...>...</svg>`), which are
+/// base64-encoded. Spaces in a data URI are read as `+`, since query strings decode `+` to a
+/// space. Inline logos keep their own colors; `logo_color` only recolors slugs. Returns
+/// `None` for anything else, including data URIs with other types or invalid base64.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "unstable")] {
+/// use shields::logos;
+/// let href = logos::inline("<svg xmlns=\"http://www.w3.org/2000/svg\"/>").unwrap();
+/// assert!(href.starts_with("data:image/svg+xml;base64,"));
+/// assert!(logos::inline("data:image/png;base64,iVBORw0KGgo=").is_some());
+/// assert!(logos::inline("data:text/html;base64,PGI+").is_none());
+/// assert!(logos::inline("rust").is_none());
+/// # }
+/// ```
+pub fn inline(logo: &str) -> Option<String> {
+    let logo = logo.trim();
+    if logo.starts_with("<svg") {
+        let base64_logo = base64::engine::general_purpose::STANDARD.encode(logo);
+        return Some(format!("data:image/svg+xml;base64,{}", base64_logo));
+    }
+    let (kind, payload) = logo.strip_prefix("data:image/")?.split_once(";base64,")?;
+    if !DATA_URI_TYPES.contains(&kind) {
+        return None;
+    }
+    let payload = payload.replace(' ', "+");
+    base64::engine::general_purpose::STANDARD
+        .decode(&payload)
+        .ok()?;
+    Some(format!("data:image/{};base64,{}", kind, payload))
+}
+
+/// Horizontal gap between logos of a list, in units of the 14px logo height.
+const LIST_GAP: u32 = 3;
+
+/// Splits a comma-separated list of logo slugs such as `rust,webassembly`.
+///
+/// Returns `None` for a single logo, including inline logos, whose data may contain commas.
+pub fn split_list(logo: &str) -> Option<Vec<&str>> {
+    let logo = logo.trim();
+    if inline(logo).is_some() || logo.starts_with("data:") || !logo.contains(',') {
+        return None;
+    }
+    let slugs: Vec<&str> = logo
+        .split(',')
+        .map(str::trim)
+        .filter(|slug| !slug.is_empty())
+        .collect();
+    (slugs.len() > 1).then_some(slugs)
+}
+
+/// Returns the accessible name of a logo drawn without any text: the glyph of a glyph logo,
+/// the slug or slugs of named icons, and `logo` for inline images and anything else that is
+/// not a plain slug.
+pub(crate) fn accessible_name(logo: &str) -> &str {
+    let logo = logo.trim();
+    let is_slug = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ',' | ' ');
+    if glyph(logo).is_some() || logo.chars().all(is_slug) {
+        logo
+    } else {
+        "logo"
+    }
+}
+
+/// Encodes several logos side by side as one SVG `href`, 14 units tall.
+///
+/// Each logo is filled with `fill`, or with its brand color when `brand` is set and one is
+/// known. Logos are square unless `auto_size` keeps their aspect ratio. Unknown slugs are
+/// skipped; returns `None` when none resolve.
+pub(crate) fn encode_list(
+    slugs: &[&str],
+    fill: &str,
+    brand: bool,
+    auto_size: bool,
+) -> Option<String> {
+    let mut images = String::new();
+    let mut x = 0;
+    for slug in slugs {
+        let fill = brand
+            .then(|| brand_color(slug))
+            .flatten()
+            .unwrap_or_else(|| fill.to_string());
+        let Some(href) = encode(slug, &fill) else {
+            continue;
+        };
+        let width = match aspect_ratio(&href).filter(|_| auto_size) {
+            Some(ratio) => (14.0 * ratio).round() as u32,
+            None => 14,
+        };
+        if !images.is_empty() {
+            x += LIST_GAP;
+        }
+        images.push_str(&format!(
+            r#"<image x="{}" width="{}" height="14" href="{}"/>"#,
+            x, width, href
+        ));
+        x += width;
+    }
+    if images.is_empty() {
+        return None;
+    }
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="14" viewBox="0 0 {0} 14">{1}</svg>"#,
+        x, images
+    );
+    let base64_logo = base64::engine::general_purpose::STANDARD.encode(svg);
+    Some(format!("data:image/svg+xml;base64,{}", base64_logo))
+}
+
+/// Returns the width-to-height ratio of an encoded logo `href`.
+///
+/// SVGs are measured by their root `viewBox`, or `width` and `height`; PNG and GIF images by
+/// their header. Returns `None` for other images or when the size cannot be read.
+pub(crate) fn aspect_ratio(href: &str) -> Option<f64> {
+    let (kind, bytes) = match href.strip_prefix("data:image/") {
+        Some(data) => {
+            let (kind, payload) = data.split_once(";base64,")?;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(payload)
+                .ok()?;
+            (kind, bytes)
+        }
+        None => ("svg+xml", href.as_bytes().to_vec()),
+    };
+    let (width, height) = match kind {
+        "svg+xml" => svg_size(std::str::from_utf8(&bytes).ok()?)?,
+        "png" if bytes.starts_with(b"\x89PNG\r\n\x1a\n") && bytes.len() >= 24 => {
+            let be = |at: usize| {
+                u32::from_be_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+            };
+            (be(16) as f64, be(20) as f64)
+        }
+        "gif" if bytes.starts_with(b"GIF") && bytes.len() >= 10 => {
+            let le = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+            (le(6) as f64, le(8) as f64)
+        }
+        _ => return None,
+    };
+    let ratio = width / height;
+    (ratio.is_finite() && ratio > 0.0).then_some(ratio)
+}
+
+/// Reads the size of an SVG document from its root element.
+fn svg_size(svg: &str) -> Option<(f64, f64)> {
+    let start = svg.find("<svg")?;
+    let root = &svg[start..start + svg[start..].find('>')?];
+    let attr = |name: &str| {
+        let at = root.find(&format!(" {}=", name))? + name.len() + 2;
+        let quote = root[at..].chars().next()?;
+        let value = &root[at + 1..];
+        Some(&value[..value.find(quote)?])
+    };
+    if let Some(view_box) = attr("viewBox") {
+        let numbers: Vec<f64> = view_box
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|n| !n.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .ok()?;
+        if let [_, _, width, height] = numbers[..] {
+            return Some((width, height));
+        }
+    }
+    let length = |name: &str| attr(name)?.trim_end_matches("px").trim().parse().ok();
+    Some((length("width")?, length("height")?))
+}
+
+/// Resolves a logo and encodes it as the `href` used by badge templates.
+///
+/// Inline logos are returned by [`inline`]. Slug artwork is filled with `fill` and returned as a
+/// base64 `data:image/svg+xml` URI; these results are cached, so repeated badges with the same
+/// logo skip the lookup and encoding.
+pub(crate) fn encode(slug: &str, fill: &str) -> Option<String> {
+    let mut href = String::new();
+    encode_into(slug, fill, &mut href).then_some(href)
+}
+
+/// Like [`encode`], but appends the `href` to `buf`, copying cached results without cloning
+/// them first. Returns whether the logo resolved.
+pub(crate) fn encode_into(slug: &str, fill: &str, buf: &mut String) -> bool {
+    if let Some(href) = inline(slug) {
+        buf.push_str(&href);
+        return true;
+    }
+    let key = (slug.to_string(), fill.to_string());
+    let cached = ENCODED_CACHE.with(&key, |cached| {
+        buf.push_str(cached.as_deref().unwrap_or(""));
+        cached.is_some()
+    });
+    if let Some(found) = cached {
+        return found;
+    }
+    let encoded = get_svg(slug).map(|svg| {
+        if svg.starts_with("<svg") {
+            let svg = svg.replace("<svg", format!("<svg fill=\"{}\"", fill).as_str());
+            let base64_logo = base64::engine::general_purpose::STANDARD.encode(svg);
+            format!("data:image/svg+xml;base64,{}", base64_logo)
+        } else {
+            svg.into_owned()
+        }
+    });
+    buf.push_str(encoded.as_deref().unwrap_or(""));
+    let found = encoded.is_some();
+    ENCODED_CACHE.put(key, encoded);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_from_json_lowercases_slugs() {
+        let dataset =
+            LogoDataset::from_json(r#"{"version": "v1", "icons": {"Acme": "<svg/>"}}"#).unwrap();
+        assert_eq!(
+            dataset.icons.get("acme").map(String::as_str),
+            Some("<svg/>")
+        );
+        assert!(LogoDataset::from_json(r#"{"icons": {}}"#).is_err());
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_brand_color() {
+        let dataset = LogoDataset::from_json(
+            r##"{"version": "v1", "icons": {}, "colors": {"Acme": "FF5500", "bad": "#12345"}}"##,
+        )
+        .unwrap();
+        assert_eq!(
+            dataset.colors.get("acme").map(String::as_str),
+            Some("FF5500")
+        );
+        assert!(
+            LogoDataset::from_json(r#"{"version": "v1", "icons": {}}"#)
+                .unwrap()
+                .colors
+                .is_empty()
+        );
+        assert_eq!(normalize_hex("FF5500"), Some("#ff5500".to_string()));
+        assert_eq!(normalize_hex(" #1a2b3c"), Some("#1a2b3c".to_string()));
+        assert_eq!(normalize_hex("#12345"), None);
+        assert_eq!(normalize_hex("red"), None);
+
+        // The bundled dataset's colors, looked up like its artwork
+        assert_eq!(brand_color("rust").as_deref(), Some("#000000"));
+        assert_eq!(brand_color(" GitHub").as_deref(), Some("#181717"));
+        assert_eq!(brand_color("googleplay").as_deref(), Some("#414141"));
+        assert_eq!(brand_color("no-such-logo"), None);
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_register_provider() {
+        struct Acme;
+        impl IconProvider for Acme {
+            fn get_svg(&self, slug: &str) -> Option<Cow<'static, str>> {
+                (slug == "test-provider-acme").then_some(Cow::Borrowed("<svg/>"))
+            }
+            fn brand_color(&self, slug: &str) -> Option<String> {
+                (slug == "test-provider-acme").then(|| "ABCDEF".to_string())
+            }
+        }
+        assert!(get_svg("test-provider-acme").is_none());
+        register_provider(Acme);
+        assert_eq!(get_svg("test-provider-acme").as_deref(), Some("<svg/>"));
+        assert_eq!(
+            brand_color("test-provider-acme").as_deref(),
+            Some("#abcdef")
+        );
+        // Other slugs still fall through to the dataset
+        assert!(get_svg("rust").is_some());
+        assert_eq!(
+            encode("test-provider-acme", "#fff").as_deref(),
+            Some("data:image/svg+xml;base64,PHN2ZyBmaWxsPSIjZmZmIi8+")
+        );
+        clear_providers();
+        assert!(get_svg("test-provider-acme").is_none());
+    }
+
+    #[test]
+    fn test_inline_logos() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"><circle r="4"/></svg>"#;
+        let href = inline(svg).unwrap();
+        let payload = href.strip_prefix("data:image/svg+xml;base64,").unwrap();
+        assert_eq!(
+            base64::engine::general_purpose::STANDARD
+                .decode(payload)
+                .unwrap(),
+            svg.as_bytes()
+        );
+        assert_eq!(encode(svg, "#fff"), Some(href));
+
+        // `+` decoded to a space by a query string is restored
+        assert_eq!(
+            inline("data:image/png;base64,ab c"),
+            Some("data:image/png;base64,ab+c".to_string())
+        );
+        // Anything that could break out of the `href` attribute is rejected
+        assert_eq!(inline(r#"data:image/png;base64,abc="/><script>"#), None);
+        assert_eq!(inline("data:image/svg+xml;utf8,<svg/>"), None);
+        assert_eq!(inline("data:text/html;base64,PGI+"), None);
+    }
+
+    #[test]
+    fn test_logo_lists() {
+        assert_eq!(split_list("rust, github"), Some(vec!["rust", "github"]));
+        assert_eq!(split_list("rust,"), None);
+        assert_eq!(split_list("rust"), None);
+        assert_eq!(split_list("data:image/png;base64,iVBO"), None);
+        assert_eq!(split_list("<svg><path d=\"M0,0\"/></svg>"), None);
+
+        let href = encode_list(&["rust", "unknown-slug", "github"], "#fff", false, false).unwrap();
+        // Two square logos and one gap
+        assert_eq!(aspect_ratio(&href), Some(31.0 / 14.0));
+        let payload = href.strip_prefix("data:image/svg+xml;base64,").unwrap();
+        let svg = base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains(r#"<image x="17" width="14" height="14""#));
+        assert_eq!(encode_list(&["unknown-slug"], "#fff", false, false), None);
+    }
+
+    #[test]
+    fn test_aspect_ratio() {
+        let svg = |root: &str| inline(&format!("{}<path d=\"M0 0h1\"/></svg>", root)).unwrap();
+        assert_eq!(
+            aspect_ratio(&svg(r#"<svg viewBox="0 0 48 12">"#)),
+            Some(4.0)
+        );
+        assert_eq!(aspect_ratio(&svg("<svg viewBox='0,0,10,20'>")), Some(0.5));
+        assert_eq!(
+            aspect_ratio(&svg(r#"<svg width="30px" height="10px">"#)),
+            Some(3.0)
+        );
+        assert_eq!(aspect_ratio(&svg("<svg>")), None);
+        assert_eq!(aspect_ratio(&svg(r#"<svg viewBox="0 0 10 0">"#)), None);
+
+        // 1x1 PNG and GIF headers
+        let png = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAIAAAABCAYAAAD0In+KAAAAC0lEQVR42mNgQAcAAA4AAWKw0vAAAAAASUVORK5CYII=";
+        assert_eq!(aspect_ratio(png), Some(2.0));
+        let gif = "data:image/gif;base64,R0lGODlhAQADAAAAACw=";
+        assert_eq!(aspect_ratio(gif), Some(1.0 / 3.0));
+        assert_eq!(aspect_ratio("data:image/jpeg;base64,/9j/"), None);
+    }
+
+    #[test]
+    fn test_glyph() {
+        assert_eq!(glyph("✔"), Some("✔"));
+        assert_eq!(glyph("⚠\u{FE0F}"), Some("⚠\u{FE0F}"));
+        assert_eq!(glyph("x"), None);
+        assert_eq!(glyph("✔✔"), None);
+        assert_eq!(glyph("é"), None);
+    }
+
+    #[test]
+    fn test_accessible_name() {
+        assert_eq!(accessible_name(" rust "), "rust");
+        assert_eq!(accessible_name("rust,webassembly"), "rust,webassembly");
+        assert_eq!(accessible_name("✔"), "✔");
+        assert_eq!(accessible_name("<svg viewBox=\"0 0 1 1\"/>"), "logo");
+        assert_eq!(
+            accessible_name("data:image/png;base64,iVBORw0KGgo="),
+            "logo"
+        );
+        assert_eq!(accessible_name("a&b"), "logo");
+    }
+}
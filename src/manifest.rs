@@ -0,0 +1,222 @@
+//! Versioned badge manifest format.
+//!
+//! A manifest is a JSON document listing named badges together with their parameters, and,
+//! once rendered, the files they were written to. The same format is used as input (a list of
+//! badges to render) and as output (an index of rendered badges), so tooling can generate
+//! manifests and read them back without depending on a particular crate version.
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "badges": [
+//!     { "name": "build", "params": { "label": "build", "message": "passing", "message_color": "brightgreen" } },
+//!     { "name": "version", "params": { "style": "flat-square", "label": "version", "message": "1.0.0" },
+//!       "file": "version.svg", "width": 96, "height": 20, "hash": "3fb1e0c2a9d4b871" }
+//!   ]
+//! }
+//! ```
+//!
+//! # Compatibility rules
+//!
+//! - [`SCHEMA_VERSION`] only changes when an existing field changes meaning or is removed.
+//!   Readers reject manifests with a newer `schema_version` instead of misreading them.
+//! - New fields are always optional and may appear within the same schema version; readers
+//!   ignore fields they don't know about.
+//! - Optional fields are omitted rather than written as `null`.
+//!
+//! The JSON Schema for the current version is published as [`JSON_SCHEMA`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "style-flat")] {
+//! use shields::manifest::Manifest;
+//!
+//! let json = r#"{"schema_version": 1, "badges": [{"name": "build", "params": {"label": "build", "message": "passing"}}]}"#;
+//! let manifest = Manifest::from_json(json).unwrap();
+//! assert_eq!(manifest.badges[0].name, "build");
+//! let svg = shields::render_badge_svg(&manifest.badges[0].params.as_params());
+//! assert!(svg.contains("passing"));
+//! # }
+//! ```
+
+use crate::BadgeParamsOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Current manifest schema version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema (draft 2020-12) describing manifest schema version 1.
+pub const JSON_SCHEMA: &str = include_str!("../assets/schema/manifest-v1.schema.json");
+
+/// A versioned list of named badges.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Manifest {
+    /// Schema version the document was written with.
+    pub schema_version: u32,
+    /// Badges described by this manifest.
+    #[serde(default)]
+    pub badges: Vec<ManifestBadge>,
+}
+
+/// A single badge entry of a [`Manifest`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ManifestBadge {
+    /// Stable badge name, also used as the file stem when rendering to a directory.
+    pub name: String,
+    /// Parameters the badge is rendered from.
+    #[serde(default)]
+    pub params: BadgeParamsOwned,
+    /// File the badge was written to, relative to the manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Rendered width in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// Rendered height in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Hex digest identifying the rendered content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+impl ManifestBadge {
+    /// Creates an entry that has not been rendered yet.
+    pub fn new(name: impl Into<String>, params: BadgeParamsOwned) -> Self {
+        ManifestBadge {
+            name: name.into(),
+            params,
+            file: None,
+            width: None,
+            height: None,
+            hash: None,
+        }
+    }
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Manifest::new(Vec::new())
+    }
+}
+
+impl Manifest {
+    /// Creates a manifest with the current [`SCHEMA_VERSION`].
+    pub fn new(badges: Vec<ManifestBadge>) -> Self {
+        Manifest {
+            schema_version: SCHEMA_VERSION,
+            badges,
+        }
+    }
+
+    /// Parses a manifest, rejecting schema versions this crate doesn't understand.
+    ///
+    /// # Errors
+    /// Returns [`ManifestError::Json`] for malformed documents and
+    /// [`ManifestError::UnsupportedVersion`] for a `schema_version` other than 1.
+    pub fn from_json(json: &str) -> Result<Self, ManifestError> {
+        let manifest: Manifest = serde_json::from_str(json).map_err(ManifestError::Json)?;
+        if manifest.schema_version == 0 || manifest.schema_version > SCHEMA_VERSION {
+            return Err(ManifestError::UnsupportedVersion(manifest.schema_version));
+        }
+        Ok(manifest)
+    }
+
+    /// Serializes the manifest as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("manifest serialization cannot fail")
+    }
+}
+
+/// Errors returned when reading a [`Manifest`].
+#[derive(Debug)]
+pub enum ManifestError {
+    /// The document is not valid manifest JSON.
+    Json(serde_json::Error),
+    /// The document uses a schema version this crate doesn't support.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Json(e) => write!(f, "invalid manifest: {}", e),
+            ManifestError::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported manifest schema_version {} (supported: {})",
+                v, SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ManifestError::Json(e) => Some(e),
+            ManifestError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BadgeStyle, FontFamily};
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip() {
+        let mut badge = ManifestBadge::new(
+            "version",
+            BadgeParamsOwned {
+                style: BadgeStyle::FlatSquare,
+                label: Some("version".to_string()),
+                message: Some("1.0.0".to_string()),
+                ..Default::default()
+            },
+        );
+        badge.file = Some("version.svg".to_string());
+        let manifest = Manifest::new(vec![badge]);
+        let json = manifest.to_json();
+        assert!(json.contains(r#""schema_version": 1"#));
+        assert!(json.contains(r#""style": "flat-square""#));
+        assert!(
+            !json.contains("null"),
+            "optional fields must be omitted: {}",
+            json
+        );
+        assert_eq!(Manifest::from_json(&json).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_unknown_fields_are_ignored() {
+        let json =
+            r#"{"schema_version": 1, "generator": "x", "badges": [{"name": "a", "future": true}]}"#;
+        let manifest = Manifest::from_json(json).unwrap();
+        assert_eq!(manifest.badges[0].params, BadgeParamsOwned::default());
+    }
+
+    #[test]
+    fn test_rejects_newer_schema() {
+        let err = Manifest::from_json(r#"{"schema_version": 2, "badges": []}"#).unwrap_err();
+        assert!(matches!(err, ManifestError::UnsupportedVersion(2)));
+        assert!(Manifest::from_json(r#"{"badges": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_json_schema_is_valid_json() {
+        let schema: serde_json::Value = serde_json::from_str(JSON_SCHEMA).unwrap();
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        let fonts: Vec<&str> = FontFamily::ALL.iter().map(|font| font.as_str()).collect();
+        assert_eq!(
+            schema["$defs"]["params"]["properties"]["font"]["enum"],
+            json!(fonts)
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! Font character width measurer for SVG badge rendering.
 //!
 //! This module provides [`CharWidthMeasurer`], a utility for loading and consuming font width tables
-//! (from JSON or string), and for calculating the width of strings in a given font. It is equivalent
+//! (from JSON, string or the compact binary format the bundled tables are embedded in), and for
+//! calculating the width of strings in a given font. It is equivalent
 //! to the JS CharWidthTableConsumer used in shields.io, and is used internally for accurate badge layout.
 //!
 //! # Typical Usage
@@ -17,10 +18,19 @@
 //! See [`CharWidthMeasurer`] for details.
 
 use serde_json::Value;
+use std::collections::BTreeMap;
+#[cfg(feature = "font-gen")]
 use std::collections::HashMap;
 use std::fs;
 use std::io::{self};
 
+/// Magic bytes opening a binary width table.
+const BINARY_MAGIC: &[u8; 4] = b"SHWT";
+/// Size of the binary table header: magic bytes and range count.
+const BINARY_HEADER_LEN: usize = 8;
+/// Size of one binary range: lower and upper code points and width.
+const BINARY_RANGE_LEN: usize = 16;
+
 /// Measures character widths for a given font, for use in SVG badge layout.
 ///
 /// This struct loads a font width table (from data, JSON file, or string) and provides methods
@@ -36,10 +46,66 @@ use std::io::{self};
 /// assert!(width > 0.0);
 /// ```
 pub struct CharWidthMeasurer {
-    /// Lookup table: char_code -> width
-    hash_map: HashMap<u32, f64>,
+    /// Sorted, disjoint character ranges and their widths
+    ranges: Ranges,
+    /// Kerning pairs `(left, right, adjustment)`, sorted by character codes
+    kerning: Vec<(u32, u32, f64)>,
     /// Width of character 'm'
     pub em_width: f64,
+    /// Width guessed for East Asian wide characters missing from the table: that of `一`
+    /// (U+4E00) when the table has it, `em_width` otherwise
+    wide_width: f64,
+}
+
+/// Width ranges, built at runtime or borrowed from a binary table without copying.
+enum Ranges {
+    Owned(Vec<(u32, u32, f64)>),
+    Binary(&'static [u8]),
+}
+
+impl Ranges {
+    fn len(&self) -> usize {
+        match self {
+            Ranges::Owned(ranges) => ranges.len(),
+            Ranges::Binary(table) => (table.len() - BINARY_HEADER_LEN) / BINARY_RANGE_LEN,
+        }
+    }
+
+    /// The `i`th range as `(lower, upper, width)`.
+    fn get(&self, i: usize) -> (u32, u32, f64) {
+        match self {
+            Ranges::Owned(ranges) => ranges[i],
+            Ranges::Binary(table) => {
+                let range = &table[BINARY_HEADER_LEN + BINARY_RANGE_LEN * i..][..BINARY_RANGE_LEN];
+                (
+                    u32::from_le_bytes(range[0..4].try_into().unwrap()),
+                    u32::from_le_bytes(range[4..8].try_into().unwrap()),
+                    f64::from_le_bytes(range[8..16].try_into().unwrap()),
+                )
+            }
+        }
+    }
+
+    /// Width of `code`, found by binary search.
+    fn width_of(&self, code: u32) -> Option<f64> {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (lower, upper, width) = self.get(mid);
+            if code < lower {
+                high = mid;
+            } else if code > upper {
+                low = mid + 1;
+            } else {
+                return Some(width);
+            }
+        }
+        None
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (u32, u32, f64)> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
 }
 
 impl CharWidthMeasurer {
@@ -71,22 +137,149 @@ impl CharWidthMeasurer {
     /// let measurer = CharWidthMeasurer::from_data(data);
     /// ```
     pub fn from_data(data: Vec<(u32, u32, f64)>) -> Self {
-        // Build lookup table: expand all ranges to char_code -> width
-        let mut hash_map = HashMap::new();
-        for &(lower, upper, width) in &data {
-            for code in lower..=upper {
-                hash_map.insert(code, width);
+        let disjoint = data.iter().all(|&(lower, upper, _)| lower <= upper)
+            && data.windows(2).all(|pair| pair[0].1 < pair[1].0);
+        let ranges = if disjoint {
+            data
+        } else {
+            // Later ranges override earlier ones where they overlap
+            let mut widths = BTreeMap::new();
+            for &(lower, upper, width) in &data {
+                for code in lower..=upper {
+                    widths.insert(code, width);
+                }
+            }
+            merge_ranges(widths)
+        };
+        Self::from_ranges(Ranges::Owned(ranges))
+    }
+
+    /// Loads a measurer from a binary width table without copying it, for tables embedded
+    /// with `include_bytes!`.
+    ///
+    /// The table starts with the magic bytes `SHWT` and the number of ranges as a
+    /// little-endian `u32`, followed by each range's lower and upper code points (`u32`) and
+    /// width (`f64`), all little-endian and sorted by code point. [`to_binary`](Self::to_binary)
+    /// writes this format.
+    ///
+    /// # Arguments
+    /// * `table` - The binary table.
+    ///
+    /// # Returns
+    /// `Ok(CharWidthMeasurer)` if successful, or an `io::Error`.
+    ///
+    /// # Errors
+    /// Returns an error if the table is truncated or its ranges are not sorted and disjoint.
+    ///
+    /// ## Example
+    /// ```
+    /// use shields::measurer::CharWidthMeasurer;
+    /// use std::sync::LazyLock;
+    ///
+    /// static TABLE: LazyLock<Vec<u8>> =
+    ///     LazyLock::new(|| CharWidthMeasurer::from_data(vec![(65, 90, 10.0)]).to_binary());
+    /// let measurer = CharWidthMeasurer::from_binary(&TABLE).unwrap();
+    /// assert_eq!(measurer.width_of("ABC", false), 30.0);
+    /// ```
+    pub fn from_binary(table: &'static [u8]) -> io::Result<Self> {
+        let invalid =
+            |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+        if table.get(..4) != Some(BINARY_MAGIC) || table.len() < BINARY_HEADER_LEN {
+            return Err(invalid("not a binary width table"));
+        }
+        let count = u32::from_le_bytes(table[4..8].try_into().unwrap()) as usize;
+        if table.len() != BINARY_HEADER_LEN + BINARY_RANGE_LEN * count {
+            return Err(invalid("binary width table is truncated"));
+        }
+        let ranges = Ranges::Binary(table);
+        let mut previous = None;
+        for (lower, upper, _) in ranges.iter() {
+            if lower > upper || previous.is_some_and(|previous| previous >= lower) {
+                return Err(invalid("ranges are not sorted and disjoint"));
             }
+            previous = Some(upper);
         }
+        Ok(Self::from_ranges(ranges))
+    }
+
+    fn from_ranges(ranges: Ranges) -> Self {
         // emWidth is the width of character 'm'
         let mut consumer = CharWidthMeasurer {
-            hash_map,
+            ranges,
+            kerning: Vec::new(),
             em_width: 0.0,
+            wide_width: 0.0,
         };
         consumer.em_width = consumer.width_of("m", true);
+        consumer.wide_width = consumer
+            .width_of_char_code(0x4E00)
+            .unwrap_or(consumer.em_width);
         consumer
     }
 
+    /// Adds kerning pairs, applied by [`width_of`](Self::width_of) wherever `right` directly
+    /// follows `left`.
+    ///
+    /// Pairs replace any earlier adjustment of the same characters. Serialized tables (see
+    /// [`to_json`](Self::to_json) and [`to_binary`](Self::to_binary)) hold widths only.
+    ///
+    /// # Arguments
+    /// * `pairs` - `(left, right, adjustment)` triples of character codes and the width added
+    ///   between them, usually negative.
+    ///
+    /// ## Example
+    /// ```
+    /// use shields::measurer::CharWidthMeasurer;
+    /// let measurer = CharWidthMeasurer::from_data(vec![(65, 90, 10.0)])
+    ///     .with_kerning(vec![('A' as u32, 'V' as u32, -1.5)]);
+    /// assert_eq!(measurer.width_of("AV", false), 18.5);
+    /// assert_eq!(measurer.width_of("VA", false), 20.0);
+    /// ```
+    pub fn with_kerning(mut self, pairs: Vec<(u32, u32, f64)>) -> Self {
+        // A stable sort keeps the last adjustment given for a pair at the end of its run
+        self.kerning.extend(pairs);
+        self.kerning.sort_by_key(|&(left, right, _)| (left, right));
+        let mut kerning: Vec<(u32, u32, f64)> = Vec::with_capacity(self.kerning.len());
+        for pair in self.kerning.drain(..) {
+            match kerning.last_mut() {
+                Some(last) if (last.0, last.1) == (pair.0, pair.1) => *last = pair,
+                _ => kerning.push(pair),
+            }
+        }
+        self.kerning = kerning;
+        self
+    }
+
+    /// Returns the kerning adjustment between two character codes, 0 for unkerned pairs.
+    ///
+    /// ## Example
+    /// ```
+    /// use shields::measurer::CharWidthMeasurer;
+    /// let measurer = CharWidthMeasurer::from_data(vec![(65, 90, 10.0)])
+    ///     .with_kerning(vec![(84, 79, -0.75)]);
+    /// assert_eq!(measurer.kerning_of(84, 79), -0.75);
+    /// assert_eq!(measurer.kerning_of(79, 84), 0.0);
+    /// ```
+    pub fn kerning_of(&self, left: u32, right: u32) -> f64 {
+        self.kerning
+            .binary_search_by_key(&(left, right), |&(left, right, _)| (left, right))
+            .map_or(0.0, |i| self.kerning[i].2)
+    }
+
+    /// Serializes the table in the binary format read by [`from_binary`](Self::from_binary).
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut table =
+            Vec::with_capacity(BINARY_HEADER_LEN + BINARY_RANGE_LEN * self.ranges.len());
+        table.extend_from_slice(BINARY_MAGIC);
+        table.extend_from_slice(&(self.ranges.len() as u32).to_le_bytes());
+        for (lower, upper, width) in self.ranges.iter() {
+            table.extend_from_slice(&lower.to_le_bytes());
+            table.extend_from_slice(&upper.to_le_bytes());
+            table.extend_from_slice(&width.to_le_bytes());
+        }
+        table
+    }
+
     /// Loads a measurer from a JSON file (synchronously).
     ///
     /// # Arguments
@@ -156,6 +349,122 @@ impl CharWidthMeasurer {
         Ok(CharWidthMeasurer::from_data(data))
     }
 
+    /// Generates a measurer from a TrueType or OpenType font, with each character as wide as
+    /// its glyph's advance at `px_size` pixels, rounded to hundredths of a pixel like the
+    /// bundled tables. Characters the font has no glyph for are left out. Horizontal pairs of
+    /// the font's `kern` table become the measurer's kerning (see
+    /// [`with_kerning`](Self::with_kerning)).
+    ///
+    /// # Arguments
+    /// * `bytes` - Contents of a `.ttf` or `.otf` file (the first face of a collection).
+    /// * `px_size` - Font size in pixels, e.g. `11.0` for the flat styles.
+    ///
+    /// # Returns
+    /// `Ok(CharWidthMeasurer)` if successful, or an `io::Error`.
+    ///
+    /// # Errors
+    /// Returns an error if the font cannot be parsed or `px_size` is not a positive number.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use shields::measurer::CharWidthMeasurer;
+    /// use shields::{Font, set_font_table};
+    ///
+    /// let ttf = std::fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf").unwrap();
+    /// let measurer = CharWidthMeasurer::from_ttf(&ttf, 11.0).unwrap();
+    /// set_font_table(Font::VerdanaNormal11, Some(measurer));
+    /// ```
+    #[cfg(feature = "font-gen")]
+    pub fn from_ttf(bytes: &[u8], px_size: f64) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        if !(px_size.is_finite() && px_size > 0.0) {
+            return Err(invalid(format!("invalid font size {}", px_size)));
+        }
+        let face = ttf_parser::Face::parse(bytes, 0).map_err(|e| invalid(e.to_string()))?;
+        let scale = px_size / f64::from(face.units_per_em());
+
+        let mut codes = Vec::new();
+        for subtable in face.tables().cmap.iter().flat_map(|cmap| cmap.subtables) {
+            if subtable.is_unicode() {
+                subtable.codepoints(|code| codes.push(code));
+            }
+        }
+        codes.sort_unstable();
+        codes.dedup();
+
+        let round = |units: f64| (units * scale * 100.0).round() / 100.0;
+
+        // Consecutive characters of equal width share one range, as in the bundled tables
+        let mut data: Vec<(u32, u32, f64)> = Vec::new();
+        let mut glyph_codes: HashMap<u16, Vec<u32>> = HashMap::new();
+        for code in codes {
+            let Some(glyph) = char::from_u32(code)
+                .filter(|_| !Self::is_control_char(code))
+                .and_then(|ch| face.glyph_index(ch))
+            else {
+                continue;
+            };
+            let Some(advance) = face.glyph_hor_advance(glyph) else {
+                continue;
+            };
+            glyph_codes.entry(glyph.0).or_default().push(code);
+            let width = round(f64::from(advance));
+            match data.last_mut() {
+                Some((_, upper, last)) if *upper + 1 == code && *last == width => *upper = code,
+                _ => data.push((code, code, width)),
+            }
+        }
+
+        let mut kerning = Vec::new();
+        let subtables = face.tables().kern.iter().flat_map(|kern| kern.subtables);
+        for subtable in
+            subtables.filter(|subtable| subtable.horizontal && !subtable.has_cross_stream)
+        {
+            let ttf_parser::kern::Format::Format0(pairs) = subtable.format else {
+                continue;
+            };
+            for pair in pairs.pairs {
+                let adjustment = round(f64::from(pair.value));
+                let (Some(lefts), Some(rights)) = (
+                    glyph_codes.get(&pair.left().0),
+                    glyph_codes.get(&pair.right().0),
+                ) else {
+                    continue;
+                };
+                if adjustment == 0.0 {
+                    continue;
+                }
+                for &left in lefts {
+                    kerning.extend(rights.iter().map(|&right| (left, right, adjustment)));
+                }
+            }
+        }
+        Ok(CharWidthMeasurer::from_data(data).with_kerning(kerning))
+    }
+
+    /// Serializes the table as JSON `[lower, upper, width]` ranges, the format read by
+    /// [`load_from_str`](Self::load_from_str) and [`load_sync`](Self::load_sync).
+    ///
+    /// ## Example
+    /// ```
+    /// use shields::measurer::CharWidthMeasurer;
+    /// let measurer = CharWidthMeasurer::from_data(vec![(65, 90, 10.0), (97, 97, 8.5)]);
+    /// assert_eq!(measurer.to_json(), "[[65,90,10.0],[97,97,8.5]]");
+    /// ```
+    pub fn to_json(&self) -> String {
+        // Neighbouring ranges of equal width are written as one
+        let mut ranges: Vec<(u32, u32, f64)> = Vec::with_capacity(self.ranges.len());
+        for (lower, upper, width) in self.ranges.iter() {
+            match ranges.last_mut() {
+                Some((_, last_upper, last)) if *last_upper + 1 == lower && *last == width => {
+                    *last_upper = upper
+                }
+                _ => ranges.push((lower, upper, width)),
+            }
+        }
+        serde_json::to_string(&ranges).expect("width tables serialize to JSON")
+    }
+
     /// Looks up the width of a single character code.
     ///
     /// Control characters have width 0. Returns `None` if not found.
@@ -177,14 +486,20 @@ impl CharWidthMeasurer {
         if Self::is_control_char(char_code) {
             return Some(0.0);
         }
-        // Directly use the hash table to look up character width
-        // The lookup table has already expanded all ranges to char_code -> width during initialization
-        self.hash_map.get(&char_code).copied()
+        self.ranges.width_of(char_code)
     }
 
     /// Calculates the width of a string.
     ///
-    /// If `guess` is true, uses `em_width` for unknown characters; otherwise panics.
+    /// If `guess` is true, unknown characters are guessed: bidi controls and Hebrew and Arabic
+    /// vowel marks as zero-width, East Asian wide characters (Hangul, kana, CJK ideographs and
+    /// symbols, fullwidth forms) as wide as a CJK ideograph, emoji outside the Basic
+    /// Multilingual Plane as two `m`s (as shields.io measures each of their UTF-16 code units),
+    /// anything else as wide as `m` (`em_width`). Emoji presentation sequences are measured as
+    /// their first emoji: variation selectors, skin tone modifiers, the second half of a flag
+    /// and emoji joined by a zero-width joiner add no width. Otherwise unknown characters
+    /// panic. Kerning pairs (see [`with_kerning`](Self::with_kerning)) adjust the width between
+    /// adjacent characters.
     ///
     /// # Arguments
     /// * `text` - The string to measure.
@@ -206,12 +521,33 @@ impl CharWidthMeasurer {
     /// ```
     pub fn width_of(&self, text: &str, guess: bool) -> f64 {
         let mut total = 0.0;
+        let mut previous = None;
+        let mut regional_indicators = 0;
         for ch in text.chars() {
             let code = ch as u32;
+            if is_regional_indicator(code) {
+                regional_indicators += 1;
+            } else {
+                regional_indicators = 0;
+            }
+            if guess && continues_emoji(previous, code, regional_indicators) {
+                previous = Some(code);
+                continue;
+            }
+            if let Some(previous) = previous.filter(|_| !self.kerning.is_empty()) {
+                total += self.kerning_of(previous, code);
+            }
+            previous = Some(code);
             match self.width_of_char_code(code) {
                 Some(width) => total += width,
                 None => {
-                    if guess {
+                    if guess && crate::bidi::is_zero_width(ch) {
+                        continue;
+                    } else if guess && is_wide(code) {
+                        total += self.wide_width;
+                    } else if guess && is_emoji(code) {
+                        total += 2.0 * self.em_width;
+                    } else if guess {
                         total += self.em_width;
                     } else {
                         panic!("No width available for character code {}", text);
@@ -223,6 +559,63 @@ impl CharWidthMeasurer {
     }
 }
 
+/// Ranges of consecutive characters sharing a width.
+fn merge_ranges(widths: BTreeMap<u32, f64>) -> Vec<(u32, u32, f64)> {
+    let mut ranges: Vec<(u32, u32, f64)> = Vec::new();
+    for (code, width) in widths {
+        match ranges.last_mut() {
+            Some((_, upper, last)) if *upper + 1 == code && *last == width => *upper = code,
+            _ => ranges.push((code, code, width)),
+        }
+    }
+    ranges
+}
+
+/// East Asian wide ranges whose glyphs share the width of a CJK ideograph.
+fn is_wide(char_code: u32) -> bool {
+    matches!(
+        char_code,
+        0x1100..=0x115F // Hangul Jamo initial consonants
+            | 0x2E80..=0x303E // CJK radicals, Kangxi radicals, CJK symbols and punctuation
+            | 0x3041..=0x33FF // kana, Bopomofo, Hangul compatibility Jamo, CJK compatibility
+            | 0x3400..=0x4DBF // CJK unified ideographs extension A
+            | 0x4E00..=0x9FFF // CJK unified ideographs
+            | 0xA960..=0xA97F // Hangul Jamo extended-A
+            | 0xAC00..=0xD7A3 // Hangul syllables
+            | 0xF900..=0xFAFF // CJK compatibility ideographs
+            | 0xFE30..=0xFE4F // CJK compatibility forms
+            | 0xFF00..=0xFF60 // fullwidth forms
+            | 0xFFE0..=0xFFE6 // fullwidth signs
+            | 0x20000..=0x3FFFD // CJK unified ideographs extensions B and later
+    )
+}
+
+/// Emoji and pictographs outside the Basic Multilingual Plane.
+fn is_emoji(char_code: u32) -> bool {
+    (0x1F000..=0x1FAFF).contains(&char_code)
+}
+
+/// Regional indicator symbols, drawn in pairs as a flag.
+fn is_regional_indicator(char_code: u32) -> bool {
+    (0x1F1E6..=0x1F1FF).contains(&char_code)
+}
+
+/// Whether `char_code` is drawn as part of the emoji before it rather than as a glyph of its
+/// own, given the preceding character and the length of the run of regional indicators it
+/// ends.
+fn continues_emoji(previous: Option<u32>, char_code: u32, regional_indicators: usize) -> bool {
+    match char_code {
+        0xFE0E | 0xFE0F => true,   // text and emoji presentation selectors
+        0x1F3FB..=0x1F3FF => true, // skin tone modifiers
+        _ if is_regional_indicator(char_code) => regional_indicators.is_multiple_of(2),
+        // A pictograph joined to the one before it by a zero-width joiner
+        _ => {
+            previous == Some(0x200D)
+                && (is_emoji(char_code) || (0x2300..=0x2BFF).contains(&char_code))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,4 +668,147 @@ mod tests {
         let measurer = CharWidthMeasurer::from_data(data);
         measurer.width_of("A測", false); // Should panic for unknown character '測'
     }
+
+    #[test]
+    fn test_wide_guess() {
+        let data = vec![(109, 109, 9.0), (0x4E00, 0x9FFF, 11.0)];
+        let measurer = CharWidthMeasurer::from_data(data);
+        // Hangul and kana missing from the table are guessed as wide as an ideograph
+        assert_eq!(measurer.width_of("한글", true), 22.0);
+        assert_eq!(measurer.width_of("ア", true), 11.0);
+        // Other unknown characters still use the width of 'm'
+        assert_eq!(measurer.width_of("@", true), 9.0);
+
+        // Without ideographs in the table, wide characters fall back to em_width
+        let measurer = CharWidthMeasurer::from_data(vec![(109, 109, 9.0)]);
+        assert_eq!(measurer.width_of("한", true), 9.0);
+    }
+
+    #[test]
+    fn test_emoji_guess() {
+        let measurer = CharWidthMeasurer::from_data(vec![
+            (32, 32, 3.0),
+            (109, 109, 9.0),
+            (0x200D, 0x200D, 0.0),
+        ]);
+        // Emoji missing from the table count as two UTF-16 code units of em width each
+        assert_eq!(measurer.width_of("🚀", true), 18.0);
+        assert_eq!(measurer.width_of("m 🚀", true), 30.0);
+        // Presentation sequences are as wide as their first emoji
+        assert_eq!(measurer.width_of("👍🏽", true), 18.0);
+        assert_eq!(measurer.width_of("👨\u{200D}💻", true), 18.0);
+        assert_eq!(measurer.width_of("🇺🇸🇫🇷", true), 36.0);
+        assert_eq!(measurer.width_of("🇺🇸🇫", true), 36.0);
+        // Other symbols are guessed as wide as 'm', whatever their presentation
+
+        assert_eq!(measurer.width_of("✅\u{FE0F}", true), 9.0);
+
+        // Emoji in the table keep their width
+        let measurer =
+            CharWidthMeasurer::from_data(vec![(109, 109, 9.0), (0x1F300, 0x1FAFF, 13.0)]);
+        assert_eq!(measurer.width_of("🚀🚀", true), 26.0);
+    }
+
+    #[test]
+    fn test_to_json_round_trip() {
+        let measurer = CharWidthMeasurer::from_data(vec![(65, 67, 10.0), (68, 68, 7.25)]);
+        let json = measurer.to_json();
+        assert_eq!(json, "[[65,67,10.0],[68,68,7.25]]");
+        let loaded = CharWidthMeasurer::load_from_str(&json).unwrap();
+        assert_eq!(loaded.width_of("ABCD", false), 37.25);
+    }
+
+    #[test]
+    fn test_binary_tables() {
+        // The tables build.rs embeds hold the same widths as their JSON sources
+        let binary = CharWidthMeasurer::from_binary(include_bytes!(concat!(
+            env!("OUT_DIR"),
+            "/verdana-11px-normal.bin"
+        )))
+        .unwrap();
+        let json = CharWidthMeasurer::load_from_str(include_str!(
+            "../assets/fonts/verdana-11px-normal.json"
+        ))
+        .unwrap();
+        assert_eq!(binary.to_json(), json.to_json());
+        assert_eq!(binary.em_width, json.em_width);
+        for text in ["build passing", "Ωμέγα", "中文", "\u{10FFFF}"] {
+            assert_eq!(binary.width_of(text, true), json.width_of(text, true));
+        }
+
+        let measurer = CharWidthMeasurer::from_data(vec![(65, 90, 10.0), (97, 122, 8.25)]);
+        let table: &'static [u8] = measurer.to_binary().leak();
+        let loaded = CharWidthMeasurer::from_binary(table).unwrap();
+        assert_eq!(loaded.width_of("Aa", false), 18.25);
+        assert_eq!(loaded.width_of_char_code(64), None);
+        assert!(CharWidthMeasurer::from_binary(&table[..table.len() - 1]).is_err());
+        assert!(CharWidthMeasurer::from_binary(b"[[65,90,10]]").is_err());
+    }
+
+    #[test]
+    fn test_kerning() {
+        let measurer = CharWidthMeasurer::from_data(vec![(65, 90, 10.0), (109, 109, 9.0)])
+            .with_kerning(vec![(65, 86, -1.0), (84, 79, -0.5), (65, 86, -1.25)]);
+        assert_eq!(measurer.kerning_of(65, 86), -1.25);
+        assert_eq!(measurer.width_of("AVA", false), 28.75);
+        assert_eq!(measurer.width_of("TOTO", false), 39.0);
+        // Pairs apply to adjacent characters only
+        assert_eq!(measurer.width_of("A V", true), 29.0);
+        assert_eq!(measurer.em_width, 9.0);
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        // Later ranges override earlier ones, as when every range was expanded into a map
+        let measurer =
+            CharWidthMeasurer::from_data(vec![(65, 90, 10.0), (70, 72, 4.0), (60, 60, 1.0)]);
+        assert_eq!(measurer.width_of_char_code(69), Some(10.0));
+        assert_eq!(measurer.width_of_char_code(71), Some(4.0));
+        assert_eq!(measurer.width_of_char_code(73), Some(10.0));
+        assert_eq!(measurer.width_of_char_code(60), Some(1.0));
+        assert_eq!(measurer.width_of_char_code(61), None);
+    }
+
+    #[cfg(feature = "font-gen")]
+    #[test]
+    fn test_from_ttf() {
+        // DejaVu Sans cut down to printable ASCII, é and …
+        let ttf = include_bytes!("../tests/fixtures/dejavu-sans-ascii.ttf");
+        let measurer = CharWidthMeasurer::from_ttf(ttf, 11.0).unwrap();
+        let bundled = CharWidthMeasurer::load_from_str(include_str!(
+            "../assets/fonts/dejavu-sans-11px-normal.json"
+        ))
+        .unwrap();
+        for c in " ~Hello,World!1.0é…".chars() {
+            assert_eq!(
+                measurer.width_of_char_code(c as u32),
+                bundled.width_of_char_code(c as u32)
+            );
+        }
+        assert_eq!(measurer.width_of_char_code('x' as u32), Some(6.51));
+        assert_eq!(measurer.width_of_char_code('中' as u32), None);
+
+        assert!(measurer.kerning_of('A' as u32, 'V' as u32) < 0.0);
+        assert!(measurer.width_of("AV", false) < bundled.width_of("AV", false));
+
+        let large = CharWidthMeasurer::from_ttf(ttf, 22.0).unwrap();
+        assert!(
+            (large.width_of("passing", false) - 2.0 * measurer.width_of("passing", false)).abs()
+                < 0.05
+        );
+        assert!(CharWidthMeasurer::from_ttf(ttf, 0.0).is_err());
+        assert!(CharWidthMeasurer::from_ttf(b"not a font", 11.0).is_err());
+    }
+
+    #[test]
+    fn test_embedded_tables_cover_non_latin_scripts() {
+        use crate::{Font, get_text_width};
+        for font in Font::ALL {
+            let ideograph = get_text_width("中", font.clone());
+            assert_eq!(get_text_width("한", font.clone()), ideograph);
+            assert_eq!(get_text_width("中文字", font.clone()), 3.0 * ideograph);
+            assert!(get_text_width("Жж", font.clone()) > 0.0);
+            assert!(get_text_width("Ωω", font) > 0.0);
+        }
+    }
 }
@@ -0,0 +1,176 @@
+//! Size optimization of rendered badges, for pages embedding many of them inline.
+
+use crate::{BadgeParams, render_badge_svg};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static ADJACENT_RECTS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<rect((?: [a-z]+="[^"]*")*)/><rect((?: [a-z]+="[^"]*")*)/>"#).unwrap()
+});
+static EMPTY_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<a(?: [a-z]+="[^"]*")* href=""(?: [a-z]+="[^"]*")*>(.*?)</a>"#).unwrap()
+});
+static EMPTY_ELEMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<g(?: [a-z-]+="[^"]*")*></g>|<a(?: [a-z-]+="[^"]*")*></a>"#).unwrap()
+});
+static DEFAULT_ATTRIBUTE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#" (?:x|y|rx)="0"| fill-opacity="1""#).unwrap());
+static CLIP_RECT_FILL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(<clipPath[^>]*><rect[^>]*?) fill="[^"]*""#).unwrap());
+
+/// Render a badge as SVG with redundant markup removed.
+///
+/// The badge looks the same as with [`render_badge_svg`], but adjacent rectangles with the
+/// same fill are merged, links with an empty `href` and empty groups are dropped, and
+/// attributes set to their default value are removed.
+///
+/// # Arguments
+/// * `params` - Badge parameters (see [`BadgeParams`]).
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::{BadgeParams, render_badge_svg, render_badge_svg_optimized};
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     label_color: Some("brightgreen"),
+///     message_color: Some("brightgreen"),
+///     ..Default::default()
+/// };
+/// assert!(render_badge_svg_optimized(&params).len() < render_badge_svg(&params).len());
+/// # }
+/// ```
+pub fn render_badge_svg_optimized(params: &BadgeParams) -> String {
+    optimize_svg(&render_badge_svg(params))
+}
+
+/// Removes redundant markup from a rendered badge.
+pub(crate) fn optimize_svg(svg: &str) -> String {
+    let mut svg = CLIP_RECT_FILL.replace_all(svg, "$1").into_owned();
+    svg = EMPTY_LINK.replace_all(&svg, "$1").into_owned();
+    while let Some(merged) = merge_rects(&svg) {
+        svg = merged;
+    }
+    svg = DEFAULT_ATTRIBUTE.replace_all(&svg, "").into_owned();
+    while EMPTY_ELEMENT.is_match(&svg) {
+        svg = EMPTY_ELEMENT.replace_all(&svg, "").into_owned();
+    }
+    svg
+}
+
+/// Merges the first pair of side-by-side rectangles sharing a fill, if any.
+fn merge_rects(svg: &str) -> Option<String> {
+    let mut start = 0;
+    while let Some(caps) = ADJACENT_RECTS.captures_at(svg, start) {
+        if let (Some(left), Some(right)) = (Rect::parse(&caps[1]), Rect::parse(&caps[2]))
+            && left.y == right.y
+            && left.height == right.height
+            && left.fill == right.fill
+            && left.x + left.width == right.x
+        {
+            let pair = caps.get(0).unwrap();
+            let merged = Rect {
+                width: left.width + right.width,
+                ..left
+            };
+            return Some(format!(
+                "{}{}{}",
+                &svg[..pair.start()],
+                merged,
+                &svg[pair.end()..]
+            ));
+        }
+        // The second rectangle may still pair with the one after it
+        start = caps.get(1).unwrap().end() + "/>".len();
+    }
+    None
+}
+
+/// A plain filled rectangle, as drawn for the label and message backgrounds.
+struct Rect<'a> {
+    x: f64,
+    y: &'a str,
+    width: f64,
+    height: &'a str,
+    fill: &'a str,
+}
+
+impl<'a> Rect<'a> {
+    /// Parses the attributes of a `<rect>`; rectangles with attributes other than position,
+    /// size and fill (such as classes or rounded corners) are never merged.
+    fn parse(attributes: &'a str) -> Option<Self> {
+        let mut rect = Rect {
+            x: 0.0,
+            y: "0",
+            width: 0.0,
+            height: "",
+            fill: "",
+        };
+        for attribute in attributes.split('"').collect::<Vec<_>>().chunks_exact(2) {
+            let (name, value) = (attribute[0].trim().strip_suffix('=')?, attribute[1]);
+            match name {
+                "x" => rect.x = value.parse().ok()?,
+                "y" => rect.y = value,
+                "width" => rect.width = value.parse().ok()?,
+                "height" => rect.height = value,
+                "fill" => rect.fill = value,
+                _ => return None,
+            }
+        }
+        (!rect.height.is_empty() && !rect.fill.is_empty()).then_some(rect)
+    }
+}
+
+impl std::fmt::Display for Rect<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            self.x, self.y, self.width, self.height, self.fill
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimize_svg() {
+        assert_eq!(
+            optimize_svg(
+                r##"<svg><clipPath id="r"><rect width="90" height="20" rx="3" fill="#fff"/></clipPath><g><rect width="37" height="20" fill="#4c1"/><rect x="37" width="53" height="20" fill="#4c1"/></g><a target="_blank" href=""><text x="0">a</text></a><g></g></svg>"##
+            ),
+            r##"<svg><clipPath id="r"><rect width="90" height="20" rx="3"/></clipPath><g><rect width="90" height="20" fill="#4c1"/></g><text>a</text></svg>"##
+        );
+        // Different fills, classes and gaps are kept apart
+        let svg = r##"<g><rect width="37" height="20" fill="#555"/><rect x="37" width="53" height="20" fill="#4c1"/><rect class="a" x="90" width="5" height="20" fill="#4c1"/></g>"##;
+        assert_eq!(optimize_svg(svg), svg);
+        assert_eq!(
+            optimize_svg(
+                r##"<g><rect width="37" height="20" fill="#555"/><rect x="37" width="53" height="20" fill="#4c1"/><rect x="90" width="5" height="20" fill="#4c1"/></g>"##
+            ),
+            r##"<g><rect width="37" height="20" fill="#555"/><rect x="37" width="58" height="20" fill="#4c1"/></g>"##
+        );
+    }
+
+    #[cfg(all_styles)]
+    #[test]
+    fn test_optimized_badges_stay_well_formed() {
+        for style in crate::BadgeStyle::ALL {
+            let params = BadgeParams {
+                style,
+                label: Some("build"),
+                message: Some("passing"),
+                label_color: Some("blue"),
+                message_color: Some("blue"),
+                logo: Some("rust"),
+                ..Default::default()
+            };
+            let svg = render_badge_svg_optimized(&params);
+            assert!(svg.len() <= render_badge_svg(&params).len(), "{:?}", style);
+            xmltree::Element::parse(svg.as_bytes()).unwrap();
+        }
+    }
+}
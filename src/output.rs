@@ -0,0 +1,328 @@
+//! Writing badge sets to a directory.
+//!
+//! [`write_badges`] renders each [`NamedBadge`] to `<name>.svg` and records the set in an
+//! `index.json` [`Manifest`] next to them. File names only depend on badge names, and every
+//! file is written atomically (to a temporary file that is then renamed), so static-site
+//! deploys never publish a half-written badge and re-running with the same input produces
+//! the same directory. Badges can also be passed as `(name, params)` pairs, which convert
+//! into [`NamedBadge`]s.
+//!
+//! Writing is incremental: every badge is rendered, but files whose content would not change
+//! are left untouched, keeping their modification times. CI artifacts and rsync-style deploys
+//! then only transfer badges that actually changed.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use shields::BadgeParamsOwned;
+//! use shields::output::{NamedBadge, write_badges};
+//!
+//! let dir = std::env::temp_dir().join("shields-doc-output");
+//! let badges = [NamedBadge::new(
+//!     "build",
+//!     BadgeParamsOwned {
+//!         label: Some("build".to_string()),
+//!         message: Some("passing".to_string()),
+//!         ..Default::default()
+//!     },
+//! )];
+//! let index = write_badges(&dir, &badges).unwrap();
+//! assert_eq!(index.badges[0].file.as_deref(), Some("build.svg"));
+//! assert!(dir.join("build.svg").exists());
+//! assert!(dir.join("index.json").exists());
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use crate::manifest::{Manifest, ManifestBadge};
+use crate::{BadgeParams, BadgeParamsOwned, content_hash, render_badge_svg, svg_size};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// File name of the manifest written by [`write_badges`].
+pub const INDEX_FILE: &str = "index.json";
+
+/// Pixels per SVG unit of PNG output, so images stay sharp on high-density displays.
+#[cfg(feature = "raster")]
+pub const PNG_SCALE: f32 = 2.0;
+
+/// A badge to be written by [`write_badges`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedBadge {
+    /// File stem; ASCII letters, digits, `-`, `_` and `.`, not starting with `.`.
+    pub name: String,
+    /// Parameters the badge is rendered from.
+    pub params: BadgeParamsOwned,
+    /// Also write `<name>.png`; requires the `raster` feature.
+    pub png: bool,
+}
+
+impl NamedBadge {
+    /// Creates a badge written as SVG only.
+    pub fn new(name: impl Into<String>, params: BadgeParamsOwned) -> Self {
+        NamedBadge {
+            name: name.into(),
+            params,
+            png: false,
+        }
+    }
+}
+
+impl<S: Into<String>> From<(S, BadgeParamsOwned)> for NamedBadge {
+    fn from((name, params): (S, BadgeParamsOwned)) -> Self {
+        NamedBadge::new(name, params)
+    }
+}
+
+impl<S: Into<String>> From<(S, BadgeParams<'_>)> for NamedBadge {
+    fn from((name, params): (S, BadgeParams<'_>)) -> Self {
+        NamedBadge::new(name, params.into())
+    }
+}
+
+impl From<&NamedBadge> for NamedBadge {
+    fn from(badge: &NamedBadge) -> Self {
+        badge.clone()
+    }
+}
+
+/// Renders `badges` into `dir`, creating it if needed, and writes an `index.json` manifest.
+///
+/// `badges` yields [`NamedBadge`]s, references to them or `(name, params)` pairs.
+///
+/// # Returns
+/// The manifest written to `index.json`, with file, size and content hash of every badge.
+///
+/// # Errors
+/// Returns [`io::ErrorKind::InvalidInput`] for invalid or duplicate names,
+/// [`io::ErrorKind::Unsupported`] when PNG output is requested but the crate was built without
+/// the `raster` feature, and any error raised while rendering or writing files.
+pub fn write_badges(
+    dir: impl AsRef<Path>,
+    badges: impl IntoIterator<Item = impl Into<NamedBadge>>,
+) -> io::Result<Manifest> {
+    let dir = dir.as_ref();
+    let badges: Vec<NamedBadge> = badges.into_iter().map(Into::into).collect();
+    let mut names = HashSet::new();
+    for badge in &badges {
+        validate_name(&badge.name)?;
+        if !names.insert(badge.name.as_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("duplicate badge name `{}`", badge.name),
+            ));
+        }
+        #[cfg(not(feature = "raster"))]
+        if badge.png {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("PNG output for `{}` needs the `raster` feature", badge.name),
+            ));
+        }
+    }
+    fs::create_dir_all(dir)?;
+
+    let mut entries = Vec::with_capacity(badges.len());
+    for badge in &badges {
+        let file = format!("{}.svg", badge.name);
+        let path = dir.join(&file);
+        let svg = render_badge_svg(&badge.params.as_params());
+        write_if_changed(&path, svg.as_bytes())?;
+        #[cfg(feature = "raster")]
+        if badge.png {
+            let png = crate::render_badge_png(&badge.params.as_params(), PNG_SCALE)
+                .map_err(io::Error::other)?;
+            write_if_changed(&dir.join(format!("{}.png", badge.name)), &png)?;
+        }
+
+        let mut entry = ManifestBadge::new(badge.name.clone(), badge.params.clone());
+        entry.file = Some(file);
+        if let Some((width, height)) = svg_size(&svg) {
+            entry.width = Some(width as u32);
+            entry.height = Some(height as u32);
+        }
+        entry.hash = Some(content_hash(svg.as_bytes()));
+        entries.push(entry);
+    }
+
+    let manifest = Manifest::new(entries);
+    write_if_changed(&dir.join(INDEX_FILE), manifest.to_json().as_bytes())?;
+    Ok(manifest)
+}
+
+/// Writes `contents` atomically unless `path` already holds exactly these bytes.
+fn write_if_changed(path: &Path, contents: &[u8]) -> io::Result<()> {
+    match fs::read(path) {
+        Ok(existing) if existing == contents => Ok(()),
+        _ => write_atomic(path, contents),
+    }
+}
+
+fn validate_name(name: &str) -> io::Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid badge name `{}`", name),
+        ))
+    }
+}
+
+/// Writes `contents` to a temporary file beside `path`, then renames it into place.
+fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let result = (|| {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("shields-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn badge(name: &str, message: &str) -> NamedBadge {
+        NamedBadge::new(
+            name,
+            BadgeParamsOwned {
+                label: Some("build".to_string()),
+                message: Some(message.to_string()),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_write_badges_and_index() {
+        let dir = temp_dir("write");
+        let manifest =
+            write_badges(&dir, &[badge("build", "passing"), badge("tests", "12")]).unwrap();
+        let svg = fs::read_to_string(dir.join("build.svg")).unwrap();
+        assert!(svg.contains("passing"));
+        assert_eq!(manifest.badges[0].hash, Some(content_hash(svg.as_bytes())));
+        assert_eq!(manifest.badges[0].height, Some(20));
+        let index = fs::read_to_string(dir.join(INDEX_FILE)).unwrap();
+        assert_eq!(Manifest::from_json(&index).unwrap(), manifest);
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_unchanged_badges_are_not_rewritten() {
+        let dir = temp_dir("incremental");
+        let first = write_badges(&dir, &[badge("build", "passing"), badge("tests", "12")]).unwrap();
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        for file in ["build.svg", "tests.svg", INDEX_FILE] {
+            fs::File::options()
+                .write(true)
+                .open(dir.join(file))
+                .unwrap()
+                .set_modified(old)
+                .unwrap();
+        }
+        let modified = |file: &str| fs::metadata(dir.join(file)).unwrap().modified().unwrap();
+
+        let second =
+            write_badges(&dir, &[badge("build", "passing"), badge("tests", "13")]).unwrap();
+        assert_eq!(second.badges[0], first.badges[0]);
+        assert_eq!(modified("build.svg"), old);
+        assert_ne!(modified("tests.svg"), old);
+        assert_ne!(modified(INDEX_FILE), old);
+        assert!(
+            fs::read_to_string(dir.join("tests.svg"))
+                .unwrap()
+                .contains("13")
+        );
+
+        // A tampered file is restored even though its parameters are unchanged
+        fs::write(dir.join("build.svg"), "tampered").unwrap();
+        write_badges(&dir, &[badge("build", "passing"), badge("tests", "13")]).unwrap();
+        assert!(
+            fs::read_to_string(dir.join("build.svg"))
+                .unwrap()
+                .contains("passing")
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_badges_from_pairs() {
+        let dir = temp_dir("pairs");
+        let params = BadgeParams {
+            label: Some("docs"),
+            message: Some("latest"),
+            ..Default::default()
+        };
+        let named = NamedBadge::from(("docs", params.clone()));
+        assert_eq!(named.params, BadgeParamsOwned::from(params.clone()));
+        let manifest = write_badges(&dir, [("docs", params)]).unwrap();
+        assert_eq!(manifest.badges[0].file.as_deref(), Some("docs.svg"));
+        let manifest =
+            write_badges(&dir, vec![("build", badge("build", "passing").params)]).unwrap();
+        assert_eq!(manifest.badges[0].name, "build");
+        assert!(manifest.badges[0].width.is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_png_output() {
+        let dir = temp_dir("png");
+        let mut png = badge("build", "passing");
+        png.png = true;
+        let result = write_badges(&dir, &[png]);
+        #[cfg(feature = "raster")]
+        {
+            result.unwrap();
+            let bytes = fs::read(dir.join("build.png")).unwrap();
+            assert!(bytes.starts_with(b"\x89PNG"));
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        #[cfg(not(feature = "raster"))]
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn test_rejects_bad_names() {
+        let dir = temp_dir("names");
+        for name in ["", ".hidden", "a/b", "a b"] {
+            let err = write_badges(&dir, &[badge(name, "x")]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{}", name);
+        }
+        let err = write_badges(&dir, &[badge("a", "x"), badge("a", "y")]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+        assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+    }
+}
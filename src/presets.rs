@@ -0,0 +1,206 @@
+//! Ready-made store-style download badges, trend badges and version badges.
+//!
+//! Each store preset returns [`BadgeParams`] for a [`BadgeStyle::ForTheBadgeTwoLine`] badge
+//! combining a logo, a two-line call to action and the store's brand color, so release pages can
+//! generate "get it on"-style badges locally. [`trend`] builds a badge showing a change such as
+//! `coverage | ▲ 2.3%` and [`version`] a release badge colored by its semantic version. The
+//! returned params can be tweaked like any others before rendering.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "style-for-the-badge-two-line")] {
+//! use shields::presets;
+//! use shields::render_badge_svg;
+//!
+//! let params = presets::get_it_on_github("https://github.com/Jannchie/shields.rs/releases");
+//! let svg = render_badge_svg(&params);
+//! assert!(svg.contains("GET IT ON"));
+//! # }
+//! ```
+
+use crate::format::DecimalFormat;
+use crate::{BadgeParams, BadgeParamsOwned, BadgeStyle};
+
+fn store_badge<'a>(
+    label: &'a str,
+    message: &'a str,
+    color: &'a str,
+    logo: &'a str,
+    link: &'a str,
+) -> BadgeParams<'a> {
+    BadgeParams {
+        style: BadgeStyle::ForTheBadgeTwoLine,
+        label: Some(label),
+        message: Some(message),
+        message_color: Some(color),
+        logo: Some(logo),
+        logo_color: Some("white"),
+        link: Some(link),
+        ..Default::default()
+    }
+}
+
+/// "GET IT ON / GITHUB" badge linking to a repository or release page.
+pub fn get_it_on_github(link: &str) -> BadgeParams<'_> {
+    store_badge("Get it on", "GitHub", "#181717", "github", link)
+}
+
+/// "DOWNLOAD FROM / GITHUB RELEASES" badge linking to a releases page.
+pub fn github_releases(link: &str) -> BadgeParams<'_> {
+    store_badge(
+        "Download from",
+        "GitHub Releases",
+        "#181717",
+        "github",
+        link,
+    )
+}
+
+/// "AVAILABLE ON / CRATES.IO" badge linking to a crate page.
+pub fn available_on_crates_io(link: &str) -> BadgeParams<'_> {
+    store_badge("Available on", "crates.io", "#3b6837", "rust", link)
+}
+
+/// "READ THE DOCS ON / DOCS.RS" badge linking to a crate's documentation.
+pub fn docs_on_docs_rs(link: &str) -> BadgeParams<'_> {
+    store_badge("Read the docs on", "docs.rs", "#000000", "docsdotrs", link)
+}
+
+/// Badge showing a numeric change, e.g. `coverage | ▲ 2.3%`.
+///
+/// The message is the absolute value of `delta` formatted with `format`, followed by `unit`
+/// and preceded by `▲` on a green badge when it is positive or `▼` on a red badge when it is
+/// negative. A change that formats as zero has no arrow and a grey badge. For metrics where
+/// lower is better, such as build times, swap the colors of the returned params.
+///
+/// ## Example
+/// ```rust
+/// use shields::format::DecimalFormat;
+/// use shields::presets;
+///
+/// let params = presets::trend("coverage", 2.34, DecimalFormat::new(1), "%");
+/// assert_eq!(params.message.as_deref(), Some("▲ 2.3%"));
+/// assert_eq!(params.message_color.as_deref(), Some("brightgreen"));
+/// ```
+pub fn trend(label: &str, delta: f64, format: DecimalFormat, unit: &str) -> BadgeParamsOwned {
+    let value = format.format(delta.abs());
+    let zero = !value.bytes().any(|b| (b'1'..=b'9').contains(&b));
+    let (arrow, color) = if zero || delta.is_nan() {
+        ("", "lightgrey")
+    } else if delta > 0.0 {
+        ("▲ ", "brightgreen")
+    } else {
+        ("▼ ", "red")
+    };
+    BadgeParamsOwned {
+        label: Some(label.to_string()),
+        message: Some(format!("{}{}{}", arrow, value, unit)),
+        message_color: Some(color.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Version badge for a semantic version string, e.g. `version | v1.4.2`.
+///
+/// The message is `version` prefixed with `v` (an existing `v` is kept, not doubled). The
+/// message color is yellow for pre-releases such as `2.0.0-rc.1`, orange for `0.x`
+/// versions and blue for stable releases. Build metadata after `+` does not affect the color.
+///
+/// ## Example
+/// ```rust
+/// use shields::presets;
+///
+/// let params = presets::version("crates.io", "0.3.1");
+/// assert_eq!(params.message.as_deref(), Some("v0.3.1"));
+/// assert_eq!(params.message_color.as_deref(), Some("orange"));
+/// ```
+pub fn version(label: &str, version: &str) -> BadgeParamsOwned {
+    let version = version.trim();
+    let number = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let core = number.split('+').next().unwrap_or_default();
+    let color = if core.contains('-') {
+        "yellow"
+    } else if core.split('.').next() == Some("0") {
+        "orange"
+    } else {
+        "blue"
+    };
+    BadgeParamsOwned {
+        label: Some(label.to_string()),
+        message: Some(format!("v{}", number)),
+        message_color: Some(color.to_string()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "style-for-the-badge-two-line")]
+    #[test]
+    fn test_presets_render_two_line_badges() {
+        let link = "https://crates.io/crates/shields";
+        for params in [
+            get_it_on_github(link),
+            github_releases(link),
+            available_on_crates_io(link),
+            docs_on_docs_rs(link),
+        ] {
+            assert_eq!(params.style, BadgeStyle::ForTheBadgeTwoLine);
+            let svg = crate::render_badge_svg(&params);
+            assert!(svg.contains(r#"height="40""#), "{}", svg);
+            assert!(svg.contains(link), "{}", svg);
+        }
+        let svg = crate::render_badge_svg(&available_on_crates_io(link));
+        assert!(svg.contains(r##"fill="#3b6837""##), "{}", svg);
+        assert!(svg.contains(">CRATES.IO</text>"), "{}", svg);
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_trend() {
+        use crate::{Font, get_text_width};
+
+        let up = trend("coverage", 2.34, DecimalFormat::new(1), "%");
+        assert_eq!(up.label.as_deref(), Some("coverage"));
+        assert_eq!(up.message.as_deref(), Some("▲ 2.3%"));
+        assert_eq!(up.message_color.as_deref(), Some("brightgreen"));
+
+        let down = trend("size", -1024.0, DecimalFormat::new(0), " B");
+        assert_eq!(down.message.as_deref(), Some("▼ 1024 B"));
+        assert_eq!(down.message_color.as_deref(), Some("red"));
+
+        let flat = trend("coverage", -0.04, DecimalFormat::new(1), "%");
+        assert_eq!(flat.message.as_deref(), Some("0.0%"));
+        assert_eq!(flat.message_color.as_deref(), Some("lightgrey"));
+
+        // The arrows are measured from the width table, not guessed from `m`
+        let arrow = get_text_width("▲", Font::VerdanaNormal11);
+        assert_eq!(get_text_width("▼", Font::VerdanaNormal11), arrow);
+        assert_ne!(arrow, get_text_width("m", Font::VerdanaNormal11));
+        let svg = crate::render_badge_svg(&up.as_params());
+        assert!(svg.contains(">▲ 2.3%</text>"), "{}", svg);
+    }
+
+    #[test]
+    fn test_version() {
+        let stable = version("version", "1.4.2");
+        assert_eq!(stable.label.as_deref(), Some("version"));
+        assert_eq!(stable.message.as_deref(), Some("v1.4.2"));
+        assert_eq!(stable.message_color.as_deref(), Some("blue"));
+
+        let zero = version("version", " v0.12.0 ");
+        assert_eq!(zero.message.as_deref(), Some("v0.12.0"));
+        assert_eq!(zero.message_color.as_deref(), Some("orange"));
+
+        let pre = version("version", "0.9.0-beta.2");
+        assert_eq!(pre.message_color.as_deref(), Some("yellow"));
+
+        // Build metadata is not a pre-release
+        let build = version("version", "V10.0.0+build-7");
+        assert_eq!(build.message.as_deref(), Some("v10.0.0+build-7"));
+        assert_eq!(build.message_color.as_deref(), Some("blue"));
+    }
+}
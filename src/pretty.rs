@@ -0,0 +1,84 @@
+//! Indented SVG output for debugging and reviewing golden files.
+
+/// Puts each element of a rendered badge on its own line, indented two spaces per level.
+///
+/// Elements holding only text, such as `<text>` and `<title>`, stay on one line so their
+/// content is not changed.
+pub(crate) fn indent_svg(svg: &str) -> String {
+    let tokens = tokenize(svg);
+    let mut out = String::with_capacity(svg.len() + svg.len() / 4);
+    let mut depth = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let mut line = token.to_string();
+        let indent = if token.starts_with("</") {
+            depth = usize::saturating_sub(depth, 1);
+            depth
+        } else if !token.starts_with('<') || token.ends_with("/>") || token.starts_with("<!") {
+            depth
+        } else {
+            match (tokens.get(i + 1), tokens.get(i + 2)) {
+                // An element that is empty or holds only text stays on one line
+                (Some(close), _) if close.starts_with("</") => {
+                    line.push_str(close);
+                    i += 1;
+                }
+                (Some(text), Some(close)) if !text.starts_with('<') && close.starts_with("</") => {
+                    line.push_str(text);
+                    line.push_str(close);
+                    i += 2;
+                }
+                _ => depth += 1,
+            }
+            depth - usize::from(!line.contains("</"))
+        };
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.extend(std::iter::repeat_n("  ", indent));
+        out.push_str(&line);
+        i += 1;
+    }
+    out
+}
+
+/// Splits markup into tags and the text between them.
+fn tokenize(svg: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = svg;
+    while !rest.is_empty() {
+        let end = if rest.starts_with('<') {
+            rest.find('>').map_or(rest.len(), |end| end + 1)
+        } else {
+            rest.find('<').unwrap_or(rest.len())
+        };
+        tokens.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indent_svg() {
+        assert_eq!(
+            indent_svg(
+                r##"<svg width="9"><title>a: b</title><g fill="#fff"><rect/><text x="1">a</text><g></g></g></svg>"##
+            ),
+            concat!(
+                "<svg width=\"9\">\n",
+                "  <title>a: b</title>\n",
+                "  <g fill=\"#fff\">\n",
+                "    <rect/>\n",
+                "    <text x=\"1\">a</text>\n",
+                "    <g></g>\n",
+                "  </g>\n",
+                "</svg>",
+            )
+        );
+    }
+}
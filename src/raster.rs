@@ -0,0 +1,101 @@
+//! PNG output through resvg, for places that do not accept SVG (release assets, email).
+
+use crate::{BadgeError, BadgeParams, RenderContext, render_svg};
+use once_cell::sync::Lazy;
+use resvg::{tiny_skia, usvg};
+
+/// Parser options with the system fonts loaded once; badge text is set in Verdana and falls
+/// back through the template's font list (`DejaVu Sans`, then any sans-serif font).
+static OPTIONS: Lazy<usvg::Options<'static>> = Lazy::new(|| {
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    options
+});
+
+/// Render a badge to PNG.
+///
+/// The badge is laid out exactly as by [`render_badge_svg`](crate::render_badge_svg) and the
+/// resulting SVG is rasterized, so both outputs have the same geometry. Text is drawn with the
+/// fonts installed on the system.
+///
+/// # Arguments
+/// * `params` - Badge parameters (see [`BadgeParams`]).
+/// * `scale` - Pixels per SVG unit; `2.0` produces a badge twice the SVG's width and height.
+///
+/// # Returns
+/// PNG-encoded image bytes.
+///
+/// # Errors
+/// Returns [`BadgeError::Raster`] for a non-positive or non-finite `scale` or when the badge
+/// cannot be rasterized, and the template errors of
+/// [`try_render_badge_svg`](crate::try_render_badge_svg).
+///
+/// ## Example
+/// ```rust
+/// use shields::{BadgeParams, render_badge_png};
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// let png = render_badge_png(&params, 2.0).unwrap();
+/// assert!(png.starts_with(b"\x89PNG"));
+/// ```
+pub fn render_badge_png(params: &BadgeParams, scale: f32) -> Result<Vec<u8>, BadgeError> {
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err(BadgeError::Raster(format!("invalid scale {}", scale)));
+    }
+    let svg = render_svg(params, &RenderContext::default())?;
+    let tree =
+        usvg::Tree::from_str(&svg, &OPTIONS).map_err(|e| BadgeError::Raster(e.to_string()))?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| BadgeError::Raster(format!("scale {} is too large", scale)))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| BadgeError::Raster(format!("scale {} is too large", scale)))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|e| BadgeError::Raster(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the width and height from the PNG header.
+    fn png_size(png: &[u8]) -> (u32, u32) {
+        let be = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+        (be(16), be(20))
+    }
+
+    #[test]
+    fn test_render_badge_png() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let png = render_badge_png(&params, 1.0).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let (width, height) = png_size(&png);
+        assert_eq!(height, 20);
+        assert_eq!(
+            png_size(&render_badge_png(&params, 2.0).unwrap()),
+            (width * 2, 40)
+        );
+
+        for scale in [0.0, -1.0, f32::NAN] {
+            assert!(matches!(
+                render_badge_png(&params, scale),
+                Err(BadgeError::Raster(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! Reusable badge renderer with hot-reloadable configuration.
+//!
+//! [`Renderer`] applies a shared [`RendererConfig`] (default colors, a palette of extra color
+//! names, and font width table overrides) to every badge it renders. The configuration sits
+//! behind an [`ArcSwap`], so long-running services can call [`Renderer::reload_config`] to
+//! change themes while other threads keep rendering. Reloading never clears the crate's
+//! width and color caches. For batch generation on one thread, [`Renderer::batch`] returns a
+//! [`BatchRenderer`] that reuses its buffers from badge to badge.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "style-flat")] {
+//! use shields::BadgeParams;
+//! use shields::renderer::{Renderer, RendererConfig};
+//!
+//! let renderer = Renderer::new(RendererConfig::default().with_color("brand", "#0f62fe"));
+//! let params = BadgeParams {
+//!     label: Some("docs"),
+//!     message: Some("latest"),
+//!     message_color: Some("brand"),
+//!     ..Default::default()
+//! };
+//! assert!(renderer.render(&params).contains("#0f62fe"));
+//!
+//! renderer.reload_config(RendererConfig::default().with_color("brand", "#da1e28"));
+//! assert!(renderer.render(&params).contains("#da1e28"));
+//! # }
+//! ```
+
+use crate::measurer::CharWidthMeasurer;
+use crate::{
+    BadgeError, BadgeParams, Font, RenderContext, RenderLimits, Scratch, get_text_width,
+    render_badge_svg_into, render_badge_svg_with, try_render_badge_svg_with,
+};
+use arc_swap::ArcSwap;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Settings applied by a [`Renderer`] to every badge.
+#[derive(Clone, Default)]
+pub struct RendererConfig {
+    /// Label color used when a badge doesn't set one.
+    pub label_color: Option<String>,
+    /// Message color used when a badge doesn't set one.
+    pub message_color: Option<String>,
+    /// Logo color used when a badge doesn't set one.
+    pub logo_color: Option<String>,
+    /// Extra color names (lowercase) mapped to any color the crate accepts.
+    pub palette: HashMap<String, String>,
+    /// Width tables replacing the embedded ones for specific fonts.
+    pub fonts: HashMap<Font, Arc<CharWidthMeasurer>>,
+}
+
+impl RendererConfig {
+    /// Adds a palette entry; `name` is matched case-insensitively.
+    pub fn with_color(mut self, name: &str, color: &str) -> Self {
+        self.palette
+            .insert(name.trim().to_ascii_lowercase(), color.to_string());
+        self
+    }
+
+    /// Replaces the width table used for `font`.
+    pub fn with_font(mut self, font: Font, measurer: CharWidthMeasurer) -> Self {
+        self.fonts.insert(font, Arc::new(measurer));
+        self
+    }
+
+    /// Applies the default colors and palette to `params`.
+    fn apply<'a>(&'a self, params: &BadgeParams<'a>) -> BadgeParams<'a> {
+        BadgeParams {
+            label_color: self.resolve(params.label_color, self.label_color.as_deref()),
+            message_color: self.resolve(params.message_color, self.message_color.as_deref()),
+            logo_color: self.resolve(params.logo_color, self.logo_color.as_deref()),
+            ..*params
+        }
+    }
+
+    /// Resolves a color through the palette, falling back to `default` when unset.
+    fn resolve<'a>(&'a self, color: Option<&'a str>, default: Option<&'a str>) -> Option<&'a str> {
+        let color = color.or(default)?;
+        let named = self.palette.get(&color.trim().to_ascii_lowercase());
+        Some(named.map_or(color, String::as_str))
+    }
+}
+
+/// Badge renderer sharing one [`RendererConfig`] that can be swapped at runtime.
+///
+/// `Renderer` is `Send + Sync`; wrap it in an `Arc` (or a `static`) and render from any thread.
+pub struct Renderer {
+    config: ArcSwap<RendererConfig>,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new(RendererConfig::default())
+    }
+}
+
+impl Renderer {
+    /// Creates a renderer with the given configuration.
+    pub fn new(config: RendererConfig) -> Self {
+        Renderer {
+            config: ArcSwap::from_pointee(config),
+        }
+    }
+
+    /// Returns a snapshot of the current configuration.
+    pub fn config(&self) -> Arc<RendererConfig> {
+        self.config.load_full()
+    }
+
+    /// Atomically replaces the configuration.
+    ///
+    /// Renders already in progress finish with the previous configuration; every render
+    /// started afterwards sees the new one.
+    pub fn reload_config(&self, config: RendererConfig) {
+        self.config.store(Arc::new(config));
+    }
+
+    /// Warms the width, color and logo caches for a known set of hot badges.
+    ///
+    /// Call this at startup with the badges a service expects to serve most, so the first
+    /// requests don't pay for loading width tables, normalizing colors or encoding logos.
+    /// Each cache is bounded, so warming far more distinct badges than it holds only keeps
+    /// the most recent ones.
+    ///
+    /// ## Example
+    /// ```rust
+    /// use shields::BadgeParams;
+    /// use shields::renderer::Renderer;
+    ///
+    /// let renderer = Renderer::default();
+    /// renderer.prewarm(&[BadgeParams {
+    ///     label: Some("build"),
+    ///     message: Some("passing"),
+    ///     logo: Some("rust"),
+    ///     ..Default::default()
+    /// }]);
+    /// ```
+    pub fn prewarm(&self, params: &[BadgeParams]) {
+        for font in Font::ALL {
+            get_text_width("", font);
+        }
+        for params in params {
+            self.render(params);
+        }
+    }
+
+    /// Renders a badge with the current configuration applied.
+    pub fn render(&self, params: &BadgeParams) -> String {
+        let config = self.config.load();
+        let params = config.apply(params);
+        let ctx = RenderContext {
+            fonts: Some(&config.fonts),
+            ..Default::default()
+        };
+        render_badge_svg_with(&params, &ctx)
+    }
+
+    /// Renders a badge with the current configuration applied, aborting when a
+    /// [`RenderLimits`] bound is exceeded.
+    ///
+    /// # Errors
+    /// Returns [`BadgeError::Limit`] naming the exceeded bound, or any other error reported by
+    /// [`try_render_badge_svg`](crate::try_render_badge_svg).
+    pub fn try_render(
+        &self,
+        params: &BadgeParams,
+        limits: &RenderLimits,
+    ) -> Result<String, BadgeError> {
+        let config = self.config.load();
+        let params = config.apply(params);
+        let ctx = RenderContext {
+            fonts: Some(&config.fonts),
+            ..Default::default()
+        };
+        try_render_badge_svg_with(&params, &ctx, limits)
+    }
+
+    /// Returns a [`BatchRenderer`] rendering with this renderer's configuration.
+    pub fn batch(&self) -> BatchRenderer<'_> {
+        BatchRenderer {
+            renderer: self,
+            scratch: Scratch::default(),
+            out: String::new(),
+        }
+    }
+}
+
+/// Renders many badges with one [`Renderer`], reusing the output, logo and accessible-text
+/// buffers between them.
+///
+/// After the first few badges, rendering allocates little beyond what the templates need,
+/// and a logo repeated from the previous badge skips the logo cache entirely. Keep one
+/// `BatchRenderer` per thread.
+///
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "style-flat")] {
+/// use shields::BadgeParams;
+/// use shields::renderer::Renderer;
+///
+/// let renderer = Renderer::default();
+/// let mut batch = renderer.batch();
+/// for crate_name in ["serde", "tokio", "rand"] {
+///     let svg = batch.render(&BadgeParams {
+///         label: Some(crate_name),
+///         message: Some("v1.0.0"),
+///         logo: Some("rust"),
+///         ..Default::default()
+///     });
+///     assert!(svg.starts_with("<svg"));
+/// }
+/// # }
+/// ```
+pub struct BatchRenderer<'r> {
+    renderer: &'r Renderer,
+    scratch: Scratch,
+    out: String,
+}
+
+impl BatchRenderer<'_> {
+    /// Renders a badge with the renderer's current configuration applied.
+    ///
+    /// The returned SVG borrows the output buffer and is overwritten by the next render.
+    pub fn render(&mut self, params: &BadgeParams) -> &str {
+        let config = self.renderer.config.load();
+        let params = config.apply(params);
+        let ctx = RenderContext {
+            fonts: Some(&config.fonts),
+            ..Default::default()
+        };
+        self.out.clear();
+        render_badge_svg_into(&params, &ctx, &mut self.scratch, &mut self.out);
+        &self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BadgeStyle, render_badge_svg};
+
+    fn params() -> BadgeParams<'static> {
+        BadgeParams {
+            style: BadgeStyle::Flat,
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_renderer_matches_free_function() {
+        assert_eq!(
+            Renderer::default().render(&params()),
+            render_badge_svg(&params())
+        );
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_defaults_and_palette() {
+        let config = RendererConfig {
+            label_color: Some("Brand".to_string()),
+            message_color: Some("green".to_string()),
+            ..Default::default()
+        }
+        .with_color("brand", "#0f62fe");
+        let svg = Renderer::new(config).render(&params());
+        assert!(svg.contains(r##"fill="#0f62fe""##));
+        assert!(svg.contains(r##"fill="#97ca00""##));
+    }
+
+    #[test]
+    fn test_prewarm_then_render_is_unchanged() {
+        let renderer = Renderer::default();
+        let hot = [
+            params(),
+            BadgeParams {
+                style: BadgeStyle::ForTheBadge,
+                logo: Some("rust"),
+                logo_color: Some("white"),
+                ..params()
+            },
+        ];
+        let cold: Vec<String> = hot.iter().map(render_badge_svg).collect();
+        renderer.prewarm(&hot);
+        let warm: Vec<String> = hot.iter().map(|p| renderer.render(p)).collect();
+        assert_eq!(cold, warm);
+    }
+
+    #[test]
+    fn test_batch_matches_render() {
+        let renderer = Renderer::new(RendererConfig::default().with_color("brand", "#0f62fe"));
+        let badges = [
+            params(),
+            BadgeParams {
+                logo: Some("rust"),
+                message_color: Some("brand"),
+                ..params()
+            },
+            BadgeParams {
+                logo: Some("rust"),
+                logo_color: Some("red"),
+                ..params()
+            },
+            BadgeParams {
+                logo: Some("rust,github"),
+                ..params()
+            },
+            BadgeParams {
+                style: BadgeStyle::Social,
+                logo: Some("rust"),
+                ..params()
+            },
+            params(),
+        ];
+        let mut batch = renderer.batch();
+        for badge in badges.iter().chain(&badges) {
+            assert_eq!(batch.render(badge), renderer.render(badge));
+        }
+    }
+
+    #[cfg(feature = "style-flat")]
+    #[test]
+    fn test_reload_swaps_fonts() {
+        let renderer = Renderer::default();
+        let before = renderer.render(&params());
+        let wide = CharWidthMeasurer::from_data(vec![(32, 126, 20.0)]);
+        renderer.reload_config(RendererConfig::default().with_font(Font::VerdanaNormal11, wide));
+        let after = renderer.render(&params());
+        assert_ne!(before, after);
+        // "build" is five 20px glyphs
+        assert!(after.contains(r#"textLength="1010""#), "{}", after);
+        renderer.reload_config(RendererConfig::default());
+        assert_eq!(renderer.render(&params()), before);
+    }
+}
@@ -0,0 +1,406 @@
+//! Self-hosted badge server compatible with shields.io static badge URLs.
+//!
+//! [`router`] serves `/badge/<label>-<message>-<color>` (and `/badge/<message>-<color>`),
+//! optionally with a `.svg` suffix, with the same escaping as shields.io: `--` is a dash, `__`
+//! an underscore and `_` a space. The query string accepts the same parameters as
+//! [`BadgeParamsOwned::from_query_str`], e.g. `?style=flat&logo=rust`. Badges are rendered with
+//! [`render_badge_svg`] and served as `image/svg+xml` with `Cache-Control` and `ETag` headers
+//! (see [`crate::http_cache`]); revalidating clients get `304 Not Modified`.
+//!
+//! [`dynamic_router`] adds `/badge/dynamic/<format>?url=<url>&query=<query>`, serving
+//! shields.io dynamic badges: the server fetches the `json`, `xml`, `yaml` or `toml` document
+//! at `url` and shows the values the `query` selects (see [`crate::dynamic`]). Documents are
+//! only fetched over HTTP(S) from the hosts it is given, redirects are only followed to those
+//! hosts and bodies larger than 1 MiB are rejected while they are read, so the route cannot be
+//! used to reach private services.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use shields::server;
+//!
+//! let app = server::router().merge(server::dynamic_router(&["raw.githubusercontent.com"]));
+//! // let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
+//! // axum::serve(listener, app).await?;
+//! # let _ = app;
+//! ```
+
+use crate::dynamic::{self, DynamicBadge, DynamicError, DynamicFormat};
+use crate::http_cache::{self, DEFAULT_MAX_AGE};
+use crate::{BadgeParamsOwned, render_badge_svg};
+use axum::Router;
+use axum::extract::{Path, Query, RawQuery, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::sync::Arc;
+
+/// Largest document fetched for a dynamic badge, in bytes.
+const MAX_DOCUMENT_BYTES: usize = 1 << 20;
+
+/// Builds a router serving static badges under `/badge/`.
+///
+/// Nest or merge it into an existing application to serve badges next to other routes.
+pub fn router() -> Router {
+    Router::new().route("/badge/{badge}", get(badge))
+}
+
+/// Most redirects followed while fetching a dynamic badge document.
+const MAX_REDIRECTS: usize = 10;
+
+/// Builds a router serving dynamic badges under `/badge/dynamic/`.
+///
+/// Only `http` and `https` URLs whose host is one of `allowed_hosts` (compared
+/// case-insensitively) are fetched; other URLs get an `invalid query parameter: url` badge.
+/// Redirects are followed only while they stay on those hosts, and documents over 1 MiB are
+/// rejected. Merge it with [`router`] to serve both kinds of badges.
+pub fn dynamic_router(allowed_hosts: &[&str]) -> Router {
+    Router::new()
+        .route("/badge/dynamic/{format}", get(dynamic_badge))
+        .with_state(Arc::new(DocumentSource::new(allowed_hosts)))
+}
+
+/// Fetches dynamic badge documents from an allowlist of hosts.
+struct DocumentSource {
+    client: reqwest::Client,
+    allowed_hosts: Arc<[String]>,
+}
+
+impl DocumentSource {
+    /// A source with a 10 second timeout whose client only follows redirects to
+    /// `allowed_hosts`.
+    fn new(allowed_hosts: &[&str]) -> Self {
+        let allowed_hosts: Arc<[String]> =
+            allowed_hosts.iter().map(|host| host.to_string()).collect();
+        let redirect_hosts = Arc::clone(&allowed_hosts);
+        let redirect = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() > MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if allows(&redirect_hosts, attempt.url()) {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        });
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .redirect(redirect)
+            .user_agent(crate::services::USER_AGENT)
+            .build()
+            .expect("HTTP client configuration is valid");
+        DocumentSource {
+            client,
+            allowed_hosts,
+        }
+    }
+
+    /// Fetches a document of at most [`MAX_DOCUMENT_BYTES`] from an allowed URL.
+    async fn fetch(&self, url: &str) -> Result<String, DynamicError> {
+        let url = reqwest::Url::parse(url).map_err(|_| DynamicError::MissingParameter("url"))?;
+        if !allows(&self.allowed_hosts, &url) {
+            return Err(DynamicError::MissingParameter("url"));
+        }
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|_| DynamicError::Inaccessible)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(DynamicError::NotFound);
+        }
+        // Redirects to other hosts are not followed and end up here too
+        if !response.status().is_success() {
+            return Err(DynamicError::Inaccessible);
+        }
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_DOCUMENT_BYTES as u64)
+        {
+            return Err(DynamicError::Inaccessible);
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|_| DynamicError::Inaccessible)?
+        {
+            if body.len() + chunk.len() > MAX_DOCUMENT_BYTES {
+                return Err(DynamicError::Inaccessible);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+/// Returns whether `url` is an `http` or `https` URL on one of `allowed_hosts`.
+fn allows(allowed_hosts: &[String], url: &reqwest::Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+        && url.host_str().is_some_and(|host| {
+            allowed_hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(host))
+        })
+}
+
+async fn badge(
+    Path(path): Path<String>,
+    Query(query): Query<Vec<(String, String)>>,
+    headers: HeaderMap,
+) -> Response {
+    match badge_params(&path, query) {
+        Some(params) => badge_response(&params, if_none_match(&headers)),
+        None => (
+            StatusCode::BAD_REQUEST,
+            "expected /badge/<label>-<message>-<color> or /badge/<message>-<color>",
+        )
+            .into_response(),
+    }
+}
+
+async fn dynamic_badge(
+    State(source): State<Arc<DocumentSource>>,
+    Path(format): Path<String>,
+    RawQuery(query): RawQuery,
+    headers: HeaderMap,
+) -> Response {
+    let Ok(format) = format.parse() else {
+        return (StatusCode::NOT_FOUND, "unknown dynamic badge format").into_response();
+    };
+    let query = query.unwrap_or_default();
+    let params = match dynamic_params(&source, format, &query).await {
+        Ok(params) => params,
+        Err(error) => dynamic::error_badge(&query, &error),
+    };
+    badge_response(&params, if_none_match(&headers))
+}
+
+/// Fetches the document of a dynamic badge and builds the badge from it.
+async fn dynamic_params(
+    source: &DocumentSource,
+    format: DynamicFormat,
+    query: &str,
+) -> Result<BadgeParamsOwned, DynamicError> {
+    let badge = DynamicBadge::from_query_str(query)?;
+    let document = source.fetch(&badge.url).await?;
+    badge.render(format, &document)
+}
+
+/// The `If-None-Match` request header, if present and readable.
+fn if_none_match(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Renders the badge, or answers `304 Not Modified` when `if_none_match` lists its `ETag`.
+fn badge_response(params: &BadgeParamsOwned, if_none_match: Option<&str>) -> Response {
+    let params = params.as_params();
+    let etag = http_cache::etag_for(&params);
+    let headers = [
+        (
+            header::CACHE_CONTROL,
+            http_cache::cache_control(DEFAULT_MAX_AGE),
+        ),
+        (header::ETAG, etag.clone()),
+    ];
+    if http_cache::is_modified(if_none_match, &etag) {
+        (
+            headers,
+            [(header::CONTENT_TYPE, "image/svg+xml;charset=utf-8")],
+            render_badge_svg(&params),
+        )
+            .into_response()
+    } else {
+        (StatusCode::NOT_MODIFIED, headers).into_response()
+    }
+}
+
+/// Builds badge parameters from a `/badge/` path segment and its query pairs.
+fn badge_params(path: &str, query: Vec<(String, String)>) -> Option<BadgeParamsOwned> {
+    let mut params = BadgeParamsOwned::from_badge_path(path)?;
+    params.apply_query(query);
+    Some(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BadgeStyle;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn query(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_badge_query() {
+        let params = badge_params(
+            "build-passing-green",
+            query(&[
+                ("style", "for-the-badge"),
+                ("labelColor", "black"),
+                ("color", "red"),
+                ("logo", "rust"),
+                ("link", "https://a.example"),
+                ("link", "https://b.example"),
+                ("unknown", "ignored"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(params.style, BadgeStyle::ForTheBadge);
+        assert_eq!(params.label_color.as_deref(), Some("black"));
+        assert_eq!(params.message_color.as_deref(), Some("red"));
+        assert_eq!(params.logo.as_deref(), Some("rust"));
+        assert_eq!(params.link.as_deref(), Some("https://a.example"));
+        assert_eq!(params.extra_link.as_deref(), Some("https://b.example"));
+
+        let params = badge_params("a-b-c", query(&[("style", "bogus")])).unwrap();
+        assert_eq!(params.style, BadgeStyle::Flat);
+        assert!(badge_params("nodash", Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_badge_response() {
+        let params = badge_params("build-passing-green", Vec::new()).unwrap();
+        let response = badge_response(&params, None);
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers[header::CONTENT_TYPE], "image/svg+xml;charset=utf-8");
+        assert_eq!(headers[header::CACHE_CONTROL], "max-age=300, s-maxage=300");
+        let etag = headers[header::ETAG].to_str().unwrap().to_string();
+
+        let response = badge_response(&params, Some(&etag));
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+
+        let failing = badge_params("build-failing-red", Vec::new()).unwrap();
+        assert_eq!(
+            badge_response(&failing, Some(&etag)).status(),
+            StatusCode::OK
+        );
+    }
+
+    /// Serves one canned HTTP response per connection on a local port, returning its URL.
+    fn serve(status: &'static str, body: &'static str) -> String {
+        serve_raw(format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        ))
+    }
+
+    /// Serves the raw HTTP `response` on every connection to a local port, returning its URL.
+    fn serve_raw(response: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/package.json", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        url
+    }
+
+    /// Serves a `302 Found` pointing at `location` on a local port, returning its URL.
+    fn redirect(location: &str) -> String {
+        serve_raw(format!(
+            "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            location
+        ))
+    }
+
+    #[test]
+    fn test_dynamic_badge() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let source = DocumentSource::new(&["127.0.0.1"]);
+        let fetch = |format, query: &str| runtime.block_on(dynamic_params(&source, format, query));
+        let url = serve("200 OK", r#"{"name": "app", "version": "1.2.0"}"#);
+        let query = format!("url={}&query=$.version&label=version&prefix=v", url);
+        let params = fetch(DynamicFormat::Json, &query).unwrap();
+        assert_eq!(params.label.as_deref(), Some("version"));
+        assert_eq!(params.message.as_deref(), Some("v1.2.0"));
+
+        let query = format!("url={}&query=$.missing", url);
+        let result = fetch(DynamicFormat::Json, &query);
+        assert_eq!(result, Err(DynamicError::NoResult));
+
+        let url = serve("404 Not Found", "{}");
+        let query = format!("url={}&query=$.version", url);
+        let result = fetch(DynamicFormat::Json, &query);
+        assert_eq!(result, Err(DynamicError::NotFound));
+
+        let result = fetch(DynamicFormat::Json, "url=http://127.0.0.1:1&query=$.x");
+        assert_eq!(result, Err(DynamicError::Inaccessible));
+
+        let url = serve("200 OK", "[package]\nname = \"app\"\nversion = \"0.4.2\"\n");
+        let query = format!("url={}&query=package.version&label=crate", url);
+        let params = fetch(DynamicFormat::Toml, &query).unwrap();
+        assert_eq!(params.message.as_deref(), Some("0.4.2"));
+
+        // Only HTTP(S) URLs on the allowed hosts are fetched
+        for url in [
+            "http://localhost:1/",
+            "http://10.0.0.1/",
+            "file:///etc/passwd",
+            "ftp://127.0.0.1/",
+            "not a url",
+        ] {
+            let query = format!("url={}&query=$.x", url);
+            let result = fetch(DynamicFormat::Json, &query);
+            assert_eq!(
+                result,
+                Err(DynamicError::MissingParameter("url")),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_dynamic_badge_redirects_and_size() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let source = DocumentSource::new(&["127.0.0.1"]);
+        let fetch = |url: &str| {
+            let query = format!("url={}&query=$.version", url);
+            runtime.block_on(dynamic_params(&source, DynamicFormat::Json, &query))
+        };
+        let document = serve("200 OK", r#"{"version": "1.2.0"}"#);
+        let params = fetch(&redirect(&document)).unwrap();
+        assert_eq!(params.message.as_deref(), Some("1.2.0"));
+
+        // `localhost` reaches the same server but is not allowed, so the redirect is not
+        // followed
+        let private = document.replace("127.0.0.1", "localhost");
+        assert_eq!(fetch(&redirect(&private)), Err(DynamicError::Inaccessible));
+
+        // Oversized documents are rejected by their length and while streaming
+        let padding = " ".repeat(MAX_DOCUMENT_BYTES);
+        let sized = serve_raw(format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{{}}{}",
+            padding.len() + 2,
+            padding
+        ));
+        assert_eq!(fetch(&sized), Err(DynamicError::Inaccessible));
+        let streamed = serve_raw(format!(
+            "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n{{}}{}",
+            padding
+        ));
+        assert_eq!(fetch(&streamed), Err(DynamicError::Inaccessible));
+    }
+}
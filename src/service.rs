@@ -0,0 +1,153 @@
+//! Badge rendering as a [`tower`](https://docs.rs/tower) service.
+//!
+//! [`BadgeService`] implements `tower::Service<BadgeRequest>`, so rate limiting, timeouts,
+//! caching and other tower middleware can be layered around badge rendering in any
+//! tower-based stack (axum, hyper, tonic-web gateways). Each request is rendered by a shared
+//! [`Renderer`] and checked against the service's [`RenderLimits`]; invalid parameters are
+//! reported as [`BadgeError`]s rather than rendered with fallbacks.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use shields::service::{BadgeRequest, BadgeService};
+//! use tower_service::Service;
+//!
+//! let mut service = BadgeService::new();
+//! let request = BadgeRequest::from_badge_url("build-passing-brightgreen.svg", "style=flat-square")
+//!     .unwrap();
+//! let svg = service.call(request).into_inner().unwrap();
+//! assert!(svg.contains("passing"));
+//! ```
+
+use crate::renderer::Renderer;
+use crate::{BadgeError, BadgeParamsOwned, RenderLimits};
+use std::future::{Ready, ready};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+/// A badge to render.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BadgeRequest {
+    /// Parameters of the badge.
+    pub params: BadgeParamsOwned,
+}
+
+impl BadgeRequest {
+    /// Builds a request from a shields.io static badge path segment and query string, e.g.
+    /// `build-passing-brightgreen` and `style=flat&logo=rust` (see
+    /// [`BadgeParamsOwned::from_badge_path`] and [`BadgeParamsOwned::from_query_str`]).
+    ///
+    /// # Returns
+    /// The request, or `None` if the path does not have two or three parts.
+    pub fn from_badge_url(path: &str, query: &str) -> Option<Self> {
+        let mut params = BadgeParamsOwned::from_badge_path(path)?;
+        let query = query.strip_prefix('?').unwrap_or(query);
+        params.apply_query(form_urlencoded::parse(query.as_bytes()));
+        Some(Self { params })
+    }
+}
+
+impl From<BadgeParamsOwned> for BadgeRequest {
+    fn from(params: BadgeParamsOwned) -> Self {
+        Self { params }
+    }
+}
+
+/// Renders [`BadgeRequest`]s into SVG strings.
+///
+/// Cloning is cheap: clones share the same [`Renderer`], so a configuration reloaded through
+/// it applies to every clone.
+#[derive(Clone, Default)]
+pub struct BadgeService {
+    renderer: Arc<Renderer>,
+    limits: RenderLimits,
+}
+
+impl BadgeService {
+    /// Creates a service rendering with the default configuration and no limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a service rendering with a shared [`Renderer`].
+    pub fn with_renderer(renderer: Arc<Renderer>) -> Self {
+        Self {
+            renderer,
+            limits: RenderLimits::default(),
+        }
+    }
+
+    /// Sets the bounds checked on every render.
+    pub fn limits(mut self, limits: RenderLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl Service<BadgeRequest> for BadgeService {
+    type Response = String;
+    type Error = BadgeError;
+    type Future = Ready<Result<String, BadgeError>>;
+
+    /// Always ready: rendering needs no connection or other resource.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), BadgeError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: BadgeRequest) -> Self::Future {
+        ready(
+            self.renderer
+                .try_render(&request.params.as_params(), &self.limits),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::RendererConfig;
+    use crate::{BadgeStyle, LimitKind, render_badge_svg};
+
+    #[test]
+    fn test_badge_service() {
+        let request =
+            BadgeRequest::from_badge_url("build-passing-brightgreen.svg", "?style=flat-square")
+                .unwrap();
+        assert_eq!(request.params.style, BadgeStyle::FlatSquare);
+        assert_eq!(request.params.label.as_deref(), Some("build"));
+        assert!(BadgeRequest::from_badge_url("a-b-c-d", "").is_none());
+
+        let mut service = BadgeService::new();
+        let svg = service.call(request.clone()).into_inner().unwrap();
+        assert_eq!(svg, render_badge_svg(&request.params.as_params()));
+
+        let mut service = BadgeService::with_renderer(Arc::new(Renderer::new(
+            RendererConfig::default().with_color("brand", "#0f62fe"),
+        )));
+        let request = BadgeRequest::from_badge_url("docs-latest-brand", "").unwrap();
+        assert!(
+            service
+                .call(request)
+                .into_inner()
+                .unwrap()
+                .contains("#0f62fe")
+        );
+
+        let request = BadgeRequest::from_badge_url("docs-latest-greenish", "").unwrap();
+        assert!(matches!(
+            service.call(request).into_inner(),
+            Err(BadgeError::InvalidColor { .. })
+        ));
+
+        let mut service = BadgeService::new().limits(RenderLimits {
+            max_chars: Some(8),
+            ..Default::default()
+        });
+        let request = BadgeRequest::from_badge_url("coverage-93%-green", "").unwrap();
+        assert!(matches!(
+            service.call(request).into_inner(),
+            Err(BadgeError::Limit(LimitKind::Chars { max: 8, .. }))
+        ));
+    }
+}
@@ -0,0 +1,296 @@
+//! Badges for third-party services, filled in from their public APIs.
+//!
+//! Each submodule queries one service and returns [`BadgeParamsOwned`] carrying the same
+//! label, message and color shields.io would show, ready to render or serve. Requests go
+//! through a [`DataFetcher`], such as [`ReqwestFetcher`] with the `services-reqwest` feature.
+//! Failures are reported as [`ServiceError`]s, which [`ServiceError::badge`] turns into the
+//! error badge shields.io would serve instead.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "services-reqwest")] {
+//! use shields::render_badge_svg;
+//! use shields::services::{ReqwestFetcher, crates_io};
+//!
+//! # async fn crates_badge() {
+//! let fetcher = ReqwestFetcher::default();
+//! let params = match crates_io::version(&fetcher, "serde").await {
+//!     Ok(params) => params,
+//!     Err(error) => error.badge("crates.io"),
+//! };
+//! let svg = render_badge_svg(&params.as_params());
+//! # let _ = svg;
+//! # }
+//! # }
+//! ```
+
+pub mod cache;
+pub mod crates_io;
+pub mod github;
+
+use crate::BadgeParamsOwned;
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+
+/// Error returned when a service cannot provide a badge's data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceError {
+    /// The service has no such package, repository or other subject.
+    NotFound,
+    /// The service could not be reached or answered with an error.
+    Inaccessible,
+    /// The service answered with data this crate does not understand.
+    InvalidResponse,
+}
+
+impl ServiceError {
+    /// Color of the error badge: `red` for [`NotFound`](Self::NotFound), `lightgrey`
+    /// otherwise, as on shields.io.
+    pub fn color(&self) -> &'static str {
+        match self {
+            ServiceError::NotFound => "red",
+            ServiceError::Inaccessible | ServiceError::InvalidResponse => "lightgrey",
+        }
+    }
+
+    /// Parameters of the badge shown in place of the service's data, e.g.
+    /// `crates.io | not found`.
+    pub fn badge(&self, label: &str) -> BadgeParamsOwned {
+        BadgeParamsOwned {
+            label: Some(label.to_string()),
+            message: Some(self.to_string()),
+            message_color: Some(self.color().to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ServiceError::NotFound => "not found",
+            ServiceError::Inaccessible => "inaccessible",
+            ServiceError::InvalidResponse => "invalid",
+        })
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+/// A response body fetched by a [`DataFetcher`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FetchedData {
+    /// The response body.
+    pub body: Vec<u8>,
+    /// The `Content-Type` response header, if any.
+    pub content_type: Option<String>,
+}
+
+/// Fetches service data over HTTP for the service modules.
+///
+/// Implement it to send requests through your own HTTP client, add authentication or
+/// logging, or answer from canned data in tests. With the `services-reqwest` feature,
+/// [`ReqwestFetcher`] provides an implementation on top of reqwest.
+///
+/// ## Example
+///
+/// ```rust
+/// use shields::services::{DataFetcher, FetchedData, ServiceError, crates_io};
+///
+/// struct Offline;
+///
+/// impl DataFetcher for Offline {
+///     async fn fetch(&self, _url: &str, _headers: &[(&str, &str)]) -> Result<FetchedData, ServiceError> {
+///         Ok(FetchedData {
+///             body: br#"{"crate": {"max_stable_version": "1.0.219"}}"#.to_vec(),
+///             content_type: Some("application/json".to_string()),
+///         })
+///     }
+/// }
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let params = runtime.block_on(crates_io::version(&Offline, "serde"));
+/// assert_eq!(params.unwrap().message.as_deref(), Some("v1.0.219"));
+/// ```
+pub trait DataFetcher: Send + Sync {
+    /// Sends a GET request for `url` with extra request `headers`.
+    ///
+    /// # Returns
+    /// The response body, [`ServiceError::NotFound`] for a `404 Not Found` response, or
+    /// [`ServiceError::Inaccessible`] when the request fails or gets any other unsuccessful
+    /// status.
+    fn fetch(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> impl Future<Output = Result<FetchedData, ServiceError>> + Send;
+}
+
+impl<F: DataFetcher + ?Sized> DataFetcher for &F {
+    fn fetch(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> impl Future<Output = Result<FetchedData, ServiceError>> + Send {
+        (**self).fetch(url, headers)
+    }
+}
+
+impl<F: DataFetcher + ?Sized> DataFetcher for Arc<F> {
+    fn fetch(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> impl Future<Output = Result<FetchedData, ServiceError>> + Send {
+        (**self).fetch(url, headers)
+    }
+}
+
+/// `User-Agent` of the HTTP clients this crate builds.
+#[cfg(feature = "services-reqwest")]
+pub(crate) const USER_AGENT: &str = concat!(
+    "shields.rs/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/Jannchie/shields.rs)"
+);
+
+/// [`DataFetcher`] sending requests with a [reqwest](https://docs.rs/reqwest) client, which
+/// must run inside a tokio runtime.
+#[cfg(feature = "services-reqwest")]
+#[derive(Debug, Clone)]
+pub struct ReqwestFetcher {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "services-reqwest")]
+impl ReqwestFetcher {
+    /// Sends requests with `client`. Some services, like crates.io, reject requests without a
+    /// user agent, so configure one.
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "services-reqwest")]
+impl Default for ReqwestFetcher {
+    /// A client with a 10 second timeout and a `shields.rs` user agent.
+    fn default() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("HTTP client configuration is valid");
+        Self { client }
+    }
+}
+
+#[cfg(feature = "services-reqwest")]
+impl DataFetcher for ReqwestFetcher {
+    async fn fetch(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<FetchedData, ServiceError> {
+        let mut request = self.client.get(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|_| ServiceError::Inaccessible)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(ServiceError::NotFound);
+        }
+        if !response.status().is_success() {
+            return Err(ServiceError::Inaccessible);
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .bytes()
+            .await
+            .map_err(|_| ServiceError::Inaccessible)?;
+        Ok(FetchedData {
+            body: body.to_vec(),
+            content_type,
+        })
+    }
+}
+
+/// Fetches and parses a JSON API response, sending extra request `headers`.
+async fn fetch_json(
+    fetcher: &impl DataFetcher,
+    url: &str,
+    headers: &[(&str, &str)],
+) -> Result<Value, ServiceError> {
+    let data = fetcher.fetch(url, headers).await?;
+    serde_json::from_slice(&data.body).map_err(|_| ServiceError::InvalidResponse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Answers requests from canned bodies by URL and records the requests.
+    #[derive(Default)]
+    pub(super) struct StubFetcher {
+        bodies: HashMap<String, String>,
+        pub(super) requests: Mutex<Vec<(String, Vec<String>)>>,
+    }
+
+    impl StubFetcher {
+        pub(super) fn with(mut self, url: &str, body: &str) -> Self {
+            self.bodies.insert(url.to_string(), body.to_string());
+            self
+        }
+    }
+
+    impl DataFetcher for StubFetcher {
+        async fn fetch(
+            &self,
+            url: &str,
+            headers: &[(&str, &str)],
+        ) -> Result<FetchedData, ServiceError> {
+            let headers = headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect();
+            self.requests
+                .lock()
+                .unwrap()
+                .push((url.to_string(), headers));
+            let body = self.bodies.get(url).ok_or(ServiceError::NotFound)?;
+            Ok(FetchedData {
+                body: body.clone().into_bytes(),
+                content_type: Some("application/json".to_string()),
+            })
+        }
+    }
+
+    /// Runs a service helper to completion.
+    pub(super) fn block_on<T>(future: impl Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_error_badge() {
+        let params = ServiceError::NotFound.badge("crates.io");
+        assert_eq!(params.label.as_deref(), Some("crates.io"));
+        assert_eq!(params.message.as_deref(), Some("not found"));
+        assert_eq!(params.message_color.as_deref(), Some("red"));
+        let params = ServiceError::Inaccessible.badge("downloads");
+        assert_eq!(params.message.as_deref(), Some("inaccessible"));
+        assert_eq!(params.message_color.as_deref(), Some("lightgrey"));
+    }
+}
@@ -0,0 +1,302 @@
+//! In-memory caching of service responses, so badge servers do not hammer upstream APIs.
+//!
+//! [`ServiceCache`] wraps a [`DataFetcher`] and is one itself, so it can be passed to any
+//! service helper. Responses are fresh for a time-to-live that defaults to what shields.io
+//! uses for the badge category (see [`default_ttl`]), then served stale for a while longer
+//! while they are revalidated.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use shields::services::cache::ServiceCache;
+//! use shields::services::{DataFetcher, FetchedData, ServiceError, crates_io};
+//! # struct Offline;
+//! # impl DataFetcher for Offline {
+//! #     async fn fetch(&self, _url: &str, _headers: &[(&str, &str)]) -> Result<FetchedData, ServiceError> {
+//! #         Ok(FetchedData {
+//! #             body: br#"{"crate": {"max_stable_version": "1.0.219", "downloads": 42}}"#.to_vec(),
+//! #             content_type: None,
+//! #         })
+//! #     }
+//! # }
+//! # let fetcher = Offline;
+//!
+//! let cache = ServiceCache::new(fetcher);
+//! let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+//! // Both badges read the same crates.io response, which is fetched once
+//! let version = runtime.block_on(crates_io::version(&cache, "serde")).unwrap();
+//! let downloads = runtime.block_on(crates_io::downloads(&cache, "serde")).unwrap();
+//! assert_eq!(version.message.as_deref(), Some("v1.0.219"));
+//! assert_eq!(downloads.message.as_deref(), Some("42"));
+//! ```
+
+use super::{DataFetcher, FetchedData, ServiceError};
+use crate::cache::ShardedLru;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Number of responses a [`ServiceCache`] keeps unless told otherwise.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+// shields.io's cache lengths by badge category, in seconds.
+const BUILD_SECONDS: u64 = 30;
+const DEFAULT_SECONDS: u64 = 120;
+const VERSION_SECONDS: u64 = 300;
+const SOCIAL_SECONDS: u64 = 900;
+
+/// How long a response from `url` stays fresh by default, mirroring shields.io's cache
+/// lengths for the badges built from it: 30 seconds for build status (GitHub Actions runs),
+/// 5 minutes for versions (crates.io crates, GitHub releases), 15 minutes for social counts
+/// (GitHub repositories, for stars) and 2 minutes for anything else, such as issue searches.
+///
+/// crates.io crate responses also feed download badges, which shields.io keeps for 15
+/// minutes; the shorter version length is used so version badges stay as current.
+pub fn default_ttl(url: &str) -> Duration {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let path = path.split('?').next().unwrap_or_default();
+    let seconds = match host {
+        "crates.io" => VERSION_SECONDS,
+        "api.github.com" if path.contains("/actions/") => BUILD_SECONDS,
+        "api.github.com" if path.ends_with("/releases/latest") => VERSION_SECONDS,
+        "api.github.com" if path.starts_with("repos/") => SOCIAL_SECONDS,
+        _ => DEFAULT_SECONDS,
+    };
+    Duration::from_secs(seconds)
+}
+
+/// A cached response.
+struct Entry {
+    result: Result<FetchedData, ServiceError>,
+    fetched_at: Instant,
+    /// Set while a caller refetches the stale response.
+    revalidating: AtomicBool,
+}
+
+/// A [`DataFetcher`] caching the responses of another in memory.
+///
+/// Successful responses and [`ServiceError::NotFound`] are cached; other errors are not, so
+/// the next request retries. A response older than its time-to-live is stale: for a further
+/// stale-while-revalidate period the first request for it refetches it while concurrent
+/// requests are answered with the stale copy, which also stands in if the refetch fails.
+/// Revalidation runs in the requesting task rather than in the background, so the cache
+/// works with any async runtime.
+///
+/// Requests are keyed by URL and headers, so responses fetched with different tokens are
+/// cached separately.
+pub struct ServiceCache<F> {
+    fetcher: F,
+    entries: ShardedLru<String, Arc<Entry>>,
+    ttl: Option<Duration>,
+    stale_while_revalidate: Option<Duration>,
+}
+
+impl<F: DataFetcher> ServiceCache<F> {
+    /// Wraps `fetcher` with a cache of [`DEFAULT_CAPACITY`] responses, fresh for their
+    /// [`default_ttl`] and then served stale for as long again while revalidated.
+    pub fn new(fetcher: F) -> Self {
+        Self {
+            fetcher,
+            entries: ShardedLru::new(DEFAULT_CAPACITY),
+            ttl: None,
+            stale_while_revalidate: None,
+        }
+    }
+
+    /// Sets how many responses are kept, evicting the least recently used beyond it.
+    pub fn capacity(self, capacity: usize) -> Self {
+        self.entries.set_capacity(capacity);
+        self
+    }
+
+    /// Keeps every response fresh for `ttl` instead of its [`default_ttl`].
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets how long a response is served stale after its time-to-live, while it is
+    /// revalidated. Zero refetches expired responses before answering.
+    pub fn stale_while_revalidate(mut self, duration: Duration) -> Self {
+        self.stale_while_revalidate = Some(duration);
+        self
+    }
+
+    /// Removes every cached response.
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    /// The wrapped fetcher.
+    pub fn fetcher(&self) -> &F {
+        &self.fetcher
+    }
+
+    /// Fetches `url` and caches the result if it is worth keeping.
+    async fn refresh(
+        &self,
+        key: String,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<FetchedData, ServiceError> {
+        let result = self.fetcher.fetch(url, headers).await;
+        if matches!(result, Ok(_) | Err(ServiceError::NotFound)) {
+            let entry = Entry {
+                result: result.clone(),
+                fetched_at: Instant::now(),
+                revalidating: AtomicBool::new(false),
+            };
+            self.entries.put(key, Arc::new(entry));
+        }
+        result
+    }
+}
+
+impl<F: DataFetcher> DataFetcher for ServiceCache<F> {
+    async fn fetch(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<FetchedData, ServiceError> {
+        let mut key = url.to_string();
+        for (name, value) in headers {
+            key.push_str(&format!("\n{}: {}", name, value));
+        }
+        let Some(entry) = self.entries.get(key.as_str()) else {
+            return self.refresh(key, url, headers).await;
+        };
+        let ttl = self.ttl.unwrap_or_else(|| default_ttl(url));
+        let age = entry.fetched_at.elapsed();
+        if age < ttl {
+            return entry.result.clone();
+        }
+        if age >= ttl + self.stale_while_revalidate.unwrap_or(ttl) {
+            return self.refresh(key, url, headers).await;
+        }
+        if entry.revalidating.swap(true, Ordering::AcqRel) {
+            return entry.result.clone();
+        }
+        match self.refresh(key, url, headers).await {
+            Err(ServiceError::Inaccessible | ServiceError::InvalidResponse) => {
+                entry.revalidating.store(false, Ordering::Release);
+                entry.result.clone()
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::tests::block_on;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Answers each request with the next scripted result.
+    struct Scripted(Mutex<VecDeque<Result<FetchedData, ServiceError>>>);
+
+    impl Scripted {
+        fn new(results: impl IntoIterator<Item = Result<&'static str, ServiceError>>) -> Self {
+            let results = results.into_iter().map(|result| {
+                result.map(|body| FetchedData {
+                    body: body.as_bytes().to_vec(),
+                    content_type: None,
+                })
+            });
+            Self(Mutex::new(results.collect()))
+        }
+
+        fn remaining(&self) -> usize {
+            self.0.lock().unwrap().len()
+        }
+    }
+
+    impl DataFetcher for Scripted {
+        async fn fetch(
+            &self,
+            _url: &str,
+            _headers: &[(&str, &str)],
+        ) -> Result<FetchedData, ServiceError> {
+            self.0
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("unexpected request")
+        }
+    }
+
+    fn body(result: Result<FetchedData, ServiceError>) -> Result<String, ServiceError> {
+        result.map(|data| String::from_utf8(data.body).unwrap())
+    }
+
+    const URL: &str = "https://crates.io/api/v1/crates/shields";
+
+    #[test]
+    fn test_service_cache_ttl() {
+        let cache = ServiceCache::new(Scripted::new([
+            Err(ServiceError::Inaccessible),
+            Ok("a"),
+            Ok("b"),
+        ]));
+        let fetch = |headers: &[(&str, &str)]| body(block_on(cache.fetch(URL, headers)));
+        assert_eq!(fetch(&[]), Err(ServiceError::Inaccessible));
+        assert_eq!(fetch(&[]), Ok("a".to_string()));
+        assert_eq!(fetch(&[]), Ok("a".to_string()));
+        assert_eq!(fetch(&[("Authorization", "Bearer x")]), Ok("b".to_string()));
+        assert_eq!(cache.fetcher().remaining(), 0);
+
+        let cache = ServiceCache::new(Scripted::new([Ok("a"), Err(ServiceError::NotFound)]))
+            .ttl(Duration::ZERO)
+            .stale_while_revalidate(Duration::ZERO);
+        assert_eq!(body(block_on(cache.fetch(URL, &[]))), Ok("a".to_string()));
+        assert_eq!(
+            body(block_on(cache.fetch(URL, &[]))),
+            Err(ServiceError::NotFound)
+        );
+        assert_eq!(cache.fetcher().remaining(), 0);
+    }
+
+    #[test]
+    fn test_service_cache_stale_while_revalidate() {
+        let cache = ServiceCache::new(Scripted::new([
+            Ok("a"),
+            Err(ServiceError::Inaccessible),
+            Ok("b"),
+        ]))
+        .ttl(Duration::ZERO)
+        .stale_while_revalidate(Duration::from_secs(60));
+        let fetch = || body(block_on(cache.fetch(URL, &[])));
+        assert_eq!(fetch(), Ok("a".to_string()));
+        // The failed revalidation falls back to the stale response
+        assert_eq!(fetch(), Ok("a".to_string()));
+        assert_eq!(fetch(), Ok("b".to_string()));
+        assert_eq!(cache.fetcher().remaining(), 0);
+
+        // While another request revalidates, the stale response is served without a request
+        let entry = cache.entries.get(URL).unwrap();
+        entry.revalidating.store(true, Ordering::Release);
+        assert_eq!(fetch(), Ok("b".to_string()));
+
+        cache.clear();
+        assert!(cache.entries.get(URL).is_none());
+    }
+
+    #[test]
+    fn test_default_ttl() {
+        let ttl = |url| default_ttl(url).as_secs();
+        assert_eq!(ttl(URL), 300);
+        assert_eq!(ttl("https://api.github.com/repos/rust-lang/rust"), 900);
+        assert_eq!(
+            ttl("https://api.github.com/repos/rust-lang/rust/releases/latest"),
+            300
+        );
+        assert_eq!(
+            ttl("https://api.github.com/repos/a/b/actions/workflows/ci.yml/runs?per_page=1"),
+            30
+        );
+        assert_eq!(ttl("https://api.github.com/search/issues?q=repo:a/b"), 120);
+        assert_eq!(ttl("https://example.com/data.json"), 120);
+    }
+}
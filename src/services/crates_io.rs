@@ -0,0 +1,167 @@
+//! [crates.io](https://crates.io) badges: latest version, total downloads and minimum
+//! supported Rust version, as on shields.io's `/crates/v`, `/crates/d` and `/crates/msrv`.
+
+use super::{DataFetcher, ServiceError, fetch_json};
+use crate::format::metric;
+use crate::{BadgeParamsOwned, color_scale, presets};
+use serde_json::Value;
+
+const API_URL: &str = "https://crates.io/api/v1/crates";
+
+/// Latest version of crate `name`, labeled `crates.io` and colored like
+/// [`presets::version`]. Pre-releases are only shown when the crate has no stable release.
+pub async fn version(
+    fetcher: &impl DataFetcher,
+    name: &str,
+) -> Result<BadgeParamsOwned, ServiceError> {
+    version_params(&fetch_crate(fetcher, name).await?)
+}
+
+/// All-time downloads of crate `name`, e.g. `downloads | 1.2M`.
+pub async fn downloads(
+    fetcher: &impl DataFetcher,
+    name: &str,
+) -> Result<BadgeParamsOwned, ServiceError> {
+    downloads_params(&fetch_crate(fetcher, name).await?)
+}
+
+/// Minimum supported Rust version of crate `name`'s latest version, from its `rust-version`
+/// manifest field, e.g. `msrv | 1.70`.
+///
+/// # Returns
+/// [`ServiceError::NotFound`] if the latest version does not declare a `rust-version`.
+pub async fn msrv(
+    fetcher: &impl DataFetcher,
+    name: &str,
+) -> Result<BadgeParamsOwned, ServiceError> {
+    msrv_params(&fetch_crate(fetcher, name).await?)
+}
+
+/// Fetches the crate's metadata, rejecting names crates.io cannot have without a request.
+async fn fetch_crate(fetcher: &impl DataFetcher, name: &str) -> Result<Value, ServiceError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(ServiceError::NotFound);
+    }
+    fetch_json(fetcher, &format!("{}/{}", API_URL, name), &[]).await
+}
+
+/// The latest stable version, or the latest version if there is no stable one.
+fn latest_version(response: &Value) -> Result<&str, ServiceError> {
+    let krate = &response["crate"];
+    krate["max_stable_version"]
+        .as_str()
+        .or_else(|| krate["max_version"].as_str())
+        .ok_or(ServiceError::InvalidResponse)
+}
+
+fn version_params(response: &Value) -> Result<BadgeParamsOwned, ServiceError> {
+    Ok(presets::version("crates.io", latest_version(response)?))
+}
+
+fn downloads_params(response: &Value) -> Result<BadgeParamsOwned, ServiceError> {
+    let downloads = response["crate"]["downloads"]
+        .as_u64()
+        .ok_or(ServiceError::InvalidResponse)?;
+    Ok(BadgeParamsOwned {
+        label: Some("downloads".to_string()),
+        message: Some(metric(downloads as f64)),
+        message_color: Some(color_scale::downloads(downloads).to_string()),
+        ..Default::default()
+    })
+}
+
+fn msrv_params(response: &Value) -> Result<BadgeParamsOwned, ServiceError> {
+    let latest = latest_version(response)?;
+    let versions = response["versions"]
+        .as_array()
+        .ok_or(ServiceError::InvalidResponse)?;
+    let rust_version = versions
+        .iter()
+        .find(|version| version["num"].as_str() == Some(latest))
+        .and_then(|version| version["rust_version"].as_str())
+        .ok_or(ServiceError::NotFound)?;
+    Ok(BadgeParamsOwned {
+        label: Some("msrv".to_string()),
+        message: Some(rust_version.to_string()),
+        message_color: Some("blue".to_string()),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::tests::{StubFetcher, block_on};
+    use serde_json::json;
+
+    fn response() -> Value {
+        json!({
+            "crate": {
+                "name": "shields",
+                "downloads": 12_345,
+                "max_version": "1.1.0-beta.1",
+                "max_stable_version": "1.0.2"
+            },
+            "versions": [
+                { "num": "1.1.0-beta.1", "rust_version": "1.85" },
+                { "num": "1.0.2", "rust_version": "1.80" },
+                { "num": "0.9.0", "rust_version": null }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_crates_io_params() {
+        let params = version_params(&response()).unwrap();
+        assert_eq!(params.label.as_deref(), Some("crates.io"));
+        assert_eq!(params.message.as_deref(), Some("v1.0.2"));
+        assert_eq!(params.message_color.as_deref(), Some("blue"));
+
+        let params = downloads_params(&response()).unwrap();
+        assert_eq!(params.label.as_deref(), Some("downloads"));
+        assert_eq!(params.message.as_deref(), Some("12k"));
+        assert_eq!(params.message_color.as_deref(), Some("brightgreen"));
+
+        let params = msrv_params(&response()).unwrap();
+        assert_eq!(params.label.as_deref(), Some("msrv"));
+        assert_eq!(params.message.as_deref(), Some("1.80"));
+
+        let mut prerelease = response();
+        prerelease["crate"]["max_stable_version"] = Value::Null;
+        let params = version_params(&prerelease).unwrap();
+        assert_eq!(params.message.as_deref(), Some("v1.1.0-beta.1"));
+        assert_eq!(params.message_color.as_deref(), Some("yellow"));
+
+        let mut unspecified = response();
+        unspecified["crate"]["max_stable_version"] = json!("0.9.0");
+        assert_eq!(msrv_params(&unspecified), Err(ServiceError::NotFound));
+        assert_eq!(
+            downloads_params(&json!({ "errors": [] })),
+            Err(ServiceError::InvalidResponse)
+        );
+    }
+
+    #[test]
+    fn test_crates_io_requests() {
+        let body = response().to_string();
+        let fetcher = StubFetcher::default().with("https://crates.io/api/v1/crates/shields", &body);
+        let params = block_on(downloads(&fetcher, "shields")).unwrap();
+        assert_eq!(params.message.as_deref(), Some("12k"));
+        assert_eq!(
+            block_on(version(&fetcher, "serde")),
+            Err(ServiceError::NotFound)
+        );
+        for name in ["", "../users", "serde?x=1"] {
+            assert_eq!(
+                block_on(version(&fetcher, name)),
+                Err(ServiceError::NotFound)
+            );
+        }
+        assert_eq!(fetcher.requests.lock().unwrap().len(), 2);
+    }
+}
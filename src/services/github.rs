@@ -0,0 +1,250 @@
+//! [GitHub](https://github.com) badges: stars, open issues, latest release and Actions
+//! workflow status, as on shields.io's `/github/stars`, `/github/issues`,
+//! `/github/v/release` and `/github/actions/workflow/status`.
+//!
+//! Repositories are given as `owner/repo`. Every helper takes an optional personal access
+//! token: anonymous requests are limited to 60 an hour (10 a minute for issue search), which
+//! a busy badge server exhausts quickly.
+
+use super::{DataFetcher, ServiceError, fetch_json};
+use crate::format::metric;
+use crate::{BadgeParamsOwned, Status, presets};
+use serde_json::Value;
+
+const API_URL: &str = "https://api.github.com";
+
+/// Stargazers of `repo`, e.g. `stars | 1.2k`.
+pub async fn stars(
+    fetcher: &impl DataFetcher,
+    repo: &str,
+    token: Option<&str>,
+) -> Result<BadgeParamsOwned, ServiceError> {
+    let response = fetch(fetcher, &format!("repos/{}", repo_path(repo)?), token).await?;
+    stars_params(&response)
+}
+
+/// Open issues of `repo`, pull requests excluded, e.g. `issues | 12 open`.
+pub async fn open_issues(
+    fetcher: &impl DataFetcher,
+    repo: &str,
+    token: Option<&str>,
+) -> Result<BadgeParamsOwned, ServiceError> {
+    let query = format!(
+        "search/issues?q=repo:{}+is:issue+is:open&per_page=1",
+        repo_path(repo)?
+    );
+    open_issues_params(&fetch(fetcher, &query, token).await?)
+}
+
+/// Tag of the latest release of `repo`, e.g. `release | v1.4.2`, colored like
+/// [`presets::version`]. Drafts and pre-releases are skipped.
+///
+/// # Returns
+/// [`ServiceError::NotFound`] if the repository has no release.
+pub async fn release(
+    fetcher: &impl DataFetcher,
+    repo: &str,
+    token: Option<&str>,
+) -> Result<BadgeParamsOwned, ServiceError> {
+    let response = fetch(
+        fetcher,
+        &format!("repos/{}/releases/latest", repo_path(repo)?),
+        token,
+    )
+    .await?;
+    release_params(&response)
+}
+
+/// Result of the latest completed run of Actions workflow `workflow` (a file name such as
+/// `ci.yml`, or a workflow id) in `repo`, optionally on `branch`: `build | passing` or
+/// `build | failing`, or `build | unknown` when there is no conclusive run.
+pub async fn workflow_status(
+    fetcher: &impl DataFetcher,
+    repo: &str,
+    workflow: &str,
+    branch: Option<&str>,
+    token: Option<&str>,
+) -> Result<BadgeParamsOwned, ServiceError> {
+    if !is_path_segment(workflow) {
+        return Err(ServiceError::NotFound);
+    }
+    let mut query = format!(
+        "repos/{}/actions/workflows/{}/runs?status=completed&per_page=1",
+        repo_path(repo)?,
+        workflow
+    );
+    if let Some(branch) = branch {
+        query.push_str("&branch=");
+        query.extend(form_urlencoded::byte_serialize(branch.as_bytes()));
+    }
+    workflow_status_params(&fetch(fetcher, &query, token).await?)
+}
+
+/// Requests `path` from the REST API, authenticated with `token` if given.
+async fn fetch(
+    fetcher: &impl DataFetcher,
+    path: &str,
+    token: Option<&str>,
+) -> Result<Value, ServiceError> {
+    let authorization = token.map(|token| format!("Bearer {}", token));
+    let mut headers = vec![("Accept", "application/vnd.github+json")];
+    if let Some(authorization) = &authorization {
+        headers.push(("Authorization", authorization));
+    }
+    fetch_json(fetcher, &format!("{}/{}", API_URL, path), &headers).await
+}
+
+/// Validates an `owner/repo` name, so it can be placed in a URL path as is.
+fn repo_path(repo: &str) -> Result<&str, ServiceError> {
+    match repo.split_once('/') {
+        Some((owner, name)) if is_path_segment(owner) && is_path_segment(name) => Ok(repo),
+        _ => Err(ServiceError::NotFound),
+    }
+}
+
+fn is_path_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment != "."
+        && segment != ".."
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+fn stars_params(response: &Value) -> Result<BadgeParamsOwned, ServiceError> {
+    let stars = response["stargazers_count"]
+        .as_u64()
+        .ok_or(ServiceError::InvalidResponse)?;
+    Ok(BadgeParamsOwned {
+        label: Some("stars".to_string()),
+        message: Some(metric(stars as f64)),
+        message_color: Some("blue".to_string()),
+        ..Default::default()
+    })
+}
+
+fn open_issues_params(response: &Value) -> Result<BadgeParamsOwned, ServiceError> {
+    let issues = response["total_count"]
+        .as_u64()
+        .ok_or(ServiceError::InvalidResponse)?;
+    Ok(BadgeParamsOwned {
+        label: Some("issues".to_string()),
+        message: Some(format!("{} open", metric(issues as f64))),
+        message_color: Some(if issues > 0 { "yellow" } else { "brightgreen" }.to_string()),
+        ..Default::default()
+    })
+}
+
+fn release_params(response: &Value) -> Result<BadgeParamsOwned, ServiceError> {
+    let tag = response["tag_name"]
+        .as_str()
+        .ok_or(ServiceError::InvalidResponse)?;
+    Ok(presets::version("release", tag))
+}
+
+fn workflow_status_params(response: &Value) -> Result<BadgeParamsOwned, ServiceError> {
+    let runs = response["workflow_runs"]
+        .as_array()
+        .ok_or(ServiceError::InvalidResponse)?;
+    let conclusion = runs.first().and_then(|run| run["conclusion"].as_str());
+    let status = match conclusion {
+        Some("success") => Status::Passing,
+        Some("failure" | "timed_out" | "startup_failure") => Status::Failing,
+        _ => Status::Unknown,
+    };
+    let (message, color): (&str, &str) = status.into();
+    Ok(BadgeParamsOwned {
+        label: Some("build".to_string()),
+        message: Some(message.to_string()),
+        message_color: Some(color.to_string()),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::tests::{StubFetcher, block_on};
+    use serde_json::json;
+
+    #[test]
+    fn test_github_params() {
+        let params = stars_params(&json!({ "stargazers_count": 1234 })).unwrap();
+        assert_eq!(params.label.as_deref(), Some("stars"));
+        assert_eq!(params.message.as_deref(), Some("1.2k"));
+
+        let params = open_issues_params(&json!({ "total_count": 12 })).unwrap();
+        assert_eq!(params.message.as_deref(), Some("12 open"));
+        assert_eq!(params.message_color.as_deref(), Some("yellow"));
+        let params = open_issues_params(&json!({ "total_count": 0 })).unwrap();
+        assert_eq!(params.message_color.as_deref(), Some("brightgreen"));
+
+        let params = release_params(&json!({ "tag_name": "v2.0.0-rc.1" })).unwrap();
+        assert_eq!(params.label.as_deref(), Some("release"));
+        assert_eq!(params.message.as_deref(), Some("v2.0.0-rc.1"));
+        assert_eq!(params.message_color.as_deref(), Some("yellow"));
+
+        let runs = |conclusion: &str| json!({ "workflow_runs": [{ "conclusion": conclusion }] });
+        let params = workflow_status_params(&runs("success")).unwrap();
+        assert_eq!(params.label.as_deref(), Some("build"));
+        assert_eq!(params.message.as_deref(), Some("passing"));
+        let params = workflow_status_params(&runs("timed_out")).unwrap();
+        assert_eq!(params.message.as_deref(), Some("failing"));
+        assert_eq!(params.message_color.as_deref(), Some("red"));
+        let params = workflow_status_params(&json!({ "workflow_runs": [] })).unwrap();
+        assert_eq!(params.message.as_deref(), Some("unknown"));
+        assert_eq!(params.message_color.as_deref(), Some("lightgrey"));
+        assert_eq!(
+            stars_params(&json!({ "message": "Not Found" })),
+            Err(ServiceError::InvalidResponse)
+        );
+    }
+
+    #[test]
+    fn test_github_requests() {
+        let fetcher = StubFetcher::default().with(
+            "https://api.github.com/repos/rust-lang/rust/actions/workflows/ci.yml/runs\
+             ?status=completed&per_page=1&branch=release%2F1.0",
+            r#"{"workflow_runs": [{"conclusion": "failure"}]}"#,
+        );
+        let params = block_on(workflow_status(
+            &fetcher,
+            "rust-lang/rust",
+            "ci.yml",
+            Some("release/1.0"),
+            Some("secret"),
+        ))
+        .unwrap();
+        assert_eq!(params.message.as_deref(), Some("failing"));
+        let requests = fetcher.requests.lock().unwrap();
+        assert!(
+            requests[0]
+                .1
+                .contains(&"Authorization: Bearer secret".to_string())
+        );
+        drop(requests);
+        assert_eq!(
+            block_on(release(&fetcher, "rust-lang/rust", None)),
+            Err(ServiceError::NotFound)
+        );
+        assert_eq!(
+            block_on(stars(&fetcher, "rust-lang", None)),
+            Err(ServiceError::NotFound)
+        );
+        assert_eq!(fetcher.requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_repo_path() {
+        assert_eq!(repo_path("rust-lang/rust.vim"), Ok("rust-lang/rust.vim"));
+        for repo in [
+            "rust-lang",
+            "a/b/c",
+            "../rust",
+            "owner/",
+            "a/b?per_page=100",
+        ] {
+            assert_eq!(repo_path(repo), Err(ServiceError::NotFound));
+        }
+    }
+}
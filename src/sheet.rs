@@ -0,0 +1,237 @@
+//! Badge collections rendered once as an SVG sprite sheet.
+//!
+//! A page showing dozens of badges repeats the same gradients, fonts and markup in every
+//! image. [`BadgeSheet`] instead renders each badge into a `<symbol>` of a single hidden SVG,
+//! with gradients shared by several badges moved to one `<defs>` element, and every badge on
+//! the page becomes a small `<svg><use href="#id"/></svg>` reference to its symbol. Both the
+//! sheet and the references must be inlined in the same HTML document.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "style-flat")] {
+//! use shields::BadgeParams;
+//! use shields::sheet::BadgeSheet;
+//!
+//! let mut sheet = BadgeSheet::new();
+//! sheet.add(
+//!     "build",
+//!     &BadgeParams {
+//!         label: Some("build"),
+//!         message: Some("passing"),
+//!         ..Default::default()
+//!     },
+//! )?;
+//! let sprite = sheet.render();
+//! assert!(sprite.contains(r#"<symbol id="build""#));
+//! let badge = sheet.use_svg("build").unwrap();
+//! assert!(badge.contains(r##"<use href="#build"/>"##));
+//! # }
+//! # Ok::<(), shields::BadgeError>(())
+//! ```
+
+use crate::{
+    BadgeError, BadgeParams, IdSuffix, RenderContext, RenderOptions, render_svg, svg_size,
+};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// A rendered badge of a [`BadgeSheet`].
+#[derive(Debug, Clone, PartialEq)]
+struct Symbol {
+    id: String,
+    title: String,
+    width: f64,
+    height: f64,
+    /// Content of the badge's root element, without its title.
+    body: String,
+}
+
+/// Collects badges and renders them as `<symbol>`s of one SVG.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BadgeSheet {
+    symbols: Vec<Symbol>,
+}
+
+impl BadgeSheet {
+    /// Creates an empty sheet.
+    pub fn new() -> Self {
+        BadgeSheet::default()
+    }
+
+    /// Renders a badge into the sheet under `id`, replacing any badge with the same id.
+    ///
+    /// Characters other than ASCII letters, digits, `-` and `_` are dropped from `id`, as
+    /// for [`IdSuffix::Fixed`]; the cleaned id is the one to pass to [`Self::use_svg`].
+    ///
+    /// # Errors
+    /// Returns the errors of [`try_render_badge_svg`](crate::try_render_badge_svg).
+    pub fn add(&mut self, id: &str, params: &BadgeParams) -> Result<&mut Self, BadgeError> {
+        let id: String = id
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            .collect();
+        let ctx = RenderContext {
+            options: Cow::Owned(RenderOptions::new().id_suffix(IdSuffix::Fixed(id.clone()))),
+            ..Default::default()
+        };
+        let svg = render_svg(params, &ctx)?;
+        let (width, height) = svg_size(&svg).unwrap_or_default();
+        let content = svg
+            .find('>')
+            .and_then(|start| Some(&svg[start + 1..svg.rfind("</svg>")?]))
+            .unwrap_or_default();
+        // The title may carry an `id` when the badge has a tooltip
+        let title_start = content
+            .find("<title")
+            .and_then(|start| Some((start, start + content[start..].find('>')? + 1)));
+        let (title, body) = match (title_start, content.find("</title>")) {
+            (Some((start, text_start)), Some(end)) => (
+                content[text_start..end].to_string(),
+                format!(
+                    "{}{}",
+                    &content[..start],
+                    &content[end + "</title>".len()..]
+                ),
+            ),
+            _ => (String::new(), content.to_string()),
+        };
+        let symbol = Symbol {
+            id,
+            title,
+            width,
+            height,
+            body,
+        };
+        match self.symbols.iter_mut().find(|s| s.id == symbol.id) {
+            Some(existing) => *existing = symbol,
+            None => self.symbols.push(symbol),
+        }
+        Ok(self)
+    }
+
+    /// Renders the sheet: a zero-sized SVG holding the shared gradients and one `<symbol>`
+    /// per badge.
+    ///
+    /// The sheet is kept out of layout with a zero size rather than `display:none`, which
+    /// would stop browsers from painting the gradients it defines.
+    pub fn render(&self) -> String {
+        let mut shared: HashMap<String, String> = HashMap::new();
+        let mut defs = String::new();
+        let mut symbols = String::new();
+        for symbol in &self.symbols {
+            let body = hoist_gradients(&symbol.body, &mut shared, &mut defs);
+            symbols.push_str(&format!(
+                r#"<symbol id="{}" viewBox="0 0 {} {}">{}</symbol>"#,
+                symbol.id, symbol.width, symbol.height, body
+            ));
+        }
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="0" style="position:absolute" aria-hidden="true"><defs>{}</defs>{}</svg>"#,
+            defs, symbols
+        )
+    }
+
+    /// Returns the SVG showing the badge added under `id`, referencing its symbol in the
+    /// sheet, or `None` if there is no such badge.
+    pub fn use_svg(&self, id: &str) -> Option<String> {
+        let symbol = self.symbols.iter().find(|s| s.id == id)?;
+        Some(format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" role="img" aria-label="{2}"><title>{2}</title><use href="#{3}"/></svg>"##,
+            symbol.width, symbol.height, symbol.title, symbol.id
+        ))
+    }
+}
+
+/// Moves the `<linearGradient>`s of a symbol body to `defs`, sharing identical gradients
+/// between symbols, and points the body's references at the shared ids.
+fn hoist_gradients(body: &str, shared: &mut HashMap<String, String>, defs: &mut String) -> String {
+    const OPEN: &str = "<linearGradient id=\"";
+    const CLOSE: &str = "</linearGradient>";
+    let mut body = body.to_string();
+    // Each pass removes the gradient it finds, so searching from the start finds the next
+    while let Some(start) = body.find(OPEN) {
+        let Some(end) = body[start..].find(CLOSE).map(|i| start + i + CLOSE.len()) else {
+            break;
+        };
+        let id_start = start + OPEN.len();
+        let Some(id_end) = body[id_start..end].find('"').map(|i| id_start + i) else {
+            break;
+        };
+        let id = body[id_start..id_end].to_string();
+        let element = &body[start..end];
+        let key = element.replacen(&format!(" id=\"{}\"", id), "", 1);
+        let next_id = format!("g{}", shared.len());
+        let shared_id = shared.entry(key.clone()).or_insert_with(|| {
+            defs.push_str(&key.replacen(
+                "<linearGradient",
+                &format!("<linearGradient id=\"{}\"", next_id),
+                1,
+            ));
+            next_id
+        });
+        body = format!("{}{}", &body[..start], &body[end..])
+            .replace(&format!("url(#{})", id), &format!("url(#{})", shared_id));
+    }
+    body
+}
+
+#[cfg(all(test, feature = "style-flat", feature = "style-social"))]
+mod tests {
+    use super::*;
+    use crate::BadgeStyle;
+
+    #[test]
+    fn test_badge_sheet() {
+        let mut sheet = BadgeSheet::new();
+        for (id, label) in [("build", "build"), ("docs", "docs"), ("tests", "tests")] {
+            sheet
+                .add(
+                    id,
+                    &BadgeParams {
+                        label: Some(label),
+                        message: Some("passing"),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        }
+        sheet
+            .add(
+                "social!",
+                &BadgeParams {
+                    style: BadgeStyle::Social,
+                    label: Some("stars"),
+                    message: Some("42"),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let sprite = sheet.render();
+        // The three flat badges share one gradient; social adds its two
+        assert_eq!(sprite.matches("<linearGradient").count(), 3, "{}", sprite);
+        assert_eq!(sprite.matches("url(#g0)").count(), 3);
+        assert!(sprite.contains("url(#g2)"));
+        assert_eq!(sprite.matches("<symbol").count(), 4);
+        assert!(!sprite.contains("<title>"));
+        assert!(sprite.contains(r#"<symbol id="social" viewBox="0 0 "#));
+
+        let badge = sheet.use_svg("docs").unwrap();
+        assert!(badge.contains(r#"aria-label="docs: passing""#));
+        assert!(badge.ends_with(r##"<use href="#docs"/></svg>"##));
+        assert!(sheet.use_svg("missing").is_none());
+
+        // Re-adding an id replaces its badge
+        sheet
+            .add(
+                "docs",
+                &BadgeParams {
+                    message: Some("latest"),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(sheet.render().matches("<symbol").count(), 4);
+        assert!(sheet.use_svg("docs").unwrap().contains("latest"));
+    }
+}
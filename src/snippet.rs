@@ -0,0 +1,192 @@
+//! Ready-to-paste embeds of a hosted badge for READMEs and documentation.
+//!
+//! Each function takes the badge's parameters and the URL it is served from. The alt text is
+//! the badge's accessible text (`label: message`), and the embed links to
+//! [`link`](BadgeParams::link) when the badge has one that the default [`LinkPolicy`] allows.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "unstable")] {
+//! use shields::BadgeParams;
+//! use shields::snippet;
+//!
+//! let params = BadgeParams {
+//!     label: Some("build"),
+//!     message: Some("passing"),
+//!     link: Some("https://ci.example.com"),
+//!     ..Default::default()
+//! };
+//! assert_eq!(
+//!     snippet::markdown(&params, "https://img.example.com/build.svg"),
+//!     "[![build: passing](https://img.example.com/build.svg)](https://ci.example.com)",
+//! );
+//! # }
+//! ```
+
+use crate::{BadgeParams, LinkPolicy, badge_dimensions, write_accessible_text};
+
+/// The badge's accessible text, used as alt text.
+fn alt_text(params: &BadgeParams) -> String {
+    let mut alt = String::new();
+    write_accessible_text(&mut alt, params.label, params.message.unwrap_or(""));
+    alt
+}
+
+/// The badge's link, if it has a non-empty one that the default [`LinkPolicy`] allows.
+fn link<'a>(params: &BadgeParams<'a>) -> Option<&'a str> {
+    let policy = LinkPolicy::default();
+    params
+        .link
+        .filter(|link| !link.is_empty() && policy.allows(link))
+}
+
+/// Markdown image, wrapped in a link when the badge has one.
+pub fn markdown(params: &BadgeParams, img_url: &str) -> String {
+    let alt = markdown_escape(&alt_text(params));
+    let image = format!("![{}]({})", alt, markdown_url(img_url));
+    match link(params) {
+        Some(link) => format!("[{}]({})", image, markdown_url(link)),
+        None => image,
+    }
+}
+
+/// HTML `<img>` tag with explicit `width` and `height` (see [`badge_dimensions`]), wrapped in
+/// an `<a>` when the badge has a link.
+pub fn html_img(params: &BadgeParams, img_url: &str) -> String {
+    let (width, height) = badge_dimensions(params);
+    let image = format!(
+        r#"<img src="{}" alt="{}" width="{}" height="{}">"#,
+        html_escape(img_url),
+        html_escape(&alt_text(params)),
+        width,
+        height
+    );
+    match link(params) {
+        Some(link) => format!(r#"<a href="{}">{}</a>"#, html_escape(link), image),
+        None => image,
+    }
+}
+
+/// reStructuredText `image` directive, with a `:target:` when the badge has a link.
+pub fn rst(params: &BadgeParams, img_url: &str) -> String {
+    let mut directive = format!(
+        ".. image:: {}\n   :alt: {}",
+        img_url,
+        alt_text(params).replace('\n', " ")
+    );
+    if let Some(link) = link(params) {
+        directive.push_str("\n   :target: ");
+        directive.push_str(link);
+    }
+    directive
+}
+
+/// AsciiDoc inline image macro, with a `link` attribute when the badge has a link.
+pub fn asciidoc(params: &BadgeParams, img_url: &str) -> String {
+    let quote = |value: &str| format!("\"{}\"", value.replace('"', "\\\""));
+    let mut attributes = quote(&alt_text(params));
+    if let Some(link) = link(params) {
+        attributes.push_str(",link=");
+        attributes.push_str(&quote(link));
+    }
+    format!("image:{}[{}]", img_url.replace(' ', "%20"), attributes)
+}
+
+fn markdown_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '*' | '_' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn markdown_url(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippets() {
+        let params = BadgeParams {
+            label: Some("docs [beta]"),
+            message: Some("a \"b\" & c"),
+            ..Default::default()
+        };
+        let url = "https://img.example.com/docs (beta).svg";
+        assert_eq!(
+            markdown(&params, url),
+            r#"![docs \[beta\]: a "b" & c](https://img.example.com/docs%20%28beta%29.svg)"#
+        );
+        let (width, height) = badge_dimensions(&params);
+        assert_eq!(
+            html_img(&params, url),
+            format!(
+                r#"<img src="https://img.example.com/docs (beta).svg" alt="docs [beta]: a &quot;b&quot; &amp; c" width="{}" height="{}">"#,
+                width, height
+            )
+        );
+        assert_eq!(
+            asciidoc(&params, url),
+            r#"image:https://img.example.com/docs%20(beta).svg["docs [beta]: a \"b\" & c"]"#
+        );
+
+        let params = BadgeParams {
+            message: Some("passing"),
+            link: Some("https://ci.example.com"),
+            ..Default::default()
+        };
+        let url = "https://img.example.com/ci.svg";
+        assert_eq!(
+            html_img(&params, url),
+            format!(
+                r#"<a href="https://ci.example.com">{}</a>"#,
+                html_img(
+                    &BadgeParams {
+                        link: None,
+                        ..params
+                    },
+                    url
+                )
+            )
+        );
+        assert_eq!(
+            rst(&params, url),
+            ".. image:: https://img.example.com/ci.svg\n   :alt: passing\n   :target: https://ci.example.com"
+        );
+        assert_eq!(
+            asciidoc(&params, url),
+            r#"image:https://img.example.com/ci.svg["passing",link="https://ci.example.com"]"#
+        );
+
+        // Links the default policy rejects are left out
+        let params = BadgeParams {
+            link: Some("javascript:alert(1)"),
+            ..params
+        };
+        for snippet in [
+            markdown(&params, url),
+            html_img(&params, url),
+            rst(&params, url),
+            asciidoc(&params, url),
+        ] {
+            assert!(!snippet.contains("javascript"), "{}", snippet);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Several badges stacked vertically in one SVG image.
+//!
+//! [`BadgeStack`] renders each badge as usual and places the results below each other, so a
+//! project header can show a column of badges as a single image instead of laying out
+//! separate ones. The stack is as wide as its widest badge; narrower badges are aligned
+//! according to [`StackAlign`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! # #[cfg(feature = "style-flat")] {
+//! use shields::BadgeParams;
+//! use shields::stack::BadgeStack;
+//!
+//! let badges = [
+//!     BadgeParams {
+//!         label: Some("build"),
+//!         message: Some("passing"),
+//!         ..Default::default()
+//!     },
+//!     BadgeParams {
+//!         label: Some("license"),
+//!         message: Some("MIT"),
+//!         ..Default::default()
+//!     },
+//! ];
+//! let svg = BadgeStack::new().gap(4).render(&badges).unwrap();
+//! assert!(svg.contains(r#"height="44""#));
+//! # }
+//! ```
+
+use crate::{
+    BadgeError, BadgeParams, IdSuffix, RenderContext, RenderOptions, render_svg, svg_size,
+};
+use std::borrow::Cow;
+
+/// Horizontal placement of badges narrower than the stack.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StackAlign {
+    /// Along the left edge.
+    #[default]
+    Left,
+    /// Centered.
+    Center,
+    /// Along the right edge.
+    Right,
+}
+
+/// Renders badges vertically into one SVG.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BadgeStack {
+    /// Vertical space between badges in pixels. Defaults to 2.
+    pub gap: u32,
+    /// Placement of badges narrower than the widest one.
+    pub align: StackAlign,
+}
+
+impl Default for BadgeStack {
+    fn default() -> Self {
+        BadgeStack::new()
+    }
+}
+
+impl BadgeStack {
+    /// Creates a left-aligned stack with a 2px gap.
+    pub fn new() -> Self {
+        BadgeStack {
+            gap: 2,
+            align: StackAlign::Left,
+        }
+    }
+
+    /// Sets the vertical space between badges in pixels.
+    pub fn gap(mut self, gap: u32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Sets the placement of badges narrower than the stack.
+    pub fn align(mut self, align: StackAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Renders `badges` top to bottom.
+    ///
+    /// Each badge is nested as its own `<svg>` element and keeps its title and links. Element
+    /// ids get a per-badge suffix (see [`IdSuffix::Auto`]), so gradients and clip paths of
+    /// different badges do not clash.
+    ///
+    /// # Errors
+    /// Returns the first error raised while rendering a badge, see
+    /// [`try_render_badge_svg`](crate::try_render_badge_svg).
+    pub fn render(&self, badges: &[BadgeParams]) -> Result<String, BadgeError> {
+        let ctx = RenderContext {
+            options: Cow::Owned(RenderOptions::new().id_suffix(IdSuffix::Auto)),
+            ..Default::default()
+        };
+        let mut rendered = Vec::with_capacity(badges.len());
+        for params in badges {
+            let svg = render_svg(params, &ctx)?;
+            let (width, height) = svg_size(&svg).unwrap_or_default();
+            rendered.push((svg, width, height));
+        }
+        let width = rendered.iter().map(|(_, w, _)| *w).fold(0.0, f64::max);
+        let mut body = String::new();
+        let mut y = 0.0;
+        for (i, (svg, badge_width, height)) in rendered.iter().enumerate() {
+            if i > 0 {
+                y += self.gap as f64;
+            }
+            let x = match self.align {
+                StackAlign::Left => 0.0,
+                StackAlign::Center => (width - badge_width) / 2.0,
+                StackAlign::Right => width - badge_width,
+            };
+            let inner = svg.strip_prefix("<svg").unwrap_or(svg);
+            body.push_str(&format!("<svg x=\"{}\" y=\"{}\"{}", x, y, inner));
+            y += height;
+        }
+        Ok(format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}">{}</svg>"#,
+            width, y, body
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "style-flat", feature = "style-for-the-badge"))]
+mod tests {
+    use super::*;
+    use crate::BadgeStyle;
+
+    #[test]
+    fn test_badge_stack() {
+        let build = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            ..Default::default()
+        };
+        let wide = BadgeParams {
+            style: BadgeStyle::ForTheBadge,
+            label: Some("documentation"),
+            message: Some("latest"),
+            ..Default::default()
+        };
+        let svg = BadgeStack::new()
+            .gap(3)
+            .align(StackAlign::Right)
+            .render(&[build.clone(), wide.clone()])
+            .unwrap();
+        let (width, height) = svg_size(&svg).unwrap();
+        let (build_width, _) = svg_size(&crate::render_badge_svg(&build)).unwrap();
+        let (wide_width, _) = svg_size(&crate::render_badge_svg(&wide)).unwrap();
+        assert_eq!(width, wide_width);
+        assert_eq!(height, 20.0 + 3.0 + 28.0);
+        assert!(svg.contains(&format!(r#"<svg x="{}" y="0""#, wide_width - build_width)));
+        assert!(svg.contains(r#"<svg x="0" y="23""#), "{}", svg);
+        // Gradient ids are made unique per badge
+        assert!(!svg.contains(r#"id="s""#));
+
+        assert_eq!(
+            BadgeStack::new().render(&[]).unwrap(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="0"></svg>"#
+        );
+    }
+}
@@ -0,0 +1,496 @@
+//! TrueType subsetting for fonts embedded into badges: only the glyphs a badge draws are
+//! kept, renumbered from zero, with the tables that index glyphs rebuilt to match.
+
+use std::collections::BTreeMap;
+
+/// Tables copied unchanged into a subset; every other table not rebuilt here is dropped.
+const COPIED_TABLES: [&[u8; 4]; 5] = [b"OS/2", b"cvt ", b"fpgm", b"prep", b"gasp"];
+
+/// `name` records kept in a subset: copyright, family and style names, version, PostScript
+/// name and license URL. The full license text is left out.
+const KEPT_NAMES: [u16; 8] = [0, 1, 2, 3, 4, 5, 6, 14];
+
+/// Composite glyph component flags.
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// Returns a TrueType font holding only the glyphs of `chars` (and `.notdef`), or `None` when
+/// `font` is not a TrueType font with `glyf` outlines.
+pub(crate) fn subset_ttf(font: &[u8], chars: impl IntoIterator<Item = char>) -> Option<Vec<u8>> {
+    let font = Font::parse(font)?;
+    let num_glyphs = usize::from(u16_at(font.table(b"maxp")?, 4)?);
+    let long_loca = i16_at(font.table(b"head")?, 50)? != 0;
+
+    // Map each character to its glyph, then pull in the parts of composite glyphs
+    let mut mapped = BTreeMap::new();
+    for c in chars {
+        if let Some(glyph) = font
+            .glyph_id(c)
+            .filter(|&glyph| usize::from(glyph) < num_glyphs)
+        {
+            mapped.insert(c, glyph);
+        }
+    }
+    let mut kept = vec![false; num_glyphs];
+    kept[0] = true;
+    let mut pending: Vec<u16> = mapped.values().copied().collect();
+    while let Some(glyph) = pending.pop() {
+        if std::mem::replace(&mut kept[usize::from(glyph)], true) && glyph != 0 {
+            continue;
+        }
+        let outline = font.outline(glyph, long_loca)?;
+        for (_, component) in components(outline)? {
+            if usize::from(component) < num_glyphs && !kept[usize::from(component)] {
+                pending.push(component);
+            }
+        }
+    }
+    let old_ids: Vec<u16> = (0..num_glyphs as u16)
+        .filter(|&glyph| kept[usize::from(glyph)])
+        .collect();
+    let mut new_ids = vec![0u16; num_glyphs];
+    for (new, &old) in old_ids.iter().enumerate() {
+        new_ids[usize::from(old)] = new as u16;
+    }
+
+    // Outlines, with composite components renumbered, and long offsets into them
+    let mut glyf = Vec::new();
+    let mut loca = Vec::with_capacity(4 * (old_ids.len() + 1));
+    for &old in &old_ids {
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+        let start = glyf.len();
+        glyf.extend_from_slice(font.outline(old, long_loca)?);
+        for (offset, component) in components(&glyf[start..])? {
+            let new = new_ids.get(usize::from(component)).copied().unwrap_or(0);
+            glyf[start + offset..start + offset + 2].copy_from_slice(&new.to_be_bytes());
+        }
+        glyf.resize(glyf.len().next_multiple_of(4), 0);
+    }
+    loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+
+    // One full metric per glyph
+    let hhea = font.table(b"hhea")?;
+    let hmtx = font.table(b"hmtx")?;
+    let metric_count = usize::from(u16_at(hhea, 34)?);
+    let mut new_hmtx = Vec::with_capacity(4 * old_ids.len());
+    for &old in &old_ids {
+        let old = usize::from(old);
+        let advance = u16_at(hmtx, 4 * old.min(metric_count.checked_sub(1)?))?;
+        let lsb = if old < metric_count {
+            u16_at(hmtx, 4 * old + 2)?
+        } else {
+            u16_at(hmtx, 4 * metric_count + 2 * (old - metric_count))?
+        };
+        new_hmtx.extend_from_slice(&advance.to_be_bytes());
+        new_hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+
+    let mut head = font.table(b"head")?.get(..54)?.to_vec();
+    head[8..12].fill(0); // checkSumAdjustment, left unset
+    head[50..52].copy_from_slice(&1u16.to_be_bytes());
+    let mut new_hhea = hhea.get(..36)?.to_vec();
+    new_hhea[34..36].copy_from_slice(&(old_ids.len() as u16).to_be_bytes());
+    let mut maxp = font.table(b"maxp")?.to_vec();
+    maxp[4..6].copy_from_slice(&(old_ids.len() as u16).to_be_bytes());
+    // Version 3 `post` tables carry no glyph names
+    let mut post = font.table(b"post")?.get(..32)?.to_vec();
+    post[..4].copy_from_slice(&0x0003_0000u32.to_be_bytes());
+    let cmap = build_cmap(
+        mapped
+            .iter()
+            .map(|(&c, &glyph)| (c, new_ids[usize::from(glyph)])),
+    );
+
+    let mut tables: Vec<([u8; 4], Vec<u8>)> = vec![
+        (*b"cmap", cmap),
+        (*b"glyf", glyf),
+        (*b"head", head),
+        (*b"hhea", new_hhea),
+        (*b"hmtx", new_hmtx),
+        (*b"loca", loca),
+        (*b"maxp", maxp),
+        (*b"post", post),
+    ];
+    if let Some(name) = font.table(b"name").and_then(build_name) {
+        tables.push((*b"name", name));
+    }
+    if let Some(kern) = font
+        .table(b"kern")
+        .and_then(|kern| build_kern(kern, &kept, &new_ids))
+    {
+        tables.push((*b"kern", kern));
+    }
+    for tag in COPIED_TABLES {
+        if let Some(table) = font.table(tag) {
+            tables.push((*tag, table.to_vec()));
+        }
+    }
+    tables.sort_by_key(|(tag, _)| *tag);
+    Some(write_font(&tables))
+}
+
+/// A parsed TrueType table directory.
+struct Font<'a> {
+    tables: Vec<([u8; 4], &'a [u8])>,
+}
+
+impl<'a> Font<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if !matches!(data.get(..4)?, [0, 1, 0, 0] | b"true") {
+            return None;
+        }
+        let count = usize::from(u16_at(data, 4)?);
+        let mut tables = Vec::with_capacity(count);
+        for i in 0..count {
+            let record = data.get(12 + 16 * i..28 + 16 * i)?;
+            let offset = u32_at(record, 8)? as usize;
+            let length = u32_at(record, 12)? as usize;
+            let tag = record[..4].try_into().ok()?;
+            tables.push((tag, data.get(offset..offset.checked_add(length)?)?));
+        }
+        Some(Font { tables })
+    }
+
+    fn table(&self, tag: &[u8; 4]) -> Option<&'a [u8]> {
+        self.tables
+            .iter()
+            .find(|(name, _)| name == tag)
+            .map(|(_, table)| *table)
+    }
+
+    /// Outline data of `glyph`, empty for glyphs without contours such as the space.
+    fn outline(&self, glyph: u16, long_loca: bool) -> Option<&'a [u8]> {
+        let (loca, glyf) = (self.table(b"loca")?, self.table(b"glyf")?);
+        let glyph = usize::from(glyph);
+        let (start, end) = if long_loca {
+            (u32_at(loca, 4 * glyph)?, u32_at(loca, 4 * glyph + 4)?)
+        } else {
+            (
+                2 * u32::from(u16_at(loca, 2 * glyph)?),
+                2 * u32::from(u16_at(loca, 2 * glyph + 2)?),
+            )
+        };
+        glyf.get(start as usize..end as usize)
+    }
+
+    /// Glyph of `c` in the font's Unicode `cmap`, preferring a full-range format 12 subtable.
+    fn glyph_id(&self, c: char) -> Option<u16> {
+        let cmap = self.table(b"cmap")?;
+        let mut subtables = Vec::new();
+        for i in 0..usize::from(u16_at(cmap, 2)?) {
+            let platform = u16_at(cmap, 4 + 8 * i)?;
+            let encoding = u16_at(cmap, 6 + 8 * i)?;
+            let subtable = cmap.get(u32_at(cmap, 8 + 8 * i)? as usize..)?;
+            if platform == 0 || (platform == 3 && matches!(encoding, 1 | 10)) {
+                subtables.push((u16_at(subtable, 0)?, subtable));
+            }
+        }
+        subtables.sort_by_key(|(format, _)| *format != 12);
+        let (format, subtable) = subtables.into_iter().find(|(f, _)| matches!(f, 4 | 12))?;
+        let c = c as u32;
+        if format == 12 {
+            for group in 0..u32_at(subtable, 12)? as usize {
+                let start = u32_at(subtable, 16 + 12 * group)?;
+                let end = u32_at(subtable, 20 + 12 * group)?;
+                if (start..=end).contains(&c) {
+                    return u16::try_from(u32_at(subtable, 24 + 12 * group)? + c - start).ok();
+                }
+            }
+            return None;
+        }
+        let c = u16::try_from(c).ok()?;
+        let segments = usize::from(u16_at(subtable, 6)? / 2);
+        let (ends, starts) = (14, 16 + 2 * segments);
+        let (deltas, range_offsets) = (starts + 2 * segments, starts + 4 * segments);
+        for segment in 0..segments {
+            if c > u16_at(subtable, ends + 2 * segment)? {
+                continue;
+            }
+            let start = u16_at(subtable, starts + 2 * segment)?;
+            if c < start {
+                return None;
+            }
+            let delta = u16_at(subtable, deltas + 2 * segment)?;
+            let range_offset = usize::from(u16_at(subtable, range_offsets + 2 * segment)?);
+            if range_offset == 0 {
+                return Some(c.wrapping_add(delta)).filter(|&glyph| glyph != 0);
+            }
+            let at = range_offsets + 2 * segment + range_offset + 2 * usize::from(c - start);
+            let glyph = u16_at(subtable, at)?;
+            return (glyph != 0).then(|| glyph.wrapping_add(delta));
+        }
+        None
+    }
+}
+
+/// Offsets of the component glyph ids of a composite outline, with the ids they hold.
+fn components(outline: &[u8]) -> Option<Vec<(usize, u16)>> {
+    let mut found = Vec::new();
+    if outline.is_empty() || i16_at(outline, 0)? >= 0 {
+        return Some(found);
+    }
+    let mut at = 10;
+    loop {
+        let flags = u16_at(outline, at)?;
+        found.push((at + 2, u16_at(outline, at + 2)?));
+        at += 4 + if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        if flags & WE_HAVE_A_SCALE != 0 {
+            at += 2;
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            at += 4;
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            at += 8;
+        }
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(found);
+        }
+    }
+}
+
+/// A `cmap` with a format 4 subtable for the Basic Multilingual Plane and, when needed, a
+/// format 12 subtable for the characters beyond it.
+fn build_cmap(mapping: impl Iterator<Item = (char, u16)>) -> Vec<u8> {
+    let mapping: Vec<(u32, u16)> = mapping.map(|(c, glyph)| (c as u32, glyph)).collect();
+
+    // One segment per character, plus the closing 0xFFFF segment
+    let bmp: Vec<(u16, u16)> = mapping
+        .iter()
+        .filter_map(|&(c, glyph)| Some((u16::try_from(c).ok().filter(|&c| c != 0xFFFF)?, glyph)))
+        .collect();
+    let segments = bmp.len() + 1;
+    let search_range = 2 * (1u16 << (usize::BITS - 1 - segments.leading_zeros()));
+    let mut format4 = Vec::new();
+    for value in [
+        4,
+        (16 + 8 * segments) as u16,
+        0,
+        2 * segments as u16,
+        search_range,
+        search_range.trailing_zeros() as u16 - 1,
+        2 * segments as u16 - search_range,
+    ] {
+        format4.extend_from_slice(&value.to_be_bytes());
+    }
+    let column = |values: &mut Vec<u8>, f: &dyn Fn(u16, u16) -> u16, last: u16| {
+        for &(c, glyph) in &bmp {
+            values.extend_from_slice(&f(c, glyph).to_be_bytes());
+        }
+        values.extend_from_slice(&last.to_be_bytes());
+    };
+    column(&mut format4, &|c, _| c, 0xFFFF);
+    format4.extend_from_slice(&0u16.to_be_bytes());
+    column(&mut format4, &|c, _| c, 0xFFFF);
+    column(&mut format4, &|c, glyph| glyph.wrapping_sub(c), 1);
+    column(&mut format4, &|_, _| 0, 0);
+
+    let mut subtables = vec![((3u16, 1u16), format4)];
+    if mapping.iter().any(|&(c, _)| c > 0xFFFF) {
+        let mut format12 = Vec::new();
+        format12.extend_from_slice(&12u16.to_be_bytes());
+        format12.extend_from_slice(&0u16.to_be_bytes());
+        format12.extend_from_slice(&(16 + 12 * mapping.len() as u32).to_be_bytes());
+        format12.extend_from_slice(&0u32.to_be_bytes());
+        format12.extend_from_slice(&(mapping.len() as u32).to_be_bytes());
+        for &(c, glyph) in &mapping {
+            format12.extend_from_slice(&c.to_be_bytes());
+            format12.extend_from_slice(&c.to_be_bytes());
+            format12.extend_from_slice(&u32::from(glyph).to_be_bytes());
+        }
+        subtables.push(((3, 10), format12));
+    }
+
+    let mut cmap = Vec::new();
+    cmap.extend_from_slice(&0u16.to_be_bytes());
+    cmap.extend_from_slice(&(subtables.len() as u16).to_be_bytes());
+    let mut offset = 4 + 8 * subtables.len() as u32;
+    for ((platform, encoding), subtable) in &subtables {
+        cmap.extend_from_slice(&platform.to_be_bytes());
+        cmap.extend_from_slice(&encoding.to_be_bytes());
+        cmap.extend_from_slice(&offset.to_be_bytes());
+        offset += subtable.len() as u32;
+    }
+    for (_, subtable) in subtables {
+        cmap.extend_from_slice(&subtable);
+    }
+    cmap
+}
+
+/// A format 0 `name` table holding the [`KEPT_NAMES`] records of `name`.
+fn build_name(name: &[u8]) -> Option<Vec<u8>> {
+    let storage = name.get(usize::from(u16_at(name, 4)?)..)?;
+    let mut records = Vec::new();
+    let mut strings = Vec::new();
+    for i in 0..usize::from(u16_at(name, 2)?) {
+        let record = name.get(6 + 12 * i..18 + 12 * i)?;
+        if !KEPT_NAMES.contains(&u16_at(record, 6)?) {
+            continue;
+        }
+        let (length, offset) = (u16_at(record, 8)?, usize::from(u16_at(record, 10)?));
+        records.extend_from_slice(&record[..8]);
+        records.extend_from_slice(&length.to_be_bytes());
+        records.extend_from_slice(&(strings.len() as u16).to_be_bytes());
+        strings.extend_from_slice(storage.get(offset..offset + usize::from(length))?);
+    }
+    let count = records.len() / 12;
+    let mut table = Vec::with_capacity(6 + records.len() + strings.len());
+    for value in [0, count as u16, (6 + 12 * count) as u16] {
+        table.extend_from_slice(&value.to_be_bytes());
+    }
+    table.extend_from_slice(&records);
+    table.extend_from_slice(&strings);
+    Some(table)
+}
+
+/// A `kern` table holding the pairs of kept glyphs from the horizontal format 0 subtables of
+/// `kern`, or `None` when no pair is left.
+fn build_kern(kern: &[u8], kept: &[bool], new_ids: &[u16]) -> Option<Vec<u8>> {
+    const HORIZONTAL: u16 = 0x0001;
+    const MINIMUM_OR_CROSS_STREAM: u16 = 0x0006;
+    if u16_at(kern, 0)? != 0 {
+        return None;
+    }
+    let is_kept = |glyph: u16| kept.get(usize::from(glyph)).copied().unwrap_or(false);
+    let mut pairs = Vec::new();
+    let mut at = 4;
+    for _ in 0..u16_at(kern, 2)? {
+        let coverage = u16_at(kern, at + 4)?;
+        let count = usize::from(u16_at(kern, at + 6)?);
+        // The length field overflows for large format 0 subtables, so it is computed instead
+        let length = if coverage >> 8 == 0 {
+            14 + 6 * count
+        } else {
+            usize::from(u16_at(kern, at + 2)?)
+        };
+        if coverage >> 8 == 0
+            && coverage & HORIZONTAL != 0
+            && coverage & MINIMUM_OR_CROSS_STREAM == 0
+        {
+            for pair in 0..count {
+                let pair = at + 14 + 6 * pair;
+                let (left, right) = (u16_at(kern, pair)?, u16_at(kern, pair + 2)?);
+                if is_kept(left) && is_kept(right) {
+                    let (left, right) = (new_ids[usize::from(left)], new_ids[usize::from(right)]);
+                    pairs.push((left, right, u16_at(kern, pair + 4)?));
+                }
+            }
+        }
+        at += length;
+    }
+    if pairs.is_empty() {
+        return None;
+    }
+    pairs.sort_unstable();
+    pairs.dedup_by_key(|pair| (pair.0, pair.1));
+
+    let count = pairs.len() as u16;
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let search_range = 6 << entry_selector;
+    let mut table = Vec::with_capacity(18 + 6 * pairs.len());
+    for value in [
+        0,
+        1,
+        0,
+        (14 + 6 * pairs.len()).min(usize::from(u16::MAX)) as u16,
+        HORIZONTAL,
+        count,
+        search_range,
+        entry_selector,
+        6 * count - search_range,
+    ] {
+        table.extend_from_slice(&value.to_be_bytes());
+    }
+    for (left, right, value) in pairs {
+        for value in [left, right, value] {
+            table.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+    Some(table)
+}
+
+/// Writes a TrueType file from tables sorted by tag.
+fn write_font(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let search_range = 16 << entry_selector;
+    let mut font = Vec::new();
+    font.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    for value in [
+        count,
+        search_range,
+        entry_selector,
+        16 * count - search_range,
+    ] {
+        font.extend_from_slice(&value.to_be_bytes());
+    }
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, table) in tables {
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&checksum(table).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += table.len().next_multiple_of(4);
+    }
+    for (_, table) in tables {
+        font.extend_from_slice(table);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    font
+}
+
+fn checksum(table: &[u8]) -> u32 {
+    table.chunks(4).fold(0u32, |sum, word| {
+        let mut padded = [0; 4];
+        padded[..word.len()].copy_from_slice(word);
+        sum.wrapping_add(u32::from_be_bytes(padded))
+    })
+}
+
+fn u16_at(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn i16_at(data: &[u8], at: usize) -> Option<i16> {
+    u16_at(data, at).map(|value| value as i16)
+}
+
+fn u32_at(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subset_ttf() {
+        // DejaVu Sans cut down to printable ASCII, é and …
+        let data = include_bytes!("../tests/fixtures/dejavu-sans-ascii.ttf");
+        let subset = subset_ttf(data, "build é".chars()).unwrap();
+        assert!(subset.len() < data.len() / 2);
+        let font = Font::parse(&subset).unwrap();
+        // .notdef, the seven characters, and the e and acute accent composing é
+        assert_eq!(u16_at(font.table(b"maxp").unwrap(), 4), Some(10));
+        for c in "build é".chars() {
+            let glyph = font.glyph_id(c).unwrap();
+            assert!(glyph > 0 && glyph < 10, "{}", c);
+        }
+        let e_acute = font.outline(font.glyph_id('é').unwrap(), true).unwrap();
+        for (_, component) in components(e_acute).unwrap() {
+            assert!(component < 10);
+        }
+        assert!(font.table(b"name").unwrap().len() < 1024);
+        // Kerning is kept for pairs of kept glyphs only
+        assert!(font.table(b"kern").is_none());
+        let subset = subset_ttf(data, "AV".chars()).unwrap();
+        assert!(Font::parse(&subset).unwrap().table(b"kern").is_some());
+        assert_eq!(font.glyph_id('x'), None);
+        assert!(subset_ttf(b"not a font", "a".chars()).is_none());
+    }
+}
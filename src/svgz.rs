@@ -0,0 +1,59 @@
+//! Gzip-compressed SVG output, served as `.svgz` or with `Content-Encoding: gzip`.
+
+use crate::{BadgeParams, render_badge_svg_to_io};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+/// Render a badge as gzip-compressed SVG.
+///
+/// The SVG is the one [`render_badge_svg`](crate::render_badge_svg) returns, compressed as it
+/// is rendered. Badge servers can send the bytes with `Content-Encoding: gzip` to clients that
+/// accept it, or save them as `.svgz` files.
+///
+/// # Arguments
+/// * `params` - Badge parameters (see [`BadgeParams`]).
+///
+/// # Returns
+/// Gzip-compressed SVG bytes.
+///
+/// ## Example
+/// ```rust
+/// use shields::{BadgeParams, render_badge_svgz};
+/// let params = BadgeParams {
+///     label: Some("build"),
+///     message: Some("passing"),
+///     ..Default::default()
+/// };
+/// let svgz = render_badge_svgz(&params);
+/// assert!(svgz.starts_with(&[0x1f, 0x8b]));
+/// ```
+pub fn render_badge_svgz(params: &BadgeParams) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    render_badge_svg_to_io(params, &mut encoder).expect("writing to a Vec cannot fail");
+    encoder.finish().expect("writing to a Vec cannot fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_badge_svg;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_svgz_round_trip() {
+        let params = BadgeParams {
+            label: Some("build"),
+            message: Some("passing"),
+            logo: Some("rust"),
+            ..Default::default()
+        };
+        let svgz = render_badge_svgz(&params);
+        let mut svg = String::new();
+        GzDecoder::new(svgz.as_slice())
+            .read_to_string(&mut svg)
+            .unwrap();
+        assert_eq!(svg, render_badge_svg(&params));
+        assert!(svgz.len() < svg.len());
+    }
+}
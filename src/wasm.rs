@@ -0,0 +1,61 @@
+//! WebAssembly bindings, for generating badges client-side with the same output as the Rust
+//! backend.
+//!
+//! The crate is built as an `rlib` only, so the module is linked with `--crate-type cdylib` and
+//! its JavaScript glue generated by the `wasm-bindgen` CLI:
+//!
+//! ```sh
+//! cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/shields.wasm
+//! ```
+//!
+//! Then call `renderBadge` from JavaScript with the JSON form of [`BadgeParamsOwned`]
+//! (snake_case keys, as accepted by `serde`):
+//!
+//! ```js
+//! import init, { renderBadge } from "./pkg/shields.js";
+//!
+//! await init();
+//! const svg = renderBadge(JSON.stringify({
+//!   style: "flat-square",
+//!   label: "build",
+//!   message: "passing",
+//!   message_color: "brightgreen",
+//! }));
+//! ```
+
+use crate::{BadgeParamsOwned, render_badge_svg};
+use wasm_bindgen::prelude::*;
+
+/// Renders a badge from JSON-encoded [`BadgeParamsOwned`].
+///
+/// Rendering matches [`render_badge_svg`]; invalid colors or logos fall back the same way.
+///
+/// # Errors
+/// Throws in JavaScript when `params_json` is not a valid parameter object.
+#[wasm_bindgen(js_name = renderBadge)]
+pub fn render_badge(params_json: &str) -> Result<String, JsError> {
+    let params: BadgeParamsOwned = serde_json::from_str(params_json)?;
+    Ok(render_badge_svg(&params.as_params()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BadgeParams, BadgeStyle};
+
+    #[test]
+    fn test_render_badge_matches_native() {
+        let json = r#"{"style": "flat-square", "label": "build", "message": "passing",
+            "message_color": "brightgreen", "logo": "rust"}"#;
+        let expected = render_badge_svg(&BadgeParams {
+            style: BadgeStyle::FlatSquare,
+            label: Some("build"),
+            message: Some("passing"),
+            message_color: Some("brightgreen"),
+            logo: Some("rust"),
+            ..Default::default()
+        });
+        assert_eq!(render_badge(json).unwrap(), expected);
+    }
+}
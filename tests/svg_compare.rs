@@ -1,9 +1,21 @@
+// Compares against the flat, flat-square, plastic and social badges of shields.io
+#![cfg(all(
+    feature = "style-flat",
+    feature = "style-flat-square",
+    feature = "style-plastic",
+    feature = "style-social"
+))]
 use pretty_assertions::assert_eq;
-use shields::{BadgeParams, BadgeStyle, render_badge_svg};
+use shields::{BadgeParams, BadgeStyle, IdSuffix, RenderOptions, render_badge_svg_with_options};
 use std::fs;
 use std::io::{Read, Write};
 use std::path::Path;
 
+/// Renders with shields.io's unsuffixed element ids.
+fn render_plain(params: &BadgeParams) -> String {
+    render_badge_svg_with_options(params, &RenderOptions::new().id_suffix(IdSuffix::Plain))
+}
+
 fn shields_io_url(params: &BadgeParams) -> String {
     let style = match params.style {
         BadgeStyle::Flat => "flat",
@@ -11,19 +23,23 @@ fn shields_io_url(params: &BadgeParams) -> String {
         BadgeStyle::FlatSquare => "flat-square",
         BadgeStyle::Social => "social",
         BadgeStyle::ForTheBadge => "for-the-badge",
+        BadgeStyle::ForTheBadgeTwoLine => unreachable!("shields.io has no two-line style"),
+        BadgeStyle::Pill => unreachable!("shields.io has no pill style"),
+        BadgeStyle::Outline => unreachable!("shields.io has no outline style"),
+        BadgeStyle::Monospace => unreachable!("shields.io has no monospace style"),
     };
-    let url = if params.label.is_some() {
+    let message = urlencoding::encode(params.message.unwrap_or(""));
+    let url = if let Some(label) = params.label {
         format!(
             "https://img.shields.io/badge/{}-{}-blue?style={}",
-            params.label.as_ref().unwrap(),
-            params.message.unwrap_or("").replace(" ", "%20"),
+            urlencoding::encode(label),
+            message,
             style
         )
     } else {
         format!(
             "https://img.shields.io/badge/{}-blue?style={}",
-            params.message.unwrap_or("").replace(" ", "%20"),
-            style
+            message, style
         )
     };
     let queries = [
@@ -105,7 +121,7 @@ fn get_shields_svg_with_cache(params: &BadgeParams, url: &str) -> String {
     });
 
     let mut file = fs::File::create(&cache_path)
-        .expect(format!("Failed to create cache file: {:?}", cache_path.display()).as_str());
+        .unwrap_or_else(|_| panic!("Failed to create cache file: {:?}", cache_path.display()));
     file.write_all(svg.as_bytes())
         .expect("Failed to write cache content");
 
@@ -114,25 +130,25 @@ fn get_shields_svg_with_cache(params: &BadgeParams, url: &str) -> String {
 
 #[test]
 fn test_svg_compare() {
-    let label_selections = vec![Some("label"), Some(""), None];
-    let message_selections = vec!["message", ""];
-    let label_color_selections = vec![Some("blue"), Some("#4c1"), Some(""), None, Some("#FFF")];
-    let message_color_selections = vec!["blue", "#4c3232", "", "#FFF"];
-    let links_selections = vec![
-        vec![None, None],
-        vec![Some(""), None],
-        vec![Some("https://example.com"), None],
-        vec![Some("https://example.com"), Some("https://example2.com")],
-        vec![Some("https://example.com"), Some("")],
+    let label_selections = [Some("label"), Some(""), None];
+    let message_selections = ["message", ""];
+    let label_color_selections = [Some("blue"), Some("#4c1"), Some(""), None, Some("#FFF")];
+    let message_color_selections = ["blue", "#4c3232", "", "#FFF"];
+    let links_selections = [
+        [None, None],
+        [Some(""), None],
+        [Some("https://example.com"), None],
+        [Some("https://example.com"), Some("https://example2.com")],
+        [Some("https://example.com"), Some("")],
     ];
-    let logo_selections = vec![Some("rust"), Some(""), None];
-    let style_selections = vec![
+    let logo_selections = [Some("rust"), Some(""), None];
+    let style_selections = [
         BadgeStyle::Flat,
         BadgeStyle::Plastic,
         BadgeStyle::FlatSquare,
         BadgeStyle::Social,
     ];
-    let logo_color_selections = vec![Some("blue"), None];
+    let logo_color_selections = [Some("blue"), None];
     let mut test_cases = vec![];
     for label in label_selections.iter() {
         for message in message_selections.iter() {
@@ -145,8 +161,8 @@ fn test_svg_compare() {
                                     if links.len() < 2 {
                                         continue;
                                     }
-                                    let link = links[0].clone();
-                                    let extra_link = links[1].clone();
+                                    let link = links[0];
+                                    let extra_link = links[1];
                                     if link.is_none() && extra_link.is_none() {
                                         continue;
                                     }
@@ -160,6 +176,7 @@ fn test_svg_compare() {
                                         extra_link: links[1],
                                         logo: *logo,
                                         logo_color: *logo_color,
+                                        ..Default::default()
                                     };
                                     test_cases.push(params);
                                 }
@@ -173,7 +190,7 @@ fn test_svg_compare() {
 
     // let mut test_case_count = 0;
     for params in test_cases {
-        let local_svg = render_badge_svg(&params);
+        let local_svg = render_plain(&params);
         let url = shields_io_url(&params);
         let shields_svg = get_shields_svg_with_cache(&params, &url);
 
@@ -202,19 +219,20 @@ fn test_svg_fast_compare() {
         extra_link: None,
         logo: Some("rust"),
         logo_color: Some("blue"),
+        ..Default::default()
     };
-    let local_svg = render_badge_svg(&params);
+    let local_svg = render_plain(&params);
     let url = shields_io_url(&params);
     let shields_svg = get_shields_svg_with_cache(&params, &url);
-    let file_name_local = format!("target/tmp/svg_local.svg");
-    let file_name_shields = format!("target/tmp/svg_shields.svg");
+    let file_name_local = "target/tmp/svg_local.svg";
+    let file_name_shields = "target/tmp/svg_shields.svg";
     let mut file_local =
-        fs::File::create(&file_name_local).expect("Failed to create local SVG file");
+        fs::File::create(file_name_local).expect("Failed to create local SVG file");
     file_local
         .write_all(local_svg.as_bytes())
         .expect("Failed to write local SVG file");
     let mut file_shields =
-        fs::File::create(&file_name_shields).expect("Failed to create shields SVG file");
+        fs::File::create(file_name_shields).expect("Failed to create shields SVG file");
     file_shields
         .write_all(shields_svg.as_bytes())
         .expect("Failed to write shields SVG file");
@@ -224,3 +242,27 @@ fn test_svg_fast_compare() {
         params, local_svg, shields_svg
     );
 }
+
+#[test]
+#[ignore = "fetches the expected badges from img.shields.io"]
+fn test_svg_compare_emoji() {
+    let messages = ["✅ passing", "🚀 deployed", "❌ failing", "status 🎉"];
+    for style in [BadgeStyle::Flat, BadgeStyle::FlatSquare, BadgeStyle::Social] {
+        for message in messages {
+            let params = BadgeParams {
+                style,
+                label: Some("status"),
+                message: Some(message),
+                ..Default::default()
+            };
+            let local_svg = render_plain(&params);
+            let url = shields_io_url(&params);
+            let shields_svg = get_shields_svg_with_cache(&params, &url);
+            assert_eq!(
+                local_svg, shields_svg,
+                "SVG mismatch\nParams: {:?}\nLocal SVG:\n{}\nshields.io SVG:\n{}",
+                params, local_svg, shields_svg
+            );
+        }
+    }
+}